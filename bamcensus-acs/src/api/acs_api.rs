@@ -1,34 +1,82 @@
-use crate::model::{AcsApiQueryParams, AcsValue, DeserializeGeoidFn};
+use crate::model::{AcsApiQueryParams, AcsRequest, AcsValue, DeserializeGeoidFn};
 use bamcensus_core::model::identifier::Geoid;
+use bamcensus_core::model::ByteCounter;
+use bamcensus_core::ops::progress::ProgressBar;
 use futures::future;
 use itertools::Itertools;
-use kdam::BarExt;
 use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 /// sets up a run of ACS queries.
-pub async fn batch_run<'a>(
+///
+/// if a `cancel` token is provided, cancelling it stops the run: queries that have
+/// not been sent are skipped, requests in flight are dropped, and the rows of the
+/// queries that already completed are returned. whether the result is partial can be
+/// checked with [`CancellationToken::is_cancelled`].
+///
+/// if a `bytes` counter is provided, the size of each response body is added to it.
+///
+/// # Example
+///
+/// a run cancelled before it starts sends no requests and returns no rows.
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_api;
+/// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+/// use bamcensus_core::model::identifier::GeoidType;
+/// use tokio_util::sync::CancellationToken;
+///
+/// let for_query = AcsGeoidQuery::new(None, Some(GeoidType::State)).unwrap();
+/// let query = AcsApiQueryParams::new(
+///     Some(String::from("http://127.0.0.1:9")),
+///     2022,
+///     AcsType::FiveYear,
+///     vec![String::from("B01001_001E")],
+///     for_query,
+///     None,
+/// );
+/// let cancel = CancellationToken::new();
+/// cancel.cancel();
+/// let client = reqwest::Client::new();
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// let rows = runtime
+///     .block_on(acs_api::batch_run(&client, &[query], Some(&cancel), None))
+///     .unwrap();
+/// assert!(rows.is_empty());
+/// ```
+pub async fn batch_run(
     client: &Client,
-    queries: &Vec<AcsApiQueryParams>,
+    queries: &[AcsApiQueryParams],
+    cancel: Option<&CancellationToken>,
+    bytes: Option<&ByteCounter>,
 ) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
-    let pb_builder = kdam::BarBuilder::default()
-        .total(queries.len())
-        .desc("ACS API calls");
-    let pb = Arc::new(Mutex::new(pb_builder.build()?));
+    let pb = Arc::new(Mutex::new(ProgressBar::new(
+        queries.len(),
+        "ACS API calls",
+    )?));
 
     let response = queries.iter().map(|params| {
         let pb = pb.clone();
         async move {
             let desc = params.build_url()?;
-            let res = run(client, params).await;
+            let res = match cancel {
+                // checked first, so that no request is sent once cancelled
+                Some(token) => tokio::select! {
+                    biased;
+                    _ = token.cancelled() => return Ok(vec![]),
+                    res = run(client, params, bytes) => res,
+                },
+                None => run(client, params, bytes).await,
+            };
 
             // update progress bar
             let mut pb_update = pb
                 .lock()
                 .map_err(|e| format!("failure aquiring progress bar mutex lock: {e}"))?;
-            pb_update
-                .update(1)
-                .map_err(|e| format!("failure on pb update: {e}"))?;
+            pb_update.update(1)?;
 
             pb_update.set_description(&desc);
 
@@ -43,63 +91,330 @@ pub async fn batch_run<'a>(
         .flatten()
         .collect_vec();
 
-    eprintln!(); // terminate progress bar
+    if let Ok(pb) = pb.lock() {
+        pb.finish();
+    }
+    if cancel.is_some_and(|t| t.is_cancelled()) {
+        log::warn!(
+            "ACS run cancelled, returning {} rows of completed queries",
+            result.len()
+        );
+    }
     Ok(result)
 }
 
-/// sets up a run of an ACS query.
+/// runs the queries of a multi-table [`AcsRequest`], see [`AcsRequest::queries`], and
+/// merges their rows into one row per geoid, in the order the geoids first appear.
+/// each value is tagged with the table it came from, see [`AcsRequest::table_of`].
+pub async fn run_request(
+    client: &Client,
+    request: &AcsRequest,
+) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
+    let queries = request.queries()?;
+    let rows = batch_run(client, &queries, None, None).await?;
+    Ok(merge_request_rows(request, rows))
+}
+
+/// merges the rows of the queries of a request by geoid and tags each value with its
+/// table. columns returned by more than one query, such as the NAME that variable
+/// group responses include, are kept once.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_api::merge_request_rows;
+/// use bamcensus_acs::model::{AcsGeoidQuery, AcsRequest, AcsType, AcsValue};
+/// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+/// use serde_json::json;
+///
+/// let for_query = AcsGeoidQuery::new(None, Some(GeoidType::State)).unwrap();
+/// let request = AcsRequest::new(2020, AcsType::FiveYear, for_query, None)
+///     .with_name()
+///     .with_table("B01001", &["B01001_001E"])
+///     .unwrap()
+///     .with_table("B25077", &[])
+///     .unwrap();
+/// let colorado = Geoid::state(8).unwrap();
+/// let value = |name: &str, value: &str| AcsValue::new(String::from(name), json![value]);
+/// let rows = vec![
+///     (colorado.clone(), vec![value("NAME", "Colorado"), value("B01001_001E", "5684926")]),
+///     (colorado.clone(), vec![value("NAME", "Colorado"), value("B25077_001E", "369900")]),
+/// ];
+///
+/// let merged = merge_request_rows(&request, rows);
+/// assert_eq!(merged.len(), 1);
+/// let tables = merged[0]
+///     .1
+///     .iter()
+///     .map(|v| (v.name.as_str(), v.table.as_deref()))
+///     .collect::<Vec<_>>();
+/// assert_eq!(tables, vec![
+///     ("NAME", None),
+///     ("B01001_001E", Some("B01001")),
+///     ("B25077_001E", Some("B25077")),
+/// ]);
+/// ```
+pub fn merge_request_rows(
+    request: &AcsRequest,
+    rows: Vec<(Geoid, Vec<AcsValue>)>,
+) -> Vec<(Geoid, Vec<AcsValue>)> {
+    let mut merged: Vec<(Geoid, Vec<AcsValue>)> = vec![];
+    let mut row_index: HashMap<Geoid, usize> = HashMap::new();
+    for (geoid, values) in rows {
+        let idx = *row_index.entry(geoid.clone()).or_insert_with(|| {
+            merged.push((geoid, vec![]));
+            merged.len() - 1
+        });
+        let row = &mut merged[idx].1;
+        for mut value in values {
+            if row.iter().any(|v| v.name == value.name) {
+                continue;
+            }
+            value.table = request.table_of(&value.name).map(String::from);
+            row.push(value);
+        }
+    }
+    merged
+}
+
+/// sets up a run of an ACS query. if a `bytes` counter is provided, the size of the
+/// response body is added to it.
 ///
 /// todo: this is faster than not parallel but we could probably do better if we
 /// remove the awaits and let the coroutines do the work.
 pub async fn run(
     client: &Client,
     query: &AcsApiQueryParams,
+    bytes: Option<&ByteCounter>,
 ) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
     let url = query.build_url()?;
+    let log_url = redact_api_key(&url);
+    log::debug!("requesting {log_url}");
+    let start = Instant::now();
 
     let response = client
         .get(&url)
         .send()
         .await
-        .map_err(|e| format!("failure calling {url}: {e}"))?;
+        .map_err(|e| format!("failure calling {log_url}: {}", e.without_url()))?;
     let status_code = response.status();
+    log::info!(
+        "ACS {log_url} responded {status_code} in {:.2?}",
+        start.elapsed()
+    );
+    // errors are reported with the redacted URL, since reqwest errors include the
+    // URL with the API key
     match response.error_for_status() {
         Err(e) => Err(format!(
-            "API request failed with error code {status_code}. error: {e}"
+            "API request to {log_url} failed with error code {status_code}. error: {}",
+            e.without_url()
         )),
         Ok(r) if r.status() == StatusCode::NO_CONTENT => {
-            Err(format!("requested URL {url} has no content"))
+            Err(format!("requested URL {log_url} has no content"))
         }
         Ok(res) => {
-            let json = res
-                .json::<serde_json::Value>()
-                .await
-                .map_err(|e| format!("failure parsing JSON for response from {url}: {e}"))?;
+            let body = res.bytes().await.map_err(|e| {
+                format!(
+                    "failure reading response from {log_url}: {}",
+                    e.without_url()
+                )
+            })?;
+            if let Some(bytes) = bytes {
+                bytes.add(body.len() as u64);
+            }
+            let json = serde_json::from_slice::<serde_json::Value>(&body)
+                .map_err(|e| format!("failure parsing JSON for response from {log_url}: {e}"))?;
 
-            // confirm the correct column names in the response arrays before deserializing
-            validate_header(query, &json)?;
+            // the API may report query errors in a JSON object with a 200 status code
+            check_error_envelope(&json).map_err(|e| format!("ACS API error for {log_url}: {e}"))?;
+            from_json(query, &json)
+        }
+    }
+}
 
-            let deserialize_fn = query.for_query.build_deserialize_geoid_fn();
-            let n_for_cols = query.for_query.response_column_count();
+/// reads the rows of an ACS API response that has already been parsed as JSON, such
+/// as a response saved to a file. this is the same path [`run`] takes once a response
+/// arrives: the error envelope is checked, the columns of the query are found in the
+/// header with [`response_columns`], and each row is deserialized into a Geoid with
+/// AcsValues.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_api;
+/// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+/// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+///
+/// let for_query = AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::County)).unwrap();
+/// let query = AcsApiQueryParams::new(
+///     None,
+///     2020,
+///     AcsType::FiveYear,
+///     vec![String::from("NAME"), String::from("B01001_001E")],
+///     for_query,
+///     None,
+/// );
+///
+/// // a fixture, such as one read from a file with serde_json::from_reader
+/// let saved = serde_json::json!([
+///     ["NAME", "B01001_001E", "state", "county"],
+///     ["Denver County, Colorado", "706799", "08", "031"],
+///     ["Jefferson County, Colorado", "579581", "08", "059"]
+/// ]);
+/// let rows = acs_api::from_json(&query, &saved).unwrap();
+/// assert_eq!(rows.len(), 2);
+/// assert_eq!(rows[0].0, Geoid::County(fips::State(8), fips::County(31)));
+/// assert_eq!(rows[1].1[1].as_f64_safe().unwrap(), 579581.0);
+///
+/// // the API may return the columns in another order
+/// let reordered = serde_json::json!([
+///     ["B01001_001E", "state", "NAME", "county"],
+///     ["706799", "08", "Denver County, Colorado", "031"]
+/// ]);
+/// let rows = acs_api::from_json(&query, &reordered).unwrap();
+/// assert_eq!(rows[0].0, Geoid::County(fips::State(8), fips::County(31)));
+/// assert_eq!(rows[0].1[0].name, "NAME");
+/// assert_eq!(rows[0].1[1].as_f64_safe().unwrap(), 706799.0);
+///
+/// // a fixture saved for a different query is rejected
+/// let mismatched = serde_json::json!([["NAME", "B01001_001E", "state"], ["Colorado", "5773714", "08"]]);
+/// assert!(acs_api::from_json(&query, &mismatched).is_err());
+///
+/// // as are saved API errors
+/// let error = serde_json::json!({"error": "error: unknown variable 'B01001_999E'"});
+/// assert!(acs_api::from_json(&query, &error).is_err());
+/// ```
+pub fn from_json(
+    query: &AcsApiQueryParams,
+    json: &serde_json::Value,
+) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
+    check_error_envelope(json).map_err(|e| format!("ACS API error: {e}"))?;
 
-            let result = json
-                .as_array()
-                .ok_or_else(|| String::from("JSON response root must be array"))?
-                .iter()
-                .skip(1) // skip the header!
-                .map(move |row| {
-                    deserialize(row, &query.get_query, n_for_cols, deserialize_fn.clone())
-                })
-                .collect::<Result<Vec<_>, String>>()?;
+    // find the columns of the query in the response header before deserializing
+    let columns = response_columns(query, json)?;
+
+    let deserialize_fn = query.for_query.build_deserialize_geoid_fn();
+
+    json.as_array()
+        .ok_or_else(|| String::from("JSON response root must be array"))?
+        .iter()
+        .skip(1) // skip the header!
+        .map(move |row| deserialize_columns(row, &columns, deserialize_fn.clone()))
+        .collect::<Result<Vec<_>, String>>()
+}
 
-            Ok(result)
+/// hides the value of the `key` query parameter so that URLs can be logged
+/// without leaking the ACS API token.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_api::redact_api_key;
+///
+/// let url = "https://api.census.gov/data/2022/acs/acs5?get=NAME&for=state:*&key=abc123";
+/// assert_eq!(redact_api_key(url), "https://api.census.gov/data/2022/acs/acs5?get=NAME&for=state:*&key=<redacted>");
+/// ```
+pub fn redact_api_key(url: &str) -> String {
+    match url.find("&key=") {
+        None => String::from(url),
+        Some(idx) => {
+            let rest = &url[idx + 5..];
+            let after = rest.find('&').map(|i| &rest[i..]).unwrap_or_default();
+            format!("{}&key=<redacted>{after}", &url[..idx])
         }
     }
 }
 
-fn validate_header(query: &AcsApiQueryParams, response: &serde_json::Value) -> Result<(), String> {
-    let expected = query.column_names();
+/// detects the `{"error": "..."}` object the census API returns in place of the expected
+/// array when a query is malformed, returning the embedded message verbatim.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_api::check_error_envelope;
+///
+/// let response = serde_json::json!({"error": "error: unknown/unsupported geography hierarchy"});
+/// let result = check_error_envelope(&response);
+/// assert_eq!(result, Err(String::from("error: unknown/unsupported geography hierarchy")));
+///
+/// let response = serde_json::json!([["NAME", "state"], ["Colorado", "08"]]);
+/// assert!(check_error_envelope(&response).is_ok());
+/// ```
+pub fn check_error_envelope(response: &serde_json::Value) -> Result<(), String> {
+    match response.as_object().and_then(|obj| obj.get("error")) {
+        None => Ok(()),
+        Some(serde_json::Value::String(msg)) => Err(msg.clone()),
+        Some(other) => Err(other.to_string()),
+    }
+}
+
+/// the position of each column of an ACS response. the census API may return
+/// columns in a different order than they were requested, so they are found by name
+/// in the response header, see [`response_columns`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseColumns {
+    /// the name and position of each 'get' column, which become [`AcsValue`]s
+    pub get: Vec<(String, usize)>,
+    /// the position of each 'for' column, in the order the geoid is read from them
+    pub geoid: Vec<usize>,
+}
+
+impl ResponseColumns {
+    /// the layout of a response in request order: the 'get' columns followed by
+    /// `n_for_cols` 'for' columns.
+    pub fn positional(get_cols: &[String], n_for_cols: usize) -> ResponseColumns {
+        let n_get_cols = get_cols.len();
+        ResponseColumns {
+            get: get_cols.iter().cloned().zip(0..n_get_cols).collect_vec(),
+            geoid: (n_get_cols..n_get_cols + n_for_cols).collect_vec(),
+        }
+    }
+
+    /// the number of values a row must have to hold every column.
+    pub fn width(&self) -> usize {
+        let get = self.get.iter().map(|(_, idx)| *idx);
+        get.chain(self.geoid.iter().copied())
+            .max()
+            .map(|idx| idx + 1)
+            .unwrap_or_default()
+    }
+}
 
+/// finds the columns of the query in the response header by name, so the order of
+/// the columns does not matter. every 'for' column and requested variable must be
+/// present, except `NAME`, which is left out of the values when it is missing.
+/// columns that were not requested are ignored, such as a `NAME` column the API
+/// added. when the query requests a variable group, the 'get' columns are
+/// data-driven, so every column besides the 'for' columns is read, in response order.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_api::response_columns;
+/// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+/// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+///
+/// let for_query = AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::County)).unwrap();
+/// let get_query = vec![String::from("B01001_001E"), String::from("B01001_002E")];
+/// let query = AcsApiQueryParams::new(None, 2020, AcsType::FiveYear, get_query, for_query, None);
+///
+/// // reordered variables and an implicit NAME column
+/// let response = serde_json::json!([["NAME", "B01001_002E", "state", "B01001_001E", "county"]]);
+/// let columns = response_columns(&query, &response).unwrap();
+/// assert_eq!(
+///     columns.get,
+///     vec![(String::from("B01001_001E"), 3), (String::from("B01001_002E"), 1)]
+/// );
+/// assert_eq!(columns.geoid, vec![2, 4]);
+///
+/// // a missing variable is an error
+/// let response = serde_json::json!([["B01001_001E", "state", "county"]]);
+/// assert!(response_columns(&query, &response).is_err());
+/// ```
+pub fn response_columns(
+    query: &AcsApiQueryParams,
+    response: &serde_json::Value,
+) -> Result<ResponseColumns, String> {
     let header_json_opt = response
         .as_array()
         .and_then(|outer| outer.first())
@@ -114,18 +429,54 @@ fn validate_header(query: &AcsApiQueryParams, response: &serde_json::Value) -> R
             })
             .collect::<Result<Vec<_>, String>>(),
     }?;
+    let mismatch = || {
+        format!(
+            "expected headers did not match found\nexpected: {}\nfound: {}",
+            query.column_names().iter().join(","),
+            header.iter().join(",")
+        )
+    };
+
+    // 'for' columns follow the 'get' columns in a response, so they are found from
+    // the end of the header in case a variable shares a name with one
+    let geoid = query
+        .for_query
+        .response_column_names()
+        .iter()
+        .map(|name| {
+            header
+                .iter()
+                .rposition(|col| col == name)
+                .ok_or_else(mismatch)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
 
-    for (exp, found) in expected.iter().zip(&header) {
-        if exp != found {
-            let exp_str = expected.iter().join(",");
-            let fnd_str = header.iter().join(",");
-            return Err(format!(
-                "expected headers did not match found\nexpected: {exp_str}\nfound: {fnd_str}"
-            ));
+    let get = if query.has_group_query() {
+        header
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !geoid.contains(idx))
+            .map(|(idx, name)| (String::from(*name), idx))
+            .collect_vec()
+    } else {
+        let mut get = vec![];
+        for name in query.get_columns().iter() {
+            let position = header
+                .iter()
+                .enumerate()
+                .position(|(idx, col)| col == name && !geoid.contains(&idx));
+            match position {
+                Some(idx) => get.push((name.clone(), idx)),
+                None if name == "NAME" => {
+                    log::debug!("ACS response has no NAME column, leaving it out of the values")
+                }
+                None => return Err(mismatch()),
+            }
         }
-    }
+        get
+    };
 
-    Ok(())
+    Ok(ResponseColumns { get, geoid })
 }
 
 /// deserializes a row of JSON values returned from an ACS response.
@@ -143,7 +494,8 @@ fn validate_header(query: &AcsApiQueryParams, response: &serde_json::Value) -> R
 /// for a given row, this function will
 ///   1. turn all 'for' columns into a single Geoid instance (via the deserialize_fn)
 ///   2. for each 'get' column, create an AcsValue which pairs the get_column_name with
-///      the corresponding get_value.
+///      the corresponding get_value. missing values, whether JSON null or the string
+///      "null", are both stored as JSON null, see [`AcsValue::normalize_missing`].
 ///
 /// # Examples
 ///
@@ -173,8 +525,14 @@ fn validate_header(query: &AcsApiQueryParams, response: &serde_json::Value) -> R
 /// let get_cols = vec![String::from("NAME"),String::from("B01001_001E")];
 /// let n_for_cols: usize = query.response_column_count();
 /// let (geoid, acs_values) = deserialize(&row, &get_cols, n_for_cols, deserialize_fn.clone()).unwrap();
-/// assert_eq!(geoid, Geoid::CountySubdivision(fips::State(48), fips::County(13), fips::CountySubdivision(90595)))
+/// assert_eq!(geoid, Geoid::CountySubdivision(fips::State(48), fips::County(13), fips::CountySubdivision(90595)));
 ///
+/// // missing values, written as null or "null", are both read as JSON null
+/// for value in [serde_json::json![null], serde_json::json!["null"]] {
+///     let row = serde_json::json![["Campbellton CCD, Atascosa County, Texas", value, "48", "013", "90595"]];
+///     let (_, acs_values) = deserialize(&row, &get_cols, n_for_cols, deserialize_fn.clone()).unwrap();
+///     assert!(acs_values[1].is_missing());
+/// }
 /// ```
 ///
 pub fn deserialize(
@@ -183,11 +541,22 @@ pub fn deserialize(
     n_for_cols: usize,
     deserialize_fn: DeserializeGeoidFn,
 ) -> Result<(Geoid, Vec<AcsValue>), String> {
-    let n_get_cols = get_cols.len();
+    let columns = ResponseColumns::positional(get_cols, n_for_cols);
+    deserialize_columns(row, &columns, deserialize_fn)
+}
+
+/// deserializes a row of JSON values as in [`deserialize`], reading each column
+/// from its position in `columns` rather than assuming the request order. missing
+/// values are normalized to JSON null, see [`AcsValue::normalize_missing`].
+pub fn deserialize_columns(
+    row: &serde_json::Value,
+    columns: &ResponseColumns,
+    deserialize_fn: DeserializeGeoidFn,
+) -> Result<(Geoid, Vec<AcsValue>), String> {
     let values = row
         .as_array()
         .ok_or_else(|| format!("row should be an array, found: {row}"))?;
-    let expected_len = n_get_cols + n_for_cols;
+    let expected_len = columns.width();
     if values.len() < expected_len {
         return Err(format!(
             "row should have length {}, found {}",
@@ -197,19 +566,21 @@ pub fn deserialize(
     }
 
     // grab geoid from row
-    let geoid_values = values[n_get_cols..].to_vec();
+    let geoid_values = columns
+        .geoid
+        .iter()
+        .map(|idx| values[*idx].clone())
+        .collect_vec();
     let geoid = deserialize_fn(geoid_values)?;
 
     // grab all values from row
-    let mut acs_values: Vec<AcsValue> = vec![];
-    for idx in 0..n_get_cols {
-        let name = &get_cols[idx];
-        let value = values[idx].clone();
-        let row = AcsValue {
-            name: String::from(name),
-            value,
-        };
-        acs_values.push(row);
-    }
+    let acs_values = columns
+        .get
+        .iter()
+        .map(|(name, idx)| {
+            let value = AcsValue::normalize_missing(values[*idx].clone());
+            AcsValue::new(name.clone(), value)
+        })
+        .collect_vec();
     Ok((geoid, acs_values))
 }
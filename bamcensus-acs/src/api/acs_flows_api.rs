@@ -0,0 +1,133 @@
+use super::acs_api::{check_error_envelope, redact_api_key};
+use crate::model::{AcsFlow, AcsFlowsQueryParams, FLOWS_GET_QUERY};
+use bamcensus_core::model::identifier::Geoid;
+use reqwest::Client;
+use std::time::Instant;
+
+/// runs an ACS migration flows query, returning one [`AcsFlow`] per pair of current
+/// and previous geographies.
+pub async fn run(client: &Client, query: &AcsFlowsQueryParams) -> Result<Vec<AcsFlow>, String> {
+    let url = query.build_url()?;
+    let log_url = redact_api_key(&url);
+    log::debug!("requesting {log_url}");
+    let start = Instant::now();
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("failure calling {log_url}: {}", e.without_url()))?;
+    let status_code = response.status();
+    log::info!(
+        "ACS flows {log_url} responded {status_code} in {:.2?}",
+        start.elapsed()
+    );
+    let json = response
+        .error_for_status()
+        .map_err(|e| {
+            format!(
+                "API request to {log_url} failed with error code {status_code}. error: {}",
+                e.without_url()
+            )
+        })?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| {
+            format!(
+                "failure parsing JSON for response from {log_url}: {}",
+                e.without_url()
+            )
+        })?;
+    from_json(&json).map_err(|e| format!("{log_url}: {e}"))
+}
+
+/// reads the rows of a migration flows response that has already been parsed as
+/// JSON. columns are found by name, so the trailing geography columns added by the
+/// `for` query are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_flows_api;
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+///
+/// let saved = serde_json::json!([
+///     ["GEOID1", "GEOID2", "FULL2_NAME", "MOVEDIN", "MOVEDIN_M", "state", "county"],
+///     ["08031", "08059", "Jefferson County, Colorado", "10215", "1042", "08", "031"],
+///     ["08031", null, "Asia", "2105", "433", "08", "031"],
+///     ["08031", "56021", "Laramie County, Wyoming", null, null, "08", "031"]
+/// ]);
+/// let flows = acs_flows_api::from_json(&saved).unwrap();
+/// let denver = Geoid::County(fips::State(8), fips::County(31));
+/// assert!(flows.iter().all(|f| f.current == denver));
+/// assert_eq!(flows[0].previous, Some(Geoid::County(fips::State(8), fips::County(59))));
+/// assert_eq!(flows[0].estimate, Some(10215));
+/// assert_eq!(flows[0].moe, Some(1042));
+///
+/// // movers from abroad have no previous geoid
+/// assert_eq!(flows[1].previous, None);
+/// assert_eq!(flows[1].previous_name, "Asia");
+///
+/// // suppressed estimates
+/// assert_eq!(flows[2].estimate, None);
+/// ```
+pub fn from_json(json: &serde_json::Value) -> Result<Vec<AcsFlow>, String> {
+    check_error_envelope(json).map_err(|e| format!("ACS API error: {e}"))?;
+    let rows = json
+        .as_array()
+        .ok_or_else(|| String::from("JSON response root must be array"))?;
+    let header = rows
+        .first()
+        .and_then(|h| h.as_array())
+        .ok_or_else(|| String::from("malformed ACS flows header"))?;
+    let indices = FLOWS_GET_QUERY
+        .iter()
+        .map(|name| {
+            header
+                .iter()
+                .position(|col| col.as_str() == Some(name))
+                .ok_or_else(|| format!("ACS flows response is missing column {name}"))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let [geoid1, geoid2, name2, moved_in, moved_in_moe] = indices[..] else {
+        return Err(String::from("internal error: unexpected flows columns"));
+    };
+
+    rows.iter()
+        .skip(1) // skip the header!
+        .map(|row| {
+            let values = row
+                .as_array()
+                .ok_or_else(|| format!("ACS flows row is not an array: {row}"))?;
+            let get = |idx: usize| values.get(idx).unwrap_or(&serde_json::Value::Null);
+            let current = match get(geoid1).as_str() {
+                Some(s) => Geoid::try_from(s),
+                None => Err(format!("ACS flows row has no GEOID1: {row}")),
+            }?;
+            let previous = get(geoid2).as_str().map(Geoid::try_from).transpose()?;
+            Ok(AcsFlow {
+                current,
+                previous,
+                previous_name: get(name2).as_str().unwrap_or_default().to_string(),
+                estimate: read_count(get(moved_in))?,
+                moe: read_count(get(moved_in_moe))?,
+            })
+        })
+        .collect()
+}
+
+/// reads a count, which the API returns as a string, or None if it is null.
+fn read_count(value: &serde_json::Value) -> Result<Option<u64>, String> {
+    match value {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) => s
+            .trim()
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|e| format!("expected a count in ACS flows response, found '{s}': {e}")),
+        other => other
+            .as_u64()
+            .map(Some)
+            .ok_or_else(|| format!("expected a count in ACS flows response, found {other}")),
+    }
+}
@@ -0,0 +1,96 @@
+use crate::model::{AcsApiQueryParams, AcsGeography, AcsType};
+use reqwest::Client;
+use std::collections::HashMap;
+
+/// builds the URL to the `geography.json` document of an ACS dataset.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_geography_api::geography_url;
+/// use bamcensus_acs::model::AcsType;
+///
+/// let url = geography_url(None, 2022, AcsType::FiveYear);
+/// assert_eq!(url, "https://api.census.gov/data/2022/acs/acs5/geography.json");
+/// ```
+pub fn geography_url(base_url: Option<&str>, year: u64, acs_type: AcsType) -> String {
+    let base = base_url.unwrap_or(crate::model::constants::BASE_URL);
+    let type_s = acs_type.to_directory_name();
+    format!("{base}/{year}/acs/{type_s}/geography.json")
+}
+
+/// fetches the geographies offered by an ACS dataset vintage.
+pub async fn get_geography(
+    client: &Client,
+    base_url: Option<&str>,
+    year: u64,
+    acs_type: AcsType,
+) -> Result<AcsGeography, String> {
+    let url = geography_url(base_url, year, acs_type);
+    client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("failure calling {url}: {e}"))?
+        .error_for_status()
+        .map_err(|e| format!("API request for {url} failed: {e}"))?
+        .json::<AcsGeography>()
+        .await
+        .map_err(|e| format!("failure parsing geography metadata from {url}: {e}"))
+}
+
+/// an in-memory cache of ACS dataset geographies, so that validating many queries
+/// against the same dataset fetches its `geography.json` once.
+#[derive(Debug, Clone, Default)]
+pub struct AcsGeographyCache {
+    geographies: HashMap<String, AcsGeography>,
+}
+
+impl AcsGeographyCache {
+    pub fn new() -> AcsGeographyCache {
+        AcsGeographyCache::default()
+    }
+
+    /// the geographies of an ACS dataset vintage, fetched on first use.
+    pub async fn get(
+        &mut self,
+        client: &Client,
+        base_url: Option<&str>,
+        year: u64,
+        acs_type: AcsType,
+    ) -> Result<&AcsGeography, String> {
+        let url = geography_url(base_url, year, acs_type);
+        if !self.geographies.contains_key(&url) {
+            let geography = get_geography(client, base_url, year, acs_type).await?;
+            self.geographies.insert(url.clone(), geography);
+        }
+        self.geographies
+            .get(&url)
+            .ok_or_else(|| format!("geography for {url} missing from cache"))
+    }
+
+    /// checks the geography of a query against the `geography.json` of its dataset,
+    /// catching combinations that are not offered for that year and dataset. see
+    /// [`AcsGeography::validate`].
+    pub async fn validate(
+        &mut self,
+        client: &Client,
+        query: &AcsApiQueryParams,
+    ) -> Result<(), String> {
+        let geography = self
+            .get(
+                client,
+                query.base_url.as_deref(),
+                query.year,
+                query.acs_type,
+            )
+            .await?;
+        geography.validate(&query.for_query).map_err(|e| {
+            format!(
+                "{} {} ACS: {e}",
+                query.year,
+                query.acs_type.to_directory_name()
+            )
+        })
+    }
+}
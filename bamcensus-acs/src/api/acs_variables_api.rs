@@ -0,0 +1,161 @@
+use crate::model::{AcsType, AcsVariable, AcsVariableReport};
+use futures::future;
+use reqwest::{Client, StatusCode};
+
+/// builds the URL to the metadata of a single variable in an ACS dataset. detailed
+/// tables are the default. variables from the data profile ("DP"), subject ("S") and
+/// comparison profile ("CP") tables are found in their own datasets.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_variables_api::variable_url;
+/// use bamcensus_acs::model::AcsType;
+///
+/// let url = variable_url(None, 2022, AcsType::FiveYear, "B19013_001E");
+/// assert_eq!(url, "https://api.census.gov/data/2022/acs/acs5/variables/B19013_001E.json");
+/// let url = variable_url(None, 2022, AcsType::FiveYear, "S1901_C01_012E");
+/// assert_eq!(url, "https://api.census.gov/data/2022/acs/acs5/subject/variables/S1901_C01_012E.json");
+/// ```
+pub fn variable_url(
+    base_url: Option<&str>,
+    year: u64,
+    acs_type: AcsType,
+    variable: &str,
+) -> String {
+    let dataset = dataset_url(base_url, year, acs_type, variable);
+    format!("{dataset}/variables/{variable}.json")
+}
+
+/// builds the URL to the metadata of every variable in an ACS table (group), such as
+/// B19001, which lists its estimates, margins of error and annotation variables.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::api::acs_variables_api::group_url;
+/// use bamcensus_acs::model::AcsType;
+///
+/// let url = group_url(None, 2022, AcsType::FiveYear, "B19001");
+/// assert_eq!(url, "https://api.census.gov/data/2022/acs/acs5/groups/B19001.json");
+/// let url = group_url(None, 2022, AcsType::OneYear, "DP02");
+/// assert_eq!(url, "https://api.census.gov/data/2022/acs/acs1/profile/groups/DP02.json");
+/// ```
+pub fn group_url(base_url: Option<&str>, year: u64, acs_type: AcsType, table: &str) -> String {
+    let dataset = dataset_url(base_url, year, acs_type, table);
+    format!("{dataset}/groups/{table}.json")
+}
+
+/// the URL of the ACS dataset publishing a table or variable code, where the data
+/// profile, comparison profile and subject tables have their own datasets.
+fn dataset_url(base_url: Option<&str>, year: u64, acs_type: AcsType, code: &str) -> String {
+    let base = base_url.unwrap_or(crate::model::constants::BASE_URL);
+    let type_s = acs_type.to_directory_name();
+    let table = if code.starts_with("DP") {
+        "/profile"
+    } else if code.starts_with("CP") {
+        "/cprofile"
+    } else if code.starts_with('S') {
+        "/subject"
+    } else {
+        ""
+    };
+    format!("{base}/{year}/acs/{type_s}{table}")
+}
+
+/// fetches the metadata for a single ACS variable in a given year. returns `None`
+/// if the variable does not exist in that year's dataset.
+pub async fn get_variable(
+    client: &Client,
+    base_url: Option<&str>,
+    year: u64,
+    acs_type: AcsType,
+    variable: &str,
+) -> Result<Option<AcsVariable>, String> {
+    let url = variable_url(base_url, year, acs_type, variable);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("failure calling {url}: {e}"))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response = response
+        .error_for_status()
+        .map_err(|e| format!("API request for {url} failed: {e}"))?;
+    let metadata = response
+        .json::<AcsVariable>()
+        .await
+        .map_err(|e| format!("failure parsing variable metadata from {url}: {e}"))?;
+    Ok(Some(metadata))
+}
+
+/// fetches the metadata of every variable in an ACS table (group), such as the
+/// estimates, margins of error and annotations of B19001, ordered by name. see
+/// [`crate::ops::acs_table::expand_table`] to select the estimates to request.
+pub async fn get_group(
+    client: &Client,
+    base_url: Option<&str>,
+    year: u64,
+    acs_type: AcsType,
+    table: &str,
+) -> Result<Vec<AcsVariable>, String> {
+    let url = group_url(base_url, year, acs_type, table);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("failure calling {url}: {e}"))?
+        .error_for_status()
+        .map_err(|e| format!("API request for {url} failed: {e}"))?;
+    let document = response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("failure parsing group metadata from {url}: {e}"))?;
+    let variables = document
+        .get("variables")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| format!("group metadata from {url} has no variables object"))?;
+    // the variable name is the key of each entry rather than one of its fields
+    variables
+        .iter()
+        .map(|(name, metadata)| {
+            let mut metadata = metadata.clone();
+            if let Some(fields) = metadata.as_object_mut() {
+                fields.insert(String::from("name"), serde_json::json!(name));
+            }
+            serde_json::from_value::<AcsVariable>(metadata)
+                .map_err(|e| format!("failure parsing metadata of {name} from {url}: {e}"))
+        })
+        .collect::<Result<Vec<_>, String>>()
+        .map(|mut variables| {
+            variables.sort_by(|a, b| a.name.cmp(&b.name));
+            variables
+        })
+}
+
+/// checks that a variable exists in each of the requested ACS dataset years, so
+/// that a trend built across vintages does not silently mix missing or renamed
+/// variables. see [`AcsVariableReport`] for the summary of each year.
+pub async fn variable_report(
+    client: &Client,
+    base_url: Option<&str>,
+    acs_type: AcsType,
+    variable: &str,
+    years: &[u64],
+) -> Result<AcsVariableReport, String> {
+    let requests = years.iter().map(|year| async move {
+        let metadata = get_variable(client, base_url, *year, acs_type, variable).await?;
+        Ok::<_, String>((*year, metadata))
+    });
+    let years = future::join_all(requests)
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(AcsVariableReport {
+        variable: String::from(variable),
+        acs_type,
+        years,
+    })
+}
@@ -1 +1,4 @@
 pub mod acs_api;
+pub mod acs_flows_api;
+pub mod acs_geography_api;
+pub mod acs_variables_api;
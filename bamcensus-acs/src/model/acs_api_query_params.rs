@@ -1,4 +1,5 @@
-use crate::model::{AcsGeoidQuery, AcsType};
+use crate::model::{AcsGeoidQuery, AcsName, AcsType};
+use bamcensus_core::model::identifier::{Geoid, GeoidType};
 use itertools::Itertools;
 
 /// parameters for running an ACS call.
@@ -10,15 +11,29 @@ pub struct AcsApiQueryParams {
     pub year: u64,
     /// one or five-year ACS cadence
     pub acs_type: AcsType,
-    /// list of fields to retrieve
+    /// list of fields to retrieve. may include variable groups such as `group(B01001)`,
+    /// which expand to every variable in that table.
     pub get_query: Vec<String>,
     /// geographic scope of request
     pub for_query: AcsGeoidQuery,
     /// optional API token in case of rate limiting issues
     pub api_token: Option<String>,
+    /// if true, the NAME variable is requested ahead of the get query when it is not
+    /// already in it, so that every row is labeled. see [`AcsApiQueryParams::get_columns`].
+    pub include_name: bool,
+    /// additional (name, value) query parameters appended to the URL, such as the
+    /// predicate `("B01001_001E", "1000:")`. see [`AcsApiQueryParams::with_extra_params`].
+    pub extra_params: Vec<(String, String)>,
 }
 
+/// query parameters written by [`AcsApiQueryParams::build_url`] itself, which may not
+/// be set as extra parameters.
+pub const MANAGED_QUERY_PARAMS: [&str; 5] = ["get", "for", "in", "ucgid", "key"];
+
 impl AcsApiQueryParams {
+    /// creates a set of ACS query parameters. the `api_token` is used as given, and
+    /// applications that accept a token from the environment resolve it first with
+    /// [`api_token_from_env`].
     pub fn new(
         base_url: Option<String>,
         year: u64,
@@ -34,7 +49,165 @@ impl AcsApiQueryParams {
             get_query,
             for_query,
             api_token,
+            include_name: false,
+            extra_params: vec![],
+        }
+    }
+
+    /// creates one set of query parameters per state, for the 50 states and the
+    /// District of Columbia (see [`Geoid::all_states`]), querying every geography of
+    /// the `wildcard_level` within the state. this is the common way to pull a
+    /// sub-state level, such as tracts or block groups, for the whole country, which
+    /// is too broad for a single request, and the result is ready for
+    /// [`crate::api::acs_api::batch_run`]. block groups are queried with a county
+    /// wildcard within each state. the `api_token` is used as in
+    /// [`AcsApiQueryParams::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::GeoidType;
+    /// use bamcensus_acs::model::{AcsType, AcsApiQueryParams};
+    ///
+    /// let get_query = vec![String::from("B01001_001E")];
+    /// let token = Some(String::from("k"));
+    /// let params = AcsApiQueryParams::per_state(2022, AcsType::FiveYear, get_query, GeoidType::BlockGroup, token).unwrap();
+    /// assert_eq!(params.len(), 51);
+    /// assert_eq!(
+    ///     params[5].build_url().unwrap(),
+    ///     "https://api.census.gov/data/2022/acs/acs5?get=B01001_001E&for=block%20group:*&in=state:08&in=county:*&in=tract:*&key=k"
+    /// );
+    ///
+    /// // states and the geographies above them are not within a state
+    /// let get_query = vec![String::from("B01001_001E")];
+    /// assert!(AcsApiQueryParams::per_state(2022, AcsType::FiveYear, get_query, GeoidType::State, None).is_err());
+    /// ```
+    pub fn per_state(
+        year: u64,
+        acs_type: AcsType,
+        get_query: Vec<String>,
+        wildcard_level: GeoidType,
+        api_token: Option<String>,
+    ) -> Result<Vec<AcsApiQueryParams>, String> {
+        Geoid::all_states()
+            .into_iter()
+            .map(|state| {
+                let for_query = match (&state, wildcard_level) {
+                    (Geoid::State(s), GeoidType::BlockGroup) => {
+                        AcsGeoidQuery::BlockGroup(*s, None, None, None)
+                    }
+                    _ => AcsGeoidQuery::within(state, wildcard_level)?,
+                };
+                Ok(AcsApiQueryParams::new(
+                    None,
+                    year,
+                    acs_type,
+                    get_query.clone(),
+                    for_query,
+                    api_token.clone(),
+                ))
+            })
+            .collect()
+    }
+
+    /// sets whether the NAME variable is requested along with the get query.
+    pub fn with_include_name(mut self, include_name: bool) -> AcsApiQueryParams {
+        self.include_name = include_name;
+        self
+    }
+
+    /// sets additional query parameters, which are form-urlencoded and appended to the
+    /// request URL after the geography. these express API features the other
+    /// parameters do not, such as predicates that filter geographies by value range on
+    /// the server, where `B01001_001E=1000:` keeps geographies with a population of at
+    /// least 1000 and `B01001_001E=0:1000` those with at most 1000. names may not be
+    /// one of the [`MANAGED_QUERY_PARAMS`], see [`AcsApiQueryParams::validate_extra_params`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::{AcsType, AcsGeoidQuery, AcsApiQueryParams};
+    ///
+    /// let for_query = AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::County)).unwrap();
+    /// let get_query = vec![String::from("NAME"), String::from("B01001_001E")];
+    /// let params = AcsApiQueryParams::new(None, 2022, AcsType::FiveYear, get_query, for_query, Some(String::from("k")))
+    ///     .with_extra_params(vec![
+    ///         (String::from("B01001_001E"), String::from("100000:")),
+    ///         (String::from("NAME"), String::from("Denver County, Colorado")),
+    ///     ]);
+    /// assert_eq!(
+    ///     params.build_url().unwrap(),
+    ///     "https://api.census.gov/data/2022/acs/acs5?get=NAME,B01001_001E&for=county:*&in=state:08&B01001_001E=100000%3A&NAME=Denver+County%2C+Colorado&key=k"
+    /// );
+    ///
+    /// // the geography is set by the for query, not an extra parameter
+    /// let params = params.with_extra_params(vec![(String::from("for"), String::from("state:*"))]);
+    /// assert!(params.build_url().is_err());
+    /// let params = params.with_extra_params(vec![(String::from("ucgid"), String::from("0400000US08"))]);
+    /// assert!(params.build_url().is_err());
+    /// ```
+    pub fn with_extra_params(mut self, extra_params: Vec<(String, String)>) -> AcsApiQueryParams {
+        self.extra_params = extra_params;
+        self
+    }
+
+    /// checks that each extra parameter has a name, and that no name is one of the
+    /// [`MANAGED_QUERY_PARAMS`], ignoring case, which would conflict with the parameters
+    /// built from the rest of the query.
+    pub fn validate_extra_params(&self) -> Result<(), String> {
+        for (name, value) in self.extra_params.iter() {
+            let trimmed = name.trim();
+            if trimmed.is_empty() {
+                return Err(format!(
+                    "extra ACS query parameter with value '{value}' has no name"
+                ));
+            }
+            if MANAGED_QUERY_PARAMS
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(trimmed))
+            {
+                return Err(format!(
+                    "extra ACS query parameter '{trimmed}' is set by the query itself, expected a name other than {}",
+                    MANAGED_QUERY_PARAMS.iter().join(", ")
+                ));
+            }
         }
+        Ok(())
+    }
+
+    /// the entries of the get parameter sent to the API: the get query, preceded by
+    /// NAME when `include_name` is set and NAME was not already requested.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::GeoidType;
+    /// use bamcensus_acs::model::{AcsType, AcsGeoidQuery, AcsApiQueryParams};
+    ///
+    /// let for_query = AcsGeoidQuery::new(None, Some(GeoidType::State)).unwrap();
+    /// let get_query = vec![String::from("B01001_001E")];
+    /// let params = AcsApiQueryParams::new(None, 2022, AcsType::FiveYear, get_query, for_query, None)
+    ///     .with_include_name(true);
+    /// assert_eq!(params.get_columns(), vec!["NAME", "B01001_001E"]);
+    /// assert_eq!(params.column_names(), vec!["NAME", "B01001_001E", "state"]);
+    /// assert!(params.build_url().unwrap().contains("?get=NAME,B01001_001E&"));
+    ///
+    /// // NAME is not requested twice
+    /// let params = AcsApiQueryParams {
+    ///     get_query: vec![String::from("B01001_001E"), String::from("NAME")],
+    ///     ..params
+    /// };
+    /// assert_eq!(params.get_columns(), vec!["B01001_001E", "NAME"]);
+    /// ```
+    pub fn get_columns(&self) -> Vec<String> {
+        let name = String::from(AcsName::NAME_VARIABLE);
+        let prepend = self.include_name && !self.get_query.iter().any(|q| q.trim() == name);
+        prepend
+            .then_some(name)
+            .into_iter()
+            .chain(self.get_query.iter().cloned())
+            .collect_vec()
     }
 
     pub fn acs_dataset_url(&self) -> String {
@@ -91,27 +264,59 @@ impl AcsApiQueryParams {
     /// assert_eq!(api_url, String::from("https://api.census.gov/data/2022/acs/acs5?get=NAME,B01001_001E&for=county:*&in=state:08"))
     /// ```
     pub fn build_url(&self) -> Result<String, String> {
+        self.acs_type
+            .validate(self.year, self.for_query.get_geoid_type())?;
+        self.validate_extra_params()?;
         let dataset_url = self.acs_dataset_url();
-        let get_query = self.get_query.iter().join(",");
+        let get_query = self.get_columns().iter().join(",");
         let for_query = self.for_query.to_query_key();
+        let extra_query = self
+            .extra_params
+            .iter()
+            .map(|(name, value)| {
+                let pair = url::form_urlencoded::Serializer::new(String::new())
+                    .append_pair(name.trim(), value)
+                    .finish();
+                format!("&{pair}")
+            })
+            .join("");
         let token_query = match &self.api_token {
             Some(k) => format!("&key={k}"),
             None => String::from(""),
         };
-        let query = format!("{dataset_url}?get={get_query}{for_query}{token_query}",);
+        let query = format!("{dataset_url}?get={get_query}{for_query}{extra_query}{token_query}",);
         Ok(query)
     }
 
-    /// in order to deconstruct an API response, we need the list of
-    /// column names in the order that they would appear in the array-
-    /// shaped ACS response object.
-    pub fn column_names(&self) -> Vec<String> {
-        let mut cols = self
-            .get_query
+    /// true if any entry of the get query requests a whole variable group, in which
+    /// case the response columns are only known once the response header is read.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::GeoidType;
+    /// use bamcensus_acs::model::{AcsType, AcsGeoidQuery, AcsApiQueryParams};
+    ///
+    /// let for_query = AcsGeoidQuery::new(None, Some(GeoidType::State)).unwrap();
+    /// let get_query = vec![String::from("NAME"), String::from("group(B01001)")];
+    /// let params = AcsApiQueryParams::new(None, 2022, AcsType::FiveYear, get_query, for_query, None);
+    /// assert!(params.has_group_query());
+    /// let url = params.build_url().unwrap();
+    /// assert_eq!(url, String::from("https://api.census.gov/data/2022/acs/acs5?get=NAME,group(B01001)&for=state:*"));
+    /// ```
+    pub fn has_group_query(&self) -> bool {
+        self.get_query
             .iter()
-            // .map(|s| String::from(s))
-            .cloned()
-            .collect_vec();
+            .any(|q| q.trim().starts_with("group(") && q.trim().ends_with(')'))
+    }
+
+    /// the column names expected in the array-shaped ACS response object, in
+    /// request order, including NAME when `include_name` is set. the API may return
+    /// them in another order, so responses are read by name, see
+    /// [`crate::api::acs_api::response_columns`]. for group queries, the get columns
+    /// are not known ahead of time, so this lists the requested get entries as-is.
+    pub fn column_names(&self) -> Vec<String> {
+        let mut cols = self.get_columns();
         cols.extend(self.for_query.response_column_names());
         cols
     }
@@ -126,3 +331,38 @@ impl AcsApiQueryParams {
         )
     }
 }
+
+/// parses an extra query parameter from `NAME=VALUE` text, splitting at the first
+/// `=`, as given on the command line.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_acs::model::parse_extra_param;
+///
+/// assert_eq!(
+///     parse_extra_param("B01001_001E=1000:").unwrap(),
+///     (String::from("B01001_001E"), String::from("1000:"))
+/// );
+/// assert!(parse_extra_param("B01001_001E").is_err());
+/// assert!(parse_extra_param("=1000:").is_err());
+/// ```
+pub fn parse_extra_param(text: &str) -> Result<(String, String), String> {
+    match text.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((String::from(name.trim()), String::from(value)))
+        }
+        _ => Err(format!(
+            "expected an ACS query parameter as NAME=VALUE, found '{text}'"
+        )),
+    }
+}
+
+/// reads the ACS API token from the [`super::constants::API_KEY_ENV_VAR`] environment
+/// variable (`CENSUS_API_KEY`), ignoring empty values. the library does not read the
+/// environment itself, so that a token is only sent when the caller provides one.
+pub fn api_token_from_env() -> Option<String> {
+    std::env::var(super::constants::API_KEY_ENV_VAR)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+}
@@ -0,0 +1,116 @@
+use crate::model::AcsGeoidQuery;
+use bamcensus_core::model::identifier::{Geoid, GeoidType};
+use serde::{Deserialize, Serialize};
+
+/// first year of the ACS migration flows API, which publishes the 2006-2010 5-year
+/// flows as 2010.
+pub const FLOWS_FIRST_YEAR: u64 = 2010;
+
+/// variables requested from the migration flows API, in response order. GEOID1 is the
+/// queried geography and GEOID2 the other end of the flow, and MOVEDIN counts the
+/// residents of GEOID1 who lived in GEOID2 one year earlier.
+pub const FLOWS_GET_QUERY: [&str; 5] = ["GEOID1", "GEOID2", "FULL2_NAME", "MOVEDIN", "MOVEDIN_M"];
+
+/// one row of the ACS migration flows: the number of people living in the `current`
+/// geography who lived in the `previous` geography one year earlier.
+///
+/// flows are published as movers into each queried geography, so the flows out of a
+/// geography are the rows where it is the `previous` geography of another query.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AcsFlow {
+    /// geography of current residence
+    pub current: Geoid,
+    /// geography of residence one year earlier, or None when it is outside of the
+    /// U.S., such as a world region, which is named in `previous_name`
+    pub previous: Option<Geoid>,
+    /// full name of the geography of residence one year earlier
+    pub previous_name: String,
+    /// estimated number of movers, or None if the estimate is suppressed
+    pub estimate: Option<u64>,
+    /// margin of error of the estimate, or None if it is suppressed
+    pub moe: Option<u64>,
+}
+
+/// parameters for a query to the ACS migration flows API, which is published at
+/// `/<year>/acs/flows` from 5-year estimates for counties and county subdivisions.
+#[derive(Debug, Clone)]
+pub struct AcsFlowsQueryParams {
+    /// optional override URL for the query. otherwise defaults to [`super::constants::BASE_URL`]
+    pub base_url: Option<String>,
+    /// final year of the 5-year estimates, such as 2020 for 2016-2020
+    pub year: u64,
+    /// geographies of current residence to retrieve flows for
+    pub for_query: AcsGeoidQuery,
+    /// optional API token in case of rate limiting issues
+    pub api_token: Option<String>,
+}
+
+impl AcsFlowsQueryParams {
+    /// creates a migration flows query. as with [`super::AcsApiQueryParams::new`], the
+    /// `api_token` is used as given.
+    pub fn new(
+        base_url: Option<String>,
+        year: u64,
+        for_query: AcsGeoidQuery,
+        api_token: Option<String>,
+    ) -> AcsFlowsQueryParams {
+        AcsFlowsQueryParams {
+            base_url,
+            year,
+            for_query,
+            api_token,
+        }
+    }
+
+    /// builds the migration flows query URL.
+    ///
+    /// # Examples
+    ///
+    /// county-to-county flows into every county in Colorado.
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::{AcsFlowsQueryParams, AcsGeoidQuery};
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    ///
+    /// let for_query = AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::County)).unwrap();
+    /// let params = AcsFlowsQueryParams::new(None, 2020, for_query, Some(String::from("abc")));
+    /// assert_eq!(
+    ///     params.build_url().unwrap(),
+    ///     "https://api.census.gov/data/2020/acs/flows?get=GEOID1,GEOID2,FULL2_NAME,MOVEDIN,MOVEDIN_M&for=county:*&in=state:08&key=abc"
+    /// );
+    ///
+    /// // flows are not published for tracts, or before 2010
+    /// let tracts = AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::CensusTract)).unwrap();
+    /// assert!(AcsFlowsQueryParams::new(None, 2020, tracts, None).build_url().is_err());
+    /// let for_query = AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::County)).unwrap();
+    /// assert!(AcsFlowsQueryParams::new(None, 2009, for_query, None).build_url().is_err());
+    /// ```
+    pub fn build_url(&self) -> Result<String, String> {
+        match self.for_query.get_geoid_type() {
+            GeoidType::County | GeoidType::CountySubdivision => Ok(()),
+            other => Err(format!(
+                "ACS migration flows are published for counties and county subdivisions, found {other}"
+            )),
+        }?;
+        if self.year < FLOWS_FIRST_YEAR {
+            return Err(format!(
+                "ACS migration flows are published from {FLOWS_FIRST_YEAR}, found year {}",
+                self.year
+            ));
+        }
+        let base = self
+            .base_url
+            .as_deref()
+            .unwrap_or(super::constants::BASE_URL);
+        let get_query = FLOWS_GET_QUERY.join(",");
+        let for_query = self.for_query.to_query_key();
+        let token_query = match &self.api_token {
+            Some(k) => format!("&key={k}"),
+            None => String::from(""),
+        };
+        Ok(format!(
+            "{base}/{}/acs/flows?get={get_query}{for_query}{token_query}",
+            self.year
+        ))
+    }
+}
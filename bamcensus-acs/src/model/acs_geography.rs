@@ -0,0 +1,162 @@
+use super::AcsGeoidQuery;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// the geographies offered by an ACS dataset vintage, as published in the
+/// `geography.json` document of each dataset. unlike the hierarchy rules encoded in
+/// [`AcsGeoidQuery::new`], this describes exactly which `for` and `in` combinations
+/// the API accepts for one year and dataset.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AcsGeography {
+    pub fips: Vec<AcsGeographyLevel>,
+}
+
+/// a geography that may be requested in the `for` clause of an ACS query. a name
+/// may appear more than once, with different `in` requirements.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AcsGeographyLevel {
+    /// geography name as used in queries, such as "block group"
+    pub name: String,
+    /// census summary level code, such as "150"
+    #[serde(default)]
+    pub geo_level_display: Option<String>,
+    /// geographies that must be provided in `in` clauses
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// required geographies that may be given as a "*" wildcard
+    #[serde(default)]
+    pub wildcard: Vec<String>,
+    /// a required geography that may be omitted entirely when the `for` clause
+    /// is a wildcard
+    #[serde(default, rename = "optionalWithWCFor")]
+    pub optional_with_wc_for: Option<String>,
+}
+
+impl AcsGeography {
+    /// names of the geographies offered by the dataset, in document order.
+    pub fn names(&self) -> Vec<&str> {
+        self.fips.iter().map(|l| l.name.as_str()).unique().collect()
+    }
+
+    /// checks a query against the geographies of the dataset. the `for` geography must
+    /// be offered, every required `in` geography must be present (unless it may be
+    /// omitted for a wildcard `for`), wildcards may only be used where allowed, and no
+    /// other `in` geographies may be given.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::{AcsGeography, AcsGeoidQuery};
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    ///
+    /// let geography: AcsGeography = serde_json::from_str(r#"{"fips": [
+    ///     {"name": "state", "geoLevelDisplay": "040"},
+    ///     {"name": "county", "geoLevelDisplay": "050", "requires": ["state"],
+    ///      "wildcard": ["state"], "optionalWithWCFor": "state"},
+    ///     {"name": "tract", "geoLevelDisplay": "140", "requires": ["state", "county"],
+    ///      "wildcard": ["county"], "optionalWithWCFor": "county"}
+    /// ]}"#).unwrap();
+    ///
+    /// let state = Geoid::State(fips::State(8));
+    /// let tracts = AcsGeoidQuery::new(Some(state.clone()), Some(GeoidType::CensusTract)).unwrap();
+    /// assert!(geography.validate(&tracts).is_ok());
+    ///
+    /// // this dataset does not offer block groups
+    /// let county = Geoid::County(fips::State(8), fips::County(31));
+    /// let block_groups = AcsGeoidQuery::new(Some(county), Some(GeoidType::BlockGroup)).unwrap();
+    /// let error = geography.validate(&block_groups).unwrap_err();
+    /// assert_eq!(error, "geography 'block group' is not offered by this dataset, found: state, county, tract");
+    ///
+    /// // a county must be requested within a state unless all counties are requested
+    /// let county_only = AcsGeoidQuery::County(None, Some(fips::County(31)));
+    /// assert!(geography.validate(&county_only).is_err());
+    /// let all_counties = AcsGeoidQuery::County(None, None);
+    /// assert!(geography.validate(&all_counties).is_ok());
+    /// ```
+    pub fn validate(&self, query: &AcsGeoidQuery) -> Result<(), String> {
+        // ucgid values are resolved by the API without the for/in geography hierarchy
+        if let AcsGeoidQuery::Ucgid { .. } = query {
+            return Ok(());
+        }
+        let key = query.to_query_key();
+        let (for_clause, in_clauses) = parse_query_key(&key)?;
+        let (for_name, for_value) = for_clause;
+        let levels = self
+            .fips
+            .iter()
+            .filter(|l| l.name == for_name)
+            .collect_vec();
+        if levels.is_empty() {
+            return Err(format!(
+                "geography '{for_name}' is not offered by this dataset, found: {}",
+                self.names().join(", ")
+            ));
+        }
+        // a query is valid if any of the levels with this name accepts it
+        let results = levels
+            .iter()
+            .map(|level| level.accepts(&for_value, &in_clauses))
+            .collect_vec();
+        if results.iter().any(Result::is_ok) {
+            return Ok(());
+        }
+        let reasons = results
+            .into_iter()
+            .filter_map(Result::err)
+            .unique()
+            .join("; ");
+        Err(format!("invalid '{for_name}' query {key}: {reasons}"))
+    }
+}
+
+impl AcsGeographyLevel {
+    /// checks the `in` clauses of a query for this geography.
+    fn accepts(&self, for_value: &str, in_clauses: &[(String, String)]) -> Result<(), String> {
+        for (name, value) in in_clauses.iter() {
+            if !self.requires.contains(name) {
+                return Err(format!(
+                    "'{name}' is not a valid 'in' geography, expected: [{}]",
+                    self.requires.join(", ")
+                ));
+            }
+            if value == "*" && !self.wildcard.contains(name) {
+                return Err(format!("'{name}' may not be a wildcard"));
+            }
+        }
+        for required in self.requires.iter() {
+            let present = in_clauses.iter().any(|(name, _)| name == required);
+            let omittable =
+                for_value == "*" && self.optional_with_wc_for.as_ref() == Some(required);
+            if !present && !omittable {
+                return Err(format!("'in' geography '{required}' is required"));
+            }
+        }
+        Ok(())
+    }
+}
+
+type QueryClause = (String, String);
+
+/// splits a query key such as "&for=county:*&in=state:08" into its `for` clause and
+/// `in` clauses, each as a (geography name, value) pair.
+fn parse_query_key(key: &str) -> Result<(QueryClause, Vec<QueryClause>), String> {
+    let mut for_clause = None;
+    let mut in_clauses = vec![];
+    for part in key.split('&').filter(|p| !p.is_empty()) {
+        let (kind, clause) = part
+            .split_once('=')
+            .ok_or_else(|| format!("invalid query clause '{part}' in {key}"))?;
+        let (name, value) = clause
+            .split_once(':')
+            .ok_or_else(|| format!("invalid query clause '{part}' in {key}"))?;
+        let parsed = (name.replace("%20", " "), String::from(value));
+        match kind {
+            "for" => for_clause = Some(parsed),
+            "in" => in_clauses.push(parsed),
+            other => return Err(format!("unexpected query clause '{other}' in {key}")),
+        }
+    }
+    let for_clause = for_clause.ok_or_else(|| format!("query {key} has no 'for' clause"))?;
+    Ok((for_clause, in_clauses))
+}
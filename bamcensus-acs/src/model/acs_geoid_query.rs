@@ -1,4 +1,5 @@
 use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+use itertools::Itertools;
 use std::rc::Rc;
 
 use super::DeserializeGeoidFn;
@@ -10,8 +11,14 @@ use super::DeserializeGeoidFn;
 /// (wildcard) components to construct a query.
 #[derive(Debug, Clone)]
 pub enum AcsGeoidQuery {
+    Nation,
+    Region(Option<fips::Region>),
+    Division(Option<fips::Division>),
     State(Option<fips::State>),
     County(Option<fips::State>, Option<fips::County>),
+    /// all counties in each of a set of states, fetched with a single multi-state
+    /// `in=state:` clause. see [`AcsGeoidQuery::counties_in_states`].
+    CountiesInStates(Vec<fips::State>),
     CountySubdivision(
         fips::State,
         Option<fips::County>,
@@ -25,6 +32,12 @@ pub enum AcsGeoidQuery {
         Option<fips::CensusTract>,
         Option<fips::BlockGroup>,
     ),
+    /// geographies selected by census universal geographic identifiers, sent as a
+    /// `ucgid=` clause in place of `for`/`in`. see [`AcsGeoidQuery::ucgid`].
+    Ucgid {
+        geoid_type: GeoidType,
+        ucgid: String,
+    },
 }
 
 impl AcsGeoidQuery {
@@ -69,6 +82,34 @@ impl AcsGeoidQuery {
     /// assert_eq!(key, String::from("&for=tract:000001&in=state:08"));
     /// ```
     ///
+    /// the nation, census regions and census divisions are queried on their own, without
+    /// any `in` clause. every census region can be fetched in a single request:
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    ///
+    /// let query = AcsGeoidQuery::new(None, Some(GeoidType::Region)).unwrap();
+    /// assert_eq!(query.to_query_key(), String::from("&for=region:*"));
+    ///
+    /// let query = AcsGeoidQuery::new(Some(Geoid::Division(fips::Division(8))), None).unwrap();
+    /// assert_eq!(query.to_query_key(), String::from("&for=division:8"));
+    ///
+    /// let query = AcsGeoidQuery::new(Some(Geoid::Nation), None).unwrap();
+    /// assert_eq!(query.to_query_key(), String::from("&for=us:1"));
+    ///
+    /// // a wildcard below the nation is the same as a national wildcard query
+    /// let query = AcsGeoidQuery::new(Some(Geoid::Nation), Some(GeoidType::County)).unwrap();
+    /// assert_eq!(query.to_query_key(), String::from("&for=county:*"));
+    ///
+    /// // ACS does not nest other geographies within regions or divisions
+    /// let west = Geoid::Region(fips::Region(4));
+    /// assert!(AcsGeoidQuery::new(Some(west), Some(GeoidType::State)).is_err());
+    ///
+    /// // voting districts are redistricting geographies, which ACS does not publish
+    /// let denver = Geoid::County(fips::State(8), fips::County(31));
+    /// assert!(AcsGeoidQuery::new(Some(denver), Some(GeoidType::VotingDistrict)).is_err());
+    /// ```
+    ///
     /// # Returns
     ///
     /// URL query string for calls to the US Census ACS API "for" section, which set the
@@ -84,15 +125,39 @@ impl AcsGeoidQuery {
                 "cannot create query without at least a geoid or wildcard",
             )),
             (None, Some(GT::CountySubdivision)) => Err(String::from(
-                "cannot create county subdivision query without State Geoid",
+                "cannot create county subdivision query without State Geoid, a national query is too broad for one ACS request. split it by state, see AcsGeoidQuery::split_by_state",
             )),
             (None, Some(GT::CensusTract)) => Err(String::from(
-                "cannot create census tract query without State Geoid",
+                "cannot create census tract query without State Geoid, a national query is too broad for one ACS request. split it by state, see AcsGeoidQuery::split_by_state",
             )),
             (None, Some(GT::BlockGroup)) => Err(String::from(
-                "cannot create block group query without State + County Geoids",
+                "cannot create block group query without State + County Geoids, a national query is too broad for one ACS request. split it by state, see AcsGeoidQuery::split_by_state",
             )),
             (_, Some(GT::Block)) => Err(String::from("acs does not support block-level queries")),
+            (_, Some(GT::VotingDistrict)) | (Some(G::VotingDistrict(_, _, _)), _) => {
+                Err(String::from(
+                    "acs does not publish voting districts, which join to the decennial PL 94-171 data",
+                ))
+            }
+
+            // ~~ nation, regions and divisions ~~
+            // - these are never combined with `in` clauses, so a wildcard below the
+            //   nation is the same as a national wildcard query
+            (None | Some(G::Nation), Some(GT::Nation)) => Ok(AcsGeoidQuery::Nation),
+            (None | Some(G::Nation), Some(GT::Region)) => Ok(AcsGeoidQuery::Region(None)),
+            (None | Some(G::Nation), Some(GT::Division)) => Ok(AcsGeoidQuery::Division(None)),
+            (Some(G::Nation), None) => Ok(AcsGeoidQuery::Nation),
+            (Some(G::Nation), wildcard) => AcsGeoidQuery::new(None, wildcard),
+            (Some(G::Region(r)), None) => Ok(AcsGeoidQuery::Region(Some(r))),
+            (Some(G::Division(d)), None) => Ok(AcsGeoidQuery::Division(Some(d))),
+            (Some(g @ (G::Region(_) | G::Division(_))), Some(wildcard)) => Err(format!(
+                "cannot append a '{wildcard}' wildcard to a {} Geoid, acs does not nest geographies within regions or divisions",
+                g.geoid_type()
+            )),
+            (Some(g), Some(wildcard @ (GT::Nation | GT::Region | GT::Division))) => Err(format!(
+                "cannot append a '{wildcard}' wildcard to a {} Geoid",
+                g.geoid_type()
+            )),
             (Some(G::Block(_, _, _, _)), _) => {
                 Err(String::from("acs does not support block-level queries"))
             }
@@ -230,11 +295,336 @@ impl AcsGeoidQuery {
         }
     }
 
+    /// creates a query for all geoids of the target type that fall within the parent geoid,
+    /// such as all block groups in a county. unlike [`AcsGeoidQuery::new`], the parent must
+    /// be a hierarchical ancestor of the target type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    ///
+    /// let county = Geoid::County(fips::State(8), fips::County(59));
+    /// let query = AcsGeoidQuery::within(county, GeoidType::BlockGroup).unwrap();
+    /// let key = query.to_query_key();
+    /// assert_eq!(key, String::from("&for=block%20group:*&in=state:08&in=county:059&in=tract:*"));
+    /// ```
+    ///
+    /// targets that are not below the parent in the geoid hierarchy are rejected.
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    ///
+    /// let tract = Geoid::CensusTract(fips::State(8), fips::County(59), fips::CensusTract(9838));
+    /// assert!(AcsGeoidQuery::within(tract, GeoidType::County).is_err());
+    /// ```
+    pub fn within(parent: Geoid, target: GeoidType) -> Result<AcsGeoidQuery, String> {
+        use GeoidType as GT;
+        let parent_type = parent.geoid_type();
+        let is_ancestor = matches!(
+            (parent_type, target),
+            (
+                GT::State,
+                GT::County | GT::CountySubdivision | GT::Place | GT::CensusTract
+            ) | (
+                GT::County,
+                GT::CountySubdivision | GT::CensusTract | GT::BlockGroup
+            ) | (GT::CensusTract, GT::BlockGroup)
+        );
+        if !is_ancestor {
+            return Err(format!(
+                "{parent_type} geoid {parent} is not a valid parent for a {target} query"
+            ));
+        }
+        AcsGeoidQuery::new(Some(parent), Some(target))
+    }
+
+    /// creates the queries for a geoid and wildcard as with [`AcsGeoidQuery::new`], except
+    /// that national county subdivision, census tract and block group queries, which are
+    /// too broad for a single ACS API request, are split into one query per state. block
+    /// groups are queried with a county wildcard within each state. all other
+    /// combinations produce a single query.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    ///
+    /// // a national block group query is rejected as a single query
+    /// let error = AcsGeoidQuery::new(None, Some(GeoidType::BlockGroup)).unwrap_err();
+    /// assert!(error.contains("split it by state"));
+    ///
+    /// // and split into a query per state instead
+    /// let states = vec![fips::State(8), fips::State(49)];
+    /// let keys = AcsGeoidQuery::split_by_state(None, Some(GeoidType::BlockGroup), &states)
+    ///     .unwrap()
+    ///     .iter()
+    ///     .map(|q| q.to_query_key())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(keys, vec![
+    ///     "&for=block%20group:*&in=state:08&in=county:*&in=tract:*",
+    ///     "&for=block%20group:*&in=state:49&in=county:*&in=tract:*",
+    /// ]);
+    ///
+    /// // queries that fit in one request are not split
+    /// let county = Geoid::County(fips::State(8), fips::County(59));
+    /// let queries = AcsGeoidQuery::split_by_state(Some(county), Some(GeoidType::CensusTract), &states).unwrap();
+    /// assert_eq!(queries.len(), 1);
+    /// assert_eq!(queries[0].to_query_key(), "&for=tract:*&in=state:08&in=county:059");
+    /// ```
+    pub fn split_by_state(
+        geoid: Option<Geoid>,
+        wildcard: Option<GeoidType>,
+        states: &[fips::State],
+    ) -> Result<Vec<AcsGeoidQuery>, String> {
+        let per_state: fn(fips::State) -> AcsGeoidQuery = match (&geoid, wildcard) {
+            (None, Some(GeoidType::CountySubdivision)) => {
+                |s| AcsGeoidQuery::CountySubdivision(s, None, None)
+            }
+            (None, Some(GeoidType::CensusTract)) => |s| AcsGeoidQuery::CensusTract(s, None, None),
+            (None, Some(GeoidType::BlockGroup)) => {
+                |s| AcsGeoidQuery::BlockGroup(s, None, None, None)
+            }
+            _ => return Ok(vec![AcsGeoidQuery::new(geoid, wildcard)?]),
+        };
+        if states.is_empty() {
+            return Err(String::from(
+                "cannot split a national query by state without at least one state",
+            ));
+        }
+        let queries = states.iter().unique().map(|s| per_state(*s)).collect_vec();
+        log::info!(
+            "national {} query split into {} state queries",
+            wildcard.map(|w| w.to_string()).unwrap_or_default(),
+            queries.len()
+        );
+        Ok(queries)
+    }
+
+    /// creates a query from a ucgid value, which is one or more comma-delimited ucgids
+    /// such as `0500000US08031`, or a pseudo-geography such as
+    /// `pseudo(0400000US08$0500000)` for every county in Colorado. the response geoid
+    /// type is read from the summary level that begins each ucgid, and every ucgid
+    /// must share one summary level. a ucgid query replaces the `for`/`in` clauses, so
+    /// a value that also sets them is rejected. the value is percent-encoded in the
+    /// request URL.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+    ///
+    /// let query = AcsGeoidQuery::ucgid("0500000US08031,0500000US08059").unwrap();
+    /// assert_eq!(query.get_geoid_type(), GeoidType::County);
+    /// let params = AcsApiQueryParams::new(None, 2022, AcsType::FiveYear, vec![String::from("B01001_001E")], query, None);
+    /// assert_eq!(
+    ///     params.build_url().unwrap(),
+    ///     "https://api.census.gov/data/2022/acs/acs5?get=B01001_001E&ucgid=0500000US08031%2C0500000US08059"
+    /// );
+    ///
+    /// // response rows end with a single ucgid column
+    /// let deserialize_fn = params.for_query.build_deserialize_geoid_fn();
+    /// let geoid = deserialize_fn(vec![serde_json::json!["0500000US08059"]]).unwrap();
+    /// assert_eq!(geoid, Geoid::County(fips::State(8), fips::County(59)));
+    ///
+    /// // pseudo-geographies take the summary level of the geographies they contain
+    /// let counties = AcsGeoidQuery::ucgid("pseudo(0400000US08$0500000)").unwrap();
+    /// assert_eq!(counties.get_geoid_type(), GeoidType::County);
+    /// assert_eq!(counties.to_query_key(), "&ucgid=pseudo%280400000US08%240500000%29");
+    ///
+    /// // ucgid cannot be combined with for/in clauses
+    /// assert!(AcsGeoidQuery::ucgid("0400000US08&for=county:*").is_err());
+    /// assert!(AcsGeoidQuery::ucgid("0400000US08,0500000US08031").is_err());
+    /// ```
+    pub fn ucgid(value: &str) -> Result<AcsGeoidQuery, String> {
+        let ucgid = value.trim();
+        if ucgid.is_empty() {
+            return Err(String::from("ucgid query cannot be empty"));
+        }
+        if ucgid.contains('&') || ucgid.contains("for=") || ucgid.contains("in=") {
+            return Err(format!(
+                "ucgid replaces the for/in clauses of a query, so both cannot be set, found: {ucgid}"
+            ));
+        }
+        let summary_levels = match ucgid
+            .strip_prefix("pseudo(")
+            .and_then(|s| s.strip_suffix(')'))
+        {
+            Some(pseudo) => match pseudo.split_once('$') {
+                Some((_, component)) => vec![component],
+                None => {
+                    return Err(format!(
+                        "pseudo-geography ucgid should have the form pseudo(<parent>$<component>), found: {ucgid}"
+                    ))
+                }
+            },
+            None => ucgid.split(',').collect_vec(),
+        };
+        let geoid_types = summary_levels
+            .into_iter()
+            .map(geoid_type_from_summary_level)
+            .collect::<Result<Vec<_>, String>>()?;
+        let geoid_type = match &geoid_types.into_iter().unique().collect_vec()[..] {
+            [one_type] => Ok(*one_type),
+            types => Err(format!(
+                "ucgids in one query should share a summary level, found: {}",
+                types.iter().join(", ")
+            )),
+        }?;
+        Ok(AcsGeoidQuery::Ucgid {
+            geoid_type,
+            ucgid: String::from(ucgid),
+        })
+    }
+
+    /// creates a query for all counties in each of the provided states, which the ACS API
+    /// resolves in a single request. duplicate states are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    ///
+    /// let states = vec![fips::State(8), fips::State(49), fips::State(56), fips::State(8)];
+    /// let query = AcsGeoidQuery::counties_in_states(&states).unwrap();
+    /// assert_eq!(query.to_query_key(), String::from("&for=county:*&in=state:08,49,56"));
+    /// assert_eq!(query.response_geoid_type(), GeoidType::County);
+    ///
+    /// // rows deserialize into county geoids as with a single-state county query
+    /// let deserialize_fn = query.build_deserialize_geoid_fn();
+    /// let geoid = deserialize_fn(vec![serde_json::json!["49"], serde_json::json!["035"]]).unwrap();
+    /// assert_eq!(geoid, Geoid::County(fips::State(49), fips::County(35)));
+    ///
+    /// assert!(AcsGeoidQuery::counties_in_states(&[]).is_err());
+    /// ```
+    pub fn counties_in_states(states: &[fips::State]) -> Result<AcsGeoidQuery, String> {
+        if states.is_empty() {
+            return Err(String::from(
+                "cannot create multi-state county query without at least one state",
+            ));
+        }
+        let unique = states.iter().copied().unique().collect_vec();
+        Ok(AcsGeoidQuery::CountiesInStates(unique))
+    }
+
     /// a query key for a unique data row in the census API. depending on the AcsGeoidQuery
     /// and the presence/absence of FIPS values, wildcards ("*") will be inserted at any level.
+    ///
+    /// # Examples
+    ///
+    /// every key begins with "&" so that it can be appended directly after the "get" clause.
+    /// for example, all places within a state:
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    ///
+    /// let geoid = Geoid::State(fips::State(8));
+    /// let query = AcsGeoidQuery::new(Some(geoid), Some(GeoidType::Place)).unwrap();
+    /// assert_eq!(query.to_query_key(), String::from("&for=place:*&in=state:08"));
+    ///
+    /// let query = AcsGeoidQuery::Place(Some(fips::State(8)), Some(fips::Place(20000)));
+    /// assert_eq!(query.to_query_key(), String::from("&for=place:20000&in=state:08"));
+    /// ```
+    ///
+    /// county subdivisions are nested in counties, so each key carries two `in`
+    /// clauses, one for the state and one for the county, with a wildcard for a missing
+    /// county or county subdivision. county subdivision codes are unique within a state,
+    /// so a single county subdivision can be found within `county:*`.
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    /// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+    /// use bamcensus_acs::api::acs_api;
+    ///
+    /// let (state, county, cousub) = (fips::State(8), fips::County(31), fips::CountySubdivision(91007));
+    /// for (query, expected) in [
+    ///     (AcsGeoidQuery::CountySubdivision(state, None, None), "&for=county%20subdivision:*&in=state:08&in=county:*"),
+    ///     (AcsGeoidQuery::CountySubdivision(state, None, Some(cousub)), "&for=county%20subdivision:91007&in=state:08&in=county:*"),
+    ///     (AcsGeoidQuery::CountySubdivision(state, Some(county), None), "&for=county%20subdivision:*&in=state:08&in=county:031"),
+    ///     (AcsGeoidQuery::CountySubdivision(state, Some(county), Some(cousub)), "&for=county%20subdivision:91007&in=state:08&in=county:031"),
+    /// ] {
+    ///     assert_eq!(query.to_query_key(), expected);
+    ///     // every combination returns the state, county and county subdivision columns
+    ///     assert_eq!(query.response_column_names(), vec!["state", "county", "county subdivision"]);
+    /// }
+    ///
+    /// // all county subdivisions in one county, from the county geoid
+    /// let query = AcsGeoidQuery::new(Some(Geoid::County(state, county)), Some(GeoidType::CountySubdivision)).unwrap();
+    /// assert_eq!(query.to_query_key(), AcsGeoidQuery::CountySubdivision(state, Some(county), None).to_query_key());
+    /// let get_query = vec![String::from("B01001_001E")];
+    /// let params = AcsApiQueryParams::new(None, 2022, AcsType::FiveYear, get_query.clone(), query.clone(), None);
+    /// assert_eq!(
+    ///     params.build_url().unwrap(),
+    ///     "https://api.census.gov/data/2022/acs/acs5?get=B01001_001E&for=county%20subdivision:*&in=state:08&in=county:031"
+    /// );
+    ///
+    /// // a single county subdivision from its geoid, and its response row
+    /// let geoid = Geoid::CountySubdivision(state, county, cousub);
+    /// let query = AcsGeoidQuery::new(Some(geoid.clone()), None).unwrap();
+    /// assert_eq!(query.to_query_key(), "&for=county%20subdivision:91007&in=state:08&in=county:031");
+    /// let row = serde_json::json![["715522", "08", "031", "91007"]];
+    /// let deserialize_fn = query.build_deserialize_geoid_fn();
+    /// let (from_row, _) = acs_api::deserialize(&row, &get_query, query.response_column_count(), deserialize_fn).unwrap();
+    /// assert_eq!(from_row, geoid);
+    /// ```
+    ///
+    /// all block groups within a single census tract. the state, county and tract are all
+    /// fixed, so the API returns exactly the block groups of that tract, and each response
+    /// row deserializes back into a block group geoid within the tract.
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+    /// use bamcensus_acs::model::AcsGeoidQuery;
+    /// use bamcensus_acs::api::acs_api;
+    ///
+    /// let tract = Geoid::CensusTract(fips::State(8), fips::County(31), fips::CensusTract(4102));
+    /// let query = AcsGeoidQuery::within(tract.clone(), GeoidType::BlockGroup).unwrap();
+    /// assert_eq!(
+    ///     query.to_query_key(),
+    ///     String::from("&for=block%20group:*&in=state:08&in=county:031&in=tract:004102")
+    /// );
+    ///
+    /// // the same query is produced from the tract with a block group wildcard
+    /// let from_new = AcsGeoidQuery::new(Some(tract.clone()), Some(GeoidType::BlockGroup)).unwrap();
+    /// assert_eq!(from_new.to_query_key(), query.to_query_key());
+    ///
+    /// // response rows end with the state, county, tract and block group columns
+    /// let get_cols = vec![String::from("B01001_001E")];
+    /// let deserialize_fn = query.build_deserialize_geoid_fn();
+    /// let n_for_cols = query.response_column_count();
+    /// let geoids = ["1", "2"]
+    ///     .iter()
+    ///     .map(|bg| {
+    ///         let row = serde_json::json![["1024", "08", "031", "004102", bg]];
+    ///         acs_api::deserialize(&row, &get_cols, n_for_cols, deserialize_fn.clone())
+    ///             .map(|(geoid, _)| geoid)
+    ///     })
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     geoids,
+    ///     vec![
+    ///         Geoid::BlockGroup(fips::State(8), fips::County(31), fips::CensusTract(4102), fips::BlockGroup(1)),
+    ///         Geoid::BlockGroup(fips::State(8), fips::County(31), fips::CensusTract(4102), fips::BlockGroup(2)),
+    ///     ]
+    /// );
+    /// assert_eq!(geoids[0].geoid_string(), String::from("080310041021"));
+    /// assert!(geoids.iter().all(|g| tract.is_parent_of(g)));
+    /// ```
     pub fn to_query_key(&self) -> String {
         use AcsGeoidQuery as G;
         match self {
+            G::Nation => String::from("&for=us:1"),
+            G::Region(region) => match region {
+                None => String::from("&for=region:*"),
+                Some(r) => format!("&for=region:{}", r.geoid_string()),
+            },
+            G::Division(division) => match division {
+                None => String::from("&for=division:*"),
+                Some(d) => format!("&for=division:{}", d.geoid_string()),
+            },
             G::State(state) => match state {
                 None => String::from("&for=state:*"),
                 Some(s) => format!("&for=state:{}", s.geoid_string()),
@@ -249,6 +639,10 @@ impl AcsGeoidQuery {
                     s.geoid_string()
                 ),
             },
+            G::CountiesInStates(states) => format!(
+                "&for=county:*&in=state:{}",
+                states.iter().map(|s| s.geoid_string()).join(",")
+            ),
             G::CountySubdivision(state, county, cousub) => match (county, cousub) {
                 (None, None) => format!(
                     "&for=county%20subdivision:*&in=state:{}&in=county:*",
@@ -348,26 +742,41 @@ impl AcsGeoidQuery {
                     ),
                 }
             }
+            G::Ucgid { ucgid, .. } => {
+                // ucgids may hold characters such as ( ) $ that are not safe in a query
+                let encoded = url::form_urlencoded::byte_serialize(ucgid.as_bytes()).join("");
+                format!("&ucgid={encoded}")
+            }
         }
     }
 
     pub fn response_geoid_type(&self) -> GeoidType {
         use AcsGeoidQuery as G;
         match self {
+            G::Nation => GeoidType::Nation,
+            G::Region(_) => GeoidType::Region,
+            G::Division(_) => GeoidType::Division,
             G::State(_) => GeoidType::State,
             G::County(_, _) => GeoidType::County,
+            G::CountiesInStates(_) => GeoidType::County,
             G::CountySubdivision(_, _, _) => GeoidType::CountySubdivision,
             G::Place(_, _) => GeoidType::Place,
             G::CensusTract(_, _, _) => GeoidType::CensusTract,
             G::BlockGroup(_, _, _, _) => GeoidType::BlockGroup,
+            G::Ucgid { geoid_type, .. } => *geoid_type,
         }
     }
 
     pub fn response_column_names(&self) -> Vec<String> {
         use AcsGeoidQuery as G;
         match self {
+            G::Nation => vec![String::from("us")],
+            G::Region(_) => vec![String::from("region")],
+            G::Division(_) => vec![String::from("division")],
             G::State(_) => vec![String::from("state")],
-            G::County(_, _) => vec![String::from("state"), String::from("county")],
+            G::County(_, _) | G::CountiesInStates(_) => {
+                vec![String::from("state"), String::from("county")]
+            }
             G::CountySubdivision(_, _, _) => vec![
                 String::from("state"),
                 String::from("county"),
@@ -385,28 +794,39 @@ impl AcsGeoidQuery {
                 String::from("tract"),
                 String::from("block group"),
             ],
+            G::Ucgid { .. } => vec![String::from("ucgid")],
         }
     }
 
     pub fn response_column_count(&self) -> usize {
         match self {
+            AcsGeoidQuery::Nation => 1,
+            AcsGeoidQuery::Region(_) => 1,
+            AcsGeoidQuery::Division(_) => 1,
             AcsGeoidQuery::State(_) => 1,
             AcsGeoidQuery::County(_, _) => 2,
+            AcsGeoidQuery::CountiesInStates(_) => 2,
             AcsGeoidQuery::CountySubdivision(_, _, _) => 3,
             AcsGeoidQuery::Place(_, _) => 2,
             AcsGeoidQuery::CensusTract(_, _, _) => 3,
             AcsGeoidQuery::BlockGroup(_, _, _, _) => 4,
+            AcsGeoidQuery::Ucgid { .. } => 1,
         }
     }
 
     pub fn get_geoid_type(&self) -> GeoidType {
         match self {
+            AcsGeoidQuery::Nation => GeoidType::Nation,
+            AcsGeoidQuery::Region(_) => GeoidType::Region,
+            AcsGeoidQuery::Division(_) => GeoidType::Division,
             AcsGeoidQuery::State(_) => GeoidType::State,
             AcsGeoidQuery::County(_, _) => GeoidType::County,
+            AcsGeoidQuery::CountiesInStates(_) => GeoidType::County,
             AcsGeoidQuery::CountySubdivision(_, _, _) => GeoidType::CountySubdivision,
             AcsGeoidQuery::Place(_, _) => GeoidType::Place,
             AcsGeoidQuery::CensusTract(_, _, _) => GeoidType::CensusTract,
             AcsGeoidQuery::BlockGroup(_, _, _, _) => GeoidType::BlockGroup,
+            AcsGeoidQuery::Ucgid { geoid_type, .. } => *geoid_type,
         }
     }
 
@@ -415,14 +835,48 @@ impl AcsGeoidQuery {
     /// the original query.
     pub fn build_deserialize_geoid_fn(&self) -> DeserializeGeoidFn {
         let geoid_type = self.get_geoid_type();
+        let is_ucgid = matches!(self, AcsGeoidQuery::Ucgid { .. });
         let f: DeserializeGeoidFn = Rc::new(move |vals| {
             let strings = as_strings(&vals)?;
-            geoid_type.geoid_from_slice_of_strings(&strings)
+            match &strings[..] {
+                [ucgid] if is_ucgid => geoid_from_ucgid(&geoid_type, ucgid),
+                _ => geoid_type.geoid_from_slice_of_strings(&strings),
+            }
         });
         f
     }
 }
 
+/// the geoid type of a ucgid summary level, which is the first three digits of a
+/// ucgid such as `0500000US08031`.
+fn geoid_type_from_summary_level(ucgid: &str) -> Result<GeoidType, String> {
+    match ucgid.trim().get(0..3) {
+        Some("010") => Ok(GeoidType::Nation),
+        Some("020") => Ok(GeoidType::Region),
+        Some("030") => Ok(GeoidType::Division),
+        Some("040") => Ok(GeoidType::State),
+        Some("050") => Ok(GeoidType::County),
+        Some("060") => Ok(GeoidType::CountySubdivision),
+        Some("140") => Ok(GeoidType::CensusTract),
+        Some("150") => Ok(GeoidType::BlockGroup),
+        Some("160") => Ok(GeoidType::Place),
+        _ => Err(format!(
+            "ucgid summary level is not supported for ACS queries, found: {ucgid}"
+        )),
+    }
+}
+
+/// reads the geoid that follows the "US" separator of a ucgid response value.
+fn geoid_from_ucgid(geoid_type: &GeoidType, ucgid: &str) -> Result<Geoid, String> {
+    let (_, code) = ucgid
+        .split_once("US")
+        .ok_or_else(|| format!("ucgid should contain 'US' before the geoid, found: {ucgid}"))?;
+    match geoid_type {
+        GeoidType::Nation => Ok(Geoid::Nation),
+        _ => geoid_type.geoid_from_str(code),
+    }
+}
+
 /// helper function to convert a vec of JSON values to their expected String values.
 fn as_strings(arr: &[serde_json::Value]) -> Result<Vec<String>, String> {
     arr.iter()
@@ -0,0 +1,108 @@
+use bamcensus_core::model::identifier::GeoidType;
+use serde::{Deserialize, Serialize};
+
+/// a geography label from the ACS "NAME" variable, split into its components.
+///
+/// labels are ordered from the smallest to the largest geography, such as
+/// "Campbellton CCD, Atascosa County, Texas". recent ACS vintages delimit
+/// components with semicolons instead of commas.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AcsName {
+    /// name of the geography itself, such as "Campbellton CCD"
+    pub leaf: String,
+    /// names of the containing geographies, from smallest to largest,
+    /// such as ["Atascosa County", "Texas"]
+    pub ancestors: Vec<String>,
+    /// a trailing parenthetical note, such as "not comparable", if present
+    pub note: Option<String>,
+}
+
+impl AcsName {
+    /// name of the ACS variable holding geography labels
+    pub const NAME_VARIABLE: &'static str = "NAME";
+
+    /// parses a NAME label for a geography of the given type. the number of
+    /// components is known from the geography type, so any extra delimiters
+    /// are kept as part of the leaf name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsName;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let name = AcsName::parse("Campbellton CCD, Atascosa County, Texas", GeoidType::CountySubdivision).unwrap();
+    /// assert_eq!(name.leaf, "Campbellton CCD");
+    /// assert_eq!(name.ancestors, vec!["Atascosa County", "Texas"]);
+    /// ```
+    ///
+    /// place names may contain commas, which are kept in the leaf:
+    /// ```rust
+    /// use bamcensus_acs::model::AcsName;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let label = "Lynchburg, Moore County metropolitan government, Tennessee";
+    /// let name = AcsName::parse(label, GeoidType::Place).unwrap();
+    /// assert_eq!(name.leaf, "Lynchburg, Moore County metropolitan government");
+    /// assert_eq!(name.ancestors, vec!["Tennessee"]);
+    /// ```
+    ///
+    /// trailing notes are separated from the label:
+    /// ```rust
+    /// use bamcensus_acs::model::AcsName;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let name = AcsName::parse("Census Tract 9838; Jefferson County; Colorado (not comparable)", GeoidType::CensusTract).unwrap();
+    /// assert_eq!(name.leaf, "Census Tract 9838");
+    /// assert_eq!(name.ancestors, vec!["Jefferson County", "Colorado"]);
+    /// assert_eq!(name.note, Some(String::from("not comparable")));
+    /// ```
+    pub fn parse(label: &str, geoid_type: GeoidType) -> Result<AcsName, String> {
+        let (label, note) = split_note(label.trim());
+        let n_components = match geoid_type {
+            GeoidType::Nation => 1,
+            GeoidType::Region => 1,
+            GeoidType::Division => 1,
+            GeoidType::State => 1,
+            GeoidType::County => 2,
+            GeoidType::CountySubdivision => 3,
+            GeoidType::Place => 2,
+            GeoidType::CensusTract => 3,
+            GeoidType::BlockGroup => 4,
+            GeoidType::Block => 5,
+            GeoidType::VotingDistrict => 3,
+        };
+        let delimiter = if label.contains(';') { ';' } else { ',' };
+        let mut components = label
+            .rsplitn(n_components, delimiter)
+            .map(|c| c.trim().to_string())
+            .collect::<Vec<_>>();
+        if components.len() != n_components {
+            return Err(format!(
+                "expected {n_components} components in {geoid_type} NAME label, found {}: '{label}'",
+                components.len()
+            ));
+        }
+        components.reverse();
+        let leaf = components.remove(0);
+        Ok(AcsName {
+            leaf,
+            ancestors: components,
+            note,
+        })
+    }
+}
+
+/// splits a trailing "(not comparable)" note from a label. other parentheticals,
+/// such as "(balance)", are part of the geography name and are kept.
+fn split_note(label: &str) -> (&str, Option<String>) {
+    if let Some(stripped) = label.strip_suffix(')') {
+        if let Some(open) = stripped.rfind('(') {
+            let note = stripped[open + 1..].trim();
+            if note.to_lowercase().starts_with("not comparable") {
+                return (label[..open].trim_end(), Some(note.to_string()));
+            }
+        }
+    }
+    (label, None)
+}
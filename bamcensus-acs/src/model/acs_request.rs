@@ -0,0 +1,196 @@
+use super::constants::MAX_GET_VARIABLES;
+use crate::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+use itertools::Itertools;
+
+/// the variables requested from one ACS table, such as B01001. with no variables,
+/// the whole table is requested as a variable group, `group(B01001)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcsTableRequest {
+    pub table: String,
+    pub variables: Vec<String>,
+}
+
+/// a request for variables from several ACS tables over one geography, which is
+/// split into as many [`AcsApiQueryParams`] as needed to stay within the
+/// [`MAX_GET_VARIABLES`] limit of the ACS API. see
+/// [`crate::api::acs_api::run_request`], which runs the queries, merges their rows
+/// and tags each value with its source table.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::model::{AcsGeoidQuery, AcsRequest, AcsType};
+/// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+///
+/// let for_query = AcsGeoidQuery::new(Some(Geoid::state(8).unwrap()), Some(GeoidType::County)).unwrap();
+/// let request = AcsRequest::new(2020, AcsType::FiveYear, for_query, Some(String::from("abc")))
+///     .with_name()
+///     .with_table("B01001", &["B01001_001E", "B01001_002E"])
+///     .unwrap()
+///     .with_table("B19013", &["B19013_001E", "B19013_001M"])
+///     .unwrap()
+///     .with_table("B25077", &[])
+///     .unwrap();
+///
+/// let keys = request
+///     .queries()
+///     .unwrap()
+///     .iter()
+///     .map(|q| q.get_query.join(","))
+///     .collect::<Vec<_>>();
+/// assert_eq!(keys, vec![
+///     "NAME,B01001_001E,B01001_002E,B19013_001E,B19013_001M",
+///     "group(B25077)",
+/// ]);
+///
+/// assert_eq!(request.table_of("B19013_001M"), Some("B19013"));
+/// assert_eq!(request.table_of("B25077_001E"), Some("B25077"));
+/// assert_eq!(request.table_of("NAME"), None);
+///
+/// // variables must belong to their table
+/// let for_query = AcsGeoidQuery::new(None, Some(GeoidType::State)).unwrap();
+/// let request = AcsRequest::new(2020, AcsType::FiveYear, for_query, None);
+/// assert!(request.with_table("B01001", &["B19013_001E"]).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct AcsRequest {
+    /// optional override URL for the queries. otherwise defaults to [`super::constants::BASE_URL`]
+    pub base_url: Option<String>,
+    pub year: u64,
+    pub acs_type: AcsType,
+    /// geographic scope of every query
+    pub for_query: AcsGeoidQuery,
+    /// optional API token in case of rate limiting issues
+    pub api_token: Option<String>,
+    /// whether the NAME of each geography is requested
+    pub name: bool,
+    pub tables: Vec<AcsTableRequest>,
+}
+
+impl AcsRequest {
+    /// creates a request without any tables. as with [`AcsApiQueryParams::new`], the
+    /// `api_token` is used as given.
+    pub fn new(
+        year: u64,
+        acs_type: AcsType,
+        for_query: AcsGeoidQuery,
+        api_token: Option<String>,
+    ) -> AcsRequest {
+        AcsRequest {
+            base_url: None,
+            year,
+            acs_type,
+            for_query,
+            api_token,
+            name: false,
+            tables: vec![],
+        }
+    }
+
+    /// sends the queries to another ACS API root, such as a mirror.
+    pub fn with_base_url(mut self, base_url: &str) -> AcsRequest {
+        self.base_url = Some(String::from(base_url));
+        self
+    }
+
+    /// also requests the NAME of each geography, which is not part of any table.
+    pub fn with_name(mut self) -> AcsRequest {
+        self.name = true;
+        self
+    }
+
+    /// adds variables from a table, such as `B01001_001E` and `B01001_001M` from
+    /// B01001, or the whole table when `variables` is empty. each variable must be
+    /// named after the table, and a table may be added only once.
+    pub fn with_table(mut self, table: &str, variables: &[&str]) -> Result<AcsRequest, String> {
+        let table = table.trim();
+        if table.is_empty() || table.contains('_') {
+            return Err(format!(
+                "ACS table should be a code such as B01001, found '{table}'"
+            ));
+        }
+        if self.tables.iter().any(|t| t.table == table) {
+            return Err(format!("ACS table {table} was added more than once"));
+        }
+        if let Some(other) = variables.iter().find(|v| table_code(v) != Some(table)) {
+            return Err(format!(
+                "variable {other} does not belong to ACS table {table}"
+            ));
+        }
+        self.tables.push(AcsTableRequest {
+            table: String::from(table),
+            variables: variables
+                .iter()
+                .map(|v| String::from(*v))
+                .unique()
+                .collect(),
+        });
+        Ok(self)
+    }
+
+    /// the table a variable in a response came from, or None for variables outside
+    /// of the requested tables, such as NAME.
+    pub fn table_of(&self, variable: &str) -> Option<&str> {
+        let code = table_code(variable)?;
+        self.tables
+            .iter()
+            .find(|t| t.table == code)
+            .map(|t| t.table.as_str())
+    }
+
+    /// the queries for this request. variables are packed in table order into
+    /// queries of at most [`MAX_GET_VARIABLES`], and each whole table requested as a
+    /// variable group, whose size is only known from the response, gets its own
+    /// query. NAME is requested in the first query only.
+    pub fn queries(&self) -> Result<Vec<AcsApiQueryParams>, String> {
+        if self.tables.is_empty() {
+            return Err(String::from("ACS request has no tables"));
+        }
+        let name = self.name.then(|| String::from("NAME"));
+        let variables = name
+            .iter()
+            .cloned()
+            .chain(self.tables.iter().flat_map(|t| t.variables.iter().cloned()))
+            .collect_vec();
+        let mut get_queries = variables
+            .chunks(MAX_GET_VARIABLES)
+            .map(|chunk| chunk.to_vec())
+            .collect_vec();
+        let groups = self
+            .tables
+            .iter()
+            .filter(|t| t.variables.is_empty())
+            .map(|t| format!("group({})", t.table));
+        for group in groups {
+            // a lone NAME is folded into the first group query
+            match get_queries.as_mut_slice() {
+                [only] if only == &vec![String::from("NAME")] => only.push(group),
+                _ => get_queries.push(vec![group]),
+            }
+        }
+        let queries = get_queries
+            .into_iter()
+            .map(|get_query| AcsApiQueryParams {
+                base_url: self.base_url.clone(),
+                year: self.year,
+                acs_type: self.acs_type,
+                get_query,
+                for_query: self.for_query.clone(),
+                api_token: self.api_token.clone(),
+                include_name: false,
+                extra_params: vec![],
+            })
+            .collect_vec();
+        Ok(queries)
+    }
+}
+
+/// the table code of an ACS variable, which is its name up to the first underscore,
+/// such as B01001 for B01001_001E.
+fn table_code(variable: &str) -> Option<&str> {
+    variable
+        .trim()
+        .split_once('_')
+        .map(|(table, _)| table)
+        .filter(|table| !table.is_empty())
+}
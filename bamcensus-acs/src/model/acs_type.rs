@@ -1,3 +1,4 @@
+use bamcensus_core::model::identifier::GeoidType;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -7,6 +8,9 @@ use std::fmt::Display;
 pub enum AcsType {
     OneYear,
     FiveYear,
+    /// 1-year supplemental estimates (acsse), a reduced set of `K`-prefixed tables
+    /// published for areas with 20,000+ residents.
+    OneYearSupplemental,
 }
 
 impl Display for AcsType {
@@ -14,15 +18,53 @@ impl Display for AcsType {
         match self {
             AcsType::OneYear => write!(f, "acs1"),
             AcsType::FiveYear => write!(f, "acs5"),
+            AcsType::OneYearSupplemental => write!(f, "acsse"),
         }
     }
 }
 
 impl AcsType {
+    /// dataset directory of this ACS product in the API URL path.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsType;
+    ///
+    /// assert_eq!(AcsType::FiveYear.to_directory_name(), "acs5");
+    /// assert_eq!(AcsType::OneYearSupplemental.to_directory_name(), "acsse");
+    /// ```
     pub fn to_directory_name(&self) -> String {
         match self {
             AcsType::OneYear => String::from("acs1"),
             AcsType::FiveYear => String::from("acs5"),
+            AcsType::OneYearSupplemental => String::from("acsse"),
+        }
+    }
+
+    /// the decennial census whose census tracts and block groups the estimates of an
+    /// ACS year are tabulated on. tracts and block groups are redrawn once a decade,
+    /// and each ACS product adopts them with the estimates whose final year is the
+    /// census year, so 2016-2020 5-year estimates use 2020 census tracts while
+    /// 2015-2019 ones use 2010 census tracts. other geographies, such as counties,
+    /// use the boundaries in effect on January 1 of the final year.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsType;
+    ///
+    /// assert_eq!(AcsType::FiveYear.geography_year(2020), 2020);
+    /// assert_eq!(AcsType::FiveYear.geography_year(2022), 2020);
+    /// assert_eq!(AcsType::FiveYear.geography_year(2029), 2020);
+    /// assert_eq!(AcsType::FiveYear.geography_year(2019), 2010);
+    /// assert_eq!(AcsType::FiveYear.geography_year(2010), 2010);
+    /// assert_eq!(AcsType::OneYear.geography_year(2019), 2010);
+    /// assert_eq!(AcsType::OneYearSupplemental.geography_year(2023), 2020);
+    /// ```
+    pub fn geography_year(&self, year: u64) -> u64 {
+        match self {
+            AcsType::OneYear | AcsType::FiveYear | AcsType::OneYearSupplemental => year - year % 10,
         }
     }
 
@@ -30,6 +72,83 @@ impl AcsType {
         match self {
             AcsType::OneYear => 1,
             AcsType::FiveYear => 5,
+            AcsType::OneYearSupplemental => 1,
+        }
+    }
+
+    /// true if the Census Bureau publishes this ACS product for the given year
+    /// and geography level. see [`AcsType::validate`] for the constraints.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsType;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// assert!(AcsType::OneYear.is_available(2022, GeoidType::County));
+    /// assert!(!AcsType::OneYear.is_available(2022, GeoidType::CensusTract));
+    /// assert!(!AcsType::OneYear.is_available(2020, GeoidType::State));
+    /// assert!(AcsType::FiveYear.is_available(2020, GeoidType::BlockGroup));
+    /// assert!(AcsType::OneYearSupplemental.is_available(2022, GeoidType::County));
+    /// assert!(!AcsType::OneYearSupplemental.is_available(2013, GeoidType::County));
+    /// assert!(!AcsType::OneYearSupplemental.is_available(2022, GeoidType::CensusTract));
+    /// ```
+    pub fn is_available(&self, year: u64, geoid_type: GeoidType) -> bool {
+        self.validate(year, geoid_type).is_ok()
+    }
+
+    /// confirms this ACS product is published for the given year and geography level,
+    /// returning an explanatory error if not. the documented constraints are:
+    ///   - ACS does not publish block-level estimates
+    ///   - 1-year estimates begin in 2005, 5-year estimates in 2009
+    ///   - 1-year estimates only cover areas with 65,000+ residents, so they are
+    ///     never available for census tracts or block groups
+    ///   - the standard 1-year release was not produced for 2020 due to COVID-19
+    ///     data collection disruptions
+    ///   - 1-year supplemental estimates begin in 2014, were also not produced for
+    ///     2020, and only cover the nation, regions, divisions, and states, counties
+    ///     and places with 20,000+ residents
+    pub fn validate(&self, year: u64, geoid_type: GeoidType) -> Result<(), String> {
+        if geoid_type == GeoidType::Block {
+            return Err(String::from("acs does not publish block-level estimates"));
+        }
+        match self {
+            AcsType::OneYear => match (year, geoid_type) {
+                (y, _) if y < 2005 => Err(format!(
+                    "1-year ACS estimates begin in 2005, found year {year}"
+                )),
+                (2020, _) => Err(String::from(
+                    "1-year ACS estimates were not released for 2020, consider 5-year estimates",
+                )),
+                (_, GeoidType::CensusTract | GeoidType::BlockGroup) => Err(format!(
+                    "1-year ACS estimates are only published for areas with 65,000+ residents and are not available at the {geoid_type} level, consider 5-year estimates"
+                )),
+                _ => Ok(()),
+            },
+            AcsType::FiveYear if year < 2009 => Err(format!(
+                "5-year ACS estimates begin in 2009, found year {year}"
+            )),
+            AcsType::FiveYear => Ok(()),
+            AcsType::OneYearSupplemental => match (year, geoid_type) {
+                (y, _) if y < 2014 => Err(format!(
+                    "1-year supplemental ACS estimates begin in 2014, found year {year}"
+                )),
+                (2020, _) => Err(String::from(
+                    "1-year supplemental ACS estimates were not released for 2020",
+                )),
+                (
+                    _,
+                    GeoidType::Nation
+                    | GeoidType::Region
+                    | GeoidType::Division
+                    | GeoidType::State
+                    | GeoidType::County
+                    | GeoidType::Place,
+                ) => Ok(()),
+                _ => Err(format!(
+                    "1-year supplemental ACS estimates are only published for the nation, regions, divisions, and states, counties and places with 20,000+ residents and are not available at the {geoid_type} level"
+                )),
+            },
         }
     }
 }
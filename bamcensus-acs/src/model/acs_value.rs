@@ -1,3 +1,4 @@
+use bamcensus_core::model::{is_acs_sentinel, NamedValue, ACS_CONTROLLED_MOE};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -5,11 +6,81 @@ use std::fmt::Display;
 pub struct AcsValue {
     pub name: String,
     pub value: serde_json::Value,
+    /// the ACS table the value came from, such as B01001, when it was requested with
+    /// an [`super::AcsRequest`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<String>,
 }
 
 impl AcsValue {
     pub fn new(name: String, value: serde_json::Value) -> AcsValue {
-        AcsValue { name, value }
+        AcsValue {
+            name,
+            value,
+            table: None,
+        }
+    }
+
+    /// whether this value is missing: JSON null, or one of the ACS sentinels (see
+    /// [`is_acs_sentinel`]) in rows that were not read by deserialization, which
+    /// stores most of them as JSON null, see [`AcsValue::normalize_missing`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsValue;
+    /// use serde_json::json;
+    ///
+    /// let value = |v| AcsValue::new(String::from("B01001_001E"), v);
+    /// assert!(value(json![null]).is_missing());
+    /// assert!(value(json!["-555555555"]).is_missing());
+    /// assert!(value(json![-666666666.0]).is_missing());
+    /// assert!(!value(json!["-1250"]).is_missing());
+    /// assert!(!value(json!["n/a"]).is_missing());
+    /// ```
+    pub fn is_missing(&self) -> bool {
+        self.value.is_null() || self.as_number().is_some_and(is_acs_sentinel)
+    }
+
+    /// the single representation of a missing value in ACS rows: JSON null. the API
+    /// writes some missing values as JSON null, others as the string "null" and the
+    /// estimates it cannot produce as negative sentinels such as -666666666, which
+    /// are all read as JSON null, so that downstream code has one case to check. the
+    /// controlled margin of error sentinel, -555555555, is kept, since it stands for
+    /// a margin of zero rather than a missing one. other values, including negative
+    /// ones, are returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsValue;
+    /// use serde_json::json;
+    ///
+    /// assert_eq!(AcsValue::normalize_missing(json!["null"]), json![null]);
+    /// assert_eq!(AcsValue::normalize_missing(json![" NULL "]), json![null]);
+    /// assert_eq!(AcsValue::normalize_missing(json![null]), json![null]);
+    /// assert_eq!(AcsValue::normalize_missing(json!["-666666666"]), json![null]);
+    /// assert_eq!(AcsValue::normalize_missing(json![-999999999]), json![null]);
+    /// assert_eq!(AcsValue::normalize_missing(json!["-555555555"]), json!["-555555555"]);
+    /// assert_eq!(AcsValue::normalize_missing(json!["3889"]), json!["3889"]);
+    /// assert_eq!(AcsValue::normalize_missing(json!["-1250"]), json!["-1250"]);
+    /// ```
+    pub fn normalize_missing(value: serde_json::Value) -> serde_json::Value {
+        if let Some(s) = value.as_str() {
+            if s.trim().eq_ignore_ascii_case("null") {
+                return serde_json::Value::Null;
+            }
+        }
+        match parse_number(&value) {
+            Some(x) if is_acs_sentinel(x) && x != ACS_CONTROLLED_MOE => serde_json::Value::Null,
+            _ => value,
+        }
+    }
+
+    /// this value as a number, whether it is a JSON number, as after aggregation, or
+    /// a numeric string, as in ACS responses. None if it is not numeric.
+    pub fn as_number(&self) -> Option<f64> {
+        parse_number(&self.value)
     }
 
     /// to numeric operation.
@@ -35,8 +106,24 @@ impl AcsValue {
     }
 }
 
+fn parse_number(value: &serde_json::Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse::<f64>().ok()))
+}
+
 impl Display for AcsValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}: {}", self.name, self.value)
     }
 }
+
+impl NamedValue for AcsValue {
+    fn value_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn numeric_value(&self) -> Option<f64> {
+        self.as_f64_safe().ok()
+    }
+}
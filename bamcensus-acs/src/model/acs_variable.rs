@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// metadata describing a single ACS variable, as published in the
+/// `variables.json` document of each ACS dataset vintage.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AcsVariable {
+    /// variable code, such as "B19013_001E"
+    pub name: String,
+    /// human-readable label, such as "Estimate!!Median household income in the past 12 months"
+    pub label: String,
+    /// description of the table this variable belongs to
+    #[serde(default)]
+    pub concept: Option<String>,
+    /// table (group) code, such as "B19013"
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+impl AcsVariable {
+    /// the label with vintage-specific details removed so that labels can be compared
+    /// across years. this drops case, colons, and four-digit years, which appear in
+    /// labels such as "(in 2019 inflation-adjusted dollars)".
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::AcsVariable;
+    ///
+    /// let a = AcsVariable {
+    ///     name: String::from("B19013_001E"),
+    ///     label: String::from("Estimate!!Median household income (in 2019 inflation-adjusted dollars)"),
+    ///     concept: None,
+    ///     group: None,
+    /// };
+    /// let b = AcsVariable {
+    ///     label: String::from("Estimate!!Median Household Income (in 2022 inflation-adjusted dollars):"),
+    ///     ..a.clone()
+    /// };
+    /// assert_eq!(a.normalized_label(), b.normalized_label());
+    /// ```
+    pub fn normalized_label(&self) -> String {
+        let mut normalized = String::with_capacity(self.label.len());
+        let mut digits = String::new();
+        for c in self.label.chars().chain(std::iter::once(' ')) {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+            if !digits.is_empty() {
+                if digits.len() == 4 {
+                    normalized.push_str("yyyy");
+                } else {
+                    normalized.push_str(&digits);
+                }
+                digits.clear();
+            }
+            if c != ':' {
+                normalized.extend(c.to_lowercase());
+            }
+        }
+        normalized.trim().to_string()
+    }
+}
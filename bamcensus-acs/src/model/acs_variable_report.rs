@@ -0,0 +1,106 @@
+use super::{AcsType, AcsVariable};
+use itertools::Itertools;
+use std::fmt::Display;
+
+/// availability of a single ACS variable across a range of dataset years. used
+/// to confirm that a time series built from multiple ACS vintages refers to the
+/// same variable in every year.
+#[derive(Debug, Clone)]
+pub struct AcsVariableReport {
+    pub variable: String,
+    pub acs_type: AcsType,
+    /// each requested year paired with the variable metadata for that year, or
+    /// `None` if the variable does not exist in that year's dataset.
+    pub years: Vec<(u64, Option<AcsVariable>)>,
+}
+
+impl AcsVariableReport {
+    /// years where the variable exists.
+    pub fn available_years(&self) -> Vec<u64> {
+        self.years
+            .iter()
+            .filter_map(|(year, v)| v.as_ref().map(|_| *year))
+            .collect_vec()
+    }
+
+    /// years where the variable does not exist.
+    pub fn missing_years(&self) -> Vec<u64> {
+        self.years
+            .iter()
+            .filter_map(|(year, v)| match v {
+                None => Some(*year),
+                Some(_) => None,
+            })
+            .collect_vec()
+    }
+
+    /// years where the variable exists but its label differs from the label in the
+    /// most recent available year, which suggests the variable code was reused
+    /// for a different measure. labels are compared using
+    /// [`AcsVariable::normalized_label`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_acs::model::{AcsType, AcsVariable, AcsVariableReport};
+    ///
+    /// let variable = |label: &str| AcsVariable {
+    ///     name: String::from("B08301_021E"),
+    ///     label: String::from(label),
+    ///     concept: None,
+    ///     group: None,
+    /// };
+    /// let report = AcsVariableReport {
+    ///     variable: String::from("B08301_021E"),
+    ///     acs_type: AcsType::FiveYear,
+    ///     years: vec![
+    ///         (2012, Some(variable("Estimate!!Total!!Other means"))),
+    ///         (2013, None),
+    ///         (2014, Some(variable("Estimate!!Total!!Worked at home"))),
+    ///         (2015, Some(variable("Estimate!!Total:!!Worked at home"))),
+    ///     ],
+    /// };
+    /// assert_eq!(report.missing_years(), vec![2013]);
+    /// assert_eq!(report.relabeled_years(), vec![2012]);
+    /// assert!(!report.is_consistent());
+    /// ```
+    pub fn relabeled_years(&self) -> Vec<u64> {
+        let latest = self
+            .years
+            .iter()
+            .filter_map(|(year, v)| v.as_ref().map(|v| (*year, v.normalized_label())))
+            .max_by_key(|(year, _)| *year);
+        let Some((_, latest_label)) = latest else {
+            return vec![];
+        };
+        self.years
+            .iter()
+            .filter_map(|(year, v)| match v {
+                Some(v) if v.normalized_label() != latest_label => Some(*year),
+                _ => None,
+            })
+            .collect_vec()
+    }
+
+    /// true if the variable exists with the same label in every requested year.
+    pub fn is_consistent(&self) -> bool {
+        self.missing_years().is_empty() && self.relabeled_years().is_empty()
+    }
+}
+
+impl Display for AcsVariableReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} ({}) availability:", self.variable, self.acs_type)?;
+        let relabeled = self.relabeled_years();
+        for (year, variable) in self.years.iter() {
+            match variable {
+                None => writeln!(f, "  {year}: missing")?,
+                Some(v) if relabeled.contains(year) => {
+                    writeln!(f, "  {year}: relabeled \"{}\"", v.label)?
+                }
+                Some(v) => writeln!(f, "  {year}: ok \"{}\"", v.label)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1 +1,5 @@
 pub const BASE_URL: &str = "https://api.census.gov/data";
+/// environment variable read for an ACS API key when none is provided explicitly
+pub const API_KEY_ENV_VAR: &str = "CENSUS_API_KEY";
+/// the most variables the ACS API accepts in the 'get' clause of one query
+pub const MAX_GET_VARIABLES: usize = 50;
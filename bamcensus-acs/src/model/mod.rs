@@ -1,14 +1,28 @@
 pub mod constants;
 
 mod acs_api_query_params;
+mod acs_flow;
+mod acs_geography;
 mod acs_geoid_query;
+mod acs_name;
+mod acs_request;
 mod acs_type;
 mod acs_value;
+mod acs_variable;
+mod acs_variable_report;
 
-pub use acs_api_query_params::AcsApiQueryParams;
+pub use acs_api_query_params::{
+    api_token_from_env, parse_extra_param, AcsApiQueryParams, MANAGED_QUERY_PARAMS,
+};
+pub use acs_flow::{AcsFlow, AcsFlowsQueryParams, FLOWS_FIRST_YEAR, FLOWS_GET_QUERY};
+pub use acs_geography::{AcsGeography, AcsGeographyLevel};
 pub use acs_geoid_query::AcsGeoidQuery;
+pub use acs_name::AcsName;
+pub use acs_request::{AcsRequest, AcsTableRequest};
 pub use acs_type::AcsType;
 pub use acs_value::AcsValue;
+pub use acs_variable::AcsVariable;
+pub use acs_variable_report::AcsVariableReport;
 
 use bamcensus_core::model::identifier::Geoid;
 use std::rc::Rc;
@@ -9,6 +9,13 @@ use serde_json::json;
 /// groups rows to the target Geoid hierarchy level and then
 /// applies the provided aggregation function to the grouped WacValues.
 ///
+/// values may be numbers or numeric strings, as in ACS responses. missing values
+/// (see [`AcsValue::is_missing`]) are skipped, including the negative sentinels ACS
+/// reports for estimates it cannot produce, such as -666666666 when there are too
+/// few sample observations. other negative values are aggregated. a variable
+/// missing from every row of a group aggregates to JSON null. any other non-numeric
+/// value is an error.
+///
 /// # Example
 ///
 /// ```rust
@@ -46,6 +53,31 @@ use serde_json::json;
 ///     assert_eq!(v_a.value, v_b.value);
 ///   }
 /// }
+///
+/// // missing values are skipped
+/// let county = |c: u64, value: serde_json::Value| {
+///   (Geoid::County(fips::State(08), fips::County(c)), vec![AcsValue::new(String::from("B19013_001E"), value)])
+/// };
+/// let rows = vec![county(1, json!["80000"]), county(5, json![null]), county(31, json!["70000"]), county(53, json![null])];
+/// let result = acs_agg::aggregate_acs(&rows, GeoidType::State, NumericAggregation::Mean).unwrap();
+/// assert_eq!(result[0].1[0].value, json![75000.0]);
+///
+/// // sentinels are skipped rather than added to the total
+/// let rows = vec![county(1, json!["80000"]), county(5, json!["-666666666"]), county(31, json!["70000"])];
+/// let result = acs_agg::aggregate_acs(&rows, GeoidType::State, NumericAggregation::Sum).unwrap();
+/// assert_eq!(result[0].1[0].value, json![150000.0]);
+///
+/// let rows = vec![county(5, json![null]), county(53, json!["-999999999"])];
+/// let result = acs_agg::aggregate_acs(&rows, GeoidType::State, NumericAggregation::Sum).unwrap();
+/// assert_eq!(result[0].1[0].value, json![null]);
+///
+/// // other negative values, such as a change in an estimate, are kept
+/// let rows = vec![county(1, json!["1200"]), county(5, json!["-1500"]), county(31, json!["-666666666"])];
+/// let result = acs_agg::aggregate_acs(&rows, GeoidType::State, NumericAggregation::Sum).unwrap();
+/// assert_eq!(result[0].1[0].value, json![-300.0]);
+///
+/// let rows = vec![county(1, json!["n/a"])];
+/// assert!(acs_agg::aggregate_acs(&rows, GeoidType::State, NumericAggregation::Sum).is_err());
 /// ```
 pub fn aggregate_acs(
     rows: &[(Geoid, Vec<AcsValue>)],
@@ -82,12 +114,18 @@ pub fn aggregate_acs(
             let xs = values.into_iter().chunk_by(|v| v.name.clone());
             let mut agg_values = vec![];
             for (name, values) in &xs {
-                let values = values.map(|v| {
-                  v.value.as_f64().ok_or_else(|| format!("ACS value for {} is not numeric (found {}) but user requested aggregation", name, v.value))
-                })
-                .collect::<Result<Vec<_>, _>>()?;
-                let aggregated = agg.aggregate(&mut values.into_iter());
-                agg_values.push(AcsValue::new(name, json![aggregated]));
+                let values = values
+                    .filter(|v| !v.is_missing())
+                    .map(|v| {
+                        v.as_number().ok_or_else(|| format!("ACS value for {} is not numeric (found {}) but user requested aggregation", name, v.value))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let aggregated = if values.is_empty() {
+                    serde_json::Value::Null
+                } else {
+                    json![agg.aggregate(&mut values.into_iter())]
+                };
+                agg_values.push(AcsValue::new(name, aggregated));
             }
             Ok((geoid, agg_values))
         })
@@ -0,0 +1,53 @@
+use crate::model::{AcsName, AcsValue};
+use bamcensus_core::model::identifier::Geoid;
+use serde_json::json;
+
+/// name of the value added to each row by [`add_place_names`]
+pub const PLACE_NAME_FIELD: &str = "place_name";
+
+/// for rows that include the ACS "NAME" variable, parses the label and appends a
+/// "place_name" value holding just the name of the row's geography, without its
+/// containing geographies. rows without a NAME value are returned unchanged.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::model::AcsValue;
+/// use bamcensus_acs::ops::acs_names::add_place_names;
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use serde_json::json;
+///
+/// let geoid = Geoid::County(fips::State(8), fips::County(59));
+/// let rows = vec![(
+///     geoid,
+///     vec![
+///         AcsValue::new(String::from("NAME"), json!["Jefferson County, Colorado"]),
+///         AcsValue::new(String::from("B01001_001E"), json!["582910"]),
+///     ],
+/// )];
+/// let result = add_place_names(rows).unwrap();
+/// let (_, values) = &result[0];
+/// assert_eq!(values[2].name, "place_name");
+/// assert_eq!(values[2].value, json!["Jefferson County"]);
+/// ```
+pub fn add_place_names(
+    rows: Vec<(Geoid, Vec<AcsValue>)>,
+) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
+    rows.into_iter()
+        .map(|(geoid, mut values)| {
+            let label = values
+                .iter()
+                .find(|v| v.name == AcsName::NAME_VARIABLE)
+                .and_then(|v| v.value.as_str());
+            if let Some(label) = label {
+                let name = AcsName::parse(label, geoid.geoid_type())
+                    .map_err(|e| format!("failure parsing NAME for {geoid}: {e}"))?;
+                values.push(AcsValue::new(
+                    String::from(PLACE_NAME_FIELD),
+                    json![name.leaf],
+                ));
+            }
+            Ok((geoid, values))
+        })
+        .collect()
+}
@@ -0,0 +1,105 @@
+use crate::model::AcsValue;
+use bamcensus_core::model::identifier::Geoid;
+use itertools::Itertools;
+use serde_json::json;
+
+/// name of the percentage value computed from a numerator variable.
+pub fn percentage_name(numerator: &str) -> String {
+    format!("{numerator}_PCT")
+}
+
+/// appends a percentage value to each row for every (numerator, denominator) pair of
+/// variable names, computed as `100 * numerator / denominator` and named with
+/// [`percentage_name`], such as `B17001_002E_PCT` for the share of the population
+/// below the poverty level.
+///
+/// ACS reports estimates it cannot produce with negative sentinel values, such as
+/// -666666666 when there are too few sample observations. the percentage is `null`
+/// when either value is a sentinel or otherwise missing (see [`AcsValue::is_missing`])
+/// or is not a number, and when the denominator is not positive. a negative
+/// numerator, such as a change in an estimate, gives a negative percentage. every variable in the pairs must be present in
+/// each row, since a variable that was never queried is a mistake in the pairs.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use bamcensus_acs::model::AcsValue;
+/// use bamcensus_acs::ops::acs_percent;
+/// use serde_json::json;
+///
+/// let row = |county: u64, below: &str, total: &str| {
+///     (
+///         Geoid::County(fips::State(8), fips::County(county)),
+///         vec![
+///             AcsValue::new(String::from("B17001_002E"), json![below]),
+///             AcsValue::new(String::from("B17001_001E"), json![total]),
+///         ],
+///     )
+/// };
+/// let rows = vec![
+///     row(31, "80000", "640000"),
+///     row(53, "0", "0"),
+///     row(111, "-666666666", "700"),
+/// ];
+/// let pairs = [("B17001_002E", "B17001_001E")];
+/// let result = acs_percent::append_percentages(&rows, &pairs).unwrap();
+///
+/// let rates = result
+///     .iter()
+///     .map(|(_, values)| values.last().unwrap().clone())
+///     .collect::<Vec<_>>();
+/// assert!(rates.iter().all(|v| v.name == "B17001_002E_PCT"));
+/// assert_eq!(rates[0].value, json![12.5]);
+/// // no residents, and an estimate that was not produced
+/// assert_eq!(rates[1].value, json![null]);
+/// assert_eq!(rates[2].value, json![null]);
+///
+/// // a loss of 40 out of 800 is -5 percent
+/// let change = vec![row(31, "-40", "800")];
+/// let result = acs_percent::append_percentages(&change, &pairs).unwrap();
+/// assert_eq!(result[0].1.last().unwrap().value, json![-5.0]);
+///
+/// // variables must have been queried
+/// assert!(acs_percent::append_percentages(&rows, &[("B17001_003E", "B17001_001E")]).is_err());
+/// ```
+pub fn append_percentages(
+    rows: &[(Geoid, Vec<AcsValue>)],
+    pairs: &[(&str, &str)],
+) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
+    if let Some(duplicate) = pairs.iter().map(|(n, _)| n).duplicates().next() {
+        return Err(format!(
+            "numerator {duplicate} appears in more than one pair, so its percentage name {} would not be unique",
+            percentage_name(duplicate)
+        ));
+    }
+    rows.iter()
+        .map(|(geoid, values)| {
+            let mut result = values.clone();
+            for (numerator, denominator) in pairs.iter() {
+                let num = find_value(geoid, values, numerator)?;
+                let den = find_value(geoid, values, denominator)?;
+                let pct = match (num, den) {
+                    (Some(n), Some(d)) if d > 0.0 => json![100.0 * n / d],
+                    _ => serde_json::Value::Null,
+                };
+                result.push(AcsValue::new(percentage_name(numerator), pct));
+            }
+            Ok((geoid.clone(), result))
+        })
+        .collect()
+}
+
+/// finds a variable in a row as a number, or None if it is missing (see
+/// [`AcsValue::is_missing`]) or not a number. values are strings in ACS responses and
+/// numbers after aggregation.
+fn find_value(geoid: &Geoid, values: &[AcsValue], name: &str) -> Result<Option<f64>, String> {
+    let value = values
+        .iter()
+        .find(|v| v.name == name)
+        .ok_or_else(|| format!("variable {name} not found in ACS row for {geoid}"))?;
+    if value.is_missing() {
+        return Ok(None);
+    }
+    Ok(value.as_number())
+}
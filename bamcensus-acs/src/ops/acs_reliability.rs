@@ -0,0 +1,198 @@
+use crate::model::AcsValue;
+use bamcensus_core::model::identifier::Geoid;
+use bamcensus_core::model::{is_acs_sentinel, ACS_CONTROLLED_MOE};
+use itertools::Itertools;
+use serde_json::json;
+
+/// z-score of the 90 percent confidence level that ACS margins of error are
+/// published at.
+pub const MOE_Z_SCORE: f64 = 1.645;
+
+/// the margin of error variable paired with an estimate variable, such as
+/// B01001_001M for B01001_001E.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::ops::acs_reliability;
+///
+/// assert_eq!(acs_reliability::moe_name("B01001_001E").unwrap(), "B01001_001M");
+/// assert!(acs_reliability::moe_name("B01001_001M").is_err());
+/// ```
+pub fn moe_name(estimate: &str) -> Result<String, String> {
+    match estimate.strip_suffix('E') {
+        Some(stem) if !stem.is_empty() => Ok(format!("{stem}M")),
+        _ => Err(format!(
+            "{estimate} is not an estimate variable, which ends in E"
+        )),
+    }
+}
+
+/// name of the coefficient of variation computed for an estimate variable.
+pub fn cv_name(estimate: &str) -> String {
+    format!("{estimate}_CV")
+}
+
+/// name of the reliability category computed for an estimate variable.
+pub fn reliability_name(estimate: &str) -> String {
+    format!("{estimate}_RELIABILITY")
+}
+
+/// reliability of an estimate, categorized by its coefficient of variation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reliability {
+    High,
+    Medium,
+    Low,
+}
+
+impl Reliability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reliability::High => "high",
+            Reliability::Medium => "medium",
+            Reliability::Low => "low",
+        }
+    }
+}
+
+/// upper bounds on the coefficient of variation, as a fraction of the estimate, of
+/// high and medium reliability estimates. the default of 0.12 and 0.40 is a common
+/// rule of thumb for ACS estimates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReliabilityThresholds {
+    pub high: f64,
+    pub medium: f64,
+}
+
+impl Default for ReliabilityThresholds {
+    fn default() -> Self {
+        ReliabilityThresholds {
+            high: 0.12,
+            medium: 0.40,
+        }
+    }
+}
+
+impl ReliabilityThresholds {
+    /// the reliability of an estimate with a coefficient of variation. a CV equal to a
+    /// threshold falls in the more reliable category.
+    pub fn classify(&self, cv: f64) -> Reliability {
+        if cv <= self.high {
+            Reliability::High
+        } else if cv <= self.medium {
+            Reliability::Medium
+        } else {
+            Reliability::Low
+        }
+    }
+}
+
+/// appends the coefficient of variation and reliability of each estimate variable to
+/// each row, named with [`cv_name`] and [`reliability_name`], such as
+/// `B17001_002E_CV` and `B17001_002E_RELIABILITY`. each estimate is paired with its
+/// margin of error variable by [`moe_name`], which must also have been queried.
+///
+/// the coefficient of variation is `(MOE / 1.645) / estimate`, the standard error as
+/// a fraction of the estimate, and the reliability is "high", "medium" or "low" by
+/// the `thresholds`. controlled estimates, whose margin of error is the -555555555
+/// sentinel, have no sampling error and a CV of 0. both values are `null` when the
+/// estimate or margin of error is any other sentinel, is missing from the response
+/// or is not a number, and when the estimate is zero, since its CV is undefined.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use bamcensus_acs::model::AcsValue;
+/// use bamcensus_acs::ops::acs_reliability::{self, ReliabilityThresholds};
+/// use serde_json::json;
+///
+/// let row = |county: u64, estimate: &str, moe: &str| {
+///     (
+///         Geoid::County(fips::State(8), fips::County(county)),
+///         vec![
+///             AcsValue::new(String::from("B17001_002E"), json![estimate]),
+///             AcsValue::new(String::from("B17001_002M"), json![moe]),
+///         ],
+///     )
+/// };
+/// let rows = vec![
+///     row(31, "80000", "3290"),
+///     row(53, "120", "98"),
+///     row(111, "0", "12"),
+///     row(1, "500000", "-555555555"),
+/// ];
+/// let thresholds = ReliabilityThresholds::default();
+/// let result = acs_reliability::append_reliability(&rows, &["B17001_002E"], &thresholds).unwrap();
+///
+/// let values = |i: usize| {
+///     let (_, values) = &result[i];
+///     assert_eq!(values[2].name, "B17001_002E_CV");
+///     assert_eq!(values[3].name, "B17001_002E_RELIABILITY");
+///     (values[2].value.clone(), values[3].value.clone())
+/// };
+/// let (cv, reliability) = values(0);
+/// assert!((cv.as_f64().unwrap() - 0.025).abs() < 1e-9);
+/// assert_eq!(reliability, json!["high"]);
+/// assert_eq!(values(1).1, json!["low"]);
+/// // a zero estimate has no CV, while a controlled one has no sampling error
+/// assert_eq!(values(2), (json![null], json![null]));
+/// assert_eq!(values(3), (json![0.0], json!["high"]));
+///
+/// // the margins of error must have been queried
+/// let estimates_only = vec![(rows[0].0.clone(), vec![rows[0].1[0].clone()])];
+/// assert!(acs_reliability::append_reliability(&estimates_only, &["B17001_002E"], &thresholds).is_err());
+/// ```
+pub fn append_reliability(
+    rows: &[(Geoid, Vec<AcsValue>)],
+    estimates: &[&str],
+    thresholds: &ReliabilityThresholds,
+) -> Result<Vec<(Geoid, Vec<AcsValue>)>, String> {
+    if let Some(duplicate) = estimates.iter().duplicates().next() {
+        return Err(format!(
+            "estimate {duplicate} appears more than once, so its CV name {} would not be unique",
+            cv_name(duplicate)
+        ));
+    }
+    let pairs = estimates
+        .iter()
+        .map(|e| moe_name(e).map(|m| (*e, m)))
+        .collect::<Result<Vec<_>, _>>()?;
+    rows.iter()
+        .map(|(geoid, values)| {
+            let mut result = values.clone();
+            for (estimate, moe) in pairs.iter() {
+                let est = find_number(geoid, values, estimate)?.filter(|x| *x > 0.0);
+                let moe = find_number(geoid, values, moe)?.and_then(|x| {
+                    if x == ACS_CONTROLLED_MOE {
+                        Some(0.0)
+                    } else {
+                        Some(x).filter(|x| !is_acs_sentinel(*x))
+                    }
+                });
+                let cv = match (est, moe) {
+                    (Some(e), Some(m)) => Some(m / MOE_Z_SCORE / e),
+                    _ => None,
+                };
+                result.push(AcsValue::new(cv_name(estimate), json![cv]));
+                let reliability = cv.map(|cv| thresholds.classify(cv).as_str());
+                result.push(AcsValue::new(
+                    reliability_name(estimate),
+                    json![reliability],
+                ));
+            }
+            Ok((geoid.clone(), result))
+        })
+        .collect()
+}
+
+/// finds a variable in a row as a number, or None if it is not a number. values are
+/// strings in ACS responses and numbers after aggregation.
+fn find_number(geoid: &Geoid, values: &[AcsValue], name: &str) -> Result<Option<f64>, String> {
+    let value = values
+        .iter()
+        .find(|v| v.name == name)
+        .ok_or_else(|| format!("variable {name} not found in ACS row for {geoid}"))?;
+    Ok(value.as_number())
+}
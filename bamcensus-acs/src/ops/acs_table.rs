@@ -0,0 +1,61 @@
+use crate::model::AcsVariable;
+use itertools::Itertools;
+
+/// expands an ACS table into the names of its estimate variables, read from the
+/// metadata of the table, such as the result of
+/// [`crate::api::acs_variables_api::get_group`]. this is a client-side alternative
+/// to requesting `group(B19001)`, which returns every variable of the table, so that
+/// exactly the estimates are requested and named.
+///
+/// estimates are the variables of the table ending in E, such as B19001_001E, or PE
+/// for the percent estimates of the data profiles. margins of error (M), annotation
+/// variables (EA, MA) and variables of other tables, including the race iterations
+/// of the table such as B19001A, are left out. names are returned in order.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_acs::model::AcsVariable;
+/// use bamcensus_acs::ops::acs_table::expand_table;
+///
+/// let variable = |name: &str| AcsVariable {
+///     name: String::from(name),
+///     label: String::from("Estimate!!Total:"),
+///     concept: None,
+///     group: Some(String::from("B19001")),
+/// };
+/// let mut metadata = vec![variable("NAME"), variable("GEO_ID"), variable("B19001A_001E")];
+/// for n in (1..=17).rev() {
+///     for suffix in ["E", "EA", "M", "MA"] {
+///         metadata.push(variable(&format!("B19001_{n:03}{suffix}")));
+///     }
+/// }
+/// let estimates = expand_table("B19001", &metadata);
+/// assert_eq!(estimates.len(), 17);
+/// assert_eq!(estimates[0], "B19001_001E");
+/// assert_eq!(estimates[16], "B19001_017E");
+///
+/// assert!(expand_table("B19013", &metadata).is_empty());
+/// ```
+pub fn expand_table(table: &str, metadata: &[AcsVariable]) -> Vec<String> {
+    let table = table.trim();
+    metadata
+        .iter()
+        .filter(|v| is_estimate_of(&v.name, table))
+        .map(|v| v.name.clone())
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// whether a variable name is an estimate of a table: the table code, an underscore
+/// and a code ending in E.
+fn is_estimate_of(name: &str, table: &str) -> bool {
+    match name
+        .strip_prefix(table)
+        .and_then(|rest| rest.strip_prefix('_'))
+    {
+        Some(code) => code.len() > 1 && code.ends_with('E'),
+        None => false,
+    }
+}
@@ -1 +1,5 @@
 pub mod acs_agg;
+pub mod acs_names;
+pub mod acs_percent;
+pub mod acs_reliability;
+pub mod acs_table;
@@ -0,0 +1,32 @@
+/// the values ACS reports in place of estimates and margins of error it cannot
+/// produce, documented with the annotation values of the Census Data API, such as
+/// -666666666 when there are too few sample observations to compute an estimate.
+pub const ACS_SENTINELS: [f64; 6] = [
+    -666666666.0,
+    -999999999.0,
+    -888888888.0,
+    -222222222.0,
+    -333333333.0,
+    ACS_CONTROLLED_MOE,
+];
+
+/// the margin of error sentinel of estimates that are controlled, such as total
+/// population in some tables, so that they have no sampling error.
+pub const ACS_CONTROLLED_MOE: f64 = -555555555.0;
+
+/// whether a number is one of the [`ACS_SENTINELS`] rather than a value. other
+/// negative numbers, such as the change in an estimate, are values.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::is_acs_sentinel;
+///
+/// assert!(is_acs_sentinel(-666666666.0));
+/// assert!(is_acs_sentinel(-555555555.0));
+/// assert!(!is_acs_sentinel(-1250.0));
+/// assert!(!is_acs_sentinel(0.0));
+/// ```
+pub fn is_acs_sentinel(x: f64) -> bool {
+    ACS_SENTINELS.contains(&x)
+}
@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// a running total of the bytes of HTTP response bodies downloaded by a run. clones
+/// share the same total, so a counter can be handed to each download of a run and
+/// read once they have finished.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::ByteCounter;
+///
+/// let bytes = ByteCounter::default();
+/// let shared = bytes.clone();
+/// shared.add(1024);
+/// bytes.add(512);
+/// assert_eq!(bytes.total(), 1536);
+/// assert_eq!(shared.total(), 1536);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ByteCounter(Arc<AtomicU64>);
+
+impl ByteCounter {
+    /// adds the size of a downloaded chunk to the total.
+    pub fn add(&self, bytes: u64) {
+        self.0.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// the bytes downloaded so far.
+    pub fn total(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,56 @@
+use super::identifier::{Geoid, HasGeoidString};
+use std::fmt::Display;
+
+/// the expected geoids within a scope that have no data in a result set, see
+/// [`crate::ops::coverage::coverage_gaps`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageGaps {
+    /// the requested geography, such as a county
+    pub scope: Geoid,
+    /// number of geoids expected within the scope
+    pub expected: usize,
+    /// expected geoids with no rows in the result set, such as from a failed download
+    pub missing: Vec<Geoid>,
+    /// expected geoids with rows but no usable value, such as suppressed estimates
+    pub empty: Vec<Geoid>,
+}
+
+impl CoverageGaps {
+    /// true if every expected geoid has data
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.empty.is_empty()
+    }
+
+    /// the missing and empty geoids together, in geoid order
+    pub fn gaps(&self) -> Vec<Geoid> {
+        let mut gaps = self
+            .missing
+            .iter()
+            .chain(self.empty.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        gaps.sort();
+        gaps
+    }
+}
+
+impl Display for CoverageGaps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: {} of {} expected geoids have data, {} missing, {} empty",
+            self.scope.geoid_string(),
+            self.expected - self.missing.len() - self.empty.len(),
+            self.expected,
+            self.missing.len(),
+            self.empty.len()
+        )?;
+        for geoid in self.missing.iter() {
+            writeln!(f, "missing {}", geoid.geoid_string())?;
+        }
+        for geoid in self.empty.iter() {
+            writeln!(f, "empty {}", geoid.geoid_string())?;
+        }
+        Ok(())
+    }
+}
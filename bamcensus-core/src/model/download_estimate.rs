@@ -0,0 +1,112 @@
+use futures::StreamExt;
+use std::fmt::Display;
+
+/// an upfront estimate of the files and bytes a run will download, built from
+/// the `Content-Length` of each file without downloading it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadEstimate {
+    /// number of files the run will download
+    pub files: usize,
+    /// total size in bytes of the files with a known size
+    pub bytes: u64,
+    /// number of files whose size was not reported by the server
+    pub unknown_size: usize,
+    /// errors for files that could not be checked
+    pub errors: Vec<String>,
+}
+
+impl DownloadEstimate {
+    /// builds an estimate from the result of checking each file, which is the file
+    /// size if known.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::DownloadEstimate;
+    ///
+    /// let checks = vec![Ok(Some(100)), Ok(None), Err(String::from("404")), Ok(Some(50))];
+    /// let estimate = DownloadEstimate::from_checks(checks);
+    /// assert_eq!(estimate.files, 4);
+    /// assert_eq!(estimate.bytes, 150);
+    /// assert_eq!(estimate.unknown_size, 1);
+    /// assert_eq!(estimate.errors, vec![String::from("404")]);
+    /// ```
+    pub fn from_checks(checks: Vec<Result<Option<u64>, String>>) -> DownloadEstimate {
+        let mut estimate = DownloadEstimate::default();
+        for check in checks.into_iter() {
+            estimate.files += 1;
+            match check {
+                Ok(Some(bytes)) => estimate.bytes += bytes,
+                Ok(None) => estimate.unknown_size += 1,
+                Err(e) => estimate.errors.push(e),
+            }
+        }
+        estimate
+    }
+
+    /// combines this estimate with another, such as the estimates for two datasets
+    /// that are downloaded in the same run.
+    pub fn merge(mut self, other: DownloadEstimate) -> DownloadEstimate {
+        self.files += other.files;
+        self.bytes += other.bytes;
+        self.unknown_size += other.unknown_size;
+        self.errors.extend(other.errors);
+        self
+    }
+}
+
+impl Display for DownloadEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gb = self.bytes as f64 / 1_000_000_000.0;
+        write!(f, "{} files, {} bytes ({gb:.2} GB)", self.files, self.bytes)?;
+        if self.unknown_size > 0 {
+            write!(f, ", {} files of unknown size", self.unknown_size)?;
+        }
+        if !self.errors.is_empty() {
+            write!(f, ", {} files could not be checked", self.errors.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// estimates the total size of the files at the given URIs from the `Content-Length`
+/// header of a HEAD request to each, sending at most `concurrency` requests at a time.
+/// a redirect that the client did not follow is reported as an error for that file,
+/// since its size would be that of the redirect page.
+pub async fn head_content_length(
+    client: &reqwest::Client,
+    uris: &[String],
+    concurrency: usize,
+) -> DownloadEstimate {
+    let checks = futures::stream::iter(uris.iter().map(|uri| async move {
+        let response = client
+            .head(uri)
+            .send()
+            .await
+            .map_err(|e| format!("failure requesting headers for {uri}: {e}"))?
+            .error_for_status()
+            .map_err(|e| format!("headers request for {uri} failed: {e}"))?;
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("an unknown location");
+            return Err(format!(
+                "headers request for {uri} was redirected to {location} ({}), but the HTTP client does not follow redirects",
+                response.status()
+            ));
+        }
+        // the body of a HEAD response is empty, so read the header directly
+        let size = response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        Ok(size)
+    }))
+    .buffered(concurrency.max(1))
+    .collect::<Vec<_>>()
+    .await;
+    DownloadEstimate::from_checks(checks)
+}
@@ -0,0 +1,77 @@
+use super::fips;
+
+/// a change to the set of counties in a state, taking effect in the geographies of a
+/// vintage year, which are the boundaries as of January 1 of that year used by
+/// TIGER/Lines and ACS. counties not named in any change exist in every vintage
+/// since 2010. see [`super::Geoid::exists_in_vintage`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountyChange {
+    /// first vintage year with the new counties
+    pub year: u64,
+    /// state FIPS code
+    pub state: fips::State,
+    /// county FIPS codes that no longer exist from `year`
+    pub removed: &'static [fips::County],
+    /// county FIPS codes that exist from `year`
+    pub added: &'static [fips::County],
+    pub description: &'static str,
+}
+
+/// the changes to county FIPS codes since the 2010 census, in order of vintage year.
+/// see <https://www.census.gov/programs-surveys/geography/technical-documentation/county-changes.html>
+pub const COUNTY_CHANGES: [CountyChange; 5] = [
+    CountyChange {
+        year: 2014,
+        state: fips::State(51),
+        removed: &[fips::County(515)],
+        added: &[],
+        description: "Bedford city, Virginia merged into Bedford County (51019)",
+    },
+    CountyChange {
+        year: 2016,
+        state: fips::State(2),
+        removed: &[fips::County(270)],
+        added: &[fips::County(158)],
+        description: "Wade Hampton Census Area, Alaska was renamed Kusilvak Census Area",
+    },
+    CountyChange {
+        year: 2016,
+        state: fips::State(46),
+        removed: &[fips::County(113)],
+        added: &[fips::County(102)],
+        description: "Shannon County, South Dakota was renamed Oglala Lakota County",
+    },
+    CountyChange {
+        year: 2020,
+        state: fips::State(2),
+        removed: &[fips::County(261)],
+        added: &[fips::County(63), fips::County(66)],
+        description: "Valdez-Cordova Census Area, Alaska was split into Chugach and Copper River Census Areas",
+    },
+    CountyChange {
+        year: 2022,
+        state: fips::State(9),
+        removed: &[
+            fips::County(1),
+            fips::County(3),
+            fips::County(5),
+            fips::County(7),
+            fips::County(9),
+            fips::County(11),
+            fips::County(13),
+            fips::County(15),
+        ],
+        added: &[
+            fips::County(110),
+            fips::County(120),
+            fips::County(130),
+            fips::County(140),
+            fips::County(150),
+            fips::County(160),
+            fips::County(170),
+            fips::County(180),
+            fips::County(190),
+        ],
+        description: "the eight counties of Connecticut were replaced by nine planning regions",
+    },
+];
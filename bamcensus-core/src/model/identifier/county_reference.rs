@@ -0,0 +1,262 @@
+use super::{fips, Geoid, HasGeoidString};
+
+/// vintage year of the embedded county reference. the counties of other vintages
+/// since 2010 are found by applying [`COUNTY_CHANGES`](super::COUNTY_CHANGES) to it.
+pub const COUNTY_REFERENCE_YEAR: u64 = 2020;
+
+/// earliest vintage year the county reference can be carried back to, the first
+/// year covered by [`COUNTY_CHANGES`](super::COUNTY_CHANGES).
+pub const EARLIEST_COUNTY_YEAR: u64 = 2010;
+
+/// the county FIPS codes of a state in the geographies of [`COUNTY_REFERENCE_YEAR`].
+/// counties are numbered with odd codes in alphabetical order, so most states are
+/// described by their last odd code, along with the odd codes of counties dissolved
+/// since they were numbered and the other codes, such as independent cities and
+/// counties created later.
+struct StateCounties {
+    state: u64,
+    /// every odd code from 1 through this one, unless removed
+    last_odd: u64,
+    /// odd codes up to `last_odd` that are not counties
+    removed: &'static [u64],
+    /// codes that are counties besides the odd ones
+    added: &'static [u64],
+}
+
+const fn odd(state: u64, last_odd: u64) -> StateCounties {
+    StateCounties {
+        state,
+        last_odd,
+        removed: &[],
+        added: &[],
+    }
+}
+
+/// the counties and county equivalents of the states, the District of Columbia,
+/// Puerto Rico and the island areas in 2020 geographies.
+/// see <https://www.census.gov/library/reference/code-lists/ansi.html>
+const STATE_COUNTIES: [StateCounties; 56] = [
+    odd(1, 133),
+    StateCounties {
+        state: 2,
+        last_odd: 0,
+        removed: &[],
+        added: &[
+            13, 16, 20, 50, 60, 63, 66, 68, 70, 90, 100, 105, 110, 122, 130, 150, 158, 164, 170,
+            180, 185, 188, 195, 198, 220, 230, 240, 275, 282, 290,
+        ],
+    },
+    StateCounties {
+        state: 4,
+        last_odd: 27,
+        removed: &[],
+        added: &[12],
+    },
+    odd(5, 149),
+    odd(6, 115),
+    StateCounties {
+        state: 8,
+        last_odd: 125,
+        removed: &[],
+        added: &[14],
+    },
+    odd(9, 15),
+    odd(10, 5),
+    odd(11, 1),
+    StateCounties {
+        state: 12,
+        last_odd: 133,
+        removed: &[25],
+        added: &[86],
+    },
+    StateCounties {
+        state: 13,
+        last_odd: 321,
+        removed: &[41, 203],
+        added: &[],
+    },
+    odd(15, 9),
+    odd(16, 87),
+    odd(17, 203),
+    odd(18, 183),
+    odd(19, 197),
+    odd(20, 209),
+    odd(21, 239),
+    odd(22, 127),
+    odd(23, 31),
+    StateCounties {
+        state: 24,
+        last_odd: 47,
+        removed: &[7],
+        added: &[510],
+    },
+    odd(25, 27),
+    odd(26, 165),
+    odd(27, 173),
+    odd(28, 163),
+    StateCounties {
+        state: 29,
+        last_odd: 229,
+        removed: &[191, 193],
+        added: &[186, 510],
+    },
+    odd(30, 111),
+    odd(31, 185),
+    StateCounties {
+        state: 32,
+        last_odd: 33,
+        removed: &[25],
+        added: &[510],
+    },
+    odd(33, 19),
+    odd(34, 41),
+    StateCounties {
+        state: 35,
+        last_odd: 61,
+        removed: &[],
+        added: &[6, 28],
+    },
+    odd(36, 123),
+    odd(37, 199),
+    odd(38, 105),
+    odd(39, 175),
+    odd(40, 153),
+    odd(41, 71),
+    odd(42, 133),
+    odd(44, 9),
+    odd(45, 91),
+    StateCounties {
+        state: 46,
+        last_odd: 137,
+        removed: &[1, 113, 131, 133],
+        added: &[102],
+    },
+    odd(47, 189),
+    odd(48, 507),
+    odd(49, 57),
+    odd(50, 27),
+    StateCounties {
+        state: 51,
+        last_odd: 199,
+        removed: &[39, 55, 123, 129, 151, 189],
+        added: &[
+            36, 510, 520, 530, 540, 550, 570, 580, 590, 595, 600, 610, 620, 630, 640, 650, 660,
+            670, 678, 680, 683, 685, 690, 700, 710, 720, 730, 735, 740, 750, 760, 770, 775, 790,
+            800, 810, 820, 830, 840,
+        ],
+    },
+    odd(53, 77),
+    odd(54, 109),
+    StateCounties {
+        state: 55,
+        last_odd: 141,
+        removed: &[],
+        added: &[78],
+    },
+    odd(56, 45),
+    StateCounties {
+        state: 60,
+        last_odd: 0,
+        removed: &[],
+        added: &[10, 20, 30, 40, 50],
+    },
+    StateCounties {
+        state: 66,
+        last_odd: 0,
+        removed: &[],
+        added: &[10],
+    },
+    StateCounties {
+        state: 69,
+        last_odd: 0,
+        removed: &[],
+        added: &[85, 100, 110, 120],
+    },
+    StateCounties {
+        state: 72,
+        last_odd: 153,
+        removed: &[],
+        added: &[54],
+    },
+    StateCounties {
+        state: 78,
+        last_odd: 0,
+        removed: &[],
+        added: &[10, 20, 30],
+    },
+];
+
+/// the counties of a state in the geographies of a vintage year, in order, from the
+/// embedded county reference. counties created or removed between the reference
+/// year and `year`, such as the Connecticut planning regions that replaced its
+/// counties in 2022, are applied per [`COUNTY_CHANGES`](super::COUNTY_CHANGES). fails for states without
+/// counties in the reference and for years before [`EARLIEST_COUNTY_YEAR`].
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::{counties, fips, Geoid, HasGeoidString, StateSelection};
+///
+/// // Connecticut had eight counties in 2020, and nine planning regions from 2022
+/// let geoid_strings = |year| {
+///     counties(fips::State(9), year)
+///         .unwrap()
+///         .iter()
+///         .map(|c| c.geoid_string())
+///         .collect::<Vec<_>>()
+/// };
+/// assert_eq!(
+///     geoid_strings(2020),
+///     ["09001", "09003", "09005", "09007", "09009", "09011", "09013", "09015"]
+/// );
+/// assert_eq!(
+///     geoid_strings(2022),
+///     ["09110", "09120", "09130", "09140", "09150", "09160", "09170", "09180", "09190"]
+/// );
+///
+/// // Valdez-Cordova Census Area, Alaska was split in 2020
+/// let alaska_2019 = counties(fips::State(2), 2019).unwrap();
+/// assert!(alaska_2019.contains(&Geoid::county(2, 261).unwrap()));
+/// assert_eq!(counties(fips::State(2), 2020).unwrap().len(), alaska_2019.len() + 1);
+///
+/// // 3,143 counties and county equivalents in the states and DC in 2020
+/// let total = |year| {
+///     StateSelection::default()
+///         .fips_codes()
+///         .into_iter()
+///         .map(|state| counties(state, year).unwrap().len())
+///         .sum::<usize>()
+/// };
+/// assert_eq!(total(2020), 3143);
+/// assert_eq!(total(2022), 3144);
+///
+/// assert!(counties(fips::State(9), 2000).is_err());
+/// assert!(counties(fips::State(3), 2020).is_err());
+/// ```
+pub fn counties(state: fips::State, year: u64) -> Result<Vec<Geoid>, String> {
+    if year < EARLIEST_COUNTY_YEAR {
+        return Err(format!(
+            "county reference covers {EARLIEST_COUNTY_YEAR} geographies and later, not {year}"
+        ));
+    }
+    let reference = STATE_COUNTIES
+        .iter()
+        .find(|s| s.state == state.0)
+        .ok_or_else(|| {
+            format!(
+                "county reference has no counties for state {}",
+                state.geoid_string()
+            )
+        })?;
+    let listed = (1..=reference.last_odd)
+        .step_by(2)
+        .filter(|county| !reference.removed.contains(county))
+        .chain(reference.added.iter().copied())
+        .map(|county| Geoid::County(state, fips::County(county)))
+        .collect::<Vec<_>>();
+    Ok(Geoid::counties_in_vintage(
+        &listed,
+        COUNTY_REFERENCE_YEAR,
+        year,
+    ))
+}
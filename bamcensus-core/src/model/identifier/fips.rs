@@ -4,39 +4,217 @@ use super::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// a census region: 1 Northeast, 2 Midwest, 3 South, 4 West
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Region(pub u64);
+
+/// a census division, numbered 1 (New England) through 9 (Pacific)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Division(pub u64);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct State(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct County(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CountySubdivision(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Place(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CensusTract(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BlockGroup(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+/// a census block code: a 4-digit block number, which is 15 characters as part of a
+/// GEOID, optionally followed by a suffix letter, which makes 16 characters. suffixes
+/// mark blocks that were split after the census, such as 1001A and 1001B.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Block(pub String);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// a voting district (VTD) code, which is 6 characters as part of a GEOID. VTDs are
+/// the precincts reported by the states for the 2020 redistricting data, and codes
+/// may contain letters, such as 00A001.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VotingDistrict(pub String);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CongressionalDistrict(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StateLegislativeDistrictUpperChamber(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StateLegislativeDistrictLowerChamber(pub u64);
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ZipCodeTabulationArea(pub u64);
 
+impl Division {
+    /// the census region containing this division.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::fips;
+    ///
+    /// assert_eq!(fips::Division(8).region(), Some(fips::Region(4)));
+    /// assert_eq!(fips::Division(10).region(), None);
+    /// ```
+    pub fn region(&self) -> Option<Region> {
+        match self.0 {
+            1 | 2 => Some(Region(1)),
+            3 | 4 => Some(Region(2)),
+            5..=7 => Some(Region(3)),
+            8 | 9 => Some(Region(4)),
+            _ => None,
+        }
+    }
+}
+
+impl State {
+    /// the census division containing this state, or None for Puerto Rico and the
+    /// island areas, which are not part of any division.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::fips;
+    ///
+    /// // Colorado is in the Mountain division
+    /// assert_eq!(fips::State(8).division(), Some(fips::Division(8)));
+    /// assert_eq!(fips::State(72).division(), None);
+    /// ```
+    pub fn division(&self) -> Option<Division> {
+        let division = match self.0 {
+            9 | 23 | 25 | 33 | 44 | 50 => 1,
+            34 | 36 | 42 => 2,
+            17 | 18 | 26 | 39 | 55 => 3,
+            19 | 20 | 27 | 29 | 31 | 38 | 46 => 4,
+            10 | 11 | 12 | 13 | 24 | 37 | 45 | 51 | 54 => 5,
+            1 | 21 | 28 | 47 => 6,
+            5 | 22 | 40 | 48 => 7,
+            4 | 8 | 16 | 30 | 32 | 35 | 49 | 56 => 8,
+            2 | 6 | 15 | 41 | 53 => 9,
+            _ => return None,
+        };
+        Some(Division(division))
+    }
+
+    /// whether county subdivisions in this state are minor civil divisions (MCDs)
+    /// that function as governmental units, such as towns and townships. this holds
+    /// for all or some of the MCDs in 20 states, mostly in New England and the
+    /// Midwest. elsewhere, county subdivisions are census county divisions (CCDs)
+    /// drawn for statistical purposes only.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::fips;
+    ///
+    /// // Massachusetts towns are governments, Texas CCDs are not
+    /// assert!(fips::State(25).has_functioning_mcds());
+    /// assert!(!fips::State(48).has_functioning_mcds());
+    /// ```
+    pub fn has_functioning_mcds(&self) -> bool {
+        FUNCTIONING_MCD_STATES.contains(&self.0)
+    }
+}
+
+impl Block {
+    /// the block group containing this block, which is the first digit of the block
+    /// number. any suffix letter is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::fips;
+    ///
+    /// assert_eq!(fips::Block(String::from("2014")).block_group(), Ok(fips::BlockGroup(2)));
+    /// assert_eq!(fips::Block(String::from("0105B")).block_group(), Ok(fips::BlockGroup(0)));
+    /// ```
+    pub fn block_group(&self) -> Result<BlockGroup, String> {
+        self.0
+            .chars()
+            .next()
+            .and_then(|c| c.to_digit(10))
+            .map(|d| BlockGroup(d as u64))
+            .ok_or_else(|| {
+                format!(
+                    "block code must start with its block group digit, found '{}'",
+                    self.0
+                )
+            })
+    }
+
+    /// the canonical form of this block code: surrounding whitespace is removed, the
+    /// block number is zero-padded to 4 digits and the suffix letter is uppercased.
+    /// the suffix is kept, since blocks 1001A and 1001B are different blocks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::fips;
+    ///
+    /// let block = |code: &str| fips::Block(String::from(code));
+    /// assert_eq!(block("105b").normalize(), block("0105B"));
+    /// assert_eq!(block(" 1001 ").normalize(), block("1001"));
+    /// ```
+    pub fn normalize(&self) -> Block {
+        let code = self.0.trim();
+        let digits = code.chars().take_while(|c| c.is_ascii_digit()).count();
+        let (number, suffix) = code.split_at(digits);
+        Block(format!("{number:0>4}{}", suffix.to_ascii_uppercase()))
+    }
+
+    /// the suffix letter of a block split after the census, if any.
+    pub fn suffix(&self) -> Option<char> {
+        self.0.chars().nth(4)
+    }
+}
+
+impl VotingDistrict {
+    /// the canonical form of this voting district code: surrounding whitespace is
+    /// removed, the code is zero-padded to 6 characters and letters are uppercased.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::fips;
+    ///
+    /// let vtd = |code: &str| fips::VotingDistrict(String::from(code));
+    /// assert_eq!(vtd("a001").normalize(), vtd("00A001"));
+    /// assert_eq!(vtd(" 000120 ").normalize(), vtd("000120"));
+    /// ```
+    pub fn normalize(&self) -> VotingDistrict {
+        VotingDistrict(format!("{:0>6}", self.0.trim().to_ascii_uppercase()))
+    }
+}
+
+/// FIPS codes of the states where all or some minor civil divisions are functioning
+/// governmental units: Connecticut, Illinois, Indiana, Kansas, Maine, Massachusetts,
+/// Michigan, Minnesota, Missouri, Nebraska, New Hampshire, New Jersey, New York, North
+/// Dakota, Ohio, Pennsylvania, Rhode Island, South Dakota, Vermont and Wisconsin.
+pub const FUNCTIONING_MCD_STATES: [u64; 20] = [
+    9, 17, 18, 20, 23, 25, 26, 27, 29, 31, 33, 34, 36, 38, 39, 42, 44, 46, 50, 55,
+];
+
+impl HasGeoidType for Region {
+    fn geoid_type(&self) -> GeoidType {
+        GeoidType::Region
+    }
+}
+
+impl HasGeoidType for Division {
+    fn geoid_type(&self) -> GeoidType {
+        GeoidType::Division
+    }
+}
+
 impl HasGeoidType for State {
     fn geoid_type(&self) -> GeoidType {
         GeoidType::State
@@ -73,6 +251,23 @@ impl HasGeoidType for Block {
         GeoidType::Block
     }
 }
+impl HasGeoidType for VotingDistrict {
+    fn geoid_type(&self) -> GeoidType {
+        GeoidType::VotingDistrict
+    }
+}
+
+impl HasGeoidString for Region {
+    fn geoid_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl HasGeoidString for Division {
+    fn geoid_string(&self) -> String {
+        self.0.to_string()
+    }
+}
 
 impl HasGeoidString for State {
     fn geoid_string(&self) -> String {
@@ -110,6 +305,11 @@ impl HasGeoidString for Block {
         self.0.clone()
     }
 }
+impl HasGeoidString for VotingDistrict {
+    fn geoid_string(&self) -> String {
+        self.0.clone()
+    }
+}
 impl HasGeoidString for CongressionalDistrict {
     fn geoid_string(&self) -> String {
         format!("{:02}", self.0)
@@ -1,10 +1,53 @@
-use super::{fips, GeoidType, HasGeoidString, StateCode};
+use super::geoid_type::strip_fully_qualified_prefix;
+use super::{counties, fips, GeoidType, HasGeoidString, StateCode, StateSelection, COUNTY_CHANGES};
+use clap::ValueEnum;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::Display;
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// a US Census geographic identifier.
+///
+/// geoids sort hierarchically. geoids of the same type are ordered component-wise,
+/// which matches the ascending order of their canonical GEOID strings. a mixed list
+/// is ordered first by type, in the order Nation, Region, Division, State, County,
+/// CountySubdivision, Place, CensusTract, BlockGroup, Block, VotingDistrict, and then
+/// by code.
+///
+/// the nation, census regions and census divisions sit above states. every state
+/// belongs to one division, and every division to one region, see
+/// [`fips::State::division`]. Puerto Rico and the island areas are not part of any
+/// region or division.
+///
+/// voting districts (VTDs) are the 2020 redistricting geographies, which nest within
+/// counties and are published with the decennial PL 94-171 data rather than ACS.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+///
+/// let mut geoids = vec![
+///     Geoid::County(fips::State(49), fips::County(35)),
+///     Geoid::State(fips::State(49)),
+///     Geoid::County(fips::State(8), fips::County(59)),
+///     Geoid::State(fips::State(8)),
+///     Geoid::County(fips::State(8), fips::County(1)),
+/// ];
+/// geoids.sort();
+/// assert_eq!(geoids, vec![
+///     Geoid::State(fips::State(8)),
+///     Geoid::State(fips::State(49)),
+///     Geoid::County(fips::State(8), fips::County(1)),
+///     Geoid::County(fips::State(8), fips::County(59)),
+///     Geoid::County(fips::State(49), fips::County(35)),
+/// ]);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Geoid {
+    Nation,
+    Region(fips::Region),
+    Division(fips::Division),
     State(fips::State),
     County(fips::State, fips::County),
     CountySubdivision(fips::State, fips::County, fips::CountySubdivision),
@@ -17,13 +60,21 @@ pub enum Geoid {
         fips::BlockGroup,
     ),
     Block(fips::State, fips::County, fips::CensusTract, fips::Block),
+    VotingDistrict(fips::State, fips::County, fips::VotingDistrict),
 }
 
 impl TryFrom<&str> for Geoid {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value.len() {
+        // regions and divisions are both 1 digit, and voting districts are 11
+        // characters as are tracts, so they must be read with
+        // GeoidType::geoid_from_str instead
+        if value == "US" {
+            return Ok(Geoid::Nation);
+        }
+        // the prefix of a fully-qualified GEOID is removed again by geoid_from_str
+        match strip_fully_qualified_prefix(value).len() {
             2 => GeoidType::State.geoid_from_str(value),
             5 => GeoidType::County.geoid_from_str(value),
             7 => GeoidType::Place.geoid_from_str(value),
@@ -40,19 +91,317 @@ impl TryFrom<&str> for Geoid {
 // - Geoid methods to unpack/pack between types (Geoid::County.to_state())
 
 impl Geoid {
-    /// generates all state level Geoids for the U.S.
+    /// generates all state level Geoids for the U.S.: the 50 states and the District
+    /// of Columbia.
     pub fn all_states() -> Vec<Geoid> {
-        StateCode::ALL
-            .iter()
-            .map(|sc| {
-                let s: fips::State = (*sc).into();
-                Geoid::State(s)
-            })
+        Geoid::states(&StateSelection::default())
+    }
+
+    /// generates state level Geoids for the 48 contiguous states and the District of
+    /// Columbia.
+    pub fn contiguous_states() -> Vec<Geoid> {
+        Geoid::states(&StateSelection::contiguous())
+    }
+
+    /// generates state level Geoids for a selection of states and state-equivalents,
+    /// ordered by FIPS code.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, StateSelection};
+    ///
+    /// assert_eq!(Geoid::all_states().len(), 51);
+    ///
+    /// let contiguous = Geoid::contiguous_states();
+    /// assert_eq!(contiguous.len(), 49);
+    /// assert!(!contiguous.contains(&Geoid::State(fips::State(2))));
+    /// assert!(!contiguous.contains(&Geoid::State(fips::State(15))));
+    ///
+    /// let selection = StateSelection {
+    ///     exclude_alaska_hawaii: true,
+    ///     exclude_dc: true,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(Geoid::states(&selection).len(), 48);
+    ///
+    /// let selection = StateSelection {
+    ///     include_puerto_rico: true,
+    ///     include_island_areas: true,
+    ///     ..Default::default()
+    /// };
+    /// let states = Geoid::states(&selection);
+    /// assert_eq!(states.len(), 56);
+    /// assert_eq!(states.last(), Some(&Geoid::State(fips::State(78))));
+    /// ```
+    pub fn states(selection: &StateSelection) -> Vec<Geoid> {
+        selection
+            .fips_codes()
+            .into_iter()
+            .map(Geoid::State)
             .collect_vec()
     }
 
+    /// splits geoids requested at county subdivision resolution into the ones in
+    /// states with functioning minor civil divisions, see
+    /// [`fips::State::has_functioning_mcds`], and the ones that are skipped. geoids
+    /// that are not within a single state are kept, so that building their query
+    /// reports the problem. fails if the resolution is not county subdivision, or if
+    /// no geoids are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+    ///
+    /// let geoids = vec![Geoid::state(25).unwrap(), Geoid::county(48, 201).unwrap()];
+    /// let (keep, skip) =
+    ///     Geoid::filter_functioning_mcd_states(geoids.clone(), Some(GeoidType::CountySubdivision))
+    ///         .unwrap();
+    /// assert_eq!(keep, vec![Geoid::state(25).unwrap()]);
+    /// assert_eq!(skip, vec![Geoid::county(48, 201).unwrap()]);
+    ///
+    /// assert!(Geoid::filter_functioning_mcd_states(geoids, Some(GeoidType::County)).is_err());
+    /// let texas = vec![Geoid::state(48).unwrap()];
+    /// assert!(
+    ///     Geoid::filter_functioning_mcd_states(texas, Some(GeoidType::CountySubdivision)).is_err()
+    /// );
+    /// ```
+    pub fn filter_functioning_mcd_states(
+        geoids: Vec<Geoid>,
+        output_resolution: Option<GeoidType>,
+    ) -> Result<(Vec<Geoid>, Vec<Geoid>), String> {
+        if output_resolution != Some(GeoidType::CountySubdivision) {
+            return Err(String::from(
+                "functioning minor civil divisions only apply to county subdivision output",
+            ));
+        }
+        let (keep, skip): (Vec<_>, Vec<_>) =
+            geoids
+                .into_iter()
+                .partition(|geoid| match geoid.to_state() {
+                    Ok(Geoid::State(state)) => state.has_functioning_mcds(),
+                    _ => true,
+                });
+        if keep.is_empty() {
+            return Err(String::from(
+                "no geoids remain in states with functioning minor civil divisions",
+            ));
+        }
+        Ok((keep, skip))
+    }
+
+    /// creates a census region Geoid from its code, 1 (Northeast) through 4 (West).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+    ///
+    /// let west = Geoid::region(4).unwrap();
+    /// assert_eq!(west, Geoid::Region(fips::Region(4)));
+    /// assert_eq!(west.geoid_type(), GeoidType::Region);
+    /// assert!(Geoid::region(5).is_err());
+    ///
+    /// // states roll up into their division and region
+    /// let colorado = Geoid::state(8).unwrap();
+    /// assert_eq!(colorado.truncate_geoid_to_type(&GeoidType::Region).unwrap(), west);
+    /// assert_eq!(
+    ///     colorado.truncate_geoid_to_type(&GeoidType::Division).unwrap(),
+    ///     Geoid::division(8).unwrap()
+    /// );
+    /// assert!(west.is_parent_of(&Geoid::county(8, 59).unwrap()));
+    /// assert!(Geoid::Nation.is_parent_of(&west));
+    /// assert_eq!(Geoid::Nation.geoid_string(), "US");
+    /// ```
+    pub fn region(region: u64) -> Result<Geoid, String> {
+        match region {
+            1..=4 => Ok(Geoid::Region(fips::Region(region))),
+            _ => Err(format!("region code must be 1 through 4, found {region}")),
+        }
+    }
+
+    /// creates a census division Geoid from its code, 1 (New England) through 9 (Pacific).
+    pub fn division(division: u64) -> Result<Geoid, String> {
+        match division {
+            1..=9 => Ok(Geoid::Division(fips::Division(division))),
+            _ => Err(format!(
+                "division code must be 1 through 9, found {division}"
+            )),
+        }
+    }
+
+    /// creates a state Geoid from its FIPS code, confirming it fits in 2 digits.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// assert_eq!(Geoid::state(8).unwrap(), Geoid::State(fips::State(8)));
+    /// assert!(Geoid::state(100).is_err());
+    /// ```
+    pub fn state(state: u64) -> Result<Geoid, String> {
+        Ok(Geoid::State(fips::State(check_digits(state, 2, "state")?)))
+    }
+
+    /// creates a county Geoid from its state and county FIPS codes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let geoid = Geoid::county(8, 59).unwrap();
+    /// assert_eq!(geoid, Geoid::County(fips::State(8), fips::County(59)));
+    /// assert!(Geoid::county(8, 1059).is_err());
+    /// ```
+    pub fn county(state: u64, county: u64) -> Result<Geoid, String> {
+        Ok(Geoid::County(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::County(check_digits(county, 3, "county")?),
+        ))
+    }
+
+    /// creates a county subdivision Geoid from its state, county and county subdivision codes.
+    pub fn county_subdivision(state: u64, county: u64, cousub: u64) -> Result<Geoid, String> {
+        Ok(Geoid::CountySubdivision(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::County(check_digits(county, 3, "county")?),
+            fips::CountySubdivision(check_digits(cousub, 5, "county subdivision")?),
+        ))
+    }
+
+    /// creates a place Geoid from its state and place codes.
+    pub fn place(state: u64, place: u64) -> Result<Geoid, String> {
+        Ok(Geoid::Place(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::Place(check_digits(place, 5, "place")?),
+        ))
+    }
+
+    /// creates a census tract Geoid from its state, county and tract codes.
+    pub fn census_tract(state: u64, county: u64, tract: u64) -> Result<Geoid, String> {
+        Ok(Geoid::CensusTract(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::County(check_digits(county, 3, "county")?),
+            fips::CensusTract(check_digits(tract, 6, "census tract")?),
+        ))
+    }
+
+    /// creates a block group Geoid from its state, county, tract and block group codes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let geoid = Geoid::block_group(8, 59, 9838, 1).unwrap();
+    /// let expected = Geoid::BlockGroup(
+    ///     fips::State(8),
+    ///     fips::County(59),
+    ///     fips::CensusTract(9838),
+    ///     fips::BlockGroup(1),
+    /// );
+    /// assert_eq!(geoid, expected);
+    /// assert!(Geoid::block_group(8, 59, 1_000_000, 1).is_err());
+    /// assert!(Geoid::block_group(8, 59, 9838, 10).is_err());
+    /// ```
+    pub fn block_group(
+        state: u64,
+        county: u64,
+        tract: u64,
+        block_group: u64,
+    ) -> Result<Geoid, String> {
+        Ok(Geoid::BlockGroup(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::County(check_digits(county, 3, "county")?),
+            fips::CensusTract(check_digits(tract, 6, "census tract")?),
+            fips::BlockGroup(check_digits(block_group, 1, "block group")?),
+        ))
+    }
+
+    /// creates a block Geoid from its state, county and tract codes and its block code,
+    /// which is 4 digits with an optional trailing suffix letter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, HasGeoidString};
+    ///
+    /// let geoid = Geoid::block(8, 59, 9838, "1000").unwrap();
+    /// assert_eq!(geoid.geoid_string(), "080590098381000");
+    /// assert!(Geoid::block(8, 59, 9838, "100").is_err());
+    ///
+    /// // 15-character GEOIDs, and 16 with a suffix letter, round-trip unchanged
+    /// for block in ["080590098380105", "080590098380105B"] {
+    ///     let geoid = Geoid::try_from(block).unwrap();
+    ///     assert_eq!(geoid.geoid_string(), block);
+    /// }
+    /// let suffixed = Geoid::try_from("080590098380105B").unwrap();
+    /// assert_eq!(suffixed, Geoid::block(8, 59, 9838, "0105B").unwrap());
+    /// assert!(Geoid::try_from("0805900983801051").is_err());
+    ///
+    /// // fully-qualified GEOIDs, as in the GEOIDFQ20 column of 2020 TIGER/Lines files
+    /// let fully_qualified = Geoid::try_from("1000000US080590098380105").unwrap();
+    /// assert_eq!(fully_qualified.geoid_string(), "080590098380105");
+    /// ```
+    pub fn block(state: u64, county: u64, tract: u64, block: &str) -> Result<Geoid, String> {
+        let valid_block = block.is_ascii()
+            && matches!(block.len(), 4 | 5)
+            && block[..4].chars().all(|c| c.is_ascii_digit())
+            && block[4..].chars().all(|c| c.is_ascii_alphabetic());
+        if !valid_block {
+            return Err(format!(
+                "block code must be 4 digits with an optional suffix letter, found '{block}'"
+            ));
+        }
+        Ok(Geoid::Block(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::County(check_digits(county, 3, "county")?),
+            fips::CensusTract(check_digits(tract, 6, "census tract")?),
+            fips::Block(String::from(block)),
+        ))
+    }
+
+    /// creates a voting district Geoid from its state and county codes and its voting
+    /// district code, which is 6 letters or digits.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+    ///
+    /// let vtd = Geoid::voting_district(8, 31, "000120").unwrap();
+    /// assert_eq!(vtd.geoid_string(), "08031000120");
+    /// assert_eq!(vtd.to_parent(), Some(Geoid::County(fips::State(8), fips::County(31))));
+    /// assert!(Geoid::county(8, 31).unwrap().is_parent_of(&vtd));
+    /// assert_eq!(
+    ///     Geoid::from_canonical_string("voting_district:08031000120").unwrap(),
+    ///     vtd
+    /// );
+    /// assert!(vtd.truncate_geoid_to_type(&GeoidType::CensusTract).is_err());
+    /// assert!(Geoid::voting_district(8, 31, "120").is_err());
+    /// assert!(Geoid::voting_district(8, 31, "00-120").is_err());
+    /// ```
+    pub fn voting_district(state: u64, county: u64, vtd: &str) -> Result<Geoid, String> {
+        if vtd.len() != 6 || !vtd.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!(
+                "voting district code must be 6 letters or digits, found '{vtd}'"
+            ));
+        }
+        Ok(Geoid::VotingDistrict(
+            fips::State(check_digits(state, 2, "state")?),
+            fips::County(check_digits(county, 3, "county")?),
+            fips::VotingDistrict(String::from(vtd)),
+        ))
+    }
+
     pub fn geoid_type(&self) -> GeoidType {
         match self {
+            Geoid::Nation => GeoidType::Nation,
+            Geoid::Region(_) => GeoidType::Region,
+            Geoid::Division(_) => GeoidType::Division,
             Geoid::State(_) => GeoidType::State,
             Geoid::County(_, _) => GeoidType::County,
             Geoid::CountySubdivision(_, _, _) => GeoidType::CountySubdivision,
@@ -60,6 +409,42 @@ impl Geoid {
             Geoid::CensusTract(_, _, _) => GeoidType::CensusTract,
             Geoid::BlockGroup(_, _, _, _) => GeoidType::BlockGroup,
             Geoid::Block(_, _, _, _) => GeoidType::Block,
+            Geoid::VotingDistrict(_, _, _) => GeoidType::VotingDistrict,
+        }
+    }
+
+    /// the canonical form of this Geoid, so that two representations of the same
+    /// geography compare and hash equal, such as in a join of ACS rows to TIGER/Lines
+    /// geometries. numeric components are stored as numbers, so tract `000100` and
+    /// tract `100` are already equal and are written zero-padded by
+    /// [`HasGeoidString`]. block and voting district codes are stored as written, and
+    /// are normalized with [`fips::Block::normalize`] and
+    /// [`fips::VotingDistrict::normalize`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+    ///
+    /// let tract = GeoidType::CensusTract
+    ///     .geoid_from_slice_of_strings(&[String::from("08"), String::from("031"), String::from("100")])
+    ///     .unwrap();
+    /// assert_eq!(tract, Geoid::try_from("08031000100").unwrap());
+    /// assert_eq!(tract.normalize().geoid_string(), "08031000100");
+    ///
+    /// let lowercase = Geoid::Block(fips::State(8), fips::County(59), fips::CensusTract(9838), fips::Block(String::from("0105b")));
+    /// let uppercase = Geoid::try_from("080590098380105B").unwrap();
+    /// assert_ne!(lowercase, uppercase);
+    /// assert_eq!(lowercase.normalize(), uppercase);
+    ///
+    /// let unpadded = Geoid::Block(fips::State(8), fips::County(59), fips::CensusTract(9838), fips::Block(String::from("105")));
+    /// assert_eq!(unpadded.normalize().geoid_string(), "080590098380105");
+    /// ```
+    pub fn normalize(&self) -> Geoid {
+        match self {
+            Geoid::Block(st, co, tr, bl) => Geoid::Block(*st, *co, *tr, bl.normalize()),
+            Geoid::VotingDistrict(st, co, vtd) => Geoid::VotingDistrict(*st, *co, vtd.normalize()),
+            other => other.clone(),
         }
     }
 
@@ -67,6 +452,119 @@ impl Geoid {
         self.geoid_type().to_string()
     }
 
+    /// writes this Geoid as `type:code`, such as `county:08031`, where the type is the
+    /// [`GeoidType`] name and the code is the GEOID string. unlike the bare GEOID
+    /// string, where a region and a division are both 1 digit, the type is explicit,
+    /// so every Geoid reads back unchanged with [`Geoid::from_canonical_string`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let place = Geoid::Place(fips::State(8), fips::Place(20000));
+    /// assert_eq!(place.to_canonical_string(), "place:0820000");
+    /// assert_eq!(Geoid::Nation.to_canonical_string(), "nation:US");
+    ///
+    /// let geoids = vec![
+    ///     Geoid::Nation,
+    ///     Geoid::region(4).unwrap(),
+    ///     Geoid::division(4).unwrap(),
+    ///     Geoid::County(fips::State(8), fips::County(31)),
+    ///     place,
+    ///     Geoid::CountySubdivision(fips::State(8), fips::County(31), fips::CountySubdivision(90760)),
+    ///     Geoid::block(8, 31, 4102, "1001A").unwrap(),
+    /// ];
+    /// for geoid in geoids {
+    ///     let parsed = Geoid::from_canonical_string(&geoid.to_canonical_string()).unwrap();
+    ///     assert_eq!(parsed, geoid);
+    /// }
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        format!("{}:{}", self.geoid_type(), self.geoid_string())
+    }
+
+    /// reads a Geoid written by [`Geoid::to_canonical_string`]. strings that would not
+    /// be written back the same way, such as `nation:1` or a code with a `+` sign, are
+    /// rejected, so that the round trip is lossless in both directions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let region = Geoid::from_canonical_string("region:1").unwrap();
+    /// let division = Geoid::from_canonical_string("division:1").unwrap();
+    /// assert_eq!(region, Geoid::region(1).unwrap());
+    /// assert_eq!(division, Geoid::division(1).unwrap());
+    ///
+    /// assert!(Geoid::from_canonical_string("08031").is_err());
+    /// assert!(Geoid::from_canonical_string("place:08031").is_err());
+    /// assert!(Geoid::from_canonical_string("township:08031").is_err());
+    /// assert!(Geoid::from_canonical_string("nation:1").is_err());
+    /// ```
+    pub fn from_canonical_string(value: &str) -> Result<Geoid, String> {
+        let (type_name, code) = value.split_once(':').ok_or_else(|| {
+            format!("canonical geoid must have the form 'type:code', found '{value}'")
+        })?;
+        let geoid_type = GeoidType::value_variants()
+            .iter()
+            .find(|t| t.to_string() == type_name)
+            .ok_or_else(|| {
+                format!(
+                    "unknown geoid type '{type_name}' in '{value}', expected one of {}",
+                    GeoidType::value_variants().iter().join(", ")
+                )
+            })?;
+        let geoid = geoid_type.geoid_from_str(code)?;
+        if geoid.to_canonical_string() != value {
+            return Err(format!(
+                "'{value}' is not a canonical geoid, expected '{}'",
+                geoid.to_canonical_string()
+            ));
+        }
+        Ok(geoid)
+    }
+
+    /// parses a comma-delimited list of GEOIDs, such as the `--geoids` argument of the
+    /// command line apps. entries are trimmed and empty entries, such as those left by
+    /// a trailing comma, are skipped, so an empty or blank list is an empty Vec, which
+    /// callers can treat as "no geoids" before anything is run. every malformed entry
+    /// is reported in one error, along with its position in the list.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let geoids = Geoid::parse_list(" 08031, 08059,").unwrap();
+    /// assert_eq!(geoids, vec![
+    ///     Geoid::County(fips::State(8), fips::County(31)),
+    ///     Geoid::County(fips::State(8), fips::County(59)),
+    /// ]);
+    /// assert!(Geoid::parse_list("").unwrap().is_empty());
+    /// assert!(Geoid::parse_list(" , ").unwrap().is_empty());
+    ///
+    /// let error = Geoid::parse_list("08031,8031,08x").unwrap_err();
+    /// assert!(error.contains("'8031' (entry 2)"));
+    /// assert!(error.contains("'08x' (entry 3)"));
+    /// ```
+    pub fn parse_list(value: &str) -> Result<Vec<Geoid>, String> {
+        let (geoids, errors): (Vec<Geoid>, Vec<String>) = value
+            .split(',')
+            .map(str::trim)
+            .enumerate()
+            .filter(|(_, entry)| !entry.is_empty())
+            .map(|(idx, entry)| {
+                Geoid::try_from(entry).map_err(|e| format!("'{entry}' (entry {}): {e}", idx + 1))
+            })
+            .partition_result();
+        if !errors.is_empty() {
+            return Err(format!("malformed geoids in list: {}", errors.join("; ")));
+        }
+        Ok(geoids)
+    }
+
     /// manipulates this GEOID via truncation to transform it's GEOID type.
     ///
     /// GEOID is a hierarchical numeric identifier. we can truncate the values
@@ -88,11 +586,44 @@ impl Geoid {
     /// let result = geoid.truncate_geoid_to_type(&GeoidType::County).unwrap();
     /// assert_eq!(result, Geoid::County(fips::State(8), fips::County(59)))
     /// ```
+    ///
+    /// a block truncates to the block group named by the first digit of its block
+    /// number, with or without a suffix letter.
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString};
+    ///
+    /// for block in ["080590098382014", "080590098382014A"] {
+    ///     let geoid = Geoid::try_from(block).unwrap();
+    ///     let result = geoid.truncate_geoid_to_type(&GeoidType::BlockGroup).unwrap();
+    ///     assert_eq!(result.geoid_string(), "080590098382");
+    /// }
+    /// ```
     pub fn truncate_geoid_to_type(&self, target: &GeoidType) -> Result<Geoid, String> {
         fn _err(src: &GeoidType, dst: &GeoidType) -> String {
             format!("{dst} not a parent type of {src}, cannot truncate geoid.")
         }
+        // regions and divisions are not encoded in the GEOID, so they are looked up
+        match target {
+            GeoidType::Nation => return Ok(Geoid::Nation),
+            GeoidType::Region => {
+                return self
+                    .region_code()
+                    .map(Geoid::Region)
+                    .ok_or_else(|| format!("{self} is not within a census region"))
+            }
+            GeoidType::Division => {
+                return self
+                    .division_code()
+                    .map(Geoid::Division)
+                    .ok_or_else(|| format!("{self} is not within a census division"))
+            }
+            _ => {}
+        }
         match (self, target) {
+            (Geoid::Nation | Geoid::Region(_) | Geoid::Division(_), _) => {
+                Err(_err(&self.geoid_type(), target))
+            }
             (Geoid::State(_), GeoidType::State) => Ok(self.clone()),
             (Geoid::State(_), _) => Err(_err(&self.geoid_type(), target)),
             (Geoid::County(s, _), GeoidType::State) => Ok(Geoid::State(*s)),
@@ -121,33 +652,61 @@ impl Geoid {
                 Ok(Geoid::CensusTract(*s, *c, *t))
             }
             (Geoid::Block(s, c, t, b), GeoidType::BlockGroup) => {
-                // special edge case of truncation, since we have no other operations for
-                // converting between Block and Block Group.
-                let block_str = &b.0[0..1];
-                let bg = block_str
-                    .parse::<u64>()
-                    .map_err(|e| format!("cannot read first digit of block as integer: {e}"))?;
-                let geoid = Geoid::BlockGroup(*s, *c, *t, fips::BlockGroup(bg));
-                Ok(geoid)
+                // the block group is not a component of the block GEOID, it is the
+                // first digit of the block number, see fips::Block::block_group
+                Ok(Geoid::BlockGroup(*s, *c, *t, b.block_group()?))
             }
             (Geoid::Block(_, _, _, _), GeoidType::Block) => Ok(self.clone()),
             (Geoid::Block(_, _, _, _), _) => Err(_err(&self.geoid_type(), target)),
+            (Geoid::VotingDistrict(s, _, _), GeoidType::State) => Ok(Geoid::State(*s)),
+            (Geoid::VotingDistrict(s, c, _), GeoidType::County) => Ok(Geoid::County(*s, *c)),
+            (Geoid::VotingDistrict(_, _, _), GeoidType::VotingDistrict) => Ok(self.clone()),
+            (Geoid::VotingDistrict(_, _, _), _) => Err(_err(&self.geoid_type(), target)),
+        }
+    }
+
+    /// the census division containing this geoid, if any.
+    fn division_code(&self) -> Option<fips::Division> {
+        match self {
+            Geoid::Nation | Geoid::Region(_) => None,
+            Geoid::Division(d) => Some(*d),
+            _ => match self.to_state() {
+                Ok(Geoid::State(s)) => s.division(),
+                _ => None,
+            },
+        }
+    }
+
+    /// the census region containing this geoid, if any.
+    fn region_code(&self) -> Option<fips::Region> {
+        match self {
+            Geoid::Nation => None,
+            Geoid::Region(r) => Some(*r),
+            _ => self.division_code().and_then(|d| d.region()),
         }
     }
 
     /// predicate to filter by hierarchical geoshed.
     pub fn is_parent_of(&self, child: &Geoid) -> bool {
         match (self, child) {
+            (Geoid::Nation, Geoid::Nation) => false,
+            (Geoid::Nation, _) => true,
+            (Geoid::Region(_), Geoid::Region(_)) => false,
+            (Geoid::Region(r), c) => c.region_code() == Some(*r),
+            (Geoid::Division(_), Geoid::Division(_)) => false,
+            (Geoid::Division(d), c) => c.division_code() == Some(*d),
             (Geoid::State(s1), Geoid::County(s2, _)) => s1 == s2,
             (Geoid::State(s1), Geoid::CountySubdivision(s2, _, _)) => s1 == s2,
             (Geoid::State(s1), Geoid::Place(s2, _)) => s1 == s2,
             (Geoid::State(s1), Geoid::CensusTract(s2, _, _)) => s1 == s2,
             (Geoid::State(s1), Geoid::BlockGroup(s2, _, _, _)) => s1 == s2,
             (Geoid::State(s1), Geoid::Block(s2, _, _, _)) => s1 == s2,
+            (Geoid::State(s1), Geoid::VotingDistrict(s2, _, _)) => s1 == s2,
             (Geoid::County(s1, c1), Geoid::CountySubdivision(s2, c2, _)) => s1 == s2 && c1 == c2,
             (Geoid::County(s1, c1), Geoid::CensusTract(s2, c2, _)) => s1 == s2 && c1 == c2,
             (Geoid::County(s1, c1), Geoid::BlockGroup(s2, c2, _, _)) => s1 == s2 && c1 == c2,
             (Geoid::County(s1, c1), Geoid::Block(s2, c2, _, _)) => s1 == s2 && c1 == c2,
+            (Geoid::County(s1, c1), Geoid::VotingDistrict(s2, c2, _)) => s1 == s2 && c1 == c2,
             (Geoid::CensusTract(s1, c1, t1), Geoid::BlockGroup(s2, c2, t2, _)) => {
                 s1 == s2 && c1 == c2 && t1 == t2
             }
@@ -163,6 +722,8 @@ impl Geoid {
     ///
     /// the base case is `None`, which is the parent of `State`, and signifies "no restriction"
     /// in census queries. for all other GeoidTypes, we simply remove the lowest area type.
+    /// above the state level, a division's parent is its region and a region's parent is
+    /// the nation.
     ///
     /// # Note
     ///
@@ -171,6 +732,9 @@ impl Geoid {
     /// which does not imply that all block groups are the first digit of all blocks.
     pub fn to_parent(&self) -> Option<Geoid> {
         match self {
+            Geoid::Nation => None,
+            Geoid::Region(_) => Some(Geoid::Nation),
+            Geoid::Division(d) => d.region().map(Geoid::Region),
             Geoid::State(_) => None,
             Geoid::County(s, _) => Some(Geoid::State(*s)),
             Geoid::CountySubdivision(s, c, _) => Some(Geoid::County(*s, *c)),
@@ -178,23 +742,41 @@ impl Geoid {
             Geoid::CensusTract(s, c, _) => Some(Geoid::County(*s, *c)),
             Geoid::BlockGroup(s, c, t, _) => Some(Geoid::CensusTract(*s, *c, *t)),
             Geoid::Block(s, c, t, _) => Some(Geoid::CensusTract(*s, *c, *t)),
+            Geoid::VotingDistrict(s, c, _) => Some(Geoid::County(*s, *c)),
         }
     }
 
-    pub fn to_state(&self) -> Geoid {
+    /// the state containing this geoid. geoids above the state level contain states
+    /// rather than being contained by one, so they fail.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let jefferson = Geoid::County(fips::State(8), fips::County(59));
+    /// assert_eq!(jefferson.to_state(), Ok(Geoid::State(fips::State(8))));
+    /// assert!(Geoid::Nation.to_state().is_err());
+    /// ```
+    pub fn to_state(&self) -> Result<Geoid, String> {
         match self {
-            Geoid::State(_) => self.clone(),
-            Geoid::County(st, _) => Geoid::State(*st),
-            Geoid::CountySubdivision(st, _, _) => Geoid::State(*st),
-            Geoid::Place(st, _) => Geoid::State(*st),
-            Geoid::CensusTract(st, _, _) => Geoid::State(*st),
-            Geoid::BlockGroup(st, _, _, _) => Geoid::State(*st),
-            Geoid::Block(st, _, _, _) => Geoid::State(*st),
+            Geoid::Nation | Geoid::Region(_) | Geoid::Division(_) => Err(format!(
+                "{} geoid does not lie within a single state",
+                self.geoid_type()
+            )),
+            Geoid::State(_) => Ok(self.clone()),
+            Geoid::County(st, _) => Ok(Geoid::State(*st)),
+            Geoid::CountySubdivision(st, _, _) => Ok(Geoid::State(*st)),
+            Geoid::Place(st, _) => Ok(Geoid::State(*st)),
+            Geoid::CensusTract(st, _, _) => Ok(Geoid::State(*st)),
+            Geoid::BlockGroup(st, _, _, _) => Ok(Geoid::State(*st)),
+            Geoid::Block(st, _, _, _) => Ok(Geoid::State(*st)),
+            Geoid::VotingDistrict(st, _, _) => Ok(Geoid::State(*st)),
         }
     }
 
     pub fn to_state_abbreviation(&self) -> Result<String, String> {
-        let state_fips = match self.to_state() {
+        let state_fips = match self.to_state()? {
             Geoid::State(s) => Ok(s),
             _ => Err(String::from("internal error")),
         }?;
@@ -205,6 +787,10 @@ impl Geoid {
 
     pub fn to_county(&self) -> Result<Geoid, String> {
         match self {
+            Geoid::Nation | Geoid::Region(_) | Geoid::Division(_) => Err(format!(
+                "{} geoid does not contain a county geoid",
+                self.geoid_type()
+            )),
             Geoid::State(_) => Err(String::from("state geoid does not contain a county geoid")),
             Geoid::County(st, ct) => Ok(Geoid::County(*st, *ct)),
             Geoid::CountySubdivision(st, ct, _) => Ok(Geoid::County(*st, *ct)),
@@ -212,11 +798,16 @@ impl Geoid {
             Geoid::CensusTract(st, ct, _) => Ok(Geoid::County(*st, *ct)),
             Geoid::BlockGroup(st, ct, _, _) => Ok(Geoid::County(*st, *ct)),
             Geoid::Block(st, ct, _, _) => Ok(Geoid::County(*st, *ct)),
+            Geoid::VotingDistrict(st, ct, _) => Ok(Geoid::County(*st, *ct)),
         }
     }
 
     pub fn to_census_tract(&self) -> Result<Geoid, String> {
         match self {
+            Geoid::Nation | Geoid::Region(_) | Geoid::Division(_) => Err(format!(
+                "{} geoid does not contain a census tract geoid",
+                self.geoid_type()
+            )),
             Geoid::State(_) => Err(String::from(
                 "state geoid does not contain a census tract geoid",
             )),
@@ -232,6 +823,183 @@ impl Geoid {
             Geoid::CensusTract(st, ct, tr) => Ok(Geoid::CensusTract(*st, *ct, *tr)),
             Geoid::BlockGroup(st, ct, tr, _) => Ok(Geoid::CensusTract(*st, *ct, *tr)),
             Geoid::Block(st, ct, tr, _) => Ok(Geoid::CensusTract(*st, *ct, *tr)),
+            Geoid::VotingDistrict(_, _, _) => Err(String::from(
+                "voting district geoid does not contain a census tract geoid",
+            )),
+        }
+    }
+
+    /// checks that the county of this geoid exists in the geographies of a vintage
+    /// year, failing if it was removed by or before that year, or not yet created,
+    /// per [`COUNTY_CHANGES`]. geoids without a county, such as states and places,
+    /// always pass.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// // Hartford County, Connecticut was replaced by planning regions in 2022
+    /// let hartford = Geoid::County(fips::State(9), fips::County(3));
+    /// assert!(hartford.exists_in_vintage(2020).is_ok());
+    /// let error = hartford.exists_in_vintage(2022).unwrap_err();
+    /// assert!(error.contains("09110, 09120"));
+    ///
+    /// let capitol = Geoid::CensusTract(fips::State(9), fips::County(110), fips::CensusTract(400100));
+    /// assert!(capitol.exists_in_vintage(2022).is_ok());
+    /// assert!(capitol.exists_in_vintage(2021).is_err());
+    ///
+    /// assert!(Geoid::County(fips::State(8), fips::County(31)).exists_in_vintage(2015).is_ok());
+    /// ```
+    pub fn exists_in_vintage(&self, year: u64) -> Result<(), String> {
+        let Ok(Geoid::County(st, ct)) = self.to_county() else {
+            return Ok(());
+        };
+        let changes = COUNTY_CHANGES.iter().filter(|c| c.state == st);
+        for change in changes {
+            if year >= change.year && change.removed.contains(&ct) {
+                let replacements = match change.added {
+                    [] => String::new(),
+                    added => format!(
+                        ", use counties {}",
+                        added
+                            .iter()
+                            .map(|a| Geoid::County(st, *a).geoid_string())
+                            .join(", ")
+                    ),
+                };
+                return Err(format!(
+                    "county {} of geoid {} does not exist in {year} geographies: {} in {}{replacements}",
+                    Geoid::County(st, ct).geoid_string(),
+                    self.geoid_string(),
+                    change.description,
+                    change.year
+                ));
+            }
+            if year < change.year && change.added.contains(&ct) {
+                return Err(format!(
+                    "county {} of geoid {} does not exist in {year} geographies: it was created in {} when {}",
+                    Geoid::County(st, ct).geoid_string(),
+                    self.geoid_string(),
+                    change.year,
+                    change.description
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// the counties in the geographies of vintage `year`, from the counties listed for
+    /// the geographies of `listed_year`, such as the counties an ACS query returned for
+    /// its dataset year. within the states of the listed counties, the counties each
+    /// [`COUNTY_CHANGES`] entry between the two years removed are dropped and the
+    /// ones it added are included, or the reverse when `year` is the earlier one, see
+    /// [`Geoid::exists_in_vintage`]. geoids that are not counties are left out, and
+    /// the counties are returned in order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::Geoid;
+    ///
+    /// let listed_2020 = vec![
+    ///     Geoid::county(8, 31).unwrap(),
+    ///     Geoid::county(9, 1).unwrap(),
+    ///     Geoid::county(9, 3).unwrap(),
+    /// ];
+    /// // the Connecticut counties became planning regions in 2022
+    /// let counties_2022 = Geoid::counties_in_vintage(&listed_2020, 2020, 2022);
+    /// assert_eq!(counties_2022.len(), 10);
+    /// assert!(counties_2022.contains(&Geoid::county(9, 110).unwrap()));
+    /// assert!(!counties_2022.contains(&Geoid::county(9, 3).unwrap()));
+    /// assert_eq!(counties_2022[0], Geoid::county(8, 31).unwrap());
+    ///
+    /// let counties_2020 = Geoid::counties_in_vintage(&counties_2022, 2022, 2020);
+    /// assert_eq!(counties_2020.len(), 9);
+    /// assert!(counties_2020.contains(&Geoid::county(9, 15).unwrap()));
+    /// assert!(counties_2020.iter().all(|c| c.exists_in_vintage(2020).is_ok()));
+    ///
+    /// assert_eq!(Geoid::counties_in_vintage(&listed_2020, 2020, 2021), listed_2020);
+    /// ```
+    pub fn counties_in_vintage(listed: &[Geoid], listed_year: u64, year: u64) -> Vec<Geoid> {
+        let mut counties = listed
+            .iter()
+            .filter(|geoid| matches!(geoid, Geoid::County(..)))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let states = counties
+            .iter()
+            .filter_map(|geoid| match geoid {
+                Geoid::County(st, _) => Some(*st),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let (from, to) = (listed_year.min(year), listed_year.max(year));
+        let changes = COUNTY_CHANGES
+            .iter()
+            .filter(|c| states.contains(&c.state) && from < c.year && c.year <= to);
+        // changes are applied in order, starting from the listed vintage
+        let forward = listed_year <= year;
+        let changes = if forward {
+            changes.collect_vec()
+        } else {
+            changes.rev().collect_vec()
+        };
+        for change in changes {
+            let (removed, added) = if forward {
+                (change.removed, change.added)
+            } else {
+                (change.added, change.removed)
+            };
+            for county in removed {
+                counties.remove(&Geoid::County(change.state, *county));
+            }
+            counties.extend(
+                added
+                    .iter()
+                    .map(|county| Geoid::County(change.state, *county)),
+            );
+        }
+        counties.into_iter().collect_vec()
+    }
+
+    /// the geoids of `geoid_type` within this geoid in the geographies of a vintage
+    /// year, in order, from embedded reference data rather than a census API query.
+    /// the counties of a state, or of the 50 states and DC for the nation, come from
+    /// the county reference, see [`counties`], and the states of the nation are those
+    /// of [`Geoid::all_states`]. other combinations are not embedded and fail.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    ///
+    /// let connecticut = Geoid::State(fips::State(9));
+    /// let counties_2020 = connecticut.children(GeoidType::County, 2020).unwrap();
+    /// assert_eq!(counties_2020.first(), Some(&Geoid::county(9, 1).unwrap()));
+    /// let counties_2022 = connecticut.children(GeoidType::County, 2022).unwrap();
+    /// assert_eq!(counties_2022.first(), Some(&Geoid::county(9, 110).unwrap()));
+    ///
+    /// assert_eq!(Geoid::Nation.children(GeoidType::State, 2020).unwrap().len(), 51);
+    /// assert_eq!(Geoid::Nation.children(GeoidType::County, 2020).unwrap().len(), 3143);
+    /// assert!(connecticut.children(GeoidType::CensusTract, 2020).is_err());
+    /// ```
+    pub fn children(&self, geoid_type: GeoidType, year: u64) -> Result<Vec<Geoid>, String> {
+        match (self, geoid_type) {
+            (Geoid::State(st), GeoidType::County) => counties(*st, year),
+            (Geoid::Nation, GeoidType::State) => Ok(Geoid::all_states()),
+            (Geoid::Nation, GeoidType::County) => {
+                let counties = Geoid::all_states()
+                    .iter()
+                    .map(|state| state.children(GeoidType::County, year))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(counties.into_iter().flatten().collect_vec())
+            }
+            _ => Err(format!(
+                "{} geoids within {} are not in the embedded reference data",
+                geoid_type,
+                self.geoid_string()
+            )),
         }
     }
 }
@@ -239,6 +1007,9 @@ impl Geoid {
 impl HasGeoidString for Geoid {
     fn geoid_string(&self) -> String {
         match self {
+            Geoid::Nation => String::from("US"),
+            Geoid::Region(r) => r.geoid_string(),
+            Geoid::Division(d) => d.geoid_string(),
             Geoid::State(st) => st.geoid_string(),
             Geoid::County(st, ct) => format!("{}{}", st.geoid_string(), ct.geoid_string()),
             Geoid::CountySubdivision(st, ct, cs) => format!(
@@ -268,6 +1039,12 @@ impl HasGeoidString for Geoid {
                 tr.geoid_string(),
                 bl.geoid_string()
             ),
+            Geoid::VotingDistrict(st, ct, vtd) => format!(
+                "{}{}{}",
+                st.geoid_string(),
+                ct.geoid_string(),
+                vtd.geoid_string()
+            ),
         }
     }
 }
@@ -277,3 +1054,14 @@ impl Display for Geoid {
         write!(f, "{}={}", self.variant_name(), self.geoid_string())
     }
 }
+
+/// confirms a FIPS code fits in the number of digits of its GEOID component.
+fn check_digits(value: u64, digits: u32, name: &str) -> Result<u64, String> {
+    if value < 10u64.pow(digits) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "{name} code must fit in {digits} digit(s), found {value}"
+        ))
+    }
+}
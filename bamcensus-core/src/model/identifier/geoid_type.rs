@@ -5,9 +5,14 @@ use clap::ValueEnum;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, ValueEnum, PartialEq, Eq, Hash)]
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, ValueEnum, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum GeoidType {
+    Nation,
+    Region,
+    Division,
     State,
     County,
     CountySubdivision,
@@ -15,11 +20,15 @@ pub enum GeoidType {
     CensusTract,
     BlockGroup,
     Block,
+    VotingDistrict,
 }
 
 impl Display for GeoidType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
+            GeoidType::Nation => String::from("nation"),
+            GeoidType::Region => String::from("region"),
+            GeoidType::Division => String::from("division"),
             GeoidType::State => String::from("state"),
             GeoidType::County => String::from("county"),
             GeoidType::CountySubdivision => String::from("county_subdivision"),
@@ -27,15 +36,74 @@ impl Display for GeoidType {
             GeoidType::CensusTract => String::from("census_tract"),
             GeoidType::BlockGroup => String::from("block_group"),
             GeoidType::Block => String::from("block"),
+            GeoidType::VotingDistrict => String::from("voting_district"),
         };
         write!(f, "{s}")
     }
 }
 
 impl GeoidType {
+    /// reads a GEOID of this type, such as a value from a TIGER/Lines file. padding
+    /// is ignored, as is the summary level prefix of a fully-qualified GEOID, such as
+    /// the `GEOIDFQ20` column of 2020 files.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString};
+    ///
+    /// // a 2020 block, as written in the GEOID20 and GEOIDFQ20 columns of the tabblock20 files
+    /// let block = Geoid::block(8, 31, 4102, "1000").unwrap();
+    /// assert_eq!(GeoidType::Block.geoid_from_str("080310041021000").unwrap(), block);
+    /// assert_eq!(GeoidType::Block.geoid_from_str("1000000US080310041021000").unwrap(), block);
+    /// assert_eq!(GeoidType::Block.geoid_from_str("080310041021000  ").unwrap(), block);
+    ///
+    /// // a water block, numbered in block group 0
+    /// let water = GeoidType::Block.geoid_from_str("060759804010001").unwrap();
+    /// assert_eq!(water.geoid_string(), "060759804010001");
+    ///
+    /// // a block split after the census keeps its suffix letter
+    /// let split = GeoidType::Block.geoid_from_str("080310041021000a").unwrap();
+    /// assert_eq!(split.geoid_string(), "080310041021000A");
+    ///
+    /// let tract = GeoidType::CensusTract.geoid_from_str("1400000US08031004102").unwrap();
+    /// assert_eq!(tract, Geoid::census_tract(8, 31, 4102).unwrap());
+    ///
+    /// assert!(GeoidType::Block.geoid_from_str("08031004102100").is_err());
+    /// assert!(GeoidType::Block.geoid_from_str("1000000US08031004102100").is_err());
+    ///
+    /// // voting districts are 11 characters, as are tracts, so they are only read as
+    /// // such by their own type, here from the GEOID20 column of the vtd20 files
+    /// let vtd = GeoidType::VotingDistrict.geoid_from_str("0803100A001").unwrap();
+    /// assert_eq!(vtd, Geoid::voting_district(8, 31, "00A001").unwrap());
+    /// assert_eq!(
+    ///     Geoid::try_from("08031000120").unwrap().geoid_type(),
+    ///     GeoidType::CensusTract
+    /// );
+    /// ```
     pub fn geoid_from_str(&self, value: &str) -> Result<Geoid, String> {
+        let value = match self {
+            GeoidType::Nation => value.trim(),
+            _ => strip_fully_qualified_prefix(value),
+        };
         let value_len = value.len();
         match self {
+            // TIGER/Lines files use "US" for the nation, while the ACS API uses "1"
+            GeoidType::Nation => match value {
+                "US" | "1" => Ok(Geoid::Nation),
+                _ => Err(format!(
+                    "for nation geoid, expected 'US' or '1', found: {value}"
+                )),
+            },
+            GeoidType::Region | GeoidType::Division => {
+                if value_len != 1 {
+                    Err(format!(
+                        "for {self} geoid, expected 1-digit value, found: {value}"
+                    ))
+                } else {
+                    self.geoid_from_slice_of_strings(&[value.to_string()])
+                }
+            }
             GeoidType::State => {
                 if value_len != 2 {
                     Err(format!(
@@ -110,16 +178,30 @@ impl GeoidType {
                 }
             }
             GeoidType::Block => {
-                if value_len != 15 && value_len != 16 {
+                // 15 characters, or 16 for a block with a suffix letter
+                if !value.is_ascii() || (value_len != 15 && value_len != 16) {
                     Err(format!(
-                        "for block geoid, expected 15 or 16-digit value, found: {value} with length {value_len}"
+                        "for block geoid, expected 15 or 16-character value, found: {value} with length {value_len}"
                     ))
                 } else {
                     self.geoid_from_slice_of_strings(&[
                         value[0..2].to_string(),
                         value[2..5].to_string(),
                         value[5..11].to_string(),
-                        value[11..].to_string(),
+                        value[11..].to_ascii_uppercase(),
+                    ])
+                }
+            }
+            GeoidType::VotingDistrict => {
+                if !value.is_ascii() || value_len != 11 {
+                    Err(format!(
+                        "for voting district geoid, expected 11-character value, found: {value}"
+                    ))
+                } else {
+                    self.geoid_from_slice_of_strings(&[
+                        value[0..2].to_string(),
+                        value[2..5].to_string(),
+                        value[5..11].to_ascii_uppercase(),
                     ])
                 }
             }
@@ -127,6 +209,35 @@ impl GeoidType {
     }
     pub fn geoid_from_slice_of_strings(&self, vals: &[String]) -> Result<Geoid, String> {
         match self {
+            GeoidType::Nation => match vals {
+                [value] => self.geoid_from_str(value),
+                _ => Err(format!(
+                    "for nation-level query, expected 1 geoid column, found: {}",
+                    vals.iter().join(",")
+                )),
+            },
+            GeoidType::Region => {
+                let arr = as_usizes(vals)?;
+                if arr.len() != 1 {
+                    Err(format!(
+                        "for region-level query, expected 1 geoid column, found: {}",
+                        arr.into_iter().join(",")
+                    ))
+                } else {
+                    Ok(Geoid::Region(fips::Region(arr[0])))
+                }
+            }
+            GeoidType::Division => {
+                let arr = as_usizes(vals)?;
+                if arr.len() != 1 {
+                    Err(format!(
+                        "for division-level query, expected 1 geoid column, found: {}",
+                        arr.into_iter().join(",")
+                    ))
+                } else {
+                    Ok(Geoid::Division(fips::Division(arr[0])))
+                }
+            }
             GeoidType::State => {
                 let arr = as_usizes(vals)?;
                 if arr.len() != 1 {
@@ -208,23 +319,47 @@ impl GeoidType {
                     ))
                 }
             }
-            GeoidType::Block => {
-                let arr = as_usizes(vals)?;
-                if arr.len() != 4 {
-                    Err(format!(
-                        "for block-level query, expected 4 geoid columns, found: {}",
-                        arr.into_iter().join(",")
-                    ))
-                } else {
-                    Ok(Geoid::Block(
-                        fips::State(arr[0]),
-                        fips::County(arr[1]),
-                        fips::CensusTract(arr[2]),
-                        fips::Block(format!("{}", arr[3])),
-                    ))
+            GeoidType::Block => match vals {
+                // the block code keeps its leading zeros and any suffix letter, so
+                // it is not read as a number
+                [state, county, tract, block] => {
+                    let arr = as_usizes(&[state.clone(), county.clone(), tract.clone()])?;
+                    Geoid::block(arr[0], arr[1], arr[2], block)
                 }
-            }
+                _ => Err(format!(
+                    "for block-level query, expected 4 geoid columns, found: {}",
+                    vals.iter().join(",")
+                )),
+            },
+            GeoidType::VotingDistrict => match vals {
+                // like block codes, voting district codes may contain letters
+                [state, county, vtd] => {
+                    let arr = as_usizes(&[state.clone(), county.clone()])?;
+                    Geoid::voting_district(arr[0], arr[1], vtd)
+                }
+                _ => Err(format!(
+                    "for voting district-level query, expected 3 geoid columns, found: {}",
+                    vals.iter().join(",")
+                )),
+            },
+        }
+    }
+}
+
+/// removes any padding and the summary level prefix of a fully-qualified GEOID, which
+/// is a 3-digit summary level, a 2-character variant and a 2-character component
+/// followed by "US", such as the `1000000US` of block `1000000US080310041021000`.
+pub(crate) fn strip_fully_qualified_prefix(value: &str) -> &str {
+    let value = value.trim();
+    match value.split_once("US") {
+        Some((prefix, geoid))
+            if prefix.len() == 7
+                && prefix.chars().all(|c| c.is_ascii_alphanumeric())
+                && !geoid.is_empty() =>
+        {
+            geoid
         }
+        _ => value,
     }
 }
 
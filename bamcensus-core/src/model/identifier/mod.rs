@@ -1,13 +1,19 @@
 pub mod fips;
 
+mod county_change;
+mod county_reference;
 mod geoid;
 mod geoid_type;
 mod has_geoid_string;
 mod has_geoid_type;
 mod state_code;
+mod state_selection;
 
+pub use county_change::{CountyChange, COUNTY_CHANGES};
+pub use county_reference::{counties, COUNTY_REFERENCE_YEAR, EARLIEST_COUNTY_YEAR};
 pub use geoid::Geoid;
 pub use geoid_type::GeoidType;
 pub use has_geoid_string::HasGeoidString;
 pub use has_geoid_type::HasGeoidType;
 pub use state_code::StateCode;
+pub use state_selection::StateSelection;
@@ -1,7 +1,7 @@
 use crate::model::identifier::{fips::State, has_geoid_string::HasGeoidString, Geoid};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StateCode {
     Alabama,
@@ -122,9 +122,12 @@ impl TryFrom<Geoid> for StateCode {
     type Error = String;
 
     fn try_from(value: Geoid) -> Result<Self, Self::Error> {
-        match value.to_state() {
+        match value.to_state()? {
             Geoid::State(s) => s.try_into(),
-            _ => Err(String::from("internal error")),
+            other => Err(format!(
+                "{} geoid {other} is not within a single state",
+                other.geoid_type()
+            )),
         }
     }
 }
@@ -0,0 +1,50 @@
+use super::{fips, StateCode};
+
+/// FIPS code of Puerto Rico
+const PUERTO_RICO: u64 = 72;
+
+/// FIPS codes of the island areas: American Samoa, Guam, the Northern Mariana
+/// Islands and the U.S. Virgin Islands
+const ISLAND_AREAS: [u64; 4] = [60, 66, 69, 78];
+
+/// selects which states and state-equivalents are listed by [`super::Geoid::states`].
+/// the default selection is the 50 states and the District of Columbia.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StateSelection {
+    /// leave out Alaska and Hawaii
+    pub exclude_alaska_hawaii: bool,
+    /// leave out the District of Columbia
+    pub exclude_dc: bool,
+    /// add Puerto Rico
+    pub include_puerto_rico: bool,
+    /// add American Samoa, Guam, the Northern Mariana Islands and the U.S. Virgin Islands
+    pub include_island_areas: bool,
+}
+
+impl StateSelection {
+    /// the 48 contiguous states and the District of Columbia
+    pub fn contiguous() -> StateSelection {
+        StateSelection {
+            exclude_alaska_hawaii: true,
+            ..Default::default()
+        }
+    }
+
+    /// the selected state FIPS codes in ascending order
+    pub fn fips_codes(&self) -> Vec<fips::State> {
+        let states = StateCode::ALL.iter().filter(|sc| match sc {
+            StateCode::Alaska | StateCode::Hawaii => !self.exclude_alaska_hawaii,
+            StateCode::DistrictOfColumbia => !self.exclude_dc,
+            _ => true,
+        });
+        let mut codes = states.map(|sc| fips::State::from(*sc)).collect::<Vec<_>>();
+        if self.include_puerto_rico {
+            codes.push(fips::State(PUERTO_RICO));
+        }
+        if self.include_island_areas {
+            codes.extend(ISLAND_AREAS.iter().map(|s| fips::State(*s)));
+        }
+        codes.sort();
+        codes
+    }
+}
@@ -1 +1,15 @@
 pub mod identifier;
+
+mod acs_sentinel;
+mod byte_counter;
+mod coverage_gaps;
+mod download_estimate;
+mod named_value;
+mod result_diff;
+
+pub use acs_sentinel::{is_acs_sentinel, ACS_CONTROLLED_MOE, ACS_SENTINELS};
+pub use byte_counter::ByteCounter;
+pub use coverage_gaps::CoverageGaps;
+pub use download_estimate::{head_content_length, DownloadEstimate};
+pub use named_value::NamedValue;
+pub use result_diff::{ResultDiff, ValueChange};
@@ -0,0 +1,8 @@
+/// a named value in a dataset row, such as an ACS variable or a LODES WAC segment.
+/// used to compare result sets across runs, see [`crate::ops::diff`].
+pub trait NamedValue {
+    /// the name of the variable, unique within a geoid
+    fn value_name(&self) -> String;
+    /// the value as a number, or None if it is not numeric
+    fn numeric_value(&self) -> Option<f64>;
+}
@@ -0,0 +1,80 @@
+use super::identifier::{Geoid, HasGeoidString};
+use std::fmt::Display;
+
+/// the differences between two result sets, see [`crate::ops::diff::diff_results`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResultDiff {
+    /// geoids present only in the second result set
+    pub added: Vec<Geoid>,
+    /// geoids present only in the first result set
+    pub removed: Vec<Geoid>,
+    /// values that differ for geoids present in both result sets
+    pub changed: Vec<ValueChange>,
+}
+
+/// a value that differs between two result sets for the same geoid. a side is None
+/// when the value is missing there or is not numeric.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueChange {
+    pub geoid: Geoid,
+    pub name: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+impl ValueChange {
+    /// the change from before to after, if both are numeric
+    pub fn delta(&self) -> Option<f64> {
+        match (self.before, self.after) {
+            (Some(before), Some(after)) => Some(after - before),
+            _ => None,
+        }
+    }
+}
+
+impl ResultDiff {
+    /// true if the result sets have the same geoids and values
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Display for ResultDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} added, {} removed, {} changed values",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )?;
+        for geoid in self.added.iter() {
+            writeln!(f, "+ {}", geoid.geoid_string())?;
+        }
+        for geoid in self.removed.iter() {
+            writeln!(f, "- {}", geoid.geoid_string())?;
+        }
+        for change in self.changed.iter() {
+            writeln!(f, "~ {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ValueChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or(String::from("-"));
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.geoid.geoid_string(),
+            self.name,
+            show(self.before),
+            show(self.after)
+        )?;
+        if let Some(delta) = self.delta() {
+            write!(f, " ({delta:+})")?;
+        }
+        Ok(())
+    }
+}
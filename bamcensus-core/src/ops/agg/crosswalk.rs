@@ -0,0 +1,160 @@
+use super::NumericAggregation;
+use crate::model::identifier::{Geoid, HasGeoidString};
+use std::collections::HashMap;
+
+/// a many-to-many mapping from geoids of one vintage to geoids of another, such as
+/// 2010 census tracts to 2020 census tracts, weighted by the area that each pair of
+/// source and target geoids have in common. a source geoid that was split maps to
+/// several targets, and several sources that were merged map to the same target.
+#[derive(Clone, Debug, Default)]
+pub struct Crosswalk {
+    /// overlap area of each target geoid, keyed by source geoid
+    overlaps: HashMap<Geoid, Vec<(Geoid, f64)>>,
+}
+
+impl Crosswalk {
+    /// builds a crosswalk from (source, target, overlap area) entries. entries that
+    /// repeat a source and target pair have their areas added together. areas must
+    /// be finite and non-negative.
+    pub fn new(
+        entries: impl IntoIterator<Item = (Geoid, Geoid, f64)>,
+    ) -> Result<Crosswalk, String> {
+        let mut overlaps: HashMap<Geoid, Vec<(Geoid, f64)>> = HashMap::new();
+        for (source, target, area) in entries {
+            if !area.is_finite() || area < 0.0 {
+                return Err(format!(
+                    "crosswalk overlap area from {} to {} must be a non-negative number, found {area}",
+                    source.geoid_string(),
+                    target.geoid_string()
+                ));
+            }
+            let targets = overlaps.entry(source).or_default();
+            match targets.iter_mut().find(|(t, _)| *t == target) {
+                Some((_, total)) => *total += area,
+                None => targets.push((target, area)),
+            }
+        }
+        Ok(Crosswalk { overlaps })
+    }
+
+    /// number of source geoids in the crosswalk
+    pub fn len(&self) -> usize {
+        self.overlaps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overlaps.is_empty()
+    }
+
+    /// the target geoids of a source geoid with the share of the source area that
+    /// falls in each, which sum to 1. a source with no overlap area is split evenly
+    /// across its targets. None if the source is not in the crosswalk.
+    pub fn weights(&self, source: &Geoid) -> Option<Vec<(Geoid, f64)>> {
+        let targets = self.overlaps.get(source)?;
+        let total: f64 = targets.iter().map(|(_, area)| area).sum();
+        let n = targets.len() as f64;
+        let weights = targets
+            .iter()
+            .map(|(target, area)| {
+                let weight = if total > 0.0 { area / total } else { 1.0 / n };
+                (target.clone(), weight)
+            })
+            .collect();
+        Some(weights)
+    }
+
+    /// translates values from source geoids to target geoids.
+    ///
+    /// with [`NumericAggregation::Sum`], values are treated as counts (such as
+    /// population) and each source value is apportioned to its targets by area share.
+    /// with [`NumericAggregation::Mean`], values are treated as rates or averages and
+    /// each target receives the mean of its overlapping source values, weighted by the
+    /// overlap area.
+    ///
+    /// targets are returned in the order they are first reached. source geoids missing
+    /// from the crosswalk are reported in the error collection and are otherwise ignored.
+    ///
+    /// # Example
+    ///
+    /// tract A was split into tracts X and Y, with X taking 75% of its area, while
+    /// tract B was merged into Y.
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::Geoid;
+    /// use bamcensus_core::ops::agg::{Crosswalk, NumericAggregation};
+    ///
+    /// let a = Geoid::census_tract(8, 31, 100).unwrap();
+    /// let b = Geoid::census_tract(8, 31, 200).unwrap();
+    /// let x = Geoid::census_tract(8, 31, 101).unwrap();
+    /// let y = Geoid::census_tract(8, 31, 102).unwrap();
+    /// let crosswalk = Crosswalk::new(vec![
+    ///     (a.clone(), x.clone(), 300.0),
+    ///     (a.clone(), y.clone(), 100.0),
+    ///     (b.clone(), y.clone(), 400.0),
+    /// ])
+    /// .unwrap();
+    ///
+    /// // counts are apportioned by area share, so totals are preserved
+    /// let rows = vec![(a.clone(), 1000.0), (b.clone(), 500.0)];
+    /// let (counts, errors) = crosswalk.translate(&rows, &NumericAggregation::Sum);
+    /// assert!(errors.is_empty());
+    /// assert_eq!(counts, vec![(x.clone(), 750.0), (y.clone(), 750.0)]);
+    ///
+    /// // averages are weighted by overlap area. Y overlaps 100 of A and 400 of B.
+    /// let rows = vec![(a.clone(), 10.0), (b.clone(), 20.0)];
+    /// let (means, _) = crosswalk.translate(&rows, &NumericAggregation::Mean);
+    /// assert_eq!(means, vec![(x.clone(), 10.0), (y.clone(), 18.0)]);
+    ///
+    /// // geoids outside of the crosswalk are reported
+    /// let unknown = Geoid::census_tract(8, 59, 100).unwrap();
+    /// let (_, errors) = crosswalk.translate(&[(unknown, 1.0)], &NumericAggregation::Sum);
+    /// assert_eq!(errors, vec!["geoid 08059000100 is not in the crosswalk"]);
+    /// ```
+    pub fn translate(
+        &self,
+        rows: &[(Geoid, f64)],
+        agg: &NumericAggregation,
+    ) -> (Vec<(Geoid, f64)>, Vec<String>) {
+        let mut order: Vec<Geoid> = vec![];
+        // accumulated per target: apportioned sum, area-weighted sum, overlap area,
+        // and the plain sum and count of source values, used for means when every
+        // overlap has zero area
+        let mut acc: HashMap<Geoid, (f64, f64, f64, f64, f64)> = HashMap::new();
+        let mut errors = vec![];
+        for (source, value) in rows.iter() {
+            let (Some(weights), Some(overlaps)) = (self.weights(source), self.overlaps.get(source))
+            else {
+                errors.push(format!(
+                    "geoid {} is not in the crosswalk",
+                    source.geoid_string()
+                ));
+                continue;
+            };
+            for ((target, share), (_, area)) in weights.into_iter().zip(overlaps.iter()) {
+                let entry = acc.entry(target.clone()).or_insert_with(|| {
+                    order.push(target);
+                    (0.0, 0.0, 0.0, 0.0, 0.0)
+                });
+                entry.0 += value * share;
+                entry.1 += value * area;
+                entry.2 += area;
+                entry.3 += value;
+                entry.4 += 1.0;
+            }
+        }
+        let result = order
+            .into_iter()
+            .map(|target| {
+                let (apportioned, weighted, area, sum, n) =
+                    acc.get(&target).copied().unwrap_or_default();
+                let value = match agg {
+                    NumericAggregation::Sum => apportioned,
+                    NumericAggregation::Mean if area > 0.0 => weighted / area,
+                    NumericAggregation::Mean => sum / n,
+                };
+                (target, value)
+            })
+            .collect();
+        (result, errors)
+    }
+}
@@ -1,3 +1,5 @@
+pub mod crosswalk;
 pub mod numeric_aggregation;
 
+pub use crosswalk::Crosswalk;
 pub use numeric_aggregation::NumericAggregation;
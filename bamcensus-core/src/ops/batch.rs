@@ -0,0 +1,33 @@
+use crate::model::identifier::Geoid;
+use std::collections::HashMap;
+
+/// groups a list of Geoids by the state that contains them, so that work can be
+/// split into per-state batches. input order is preserved within each group. fails
+/// for geoids above the state level, which span several states.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use bamcensus_core::ops::batch::group_geoids_by_state;
+///
+/// let jefferson = Geoid::County(fips::State(8), fips::County(59));
+/// let adams = Geoid::County(fips::State(8), fips::County(1));
+/// let utah = Geoid::State(fips::State(49));
+/// let groups = group_geoids_by_state(&[jefferson.clone(), utah.clone(), adams.clone()]).unwrap();
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[&Geoid::State(fips::State(8))], vec![jefferson, adams]);
+/// assert_eq!(groups[&Geoid::State(fips::State(49))], vec![utah]);
+///
+/// assert!(group_geoids_by_state(&[Geoid::Nation]).is_err());
+/// ```
+pub fn group_geoids_by_state(geoids: &[Geoid]) -> Result<HashMap<Geoid, Vec<Geoid>>, String> {
+    let mut groups: HashMap<Geoid, Vec<Geoid>> = HashMap::new();
+    for geoid in geoids.iter() {
+        groups
+            .entry(geoid.to_state()?)
+            .or_default()
+            .push(geoid.clone());
+    }
+    Ok(groups)
+}
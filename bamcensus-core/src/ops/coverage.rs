@@ -0,0 +1,96 @@
+use crate::model::identifier::Geoid;
+use crate::model::{is_acs_sentinel, CoverageGaps, NamedValue};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// finds the expected geoids within a scope that have no data in a result set, such
+/// as the block groups of a county that a block group run did not return. `expected`
+/// is the authoritative list of geoids within the scope, and every one must be within
+/// it. an expected geoid is missing if it has no rows in the results, and empty if
+/// none of its values is a number other than the sentinels ACS reports for
+/// suppressed estimates, see [`is_acs_sentinel`]. result rows outside of the expected
+/// geoids are ignored. gaps are ordered by geoid.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::Geoid;
+/// use bamcensus_core::model::NamedValue;
+/// use bamcensus_core::ops::coverage::coverage_gaps;
+///
+/// struct Population(f64);
+///
+/// impl NamedValue for Population {
+///     fn value_name(&self) -> String {
+///         String::from("population")
+///     }
+///     fn numeric_value(&self) -> Option<f64> {
+///         Some(self.0)
+///     }
+/// }
+///
+/// let county = Geoid::county(8, 31).unwrap();
+/// let bg = |b: u64| Geoid::block_group(8, 31, 4102, b).unwrap();
+/// let expected = vec![bg(1), bg(2), bg(3)];
+/// let results = vec![
+///     (bg(1), vec![Population(1024.0)]),
+///     (bg(3), vec![Population(-666666666.0)]),
+/// ];
+///
+/// // other negative values, such as a change in an estimate, are data
+/// let changes = vec![(bg(1), vec![Population(-12.0)])];
+/// let change_gaps = coverage_gaps(&county, &[bg(1)], &changes).unwrap();
+/// assert!(change_gaps.is_complete());
+///
+/// let gaps = coverage_gaps(&county, &expected, &results).unwrap();
+/// assert_eq!(gaps.missing, vec![bg(2)]);
+/// assert_eq!(gaps.empty, vec![bg(3)]);
+/// assert_eq!(gaps.gaps(), vec![bg(2), bg(3)]);
+/// assert!(!gaps.is_complete());
+/// assert_eq!(
+///     gaps.to_string(),
+///     "08031: 1 of 3 expected geoids have data, 1 missing, 1 empty\nmissing 080310041022\nempty 080310041023\n"
+/// );
+///
+/// // expected geoids must be within the scope
+/// let elsewhere = vec![Geoid::block_group(8, 59, 9838, 1).unwrap()];
+/// assert!(coverage_gaps(&county, &elsewhere, &results).is_err());
+/// ```
+pub fn coverage_gaps<V: NamedValue>(
+    scope: &Geoid,
+    expected: &[Geoid],
+    results: &[(Geoid, Vec<V>)],
+) -> Result<CoverageGaps, String> {
+    if let Some(outside) = expected
+        .iter()
+        .find(|g| *g != scope && !scope.is_parent_of(g))
+    {
+        return Err(format!(
+            "expected geoid {outside} is not within the scope {scope}"
+        ));
+    }
+    let mut has_value: HashMap<&Geoid, bool> = HashMap::new();
+    for (geoid, values) in results.iter() {
+        let usable = values
+            .iter()
+            .any(|v| v.numeric_value().is_some_and(|x| !is_acs_sentinel(x)));
+        *has_value.entry(geoid).or_default() |= usable;
+    }
+    let expected = expected.iter().unique().sorted().collect_vec();
+    let missing = expected
+        .iter()
+        .filter(|g| !has_value.contains_key(**g))
+        .map(|g| (*g).clone())
+        .collect_vec();
+    let empty = expected
+        .iter()
+        .filter(|g| has_value.get(**g) == Some(&false))
+        .map(|g| (*g).clone())
+        .collect_vec();
+    Ok(CoverageGaps {
+        scope: scope.clone(),
+        expected: expected.len(),
+        missing,
+        empty,
+    })
+}
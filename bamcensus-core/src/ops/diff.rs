@@ -0,0 +1,112 @@
+use crate::model::identifier::Geoid;
+use crate::model::{NamedValue, ResultDiff, ValueChange};
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
+
+/// compares two result sets by geoid, such as the same query run for two vintages.
+/// geoids found in only one set are reported as added or removed. for geoids in both,
+/// each value is matched by name and reported as changed if its numeric value differs,
+/// or if it is present in only one set. values that are not numeric in either set are
+/// not compared. rows for the same geoid are combined. results are ordered by geoid
+/// and then by value name.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::Geoid;
+/// use bamcensus_core::model::NamedValue;
+/// use bamcensus_core::ops::diff::diff_results;
+///
+/// struct Population(f64);
+///
+/// impl NamedValue for Population {
+///     fn value_name(&self) -> String {
+///         String::from("population")
+///     }
+///     fn numeric_value(&self) -> Option<f64> {
+///         Some(self.0)
+///     }
+/// }
+///
+/// let adams = Geoid::county(8, 1).unwrap();
+/// let denver = Geoid::county(8, 31).unwrap();
+/// let jefferson = Geoid::county(8, 59).unwrap();
+/// let before = vec![
+///     (adams.clone(), vec![Population(100.0)]),
+///     (denver.clone(), vec![Population(700.0)]),
+/// ];
+/// let after = vec![
+///     (denver.clone(), vec![Population(715.0)]),
+///     (jefferson.clone(), vec![Population(580.0)]),
+/// ];
+/// let diff = diff_results(&before, &after);
+/// assert_eq!(diff.added, vec![jefferson]);
+/// assert_eq!(diff.removed, vec![adams]);
+/// assert_eq!(diff.changed.len(), 1);
+/// assert_eq!(diff.changed[0].geoid, denver);
+/// assert_eq!(diff.changed[0].delta(), Some(15.0));
+/// assert_eq!(diff.changed[0].to_string(), "08031 population: 700 -> 715 (+15)");
+/// ```
+pub fn diff_results<V: NamedValue>(
+    before: &[(Geoid, Vec<V>)],
+    after: &[(Geoid, Vec<V>)],
+) -> ResultDiff {
+    let before = index_values(before);
+    let after = index_values(after);
+    let added = after
+        .keys()
+        .filter(|g| !before.contains_key(*g))
+        .cloned()
+        .collect_vec();
+    let removed = before
+        .keys()
+        .filter(|g| !after.contains_key(*g))
+        .cloned()
+        .collect_vec();
+    let changed = before
+        .iter()
+        .filter_map(|(geoid, b)| after.get(geoid).map(|a| (geoid, b, a)))
+        .flat_map(|(geoid, b, a)| {
+            b.keys()
+                .chain(a.keys())
+                .unique()
+                .sorted()
+                .filter_map(|name| {
+                    let (before, after) = (b.get(name).copied(), a.get(name).copied());
+                    let differs = match (before, after) {
+                        (Some(x), Some(y)) => x != y,
+                        (None, None) => false,
+                        _ => true,
+                    };
+                    differs.then(|| ValueChange {
+                        geoid: geoid.clone(),
+                        name: name.clone(),
+                        before: before.flatten(),
+                        after: after.flatten(),
+                    })
+                })
+                // a value that is not numeric on either side cannot be compared
+                .filter(|c| c.before.is_some() || c.after.is_some())
+                .collect_vec()
+        })
+        .collect_vec();
+    ResultDiff {
+        added,
+        removed,
+        changed,
+    }
+}
+
+/// the numeric values of each geoid by name, ordered by geoid.
+fn index_values<V: NamedValue>(
+    rows: &[(Geoid, Vec<V>)],
+) -> BTreeMap<Geoid, HashMap<String, Option<f64>>> {
+    let mut index: BTreeMap<Geoid, HashMap<String, Option<f64>>> = BTreeMap::new();
+    for (geoid, values) in rows.iter() {
+        let entry = index.entry(geoid.clone()).or_default();
+        for value in values.iter() {
+            entry.insert(value.value_name(), value.numeric_value());
+        }
+    }
+    index
+}
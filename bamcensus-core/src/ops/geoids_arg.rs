@@ -0,0 +1,25 @@
+use crate::model::identifier::Geoid;
+
+/// parses the comma-delimited geoids of a command line flag, see
+/// [`Geoid::parse_list`]. a list without any geoids, such as `--geoids ""` or
+/// `--geoids ,`, is an error naming the flag rather than an empty run.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::ops::geoids_arg::try_parse_geoids_arg;
+///
+/// let geoids = try_parse_geoids_arg("--geoids", "08031,08059,").unwrap();
+/// assert_eq!(geoids.len(), 2);
+/// assert!(try_parse_geoids_arg("--geoids", "").is_err());
+/// assert!(try_parse_geoids_arg("--geoids", "08031,nope").is_err());
+/// ```
+pub fn try_parse_geoids_arg(flag: &str, value: &str) -> Result<Vec<Geoid>, String> {
+    let geoids = Geoid::parse_list(value).map_err(|e| format!("invalid {flag}: {e}"))?;
+    if geoids.is_empty() {
+        return Err(format!(
+            "invalid {flag}: '{value}' does not contain any geoids"
+        ));
+    }
+    Ok(geoids)
+}
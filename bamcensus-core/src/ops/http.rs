@@ -0,0 +1,70 @@
+use super::user_agent::user_agent;
+
+/// maximum number of HTTP redirects followed by a client from [`client_builder`].
+pub const MAX_REDIRECTS: usize = 10;
+
+/// creates a builder for an HTTP client that downloads from the census servers, such
+/// as TIGER/Lines archives, LODES files and ACS responses. the census host
+/// occasionally answers with a 301 redirect, between paths on www2.census.gov or
+/// from http to https. the builder explicitly follows up to [`MAX_REDIRECTS`]
+/// redirects, so that these resolve transparently, and it may be further configured
+/// (for example, with timeouts) before it is built. requests carry the bamcensus
+/// User-Agent, see [`user_agent`].
+///
+/// a client that does not follow redirects receives the 3xx response instead of the
+/// file. TIGER/Lines downloads with such a client fail with an error naming the
+/// redirect location, rather than attempting to read the redirect page as a zip
+/// archive.
+///
+/// # Example
+///
+/// a request to a path that redirects succeeds with the content of the new location.
+///
+/// ```rust
+/// use bamcensus_core::model::head_content_length;
+/// use bamcensus_core::ops::http;
+/// use std::io::{BufRead, BufReader, Write};
+/// use std::net::TcpListener;
+///
+/// // a local server that redirects /old.zip to /new.zip, which is 42 bytes long
+/// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+/// let address = listener.local_addr().unwrap();
+/// std::thread::spawn(move || {
+///     for stream in listener.incoming() {
+///         let mut stream = stream.unwrap();
+///         let mut request_line = String::new();
+///         let mut reader = BufReader::new(stream.try_clone().unwrap());
+///         reader.read_line(&mut request_line).unwrap();
+///         let mut line = String::new();
+///         while reader.read_line(&mut line).unwrap() > 2 {
+///             line.clear();
+///         }
+///         let response = if request_line.contains("/old.zip") {
+///             "HTTP/1.1 301 Moved Permanently\r\nLocation: /new.zip\r\nContent-Length: 0\r\n\r\n"
+///         } else {
+///             "HTTP/1.1 200 OK\r\nContent-Length: 42\r\n\r\n"
+///         };
+///         stream.write_all(response.as_bytes()).unwrap();
+///     }
+/// });
+///
+/// let uri = format!("http://{address}/old.zip");
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// let client = http::client_builder().build().unwrap();
+/// let estimate = runtime.block_on(head_content_length(&client, &[uri.clone()], 1));
+/// assert_eq!(estimate.bytes, 42);
+/// assert!(estimate.errors.is_empty());
+///
+/// // without following redirects, the redirect is reported as an error
+/// let client = reqwest::Client::builder()
+///     .redirect(reqwest::redirect::Policy::none())
+///     .build()
+///     .unwrap();
+/// let estimate = runtime.block_on(head_content_length(&client, &[uri], 1));
+/// assert!(estimate.errors[0].contains("redirected to /new.zip"));
+/// ```
+pub fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .user_agent(user_agent())
+}
@@ -1 +1,8 @@
 pub mod agg;
+pub mod batch;
+pub mod coverage;
+pub mod diff;
+pub mod geoids_arg;
+pub mod http;
+pub mod progress;
+pub mod user_agent;
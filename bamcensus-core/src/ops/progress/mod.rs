@@ -0,0 +1,3 @@
+pub mod progress_bar;
+
+pub use progress_bar::ProgressBar;
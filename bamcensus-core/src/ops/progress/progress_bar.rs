@@ -0,0 +1,66 @@
+/// a terminal progress bar for long-running operations. when the `progress`
+/// feature is enabled this wraps a kdam bar that renders to stderr. when
+/// disabled, every method is a no-op and nothing is written to stderr.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::ops::progress::ProgressBar;
+///
+/// let mut pb = ProgressBar::new(2, "example").unwrap();
+/// pb.update(1).unwrap();
+/// pb.set_description("example, halfway");
+/// pb.update(1).unwrap();
+/// pb.finish();
+/// ```
+pub struct ProgressBar {
+    #[cfg(feature = "progress")]
+    bar: kdam::Bar,
+}
+
+impl ProgressBar {
+    /// creates a progress bar expecting `total` updates.
+    #[cfg(feature = "progress")]
+    pub fn new(total: usize, desc: impl Into<String>) -> Result<ProgressBar, String> {
+        let bar = kdam::BarBuilder::default()
+            .total(total)
+            .desc(desc.into())
+            .build()
+            .map_err(|e| format!("error building progress bar: {e}"))?;
+        Ok(ProgressBar { bar })
+    }
+
+    /// creates a progress bar expecting `total` updates.
+    #[cfg(not(feature = "progress"))]
+    pub fn new(_total: usize, _desc: impl Into<String>) -> Result<ProgressBar, String> {
+        Ok(ProgressBar {})
+    }
+
+    /// advances the progress bar by `n` steps.
+    pub fn update(&mut self, n: usize) -> Result<(), String> {
+        #[cfg(feature = "progress")]
+        {
+            use kdam::BarExt;
+            self.bar
+                .update(n)
+                .map_err(|e| format!("error updating progress bar: {e}"))?;
+        }
+        #[cfg(not(feature = "progress"))]
+        let _ = n;
+        Ok(())
+    }
+
+    /// replaces the text shown alongside the progress bar.
+    pub fn set_description(&mut self, desc: impl Into<String>) {
+        #[cfg(feature = "progress")]
+        self.bar.set_description(desc.into());
+        #[cfg(not(feature = "progress"))]
+        let _ = desc.into();
+    }
+
+    /// terminates the progress bar line on stderr.
+    pub fn finish(&self) {
+        #[cfg(feature = "progress")]
+        eprintln!();
+    }
+}
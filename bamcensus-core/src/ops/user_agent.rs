@@ -0,0 +1,53 @@
+/// environment variable replacing the User-Agent sent with every request
+pub const USER_AGENT_ENV_VAR: &str = "BAMCENSUS_USER_AGENT";
+/// environment variable adding contact details, such as an email address, to the
+/// default User-Agent
+pub const CONTACT_ENV_VAR: &str = "BAMCENSUS_CONTACT";
+
+/// the User-Agent sent with requests to the census servers, which identifies the
+/// tool and version so that heavy runs are not mistaken for anonymous scrapers.
+/// `BAMCENSUS_USER_AGENT` replaces it entirely, while `BAMCENSUS_CONTACT` adds
+/// contact details to the default, see [`user_agent_from`].
+pub fn user_agent() -> String {
+    let user_agent = std::env::var(USER_AGENT_ENV_VAR).ok();
+    let contact = std::env::var(CONTACT_ENV_VAR).ok();
+    user_agent_from(user_agent.as_deref(), contact.as_deref())
+}
+
+/// the User-Agent for an optional override and optional contact details. blank
+/// values are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::ops::user_agent::user_agent_from;
+///
+/// let version = env!("CARGO_PKG_VERSION");
+/// assert_eq!(
+///     user_agent_from(None, None),
+///     format!("bamcensus/{version} (+https://github.com/NREL/bamcensus)")
+/// );
+/// assert_eq!(
+///     user_agent_from(None, Some("analyst@example.org")),
+///     format!("bamcensus/{version} (+https://github.com/NREL/bamcensus; analyst@example.org)")
+/// );
+/// assert_eq!(user_agent_from(Some("my-pipeline/2.0"), Some("analyst@example.org")), "my-pipeline/2.0");
+/// assert_eq!(user_agent_from(Some(" "), None), user_agent_from(None, None));
+/// ```
+pub fn user_agent_from(user_agent: Option<&str>, contact: Option<&str>) -> String {
+    if let Some(user_agent) = non_blank(user_agent) {
+        return String::from(user_agent);
+    }
+    let version = env!("CARGO_PKG_VERSION");
+    match non_blank(contact) {
+        Some(contact) => {
+            format!("bamcensus/{version} (+https://github.com/NREL/bamcensus; {contact})")
+        }
+        None => format!("bamcensus/{version} (+https://github.com/NREL/bamcensus)"),
+    }
+}
+
+/// the trimmed value, or None if it is missing or blank.
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
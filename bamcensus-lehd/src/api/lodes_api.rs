@@ -1,90 +1,369 @@
-use crate::model::{WacRow, WacSegment, WacValue};
+use crate::model::{DownloadOptions, LodesDataset, RetryPolicy, WacRow, WacSegment, WacValue};
 use crate::ops::lodes_agg;
 use bamcensus_core::{
     model::identifier::{Geoid, GeoidType},
+    model::{head_content_length, ByteCounter, DownloadEstimate},
     ops::agg::NumericAggregation,
+    ops::progress::ProgressBar,
 };
 use csv::ReaderBuilder;
 use flate2::read::GzDecoder;
-use futures::future;
+use futures::{stream, StreamExt, TryStreamExt};
 use itertools::Itertools;
-use kdam::BarExt;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio_util::io::{StreamReader, SyncIoBridge};
 
-/// runs a set of LODES queries. each required LODES file is collected in
-/// memory and deserialized into rows of Geoids with WacValues for each
-/// requested WacSegment. the entire dataset is aggregated to the requested
-/// output GeoidType, which should be
+/// default number of LODES files downloaded at a time
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// rows of Geoids with WacValues, paired with the errors of any LODES files that
+/// could not be read.
+pub type WacResponse = (Vec<(Geoid, Vec<WacValue>)>, Vec<String>);
+
+/// the result of a [`run`], with one variant per LODES dataset type that can be read.
+/// only WAC datasets are read so far, and the RAC and OD variants will be added with
+/// their readers, so matches outside of this crate need a wildcard arm.
+#[non_exhaustive]
+pub enum LodesResult {
+    Wac(WacResponse),
+}
+
+/// runs the LODES downloads for a dataset covering the given geoids, dispatching on
+/// the dataset type. one file is downloaded for each state of the geoids, and a URI
+/// that cannot be built for a state is reported as an error of the result. the
+/// `wac_segments` and `agg` arguments apply to WAC datasets, see [`run_wac`]. the
+/// files are downloaded with the `options`, see [`DownloadOptions`].
+///
+/// only WAC datasets are read so far. RAC and OD datasets return an error until
+/// their readers are implemented.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::Geoid;
+/// use bamcensus_core::ops::http;
+/// use bamcensus_lehd::api::lodes_api::{self, LodesResult};
+/// use bamcensus_lehd::model::{DownloadOptions, LodesDataset};
+///
+/// # async fn example() -> Result<(), String> {
+/// let client = http::client_builder().build().map_err(|e| e.to_string())?;
+/// let dataset = LodesDataset::default();
+/// let states = vec![Geoid::state(8)?, Geoid::state(49)?];
+/// let options = DownloadOptions::default();
+/// match lodes_api::run(&client, &dataset, &states, &[], None, &options).await? {
+///     LodesResult::Wac((rows, errors)) => println!("{} rows, {} errors", rows.len(), errors.len()),
+///     _ => println!("not a WAC dataset"),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn run(
+    client: &Client,
+    dataset: &LodesDataset,
+    geoids: &[Geoid],
+    wac_segments: &[WacSegment],
+    agg: Option<(GeoidType, NumericAggregation)>,
+    options: &DownloadOptions,
+) -> Result<LodesResult, String> {
+    let states = geoids
+        .iter()
+        .map(|g| g.to_state())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unique()
+        .collect_vec();
+    let (queries, uri_errors): (Vec<String>, Vec<String>) = states
+        .iter()
+        .map(|geoid| dataset.create_uri_at(&options.lodes_base_url(), geoid))
+        .partition_result();
+    match dataset {
+        LodesDataset::WAC { .. } => {
+            let (rows, run_errors) = run_wac(client, &queries, wac_segments, agg, options).await?;
+            let errors = uri_errors.into_iter().chain(run_errors).collect_vec();
+            Ok(LodesResult::Wac((rows, errors)))
+        }
+        LodesDataset::RAC { .. } | LodesDataset::OD { .. } => Err(format!(
+            "reading LODES {} files is not yet supported",
+            dataset.dataset_directory()
+        )),
+    }
+}
+
+/// runs a set of LODES queries. each required LODES file is streamed from the
+/// response body through a gzip decoder and deserialized row-by-row into Geoids
+/// with WacValues for each requested WacSegment, so neither the compressed nor
+/// the decompressed file is held in memory. the entire dataset is aggregated
+/// to the requested output GeoidType, which should be
+///
+/// at most `options.concurrency` files are downloaded at a time, so that a national
+/// run does not overwhelm the LEHD server. see [`DEFAULT_CONCURRENCY`]. rows are
+/// returned in the order of the queries.
+///
+/// transient failures, such as timeouts, dropped connections and 5xx responses, are
+/// retried following the `options.retry` policy. a 404 response means the file is not
+/// published for that state and year, and is reported without retrying.
+///
+/// if the options provide a `bytes` counter, the size of each response body read is
+/// added to it, including the bodies of attempts that were retried.
+///
+/// if the options provide a `cancel` token, cancelling it stops the downloads: files
+/// that have not been requested are skipped and downloads in flight, along with
+/// their retries, are dropped. each of these files is an error of the form
+/// "{url}: cancelled", and the rows of the files already read are still returned.
+///
+/// a WAC segment that is not published for the year of a file, which is read from
+/// the file name, is an error before anything is downloaded, see
+/// [`WacSegment::available_since`].
+///
+/// a failure downloading or reading any one file is reported in the error
+/// collection of the response, and the rows of the remaining files are still
+/// returned. the outer `Result` only fails during setup or aggregation.
 pub async fn run_wac(
     client: &Client,
     queries: &[String],
     wac_segments: &[WacSegment],
     agg: Option<(GeoidType, NumericAggregation)>,
-) -> Result<Vec<(Geoid, Vec<WacValue>)>, String> {
+    options: &DownloadOptions,
+) -> Result<WacResponse, String> {
+    let DownloadOptions {
+        concurrency,
+        retry,
+        bytes,
+        cancel,
+        ..
+    } = options;
+    let concurrency = *concurrency;
+    if concurrency == 0 {
+        return Err(String::from(
+            "LODES download concurrency must be at least 1",
+        ));
+    }
+
+    // a segment requested twice would be counted twice when aggregated
+    let wac_segments = wac_segments.iter().copied().unique().collect_vec();
+    validate_segment_years(queries, &wac_segments)?;
+
     // setup progress bar
-    let pb_builder = kdam::BarBuilder::default()
-        .total(queries.len())
-        .desc("LODES downloads");
-    let pb = Arc::new(Mutex::new(pb_builder.build()?));
+    let pb = Arc::new(Mutex::new(ProgressBar::new(
+        queries.len(),
+        "LODES downloads",
+    )?));
 
-    // run each query in parallel
+    // run queries in parallel, up to the concurrency limit
+    let n_cancelled = AtomicUsize::new(0);
     let responses = queries.iter().map(|url| {
         let client = &client;
         let wac_segments = &wac_segments;
         let pb = pb.clone();
+        let n_cancelled = &n_cancelled;
         async move {
-            let res = client
-                .get(url)
-                .send()
-                .await
-                .map_err(|e| format!("failure sending LODES HTTP request: {e}"))?;
-            let gzip_bytes = res
-                .bytes()
-                .await
-                .map_err(|e| format!("failure reading response body: {e}"))?;
-            let mut reader = ReaderBuilder::new().from_reader(GzDecoder::new(&gzip_bytes[..]));
-            let mut result = vec![];
-            for r in reader.deserialize() {
-                let row: WacRow =
-                    r.map_err(|e| format!("failure reading LODES response row: {e}"))?;
-                let geoid = row.geoid()?;
-                let mut row_result = vec![];
-                for segment in wac_segments.iter() {
-                    row_result.push(WacValue::new(*segment, row.get(segment)));
+            let start = Instant::now();
+            let download = async {
+                let mut attempt = 0;
+                loop {
+                    match download_wac(client, url, wac_segments, retry, bytes.as_ref()).await {
+                        Ok(rows) => return Ok(rows),
+                        Err(Failure::Transient(e)) if attempt < retry.max_retries => {
+                            attempt += 1;
+                            let wait = retry.backoff(attempt);
+                            log::warn!(
+                                "{e}, retrying in {wait:.2?} ({attempt}/{})",
+                                retry.max_retries
+                            );
+                            tokio::time::sleep(wait).await;
+                        }
+                        Err(Failure::Transient(e)) if attempt > 0 => {
+                            return Err(format!("{e}, after {attempt} retries"))
+                        }
+                        Err(Failure::Transient(e) | Failure::Permanent(e)) => return Err(e),
+                    }
                 }
-                result.push((geoid, row_result));
-            }
+            };
+            let result = match cancel {
+                // checked first, so that no request is sent once cancelled
+                Some(token) => tokio::select! {
+                    biased;
+                    _ = token.cancelled() => {
+                        n_cancelled.fetch_add(1, Ordering::Relaxed);
+                        return Err(format!("{url}: cancelled"));
+                    }
+                    result = download => result,
+                },
+                None => download.await,
+            }?;
+            log::info!(
+                "LODES {url} read {} rows in {:.2?}",
+                result.len(),
+                start.elapsed()
+            );
 
             // update progress bar
             let mut pb_update = pb
                 .lock()
                 .map_err(|e| format!("failure aquiring progress bar mutex lock: {e}"))?;
-            pb_update
-                .update(1)
-                .map_err(|e| format!("failure on pb update: {e}"))?;
+            pb_update.update(1)?;
             pb_update.set_description(url.split('/').next_back().unwrap_or_default());
 
             Ok(result)
         }
     });
-    eprintln!(); // progress bar terminated
 
-    // join query result
-    let response_rows = future::join_all(responses)
+    // join query result, keeping rows from every file that succeeded
+    let (response_rows_nested, errors): (Vec<Vec<_>>, Vec<String>) = stream::iter(responses)
+        .buffered(concurrency)
+        .collect::<Vec<_>>()
         .await
         .into_iter()
-        .collect::<Result<Vec<_>, String>>()?
-        .into_iter()
-        .flatten()
-        .collect_vec();
+        .partition_result();
+    let response_rows = response_rows_nested.into_iter().flatten().collect_vec();
+    if let Ok(pb) = pb.lock() {
+        pb.finish();
+    }
+    let n_cancelled = n_cancelled.into_inner();
+    if n_cancelled > 0 {
+        log::warn!(
+            "LODES run cancelled, {} of {} files were not read",
+            n_cancelled,
+            queries.len()
+        );
+    }
 
     // if requested, aggregate the result
     let aggregated_rows = match agg {
         Some((output_geoid_type, agg)) => {
             lodes_agg::aggregate_lodes_wac(&response_rows, output_geoid_type, agg)?
         }
-        None => response_rows.to_vec(),
+        None => response_rows,
     };
-    Ok(aggregated_rows)
+    Ok((aggregated_rows, errors))
+}
+
+/// checks that each WAC segment is published for the year of every LODES file, read
+/// from the end of its name, as in `co_wac_S000_JT00_2020.csv.gz`. files named
+/// otherwise, such as on a mirror with another layout, are not checked.
+fn validate_segment_years(queries: &[String], wac_segments: &[WacSegment]) -> Result<(), String> {
+    let unavailable = queries
+        .iter()
+        .filter_map(|url| {
+            let name = url.split('/').next_back()?.strip_suffix(".csv.gz")?;
+            name.rsplit('_').next()?.parse::<u64>().ok()
+        })
+        .unique()
+        .flat_map(|year| {
+            wac_segments
+                .iter()
+                .filter_map(move |s| s.validate_year(year).err())
+        })
+        .collect_vec();
+    if unavailable.is_empty() {
+        Ok(())
+    } else {
+        Err(unavailable.join(", "))
+    }
+}
+
+/// a failed attempt to download a LODES file.
+enum Failure {
+    /// a timeout, dropped connection or server error, which may succeed on retry
+    Transient(String),
+    /// a missing file or invalid content, which will fail again on retry
+    Permanent(String),
+}
+
+/// makes one attempt at downloading and reading a LODES WAC file.
+async fn download_wac(
+    client: &Client,
+    url: &str,
+    wac_segments: &[WacSegment],
+    retry: &RetryPolicy,
+    bytes: Option<&ByteCounter>,
+) -> Result<Vec<(Geoid, Vec<WacValue>)>, Failure> {
+    log::debug!("requesting {url}");
+    let res = client
+        .get(url)
+        .timeout(retry.timeout)
+        .send()
+        .await
+        .map_err(|e| {
+            Failure::Transient(format!("failure sending LODES HTTP request for {url}: {e}"))
+        })?;
+    let status = res.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(Failure::Permanent(format!(
+            "LODES file {url} not found (HTTP 404), it is not published for this state and year"
+        )));
+    }
+    if status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::REQUEST_TIMEOUT
+    {
+        return Err(Failure::Transient(format!(
+            "LODES HTTP request for {url} failed with status {status}"
+        )));
+    }
+    let res = res
+        .error_for_status()
+        .map_err(|e| Failure::Permanent(format!("LODES HTTP request for {url} failed: {e}")))?;
+
+    // bridge the async response body into a blocking reader so that the
+    // gzip decoder and csv reader can pull bytes as rows are parsed
+    let bytes = bytes.cloned();
+    let body = res
+        .bytes_stream()
+        .inspect_ok(move |chunk| {
+            if let Some(bytes) = &bytes {
+                bytes.add(chunk.len() as u64);
+            }
+        })
+        .map_err(std::io::Error::other);
+    let body_reader = SyncIoBridge::new(StreamReader::new(body));
+    let segments = wac_segments.to_vec();
+    tokio::task::spawn_blocking(move || {
+        deserialize_wac_rows(GzDecoder::new(body_reader), &segments)
+    })
+    .await
+    .map_err(|e| Failure::Permanent(format!("failure joining LODES read task for {url}: {e}")))?
+    .map_err(|e| match e {
+        Failure::Transient(e) => Failure::Transient(format!("{url}: {e}")),
+        Failure::Permanent(e) => Failure::Permanent(format!("{url}: {e}")),
+    })
+}
+
+/// estimates the size of a [`run_wac`] for the queries without downloading anything,
+/// from the `Content-Length` header of a HEAD request to each LODES file, with at
+/// most `concurrency` requests at a time.
+pub async fn estimate(client: &Client, queries: &[String], concurrency: usize) -> DownloadEstimate {
+    head_content_length(client, queries, concurrency).await
+}
+
+/// reads WAC rows from an uncompressed CSV source, keeping only the
+/// values of the requested WacSegments. failures reading the source, such as
+/// a dropped connection or a truncated gzip stream, are transient, while rows
+/// that cannot be deserialized are not.
+fn deserialize_wac_rows<R: Read>(
+    source: R,
+    wac_segments: &[WacSegment],
+) -> Result<Vec<(Geoid, Vec<WacValue>)>, Failure> {
+    let mut reader = ReaderBuilder::new().from_reader(source);
+    let mut result = vec![];
+    for r in reader.deserialize() {
+        let row: WacRow = r.map_err(|e| {
+            let message = format!("failure reading LODES response row: {e}");
+            match e.kind() {
+                csv::ErrorKind::Io(_) => Failure::Transient(message),
+                _ => Failure::Permanent(message),
+            }
+        })?;
+        let geoid = row.geoid().map_err(Failure::Permanent)?;
+        let row_result = wac_segments
+            .iter()
+            .map(|segment| WacValue::new(*segment, row.get(segment)))
+            .collect_vec();
+        result.push((geoid, row_result));
+    }
+    Ok(result)
 }
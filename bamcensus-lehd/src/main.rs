@@ -1,9 +1,13 @@
-use bamcensus_core::model::identifier::{Geoid, GeoidType};
+use bamcensus_core::model::identifier::{Geoid, GeoidType, StateSelection};
 use bamcensus_core::ops::agg::NumericAggregation;
-use bamcensus_lehd::api::lodes_api;
+use bamcensus_core::ops::geoids_arg::try_parse_geoids_arg;
+use bamcensus_core::ops::http;
+use bamcensus_lehd::api::lodes_api::{self, LodesResult};
 use bamcensus_lehd::model::{
-    LodesDataset, LodesEdition, LodesJobType, WacSegment, WorkplaceSegment,
+    DownloadOptions, LodesDataset, LodesEdition, LodesJobType, RetryPolicy, WacSegment,
+    WorkplaceSegment,
 };
+use clap::error::ErrorKind;
 use clap::Parser;
 
 // todo: top level here should be a LEHD command
@@ -16,6 +20,13 @@ pub struct LodesCliArgs {
     /// states to download by FIPS code. omit to download all states.
     #[arg(short, long)]
     geoids: Option<String>,
+    /// without --geoids, download only the 48 contiguous states and DC, leaving out
+    /// Alaska and Hawaii
+    #[arg(long, default_value_t = false, conflicts_with = "geoids")]
+    contiguous: bool,
+    /// without --geoids, leave out the District of Columbia
+    #[arg(long, default_value_t = false, conflicts_with = "geoids")]
+    exclude_dc: bool,
     /// LODES are created in editions, see website for details. LODES8 by default if not provided.
     #[arg(long)]
     edition: Option<LodesEdition>,
@@ -25,24 +36,34 @@ pub struct LodesCliArgs {
     /// WAC job type defined in LODES schema documentation
     #[arg(long)]
     jobtype: Option<LodesJobType>,
+    /// comma-delimited list of workplace area characteristic segments, each of which
+    /// is aggregated separately, see LODES documentation
+    #[arg(long, default_value_t = String::from("C000"))]
+    wac_segments: String,
     /// level to aggregate result value
     #[arg(long)]
     agg_geoid_type: Option<GeoidType>,
     /// function to aggregate result value
     #[arg(long)]
     agg_fn: Option<NumericAggregation>,
+    /// maximum number of LODES files to download at a time
+    #[arg(long, default_value_t = lodes_api::DEFAULT_CONCURRENCY)]
+    concurrency: usize,
     // todo: use clap.Parser's subcommand structures to flip between WAC, OD, and RAC data since they
     // are structurally different
 }
 
 impl LodesCliArgs {
-    pub fn get_state_geoids(&self) -> Result<Vec<Geoid>, String> {
+    pub fn get_state_geoids(&self) -> Vec<Geoid> {
         match &self.geoids {
-            Some(s) => s
-                .split(',')
-                .map(Geoid::try_from)
-                .collect::<Result<Vec<_>, _>>(),
-            None => Ok(Geoid::all_states()),
+            Some(s) => try_parse_geoids_arg("--geoids", s).unwrap_or_else(|e| {
+                clap::Error::raw(ErrorKind::ValueValidation, format!("{e}\n")).exit()
+            }),
+            None => Geoid::states(&StateSelection {
+                exclude_alaska_hawaii: self.contiguous,
+                exclude_dc: self.exclude_dc,
+                ..Default::default()
+            }),
         }
     }
 }
@@ -62,29 +83,44 @@ async fn main() {
         segment,
         year,
     };
-    let wac_segments = vec![WacSegment::C000];
-    let state_codes = args.get_state_geoids().unwrap();
-    let agg_fn = args.agg_fn.unwrap_or_default();
-    let output_geoid_type = args.agg_geoid_type.unwrap_or(GeoidType::Block);
-    let queries = state_codes
-        .iter()
-        .map(|s| dataset.create_uri(s))
+    let wac_segments = args
+        .wac_segments
+        .split(',')
+        .map(WacSegment::try_from)
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
+    let state_codes = args.get_state_geoids();
+    let agg_fn = args.agg_fn.unwrap_or_default();
+    let output_geoid_type = args.agg_geoid_type.unwrap_or(GeoidType::Block);
 
     println!("executing LODES download");
-    let client = reqwest::Client::new();
-    let agg_rows = lodes_api::run_wac(
+    let client = http::client_builder().build().unwrap();
+    let result = lodes_api::run(
         &client,
-        &queries,
+        &dataset,
+        &state_codes,
         &wac_segments,
         Some((output_geoid_type, agg_fn)),
+        &DownloadOptions {
+            concurrency: args.concurrency,
+            retry: RetryPolicy::from_env().unwrap(),
+            ..Default::default()
+        },
     )
     .await
     .unwrap();
+    let LodesResult::Wac((agg_rows, errors)) = result else {
+        panic!("expected WAC rows for a WAC dataset");
+    };
 
     let n_res = agg_rows.len();
     println!("{n_res} agg rows");
+    if !errors.is_empty() {
+        println!("LODES ERRORS");
+        for error in errors.iter() {
+            println!("{error}");
+        }
+    }
     println!("queries:");
     for (geoid, values) in agg_rows.iter() {
         println!("{geoid}");
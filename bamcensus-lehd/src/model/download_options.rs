@@ -0,0 +1,81 @@
+use super::{RetryPolicy, BASE_URL};
+use crate::api::lodes_api::DEFAULT_CONCURRENCY;
+use bamcensus_core::model::ByteCounter;
+use tokio_util::sync::CancellationToken;
+
+/// settings of the LODES downloads of a run, see [`crate::api::lodes_api::run`]. the
+/// defaults download [`DEFAULT_CONCURRENCY`] files at a time with the default
+/// [`RetryPolicy`], and each setting is changed with a builder method.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_lehd::model::{DownloadOptions, RetryPolicy};
+///
+/// let options = DownloadOptions::default().with_concurrency(2);
+/// assert_eq!(options.concurrency, 2);
+/// assert_eq!(options.retry, RetryPolicy::default());
+/// assert!(options.bytes.is_none());
+/// assert_eq!(options.lodes_base_url(), "https://lehd.ces.census.gov/data/lodes");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    /// maximum number of files downloaded at a time
+    pub concurrency: usize,
+    /// how transient download failures are retried
+    pub retry: RetryPolicy,
+    /// running total of the bytes of the files downloaded
+    pub bytes: Option<ByteCounter>,
+    /// root of the LODES directory files are downloaded from, such as a mirror, in
+    /// place of [`BASE_URL`]
+    pub base_url: Option<String>,
+    /// token that stops the downloads once cancelled
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        DownloadOptions {
+            concurrency: DEFAULT_CONCURRENCY,
+            retry: RetryPolicy::default(),
+            bytes: None,
+            base_url: None,
+            cancel: None,
+        }
+    }
+}
+
+impl DownloadOptions {
+    pub fn with_concurrency(mut self, concurrency: usize) -> DownloadOptions {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> DownloadOptions {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_bytes(mut self, bytes: ByteCounter) -> DownloadOptions {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> DownloadOptions {
+        self.base_url = Some(String::from(base_url));
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> DownloadOptions {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// the root of the LODES directory of the run, the `base_url` if provided,
+    /// otherwise [`BASE_URL`].
+    pub fn lodes_base_url(&self) -> String {
+        self.base_url
+            .clone()
+            .unwrap_or_else(|| String::from(BASE_URL))
+    }
+}
@@ -1,5 +1,5 @@
 use super::{LodesEdition, LodesJobType, OdPart, WorkplaceSegment, BASE_URL, LATEST_YEAR};
-use bamcensus_core::model::identifier::{Geoid, GeoidType, StateCode};
+use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString, StateCode};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -97,11 +97,106 @@ impl LodesDataset {
         }
     }
 
+    /// confirms that LODES publishes this dataset for the state of a geoid. LODES covers
+    /// the 50 states and the District of Columbia, and some states are missing WAC and
+    /// OD data for certain years. the missing years depend on the edition, as listed in
+    /// the technical documentation of each edition. callers that run many states can
+    /// use this to skip states without data instead of requesting files that do not
+    /// exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::Geoid;
+    /// use bamcensus_lehd::model::{LodesDataset, LodesEdition, LodesJobType, OdPart, WorkplaceSegment};
+    ///
+    /// let dataset = LodesDataset::WAC {
+    ///     edition: LodesEdition::Lodes8,
+    ///     job_type: LodesJobType::JT00,
+    ///     segment: WorkplaceSegment::S000,
+    ///     year: 2002,
+    /// };
+    /// assert!(dataset.check_coverage(&Geoid::state(8).unwrap()).is_ok());
+    /// // no WAC data for Arkansas in 2002
+    /// assert!(dataset.check_coverage(&Geoid::state(5).unwrap()).is_err());
+    /// // Puerto Rico is not covered
+    /// assert!(dataset.check_coverage(&Geoid::state(72).unwrap()).is_err());
+    ///
+    /// // OD data has the same gaps, which grew in LODES8 with Alaska from 2017
+    /// let od = |edition| LodesDataset::OD {
+    ///     edition,
+    ///     job_type: LodesJobType::JT00,
+    ///     od_part: OdPart::Main,
+    ///     year: 2018,
+    /// };
+    /// let alaska = Geoid::state(2).unwrap();
+    /// assert!(od(LodesEdition::Lodes8).check_coverage(&alaska).is_err());
+    /// assert!(od(LodesEdition::Lodes8).check_coverage(&Geoid::state(8).unwrap()).is_ok());
+    /// ```
+    pub fn check_coverage(&self, geoid: &Geoid) -> Result<(), String> {
+        let state = geoid.to_state()?;
+        let sc: StateCode = state.clone().try_into().map_err(|_| {
+            format!(
+                "LODES does not publish data for state {}",
+                state.geoid_string()
+            )
+        })?;
+        self.validate_availability(&sc)
+    }
+
+    /// fails if the technical documentation of the edition lists the state as missing
+    /// from this dataset in its year, see [`coverage_gaps`].
+    fn validate_availability(&self, state_code: &StateCode) -> Result<(), String> {
+        let (edition, year) = match self {
+            LodesDataset::OD { edition, year, .. } => (edition, year),
+            LodesDataset::RAC { edition, year, .. } => (edition, year),
+            LodesDataset::WAC { edition, year, .. } => (edition, year),
+        };
+        let missing = coverage_gaps(edition, self)
+            .iter()
+            .any(|(state, first, last)| state == state_code && (*first..=*last).contains(year));
+        if missing {
+            Err(format!(
+                "{edition} {} is not available in {year} for {} (code {})",
+                self.dataset_directory().to_uppercase(),
+                state_code.to_full_name(),
+                state_code.to_fips_string()
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     /// creates a URI to a LODES datasets based on the directory and file
     /// naming conventions described in the LODESTechDoc8.1.pdf file.
     /// see <https://lehd.ces.census.gov/data/lodes/LODES8/LODESTechDoc8.1.pdf>
     pub fn create_uri(&self, geoid: &Geoid) -> Result<String, String> {
-        let sc: StateCode = geoid.to_state().try_into()?;
+        self.create_uri_at(BASE_URL, geoid)
+    }
+
+    /// creates a URI as in [`LodesDataset::create_uri`] under another root of the
+    /// LODES directory, such as a mirror.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_core::model::identifier::Geoid;
+    /// use bamcensus_lehd::model::{LodesDataset, LodesEdition, LodesJobType, WorkplaceSegment};
+    ///
+    /// let dataset = LodesDataset::WAC {
+    ///     edition: LodesEdition::Lodes8,
+    ///     job_type: LodesJobType::JT00,
+    ///     segment: WorkplaceSegment::S000,
+    ///     year: 2020,
+    /// };
+    /// let uri = dataset
+    ///     .create_uri_at("http://localhost:8080/", &Geoid::state(8).unwrap())
+    ///     .unwrap();
+    /// assert_eq!(uri, "http://localhost:8080/LODES8/co/wac/co_wac_S000_JT00_2020.csv.gz");
+    /// ```
+    pub fn create_uri_at(&self, root: &str, geoid: &Geoid) -> Result<String, String> {
+        let root = root.trim_end_matches('/');
+        let sc: StateCode = geoid.to_state()?.try_into()?;
         let state_code = sc.to_state_abbreviation();
         match self {
             LodesDataset::OD {
@@ -110,7 +205,7 @@ impl LodesDataset {
                 od_part,
                 year,
             } => {
-                validate_availability(*year, &sc)?;
+                self.validate_availability(&sc)?;
                 let filename = format!(
                     "{}_od_{}_{}_{}.csv.gz",
                     state_code.to_lowercase(),
@@ -120,7 +215,7 @@ impl LodesDataset {
                 );
                 let uri = format!(
                     "{}/{}/{}/{}/{}",
-                    BASE_URL,
+                    root,
                     edition,
                     state_code.to_lowercase(),
                     self.dataset_directory(),
@@ -143,7 +238,7 @@ impl LodesDataset {
                 );
                 let uri = format!(
                     "{}/{}/{}/{}/{}",
-                    BASE_URL,
+                    root,
                     edition,
                     state_code.to_lowercase(),
                     self.dataset_directory(),
@@ -157,7 +252,7 @@ impl LodesDataset {
                 segment,
                 year,
             } => {
-                validate_availability(*year, &sc)?;
+                self.validate_availability(&sc)?;
                 let filename = format!(
                     "{}_wac_{}_{}_{}.csv.gz",
                     state_code.to_lowercase(),
@@ -167,7 +262,7 @@ impl LodesDataset {
                 );
                 let uri = format!(
                     "{}/{}/{}/{}/{}",
-                    BASE_URL,
+                    root,
                     edition,
                     state_code.to_lowercase(),
                     self.dataset_directory(),
@@ -237,31 +332,42 @@ impl LodesDataset {
     }
 }
 
-/// as outlined in the tech doc, some states do not have WAC or OD data for certain years
+/// states and inclusive year ranges missing from the WAC and OD data of LODES8,
 /// see <https://lehd.ces.census.gov/data/lodes/LODES8/LODESTechDoc8.1.pdf>
-fn validate_availability(year: u64, state_code: &StateCode) -> Result<(), String> {
-    let err = || {
-        Err(format!(
-            "WAC is not available in {} for {} (code {})",
-            year,
-            state_code.to_full_name(),
-            state_code.to_fips_string()
-        ))
-    };
-    match (year, state_code) {
-        (2002, StateCode::Arkansas) => err(),
-        (2002, StateCode::NewHampshire) => err(),
-        (y, StateCode::Arizona) if in_range_exclusive(y, 2002, 2003) => err(),
-        (y, StateCode::Mississippi) if in_range_exclusive(y, 2002, 2003) => err(),
-        (y, StateCode::DistrictOfColumbia) if in_range_exclusive(y, 2002, 2009) => err(),
-        (y, StateCode::Massachusetts) if in_range_exclusive(y, 2002, 2010) => err(),
-        (y, StateCode::Alaska) if in_range_exclusive(y, 2017, 2020) => err(),
-        (y, StateCode::Arkansas) if in_range_exclusive(y, 2019, 2020) => err(),
-        (y, StateCode::Mississippi) if in_range_exclusive(y, 2019, 2020) => err(),
-        _ => Ok(()),
-    }
-}
+const LODES8_GAPS: [(StateCode, u64, u64); 9] = [
+    (StateCode::Arkansas, 2002, 2002),
+    (StateCode::NewHampshire, 2002, 2002),
+    (StateCode::Arizona, 2002, 2003),
+    (StateCode::Mississippi, 2002, 2003),
+    (StateCode::DistrictOfColumbia, 2002, 2009),
+    (StateCode::Massachusetts, 2002, 2010),
+    (StateCode::Alaska, 2017, 2020),
+    (StateCode::Arkansas, 2019, 2020),
+    (StateCode::Mississippi, 2019, 2020),
+];
+
+/// states and inclusive year ranges missing from the WAC and OD data of LODES6 and
+/// LODES7, which end before the gaps of LODES8 in Arkansas and Mississippi, see
+/// <https://lehd.ces.census.gov/data/lodes/LODES7/LODESTechDoc7.5.pdf>
+const LODES7_GAPS: [(StateCode, u64, u64); 7] = [
+    (StateCode::Arkansas, 2002, 2002),
+    (StateCode::NewHampshire, 2002, 2002),
+    (StateCode::Arizona, 2002, 2003),
+    (StateCode::Mississippi, 2002, 2003),
+    (StateCode::DistrictOfColumbia, 2002, 2009),
+    (StateCode::Massachusetts, 2002, 2010),
+    (StateCode::Alaska, 2017, 2019),
+];
 
-fn in_range_exclusive(y: u64, min: u64, max: u64) -> bool {
-    min <= y && y <= max
+/// the known coverage gaps of a dataset in an edition. RAC files are published for
+/// every state and year.
+fn coverage_gaps(
+    edition: &LodesEdition,
+    dataset: &LodesDataset,
+) -> &'static [(StateCode, u64, u64)] {
+    match (edition, dataset) {
+        (_, LodesDataset::RAC { .. }) => &[],
+        (LodesEdition::Lodes8, _) => &LODES8_GAPS,
+        (LodesEdition::Lodes6 | LodesEdition::Lodes7, _) => &LODES7_GAPS,
+    }
 }
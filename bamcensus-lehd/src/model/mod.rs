@@ -1,4 +1,5 @@
 pub mod constants;
+mod download_options;
 mod lodes_dataset;
 mod lodes_edition;
 mod lodes_job_type;
@@ -6,16 +7,19 @@ mod od_job_segment;
 mod od_part;
 pub mod od_row;
 pub mod rac_row;
+mod retry_policy;
 mod wac_row;
 mod wac_segment;
 mod wac_value;
 mod workplace_segment;
 
+pub use download_options::DownloadOptions;
 pub use lodes_dataset::LodesDataset;
 pub use lodes_edition::LodesEdition;
 pub use lodes_job_type::LodesJobType;
 pub use od_job_segment::OdJobSegment;
 pub use od_part::OdPart;
+pub use retry_policy::RetryPolicy;
 pub use wac_row::WacRow;
 pub use wac_segment::WacSegment;
 pub use wac_value::WacValue;
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+/// how failed LODES downloads are retried. a download that fails with a transient
+/// error, such as a timeout, a dropped connection or a 5xx response, is retried up
+/// to `max_retries` times, waiting `initial_backoff` before the first retry and
+/// doubling the wait before each retry after that. a 404 response means the file
+/// is not published, so it is never retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// number of retries after the first attempt
+    pub max_retries: u32,
+    /// wait before the first retry
+    pub initial_backoff: Duration,
+    /// limit on the time taken by one attempt, including reading the entire file
+    pub timeout: Duration,
+}
+
+impl RetryPolicy {
+    /// environment variable overriding [`RetryPolicy::DEFAULT_MAX_RETRIES`]
+    pub const MAX_RETRIES_ENV_VAR: &'static str = "BAMCENSUS_LODES_RETRIES";
+    /// environment variable overriding [`RetryPolicy::DEFAULT_TIMEOUT_SECS`]
+    pub const TIMEOUT_SECS_ENV_VAR: &'static str = "BAMCENSUS_LODES_TIMEOUT_SECS";
+    pub const DEFAULT_MAX_RETRIES: u32 = 3;
+    pub const DEFAULT_INITIAL_BACKOFF_MILLIS: u64 = 1000;
+    pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+    /// the default policy, with the number of retries and the timeout overridden by
+    /// the [`RetryPolicy::MAX_RETRIES_ENV_VAR`] and [`RetryPolicy::TIMEOUT_SECS_ENV_VAR`]
+    /// environment variables when set.
+    pub fn from_env() -> Result<RetryPolicy, String> {
+        let default = RetryPolicy::default();
+        let max_retries = match std::env::var(Self::MAX_RETRIES_ENV_VAR) {
+            Ok(value) => value.trim().parse::<u32>().map_err(|e| {
+                format!(
+                    "{} must be a number of retries, found '{value}': {e}",
+                    Self::MAX_RETRIES_ENV_VAR
+                )
+            })?,
+            Err(_) => default.max_retries,
+        };
+        let timeout = match std::env::var(Self::TIMEOUT_SECS_ENV_VAR) {
+            Ok(value) => value
+                .trim()
+                .parse::<u64>()
+                .map(Duration::from_secs)
+                .map_err(|e| {
+                    format!(
+                        "{} must be a number of seconds, found '{value}': {e}",
+                        Self::TIMEOUT_SECS_ENV_VAR
+                    )
+                })?,
+            Err(_) => default.timeout,
+        };
+        Ok(RetryPolicy {
+            max_retries,
+            timeout,
+            ..default
+        })
+    }
+
+    /// the wait before a retry, where `retry` counts from 1.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_lehd::model::RetryPolicy;
+    /// use std::time::Duration;
+    ///
+    /// let policy = RetryPolicy::default();
+    /// assert_eq!(policy.backoff(1), Duration::from_secs(1));
+    /// assert_eq!(policy.backoff(2), Duration::from_secs(2));
+    /// assert_eq!(policy.backoff(3), Duration::from_secs(4));
+    /// ```
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: Self::DEFAULT_MAX_RETRIES,
+            initial_backoff: Duration::from_millis(Self::DEFAULT_INITIAL_BACKOFF_MILLIS),
+            timeout: Duration::from_secs(Self::DEFAULT_TIMEOUT_SECS),
+        }
+    }
+}
@@ -153,6 +153,52 @@ impl WacSegment {
         }
     }
 
+    /// the first LODES year with values for this segment, or None if it is published
+    /// for every year. per the LODES technical documentation, the race, ethnicity,
+    /// educational attainment and sex segments are only available for 2009 and later.
+    pub fn available_since(&self) -> Option<u64> {
+        match self {
+            Self::CR01
+            | Self::CR02
+            | Self::CR03
+            | Self::CR04
+            | Self::CR05
+            | Self::CR07
+            | Self::CT01
+            | Self::CT02
+            | Self::CD01
+            | Self::CD02
+            | Self::CD03
+            | Self::CD04
+            | Self::CS01
+            | Self::CS02 => Some(2009),
+            _ => None,
+        }
+    }
+
+    /// confirms this segment has values for the given LODES year.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_lehd::model::WacSegment;
+    ///
+    /// assert!(WacSegment::C000.validate_year(2005).is_ok());
+    /// assert!(WacSegment::CD01.validate_year(2009).is_ok());
+    /// assert_eq!(
+    ///     WacSegment::CD01.validate_year(2005),
+    ///     Err(String::from("WAC segment CD01 is not available before 2009, found year 2005"))
+    /// );
+    /// ```
+    pub fn validate_year(&self, year: u64) -> Result<(), String> {
+        match self.available_since() {
+            Some(since) if year < since => Err(format!(
+                "WAC segment {self} is not available before {since}, found year {year}"
+            )),
+            _ => Ok(()),
+        }
+    }
+
     pub fn naics(&self) -> Option<Vec<u64>> {
         match self {
             Self::CNS01 => Some(vec![11]),
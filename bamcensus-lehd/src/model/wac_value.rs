@@ -1,4 +1,5 @@
 use super::WacSegment;
+use bamcensus_core::model::NamedValue;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -25,3 +26,13 @@ impl Display for WacValue {
         )
     }
 }
+
+impl NamedValue for WacValue {
+    fn value_name(&self) -> String {
+        self.segment.to_string()
+    }
+
+    fn numeric_value(&self) -> Option<f64> {
+        Some(self.value)
+    }
+}
@@ -1,14 +1,16 @@
 use crate::model::{WacSegment, WacValue};
 use bamcensus_core::{
-    model::identifier::{Geoid, GeoidType},
-    ops::agg::NumericAggregation,
+    model::identifier::{Geoid, GeoidType, HasGeoidString},
+    ops::agg::{Crosswalk, NumericAggregation},
+    ops::progress::ProgressBar,
 };
-use itertools::Itertools;
-use kdam::BarExt;
+use itertools::{Either, Itertools};
 use std::collections::HashMap;
 
 /// groups rows to the target Geoid hierarchy level and then
-/// applies the provided aggregation function to the grouped WacValues.
+/// applies the provided aggregation function to the grouped WacValues. every
+/// WacSegment is aggregated separately. geoids are returned in the order they
+/// first appear in, each with its segments in the order of the input values.
 ///
 /// # Example
 ///
@@ -46,6 +48,24 @@ use std::collections::HashMap;
 ///     assert_eq!(v_a.value, v_b.value);
 ///   }
 /// }
+///
+/// // several segments of blocks in two tracts are aggregated side by side
+/// let segments = [WacSegment::C000, WacSegment::CA01, WacSegment::CNS01];
+/// let block = |tract, block, counts: [f64; 3]| {
+///   let values = segments.iter().zip(counts).map(|(s, c)| WacValue::new(*s, c)).collect();
+///   (Geoid::block(8, 31, tract, block).unwrap(), values)
+/// };
+/// let rows = vec![
+///   block(4102, "1000", [10.0, 2.0, 1.0]),
+///   block(4101, "1000", [7.0, 3.0, 0.0]),
+///   block(4102, "1001", [5.0, 1.0, 4.0]),
+/// ];
+/// let result = lodes_agg::aggregate_lodes_wac(&rows, GeoidType::CensusTract, NumericAggregation::Sum).unwrap();
+/// let tracts = result.iter().map(|(g, _)| g.clone()).collect::<Vec<_>>();
+/// assert_eq!(tracts, vec![Geoid::census_tract(8, 31, 4102).unwrap(), Geoid::census_tract(8, 31, 4101).unwrap()]);
+/// let (segs, values): (Vec<_>, Vec<_>) = result[0].1.iter().map(|v| (v.segment, v.value)).unzip();
+/// assert_eq!(segs, segments.to_vec());
+/// assert_eq!(values, vec![15.0, 3.0, 5.0]);
 /// ```
 pub fn aggregate_lodes_wac(
     rows: &[(Geoid, Vec<WacValue>)],
@@ -73,66 +93,143 @@ pub fn aggregate_lodes_wac(
         ));
     }
 
-    // nested groupby operation collected into a hashmap
-    let mut grouped: HashMap<Geoid, HashMap<WacSegment, Vec<f64>>> = HashMap::new();
+    // nested groupby operation collected into a hashmap, remembering the order that
+    // geoids first appear in. segments are kept in the order of the input values.
+    let mut order: Vec<Geoid> = vec![];
+    let mut grouped: HashMap<Geoid, Vec<(WacSegment, Vec<f64>)>> = HashMap::new();
     let n_geoid_oks = geoid_oks.len();
     let group_iter_desc = format!("LODES - geoids to {target}");
-    let pb1_builder = kdam::BarBuilder::default()
-        .total(n_geoid_oks)
-        .desc(group_iter_desc);
-    let mut pb1 = pb1_builder
-        .build()
-        .map_err(|e| format!("error building progress bar: {e}"))?;
+    let mut pb1 = ProgressBar::new(n_geoid_oks, group_iter_desc)?;
 
     for (geoid, values) in geoid_oks.into_iter() {
+        let segments = grouped.entry(geoid.clone()).or_insert_with(|| {
+            order.push(geoid);
+            vec![]
+        });
         for wac in values.iter() {
-            match grouped.get_mut(&geoid) {
-                Some(inner) => match inner.get_mut(&wac.segment) {
-                    Some(inner_vec) => {
-                        inner_vec.push(wac.value);
-                    }
-                    None => {
-                        let _ = inner.insert(wac.segment, vec![wac.value]);
-                    }
-                },
-                None => {
-                    let mut map = HashMap::new();
-                    map.insert(wac.segment, vec![wac.value]);
-                    grouped.insert(geoid.clone(), map);
-                }
+            match segments.iter_mut().find(|(s, _)| *s == wac.segment) {
+                Some((_, inner_vec)) => inner_vec.push(wac.value),
+                None => segments.push((wac.segment, vec![wac.value])),
             }
         }
-        pb1.update(1)
-            .map_err(|e| format!("error updating progress bar: {e}"))?;
+        pb1.update(1)?;
     }
-    eprintln!();
+    pb1.finish();
 
     // flattended into vector collection
-    let n_grouped = grouped.len();
+    let n_grouped = order.len();
     let reduce_desc = format!("LODES - aggregate by {agg}");
-    let pb2_builder = kdam::BarBuilder::default()
-        .total(n_grouped)
-        .desc(reduce_desc);
-    let mut pb2 = pb2_builder
-        .build()
-        .map_err(|e| format!("error building progress bar: {e}"))?;
-    let output: Result<Vec<(Geoid, Vec<WacValue>)>, String> = grouped
+    let mut pb2 = ProgressBar::new(n_grouped, reduce_desc)?;
+    let output: Result<Vec<(Geoid, Vec<WacValue>)>, String> = order
         .into_iter()
-        .map(|(geoid, map)| {
-            let values = map
+        .map(|geoid| {
+            let values = grouped
+                .remove(&geoid)
+                .unwrap_or_default()
                 .into_iter()
                 .map(|(seg, values)| {
-                    // let mut mut_values = values;
                     let value = agg.aggregate(&mut values.into_iter());
                     WacValue::new(seg, value)
                 })
                 .collect_vec();
-            pb2.update(1)
-                .map_err(|e| format!("error updating progress bar: {e}"))?;
+            pb2.update(1)?;
             Ok((geoid, values))
         })
         .collect::<Result<Vec<_>, _>>();
-    eprintln!(); // end progress bar
+    pb2.finish();
 
     output
 }
+
+/// translates WAC rows from the geoids of one vintage to another, such as 2010 to
+/// 2020 census tracts. WAC values are job counts, so each value is apportioned to
+/// the target geoids by area share, see [`Crosswalk::translate`]. rows whose geoid is
+/// not in the crosswalk are reported in the error collection.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::Geoid;
+/// use bamcensus_core::ops::agg::Crosswalk;
+/// use bamcensus_lehd::model::{WacSegment, WacValue};
+/// use bamcensus_lehd::ops::lodes_agg;
+///
+/// let tract_2010 = Geoid::census_tract(8, 31, 4102).unwrap();
+/// let (a, b) = (Geoid::census_tract(8, 31, 4103).unwrap(), Geoid::census_tract(8, 31, 4104).unwrap());
+/// let crosswalk = Crosswalk::new(vec![
+///     (tract_2010.clone(), a.clone(), 3.0),
+///     (tract_2010.clone(), b.clone(), 1.0),
+/// ])
+/// .unwrap();
+/// let rows = vec![(tract_2010.clone(), vec![WacValue::new(WacSegment::C000, 100.0)])];
+/// let (result, errors) = lodes_agg::translate_lodes_wac(&rows, &crosswalk);
+/// assert!(errors.is_empty());
+/// assert_eq!(result[0].0, a);
+/// assert_eq!(result[0].1[0].value, 75.0);
+/// assert_eq!(result[1].0, b);
+/// assert_eq!(result[1].1[0].value, 25.0);
+///
+/// // each segment is apportioned, and a geoid outside of the crosswalk is reported once
+/// let elsewhere = Geoid::census_tract(8, 59, 100).unwrap();
+/// let segments = |c000: f64, cns01: f64| vec![WacValue::new(WacSegment::C000, c000), WacValue::new(WacSegment::CNS01, cns01)];
+/// let rows = vec![(tract_2010, segments(100.0, 40.0)), (elsewhere, segments(10.0, 1.0))];
+/// let (result, errors) = lodes_agg::translate_lodes_wac(&rows, &crosswalk);
+/// assert_eq!(errors, vec!["geoid 08059000100 is not in the crosswalk"]);
+/// assert_eq!(result[0].1[1].segment, WacSegment::CNS01);
+/// assert_eq!(result[0].1[1].value, 30.0);
+/// assert_eq!(result[1].1[1].value, 10.0);
+/// ```
+pub fn translate_lodes_wac(
+    rows: &[(Geoid, Vec<WacValue>)],
+    crosswalk: &Crosswalk,
+) -> (Vec<(Geoid, Vec<WacValue>)>, Vec<String>) {
+    // rows outside of the crosswalk are reported once, rather than once per segment
+    let (rows, errors): (Vec<_>, Vec<_>) =
+        rows.iter()
+            .partition_map(|(source, values)| match crosswalk.weights(source) {
+                Some(weights) => Either::Left((source, values, weights)),
+                None => Either::Right(format!(
+                    "geoid {} is not in the crosswalk",
+                    source.geoid_string()
+                )),
+            });
+    let order = rows
+        .iter()
+        .flat_map(|(_, _, weights)| weights.iter().map(|(target, _)| target.clone()))
+        .unique()
+        .collect_vec();
+    let segments = rows
+        .iter()
+        .flat_map(|(_, values, _)| values.iter().map(|v| v.segment))
+        .unique()
+        .collect_vec();
+
+    // each segment is a job count, translated on its own
+    let mut translated: HashMap<Geoid, Vec<WacValue>> = HashMap::new();
+    for segment in segments.into_iter() {
+        let segment_rows = rows
+            .iter()
+            .flat_map(|(source, values, _)| {
+                values
+                    .iter()
+                    .filter(|v| v.segment == segment)
+                    .map(|v| ((*source).clone(), v.value))
+            })
+            .collect_vec();
+        let (segment_values, _) = crosswalk.translate(&segment_rows, &NumericAggregation::Sum);
+        for (target, value) in segment_values.into_iter() {
+            translated
+                .entry(target)
+                .or_default()
+                .push(WacValue::new(segment, value));
+        }
+    }
+    let result = order
+        .into_iter()
+        .map(|geoid| {
+            let values = translated.remove(&geoid).unwrap_or_default();
+            (geoid, values)
+        })
+        .collect_vec();
+    (result, errors)
+}
@@ -6,7 +6,10 @@ from shapely import wkt
 
 def run_wac_tiger(year: int, **kwds):
     data = api.run_wac_tiger_python(year, **kwds)
-    df = pd.DataFrame.from_dict(data, orient="index")
+    # one row per (geoid, segment) key, both also stored as columns
+    df = pd.DataFrame.from_dict(data, orient="index").reset_index(drop=True)
+    if not kwds.get("with_geometry", True):
+        return df
     df["geometry"] = df.geometry.apply(wkt.loads)
     df = gpd.GeoDataFrame(df, crs="EPSG:4326")
     return df
@@ -15,7 +18,8 @@ def run_wac_tiger(year: int, **kwds):
 def run_acs_tiger(year: int, **kwds):
     print(api.__all__)
     data = api.run_acs_tiger_python(year, **kwds)
-    df = pd.DataFrame.from_dict(data, orient="index")
+    # one row per (geoid, variable name) key, both also stored as columns
+    df = pd.DataFrame.from_dict(data, orient="index").reset_index(drop=True)
     df["geometry"] = df.geometry.apply(wkt.loads)
     df = gpd.GeoDataFrame(df, crs="EPSG:4326")
     return df
@@ -1,14 +1,23 @@
+// pyo3 0.22 #[pyfunction] expansion triggers a false positive for this lint
+#![allow(clippy::useless_conversion)]
+
+use crate::cancel;
 use bamcensus::app::acs_tiger;
+use bamcensus::model::geometry_output::GeometryOutput;
+use bamcensus::model::join_options::JoinOptions;
+use bamcensus_acs::model::api_token_from_env;
 use bamcensus_acs::model::AcsApiQueryParams;
 use bamcensus_acs::model::AcsGeoidQuery;
 use bamcensus_acs::model::AcsType;
-use bamcensus_core::model::identifier::Geoid;
+use bamcensus_core::model::identifier::{Geoid, StateSelection};
+use bamcensus_tiger::model::tiger_base_url_from_env;
 use itertools::Itertools;
 use pyo3::types::IntoPyDict;
 use pyo3::types::PyDict;
 use pyo3::types::PyNone;
 use pyo3::{exceptions::PyException, prelude::*};
 use serde::de;
+use tokio_util::sync::CancellationToken;
 use wkt::ToWkt;
 
 #[pyfunction]
@@ -27,18 +36,23 @@ pub fn run_acs_tiger_python<'a>(
     })?;
     // default: populations total
     let acs_get_query = kwds.map_or(Ok(vec![String::from("B01001_001E")]), |m| {
-        if m.contains("wac_segments")? {
-            get_comma_separated("wac_segments", m)
+        if m.contains("acs_query")? {
+            let acs_query = get_string("acs_query", m)?;
+            Ok::<_, PyErr>(acs_query.split(',').map(String::from).collect_vec())
         } else {
             Ok(vec![String::from("B01001_001E")])
         }
     })?;
 
-    let geoids_string: String = kwds.map_or(Ok(String::from("")), |m| get_string("geoids", m))?;
-    let geoids = geoids_string
-        .split(',')
-        .map(Geoid::try_from)
-        .collect::<Result<Vec<_>, String>>()
+    let geoids_string: String = kwds.map_or(Ok(String::from("")), |m| {
+        if m.contains("geoids")? {
+            get_string("geoids", m)
+        } else {
+            Ok(String::from(""))
+        }
+    })?;
+    // an empty or blank geoids argument means national, the same as omitting it
+    let geoids = Geoid::parse_list(&geoids_string)
         .map_err(|e| PyException::new_err(format!("failure decoding geoids argument: {e}")))?;
 
     let wildcard = kwds.map_or(Ok(None), |m| {
@@ -48,13 +62,74 @@ pub fn run_acs_tiger_python<'a>(
             Ok(None)
         }
     })?;
-    let acs_api_token = kwds.map_or(Ok(None), |m| {
-        if m.contains("acs_api_token")? {
-            get_string_deserializable("acs_api_token", m)
+    let acs_api_token = kwds
+        .map_or(Ok(None), |m| {
+            if m.contains("acs_api_token")? {
+                get_string_deserializable("acs_api_token", m)
+            } else {
+                Ok(None)
+            }
+        })?
+        .or_else(api_token_from_env);
+    let geoid_column = kwds.map_or(Ok(None), |m| {
+        if m.contains("geoid_column")? {
+            get_string("geoid_column", m).map(Some)
         } else {
             Ok(None)
         }
     })?;
+    let geometry = kwds.map_or(Ok(GeometryOutput::default()), |m| {
+        if m.contains("geometry")? {
+            get_string_deserializable("geometry", m)
+        } else {
+            Ok(GeometryOutput::default())
+        }
+    })?;
+    let tiger_year = kwds.map_or(Ok(None), |m| {
+        if m.contains("tiger_year")? {
+            get_u64("tiger_year", m).map(Some)
+        } else {
+            Ok(None)
+        }
+    })?;
+    let include_name = kwds.map_or(Ok(false), |m| {
+        if m.contains("include_name")? {
+            get_bool("include_name", m)
+        } else {
+            Ok(false)
+        }
+    })?;
+    let keep_unmatched = kwds.map_or(Ok(false), |m| {
+        if m.contains("keep_unmatched")? {
+            get_bool("keep_unmatched", m)
+        } else {
+            Ok(false)
+        }
+    })?;
+    let functioning_mcds_only = kwds.map_or(Ok(false), |m| {
+        if m.contains("functioning_mcds_only")? {
+            get_bool("functioning_mcds_only", m)
+        } else {
+            Ok(false)
+        }
+    })?;
+    // roots of the ACS API and TIGER/Lines directory, such as mirrors
+    let acs_base_url = kwds.map_or(Ok(None), |m| {
+        if m.contains("acs_base_url")? {
+            get_string("acs_base_url", m).map(Some)
+        } else {
+            Ok(None)
+        }
+    })?;
+    let tiger_base_url = kwds
+        .map_or(Ok(None), |m| {
+            if m.contains("tiger_base_url")? {
+                get_string("tiger_base_url", m).map(Some)
+            } else {
+                Ok(None)
+            }
+        })?
+        .or_else(tiger_base_url_from_env);
 
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -63,9 +138,12 @@ pub fn run_acs_tiger_python<'a>(
             PyException::new_err(format!("failure creating async rust tokio runtime: {e}"))
         })?;
 
-    // if no geoids are supplied we can run a query across the entire ACS dataset
+    // if no geoids are supplied we can run a query across the entire ACS dataset.
+    // wildcards that the API only accepts within a state, such as block groups, are
+    // run as one query per state, covering the same states as Geoid::all_states
     let queries = if geoids.is_empty() {
-        vec![AcsGeoidQuery::new(None, wildcard).unwrap()]
+        let states = StateSelection::default().fips_codes();
+        AcsGeoidQuery::split_by_state(None, wildcard, &states).map_err(PyException::new_err)?
     } else {
         geoids
             .into_iter()
@@ -73,20 +151,45 @@ pub fn run_acs_tiger_python<'a>(
             .collect_vec()
     };
 
-    // run ACS queries and collect ACS/TIGER joined Rows
-    let results = queries
+    // cancelled on a KeyboardInterrupt, see cancel::block_on
+    let cancel_token = CancellationToken::new();
+    let options = JoinOptions {
+        geoid_column,
+        transform: geometry.transform(),
+        tiger_year,
+        keep_unmatched,
+        tiger_base_url,
+        cancel: Some(cancel_token.clone()),
+        ..Default::default()
+    };
+
+    let queries = queries
         .into_iter()
         .map(|q| {
-            let query_params = AcsApiQueryParams::new(
-                None,
+            AcsApiQueryParams::new(
+                acs_base_url.clone(),
                 year,
                 acs_type,
                 acs_get_query.clone(),
                 q,
                 acs_api_token.clone(),
-            );
-            let future = acs_tiger::run(&query_params);
-            let result = runtime.block_on(future).map_err(|e| {
+            )
+            .with_include_name(include_name)
+        })
+        .collect_vec();
+    // each query is run on its own, so the states are filtered ahead of the runs
+    let queries = if functioning_mcds_only {
+        acs_tiger::functioning_mcd_queries(&queries).map_err(PyException::new_err)?
+    } else {
+        queries
+    };
+
+    // run ACS queries and collect ACS/TIGER joined Rows
+    let results = queries
+        .into_iter()
+        .map(|query_params| {
+            let future = acs_tiger::run(&query_params, &options);
+            let result = cancel::block_on(&runtime, py, &cancel_token, future)?.map_err(|e| {
                 PyException::new_err(format!("failure running LODES WAC + TIGER workflow: {e}"))
             })?;
             if !result.tiger_errors.is_empty() {
@@ -102,7 +205,7 @@ pub fn run_acs_tiger_python<'a>(
         })
         .collect::<Result<Vec<_>, _>>()?;
 
-    //
+    // rows are keyed by (geoid, variable name), as a geoid has one row per variable
     let vals = results
         .into_iter()
         .flatten()
@@ -111,7 +214,8 @@ pub fn run_acs_tiger_python<'a>(
             let value_json = row.acs_value.value;
 
             dict.set_item("geoid", row.geoid.to_string())?;
-            dict.set_item("name", row.acs_value.name)?;
+            let name = row.acs_value.name;
+            dict.set_item("name", name.clone())?;
             // dict.set_item("value", value_json.to_object(py)) <-- doesn't work, hence
             // we unpack each JSON value and serialize via ToPyObject on it's underlying
             // standard rust data structure implementation
@@ -131,33 +235,17 @@ pub fn run_acs_tiger_python<'a>(
                     dict.set_item("value", value_json.to_string())
                 }
             }?;
-            dict.set_item("geometry", row.geometry.to_wkt().to_string())?;
-            Ok((row.geoid.to_string(), dict.to_object(py)))
+            match &row.geometry {
+                Some(g) => dict.set_item("geometry", g.to_wkt().to_string()),
+                None => dict.set_item("geometry", PyNone::get_bound(py)),
+            }?;
+            Ok(((row.geoid.to_string(), name), dict.to_object(py)))
         })
         .collect::<PyResult<Vec<_>>>()?;
     let out_dict = vals.into_py_dict_bound(py);
     Ok(out_dict)
 }
 
-fn get_comma_separated<T>(key: &str, map: &Bound<'_, PyDict>) -> PyResult<Vec<T>>
-where
-    T: de::DeserializeOwned,
-{
-    let ss: String = get_string(key, map)?;
-    let result = ss
-        .split(',')
-        .map(|s| {
-            serde_json::from_str(s)
-                .map_err(|e| format!("failure decoding comma-separated arguments in '{key}': {e}"))
-        })
-        .collect::<Result<Vec<T>, String>>();
-    result.map_err(|e| {
-        PyException::new_err(format!(
-            "failure decoding '{key}' argument as comma-separated list: {e}"
-        ))
-    })
-}
-
 fn get_string(key: &str, map: &Bound<'_, PyDict>) -> PyResult<String> {
     let item_opt = map
         .get_item(key)
@@ -172,6 +260,34 @@ fn get_string(key: &str, map: &Bound<'_, PyDict>) -> PyResult<String> {
     Ok(string)
 }
 
+fn get_u64(key: &str, map: &Bound<'_, PyDict>) -> PyResult<u64> {
+    let item_opt = map
+        .get_item(key)
+        .map_err(|e| PyException::new_err(format!("failure retreiving key {key}: {e}")))?;
+    let item = match item_opt {
+        None => Err(PyException::new_err(format!("key {key} not present"))),
+        Some(item) => Ok(item),
+    }?;
+    let value: u64 = item.extract().map_err(|e| {
+        PyException::new_err(format!("value at {key} is not an integer. error: {e}"))
+    })?;
+    Ok(value)
+}
+
+fn get_bool(key: &str, map: &Bound<'_, PyDict>) -> PyResult<bool> {
+    let item_opt = map
+        .get_item(key)
+        .map_err(|e| PyException::new_err(format!("failure retreiving key {key}: {e}")))?;
+    let item = match item_opt {
+        None => Err(PyException::new_err(format!("key {key} not present"))),
+        Some(item) => Ok(item),
+    }?;
+    let value: bool = item.extract().map_err(|e| {
+        PyException::new_err(format!("value at {key} is not a boolean. error: {e}"))
+    })?;
+    Ok(value)
+}
+
 fn get_string_deserializable<T>(key: &str, map: &Bound<'_, PyDict>) -> PyResult<T>
 where
     T: de::DeserializeOwned,
@@ -186,7 +302,10 @@ where
     let string: String = item
         .extract()
         .map_err(|e| PyException::new_err(format!("value at {key} is not string. error: {e}")))?;
-    let t: T = serde_json::from_str(string.as_str()).map_err(|e| {
+    // accept both a JSON string, such as '"county"', and the bare value, such as 'county'
+    let decoded = serde_json::from_str(string.as_str())
+        .or_else(|_| serde_json::from_value(serde_json::Value::String(string.clone())));
+    let t: T = decoded.map_err(|e| {
         PyException::new_err(format!(
             "failure decoding '{key}' argument from string '{string}': {e}"
         ))
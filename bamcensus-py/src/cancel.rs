@@ -0,0 +1,37 @@
+use pyo3::prelude::*;
+use std::future::Future;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio_util::sync::CancellationToken;
+
+/// how often pending Python signals, such as a KeyboardInterrupt, are checked while a
+/// run is blocking the interpreter.
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// runs a future to completion on the runtime, checking for Python signals along the
+/// way. the interpreter cannot raise a KeyboardInterrupt while the run holds the GIL,
+/// so on a pending signal the `cancel` token is cancelled instead, the future is left
+/// to wind down its requests and the signal's exception is returned.
+pub fn block_on<F: Future>(
+    runtime: &Runtime,
+    py: Python<'_>,
+    cancel: &CancellationToken,
+    future: F,
+) -> PyResult<F::Output> {
+    runtime.block_on(async {
+        tokio::pin!(future);
+        let mut interval = tokio::time::interval(SIGNAL_CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                output = &mut future => return Ok(output),
+                _ = interval.tick() => {
+                    if let Err(e) = py.check_signals() {
+                        cancel.cancel();
+                        let _ = future.await;
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    })
+}
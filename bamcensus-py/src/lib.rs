@@ -1,6 +1,7 @@
 use pyo3::prelude::*;
-mod acs_tiger_python;
-mod wac_tiger_python;
+pub mod acs_tiger_python;
+mod cancel;
+pub mod wac_tiger_python;
 
 #[pymodule]
 #[pyo3(name = "bamcensus")]
@@ -1,13 +1,21 @@
+// pyo3 0.22 #[pyfunction] expansion triggers a false positive for this lint
+#![allow(clippy::useless_conversion)]
+
+use crate::cancel;
 use bamcensus::app::lodes_tiger;
+use bamcensus::model::geometry_output::GeometryOutput;
+use bamcensus::model::join_options::JoinOptions;
 use bamcensus_core::model::identifier::Geoid;
 use bamcensus_lehd::model::{
     LodesDataset, LodesEdition, LodesJobType, WacSegment, WorkplaceSegment,
 };
+use bamcensus_tiger::model::tiger_base_url_from_env;
 use itertools::Itertools;
 use pyo3::types::IntoPyDict;
 use pyo3::types::PyDict;
 use pyo3::{exceptions::PyException, prelude::*};
 use serde::de;
+use tokio_util::sync::CancellationToken;
 use wkt::ToWkt;
 
 /// kwds example: https://pyo3.rs/main/function/signature#using-pyo3signature--
@@ -39,11 +47,13 @@ pub fn run_wac_tiger_python<'a>(
     let dataset = dataset_result?;
 
     let geoids_string: String = kwds.map_or(Ok(String::from("")), |m| get_string("geoids", m))?;
-    let geoids = geoids_string
-        .split(',')
-        .map(Geoid::try_from)
-        .collect::<Result<Vec<_>, String>>()
+    let geoids = Geoid::parse_list(&geoids_string)
         .map_err(|e| PyException::new_err(format!("failure decoding geoids argument: {e}")))?;
+    if geoids.is_empty() {
+        return Err(PyException::new_err(format!(
+            "geoids argument '{geoids_string}' does not contain any geoids"
+        )));
+    }
     let wac_segments = kwds.map_or(Ok(vec![WacSegment::C000]), |m| {
         if m.contains("wac_segments")? {
             get_comma_separated("wac_segments", m)
@@ -58,18 +68,93 @@ pub fn run_wac_tiger_python<'a>(
             Ok(None)
         }
     })?;
-
-    let future = lodes_tiger::run(&geoids, &wildcard, &wac_segments, &dataset);
+    let geoid_column = kwds.map_or(Ok(None), |m| {
+        if m.contains("geoid_column")? {
+            get_string("geoid_column", m).map(Some)
+        } else {
+            Ok(None)
+        }
+    })?;
+    let geometry = kwds.map_or(Ok(GeometryOutput::default()), |m| {
+        if m.contains("geometry")? {
+            get_string_deserializable("geometry", m)
+        } else {
+            Ok(GeometryOutput::default())
+        }
+    })?;
+    let tiger_year = kwds.map_or(Ok(None), |m| {
+        if m.contains("tiger_year")? {
+            get_u64("tiger_year", m).map(Some)
+        } else {
+            Ok(None)
+        }
+    })?;
+    let with_geometry = kwds.map_or(Ok(true), |m| {
+        if m.contains("with_geometry")? {
+            get_bool("with_geometry", m)
+        } else {
+            Ok(true)
+        }
+    })?;
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
         .map_err(|e| {
             PyException::new_err(format!("failure creating async rust tokio runtime: {e}"))
         })?;
-    let result = runtime.block_on(future).map_err(|e| {
+
+    // cancelled on a KeyboardInterrupt, see cancel::block_on
+    let cancel_token = CancellationToken::new();
+    let options = JoinOptions {
+        geoid_column,
+        transform: geometry.transform(),
+        tiger_year,
+        tiger_base_url: tiger_base_url_from_env(),
+        cancel: Some(cancel_token.clone()),
+        ..Default::default()
+    };
+
+    if !with_geometry {
+        // skip the TIGER/Lines downloads, returning only the LODES values
+        let future = lodes_tiger::run_without_geometry(
+            &geoids,
+            &wildcard,
+            &wac_segments,
+            &dataset,
+            &options,
+        );
+        let result = cancel::block_on(&runtime, py, &cancel_token, future)?.map_err(|e| {
+            PyException::new_err(format!("failure running LODES WAC workflow: {e}"))
+        })?;
+        if !result.lodes_errors.is_empty() {
+            let msg = result.lodes_errors.iter().join(",");
+            return Err(PyException::new_err(format!("lodes errors: {msg}")));
+        }
+        let vals = result
+            .dataset
+            .into_iter()
+            .flat_map(|(geoid, values)| values.into_iter().map(move |v| (geoid.clone(), v)))
+            .map(|(geoid, value)| {
+                let dict = PyDict::new_bound(py);
+                dict.set_item("geoid", geoid.to_string())?;
+                let segment = value.segment.to_string();
+                dict.set_item("segment", segment.clone())?;
+                dict.set_item("value", value.value)?;
+                Ok(((geoid.to_string(), segment), dict.to_object(py)))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(vals.into_py_dict_bound(py));
+    }
+
+    let future = lodes_tiger::run(&geoids, &wildcard, &wac_segments, &dataset, &options);
+    let result = cancel::block_on(&runtime, py, &cancel_token, future)?.map_err(|e| {
         PyException::new_err(format!("failure running LODES WAC + TIGER workflow: {e}"))
     })?;
 
+    if !result.lodes_errors.is_empty() {
+        let msg = result.lodes_errors.iter().join(",");
+        return Err(PyException::new_err(format!("lodes errors: {msg}")));
+    }
     if !result.tiger_errors.is_empty() {
         let msg = result.tiger_errors.iter().join(",");
         return Err(PyException::new_err(format!("tiger errors: {msg}")));
@@ -79,15 +164,18 @@ pub fn run_wac_tiger_python<'a>(
         return Err(PyException::new_err(format!("join errors: {msg}")));
     }
 
+    // rows are keyed by (geoid, segment), as a geoid has one row per WAC segment
     let vals = result
         .join_dataset
         .into_iter()
         .map(|row| {
             let dict = PyDict::new_bound(py);
-            dict.set_item("segment", row.value.segment.to_string())?;
+            dict.set_item("geoid", row.geoid.to_string())?;
+            let segment = row.value.segment.to_string();
+            dict.set_item("segment", segment.clone())?;
             dict.set_item("value", row.value.value)?;
             dict.set_item("geometry", row.geometry.to_wkt().to_string())?;
-            Ok((row.geoid.to_string(), dict.to_object(py)))
+            Ok(((row.geoid.to_string(), segment), dict.to_object(py)))
         })
         .collect::<PyResult<Vec<_>>>()?;
     let out_dict = vals.into_py_dict_bound(py);
@@ -127,6 +215,34 @@ fn get_string(key: &str, map: &Bound<'_, PyDict>) -> PyResult<String> {
     Ok(string)
 }
 
+fn get_u64(key: &str, map: &Bound<'_, PyDict>) -> PyResult<u64> {
+    let item_opt = map
+        .get_item(key)
+        .map_err(|e| PyException::new_err(format!("failure retreiving key {key}: {e}")))?;
+    let item = match item_opt {
+        None => Err(PyException::new_err(format!("key {key} not present"))),
+        Some(item) => Ok(item),
+    }?;
+    let value: u64 = item.extract().map_err(|e| {
+        PyException::new_err(format!("value at {key} is not an integer. error: {e}"))
+    })?;
+    Ok(value)
+}
+
+fn get_bool(key: &str, map: &Bound<'_, PyDict>) -> PyResult<bool> {
+    let item_opt = map
+        .get_item(key)
+        .map_err(|e| PyException::new_err(format!("failure retreiving key {key}: {e}")))?;
+    let item = match item_opt {
+        None => Err(PyException::new_err(format!("key {key} not present"))),
+        Some(item) => Ok(item),
+    }?;
+    let value: bool = item.extract().map_err(|e| {
+        PyException::new_err(format!("value at {key} is not a boolean. error: {e}"))
+    })?;
+    Ok(value)
+}
+
 fn get_string_deserializable<T>(key: &str, map: &Bound<'_, PyDict>) -> PyResult<T>
 where
     T: de::DeserializeOwned,
@@ -141,7 +257,10 @@ where
     let string: String = item
         .extract()
         .map_err(|e| PyException::new_err(format!("value at {key} is not string. error: {e}")))?;
-    let t: T = serde_json::from_str(string.as_str()).map_err(|e| {
+    // accept both a JSON string, such as '"county"', and the bare value, such as 'county'
+    let decoded = serde_json::from_str(string.as_str())
+        .or_else(|_| serde_json::from_value(serde_json::Value::String(string.clone())));
+    let t: T = decoded.map_err(|e| {
         PyException::new_err(format!(
             "failure decoding '{key}' argument from string '{string}': {e}"
         ))
@@ -0,0 +1,76 @@
+//! runs the Python ACS wrapper against a local mock server, checking the rows a Python
+//! caller receives without reaching census.gov.
+#[allow(dead_code)]
+#[path = "../../bamcensus/tests/common/mod.rs"]
+mod common;
+
+use bamcensus_core::model::identifier::{fips, Geoid};
+use bamcensus_py::acs_tiger_python::run_acs_tiger_python;
+use bamcensus_tiger::model::TigerResourceBuilder;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use wiremock::MockServer;
+
+/// starts a server with the 2020 ACS population and median household income of two
+/// Colorado counties, and the 2020 TIGER/Lines county archive holding both.
+async fn start() -> MockServer {
+    let server = MockServer::start().await;
+    let counties = serde_json::json!([
+        ["B01001_001E", "B19013_001E", "state", "county"],
+        ["715522", "78177", "08", "031"],
+        ["582910", "98493", "08", "059"]
+    ]);
+    common::route(&server, "/data/2020/acs/acs5", 200, counties.to_string()).await;
+    let archive = common::shapefile_zip(&[
+        ("08031", [-105.11, 39.61, -104.60, 39.91]),
+        ("08059", [-105.40, 39.13, -105.05, 39.93]),
+    ]);
+    let denver = Geoid::County(fips::State(8), fips::County(31));
+    let archive_path = TigerResourceBuilder::new(2020)
+        .unwrap()
+        .create_resource_at("", &denver)
+        .unwrap()
+        .uri;
+    common::route(&server, &archive_path, 200, archive).await;
+    server
+}
+
+#[test]
+fn county_query_returns_a_row_per_geoid_and_variable() {
+    // the wrapper runs its own runtime, so the server is started on another one
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(start());
+    pyo3::prepare_freethreaded_python();
+
+    Python::with_gil(|py| {
+        let kwds = PyDict::new_bound(py);
+        kwds.set_item("geoids", "08").unwrap();
+        kwds.set_item("wildcard", "county").unwrap();
+        kwds.set_item("acs_query", "B01001_001E,B19013_001E")
+            .unwrap();
+        kwds.set_item("acs_base_url", format!("{}/data", server.uri()))
+            .unwrap();
+        kwds.set_item("tiger_base_url", server.uri()).unwrap();
+        let rows = run_acs_tiger_python(2020, Some(&kwds), py).unwrap();
+
+        // two counties with two variables each, none overwriting another
+        assert_eq!(rows.len(), 4);
+        for (county, name, value) in [
+            (31, "B01001_001E", "715522"),
+            (31, "B19013_001E", "78177"),
+            (59, "B01001_001E", "582910"),
+            (59, "B19013_001E", "98493"),
+        ] {
+            let geoid = Geoid::County(fips::State(8), fips::County(county)).to_string();
+            let key = PyTuple::new_bound(py, [geoid.as_str(), name]);
+            let row = rows.get_item(key).unwrap().unwrap();
+            let row = row.downcast::<PyDict>().unwrap();
+            let column = |column: &str| row.get_item(column).unwrap().unwrap();
+            assert_eq!(column("geoid").extract::<String>().unwrap(), geoid);
+            assert_eq!(column("name").extract::<String>().unwrap(), name);
+            assert_eq!(column("value").str().unwrap().to_string(), value);
+            let geometry = column("geometry").extract::<String>().unwrap();
+            assert!(geometry.contains("POLYGON"), "{geometry}");
+        }
+    });
+}
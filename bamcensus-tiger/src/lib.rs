@@ -10,6 +10,10 @@
 //! # Usage
 //!
 //! LODES datasets can be downloaded using the [`crate::ops::tiger_api`] module `run` method.
+//! National feature files that are not keyed by GEOID, such as primary roads and rails, can be
+//! downloaded using the `run_feature` method of the same module.
+//! To work offline, the archives needed for a set of years and GEOIDs can be downloaded ahead of
+//! time into a [TigerCache] with the `prefetch` method, and that cache passed to `run`.
 //!
 //! # Data model
 //!
@@ -26,6 +30,7 @@
 //!
 //! [TigerResourceBuilder]: crate::model::TigerResourceBuilder
 //! [TigerResource]: crate::model::TigerResource
+//! [TigerCache]: crate::model::TigerCache
 
 pub mod model;
 pub mod ops;
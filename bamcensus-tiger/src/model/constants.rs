@@ -1 +1,6 @@
 pub const LATEST_YEAR: u64 = 2023;
+
+/// environment variable the binaries read to override the root of the TIGER/Lines
+/// directory, see [`super::tiger_base_url_from_env`]. useful with a mirror of the
+/// directory.
+pub const TIGER_BASE_URL_ENV_VAR: &str = "BAMCENSUS_TIGER_BASE_URL";
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// a change applied to each geometry as it is read from a TIGER/Lines file.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum GeometryTransform {
+    /// simplifies each geometry with the given tolerance, in the native units of the
+    /// geometry (degrees), see [`crate::ops::simplify::simplify_geometry`].
+    Simplify { tolerance: f64 },
+    /// replaces each geometry with a single point, using the internal point published
+    /// in the INTPTLAT and INTPTLON columns when present, which always falls inside the
+    /// geography, and the geometric centroid otherwise. see
+    /// [`crate::ops::centroid::centroid_geometry`].
+    Centroid,
+}
@@ -1,7 +1,19 @@
 pub mod constants;
 
+mod geometry_transform;
+mod prefetch_summary;
+mod run_options;
+mod tiger_cache;
+mod tiger_feature;
 mod tiger_resource;
 mod tiger_uri_builder;
+mod tract_relationship;
 
+pub use geometry_transform::GeometryTransform;
+pub use prefetch_summary::PrefetchSummary;
+pub use run_options::{tiger_base_url_from_env, RunOptions};
+pub use tiger_cache::TigerCache;
+pub use tiger_feature::TigerFeature;
 pub use tiger_resource::TigerResource;
 pub use tiger_uri_builder::TigerResourceBuilder;
+pub use tract_relationship::{TractRelationship, TractVintage};
@@ -0,0 +1,27 @@
+use std::fmt::Display;
+
+/// summary of a [`crate::ops::tiger_api::prefetch`] run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefetchSummary {
+    /// number of archives downloaded into the cache
+    pub files_fetched: usize,
+    /// number of archives that were already in the cache
+    pub files_cached: usize,
+    /// total size in bytes of the downloaded archives
+    pub bytes_fetched: u64,
+    /// errors for archives that could not be downloaded
+    pub errors: Vec<String>,
+}
+
+impl Display for PrefetchSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "fetched {} files ({} bytes), {} already cached, {} errors",
+            self.files_fetched,
+            self.bytes_fetched,
+            self.files_cached,
+            self.errors.len()
+        )
+    }
+}
@@ -0,0 +1,127 @@
+use super::constants::TIGER_BASE_URL_ENV_VAR;
+use super::{GeometryTransform, TigerCache, TigerResourceBuilder};
+use bamcensus_core::model::ByteCounter;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+/// optional settings of a TIGER/Lines run, see [`crate::ops::tiger_api::run`]. every
+/// setting is off by default, and each is set with a builder method.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_tiger::model::{GeometryTransform, RunOptions};
+///
+/// let options = RunOptions::default()
+///     .with_transform(GeometryTransform::Centroid)
+///     .with_geoid_column("GEOID20")
+///     .with_limit(10);
+/// assert_eq!(options.geoid_column.as_deref(), Some("GEOID20"));
+/// assert_eq!(options.limit, Some(10));
+/// assert!(options.cache.is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// directory where archives are written while they are read
+    pub scratch_dir: Option<PathBuf>,
+    /// cache of archives, read before downloading and filled with downloads
+    pub cache: Option<TigerCache>,
+    /// transform applied to each matching geometry
+    pub transform: Option<GeometryTransform>,
+    /// the only column geoids are read from
+    pub geoid_column: Option<String>,
+    /// maximum number of matching rows read from each archive
+    pub limit: Option<usize>,
+    /// directory where archives and the files extracted from them are kept
+    pub keep_files: Option<PathBuf>,
+    /// token that stops the run once cancelled
+    pub cancel: Option<CancellationToken>,
+    /// root of the TIGER/Lines directory archives are downloaded from, such as a
+    /// mirror, in place of [`TigerResourceBuilder::TIGER_BASE_URL`]
+    pub base_url: Option<String>,
+    /// running total of the bytes of the archives downloaded
+    pub bytes: Option<ByteCounter>,
+    /// maximum number of archives downloaded at a time, in place of
+    /// [`crate::ops::tiger_api::DEFAULT_CONCURRENCY`]
+    pub concurrency: Option<usize>,
+}
+
+impl RunOptions {
+    pub fn with_scratch_dir(mut self, scratch_dir: &Path) -> RunOptions {
+        self.scratch_dir = Some(scratch_dir.to_path_buf());
+        self
+    }
+
+    pub fn with_cache(mut self, cache: TigerCache) -> RunOptions {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn with_transform(mut self, transform: GeometryTransform) -> RunOptions {
+        self.transform = Some(transform);
+        self
+    }
+
+    pub fn with_geoid_column(mut self, geoid_column: &str) -> RunOptions {
+        self.geoid_column = Some(String::from(geoid_column));
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> RunOptions {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_keep_files(mut self, keep_files: &Path) -> RunOptions {
+        self.keep_files = Some(keep_files.to_path_buf());
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> RunOptions {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> RunOptions {
+        self.base_url = Some(String::from(base_url));
+        self
+    }
+
+    pub fn with_bytes(mut self, bytes: ByteCounter) -> RunOptions {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> RunOptions {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// the root of the TIGER/Lines directory of the run, the `base_url` if provided,
+    /// otherwise [`TigerResourceBuilder::TIGER_BASE_URL`].
+    pub fn tiger_base_url(&self) -> String {
+        self.base_url
+            .clone()
+            .unwrap_or_else(|| String::from(TigerResourceBuilder::TIGER_BASE_URL))
+    }
+
+    /// the cache of the run, storing archives by their path under the root of the
+    /// run's TIGER/Lines directory, see [`TigerCache::with_base_url`].
+    pub fn tiger_cache(&self) -> Option<TigerCache> {
+        self.cache.as_ref().map(|cache| match &cache.base_url {
+            Some(_) => cache.clone(),
+            None => cache.clone().with_base_url(&self.tiger_base_url()),
+        })
+    }
+}
+
+/// reads the root of the TIGER/Lines directory from the [`TIGER_BASE_URL_ENV_VAR`]
+/// environment variable (`BAMCENSUS_TIGER_BASE_URL`), ignoring empty values. the
+/// library does not read the environment itself; applications pass the root to a run
+/// as the `base_url` of [`RunOptions`].
+pub fn tiger_base_url_from_env() -> Option<String> {
+    std::env::var(TIGER_BASE_URL_ENV_VAR)
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
@@ -0,0 +1,72 @@
+use super::TigerResourceBuilder;
+use std::path::{Path, PathBuf};
+
+/// a local directory of downloaded TIGER/Lines .zip archives. archives are stored at
+/// the same relative path they have on the TIGER/Lines website, so files from
+/// different years never collide. when a cache is provided to
+/// [`crate::ops::tiger_api::run`], archives found in the cache are read from disk
+/// instead of being downloaded, and downloaded archives are added to the cache.
+#[derive(Debug, Clone)]
+pub struct TigerCache {
+    pub directory: PathBuf,
+    /// root of the TIGER/Lines directory the archives are downloaded from, such as a
+    /// mirror, when it is not [`TigerResourceBuilder::TIGER_BASE_URL`]
+    pub base_url: Option<String>,
+}
+
+impl TigerCache {
+    pub fn new(directory: &Path) -> TigerCache {
+        TigerCache {
+            directory: directory.to_path_buf(),
+            base_url: None,
+        }
+    }
+
+    /// stores archives downloaded from a mirror at the same paths as those downloaded
+    /// from census.gov, relative to the given root of the mirror.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerCache;
+    /// use std::path::Path;
+    ///
+    /// let cache = TigerCache::new(Path::new("/data/tiger")).with_base_url("http://localhost:8080/tiger");
+    /// let path = cache.path("http://localhost:8080/tiger/TIGER2020/TRACT/tl_2020_08_tract.zip");
+    /// assert_eq!(path, Path::new("/data/tiger/TIGER2020/TRACT/tl_2020_08_tract.zip"));
+    /// ```
+    pub fn with_base_url(mut self, base_url: &str) -> TigerCache {
+        self.base_url = Some(String::from(base_url));
+        self
+    }
+
+    /// location in the cache of the archive at the given TIGER/Lines URI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerCache;
+    /// use std::path::Path;
+    ///
+    /// let cache = TigerCache::new(Path::new("/data/tiger"));
+    /// let path = cache.path("https://www2.census.gov/geo/tiger/TIGER2020/TRACT/tl_2020_08_tract.zip");
+    /// assert_eq!(path, Path::new("/data/tiger/TIGER2020/TRACT/tl_2020_08_tract.zip"));
+    /// ```
+    pub fn path(&self, uri: &str) -> PathBuf {
+        let relative = self
+            .base_url
+            .as_deref()
+            .and_then(|base_url| uri.strip_prefix(base_url.trim_end_matches('/')))
+            .or_else(|| uri.strip_prefix(TigerResourceBuilder::TIGER_BASE_URL))
+            .unwrap_or(uri)
+            .trim_start_matches('/');
+        self.directory.join(relative)
+    }
+
+    /// true if the archive at the given URI has already been downloaded to the cache.
+    pub fn contains(&self, uri: &str) -> bool {
+        std::fs::metadata(self.path(uri))
+            .map(|m| m.is_file() && m.len() > 0)
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// national TIGER/Lines feature files which are not keyed by GEOID. each row of
+/// these files is identified by its feature id (the LINEARID column) instead.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TigerFeature {
+    /// primary roads (interstates and other limited-access highways) as line geometries.
+    ///
+    /// # Examples
+    /// <https://www2.census.gov/geo/tiger/TIGER2020/PRIMARYROADS/tl_2020_us_primaryroads.zip>
+    PrimaryRoads,
+    /// rail lines as line geometries.
+    ///
+    /// # Examples
+    /// <https://www2.census.gov/geo/tiger/TIGER2020/RAILS/tl_2020_us_rails.zip>
+    Rails,
+}
+
+impl TigerFeature {
+    /// name of the TIGER/Lines directory containing this feature's files.
+    pub fn directory_name(&self) -> &'static str {
+        match self {
+            TigerFeature::PrimaryRoads => "PRIMARYROADS",
+            TigerFeature::Rails => "RAILS",
+        }
+    }
+
+    /// name of the column holding the unique id of each feature.
+    pub fn id_column_name(&self) -> &'static str {
+        match self {
+            TigerFeature::PrimaryRoads => "LINEARID",
+            TigerFeature::Rails => "LINEARID",
+        }
+    }
+}
+
+impl Display for TigerFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TigerFeature::PrimaryRoads => write!(f, "primaryroads"),
+            TigerFeature::Rails => write!(f, "rails"),
+        }
+    }
+}
@@ -1,8 +1,31 @@
 use bamcensus_core::model::identifier::GeoidType;
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 /// represents everything about a TIGER/Lines shapefile needed to
 /// download it and represent the result with bamcensus types.
+///
+/// resources serialize with their uri, geoid type and file scope, so the set of
+/// resources resolved for a run can be written to a manifest and read back later.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_tiger::model::{TigerResource, TigerResourceBuilder};
+/// use bamcensus_core::model::identifier::Geoid;
+///
+/// let builder = TigerResourceBuilder::new(2020).unwrap();
+/// let geoid = Geoid::census_tract(8, 31, 4102).unwrap();
+/// let resources = builder.create_resources(&[&geoid]).unwrap();
+/// assert_eq!(
+///     resources[0].to_string(),
+///     "https://www2.census.gov/geo/tiger/TIGER2020/TRACT/tl_2020_08_tract.zip (census_tract, state scope)"
+/// );
+///
+/// let manifest = serde_json::to_string(&resources).unwrap();
+/// let restored: Vec<TigerResource> = serde_json::from_str(&manifest).unwrap();
+/// assert_eq!(restored, resources);
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct TigerResource {
     /// complete URI to a file location in the TIGER/LINES HTTP website
@@ -23,6 +46,15 @@ pub struct TigerResource {
     // pub geoid_column_name: String,
 }
 
+impl Display for TigerResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file_scope {
+            Some(scope) => write!(f, "{} ({}, {scope} scope)", self.uri, self.geoid_type),
+            None => write!(f, "{} ({}, national scope)", self.uri, self.geoid_type),
+        }
+    }
+}
+
 impl TigerResource {
     pub fn new(
         uri: String,
@@ -37,4 +69,123 @@ impl TigerResource {
             // geoid_column_name,
         }
     }
+
+    /// parses a TIGER/Lines file URI back into a resource, recovering the geoid type and
+    /// file scope from the file naming convention `tl_<year>_<scope>_<layer>.zip`. only the
+    /// file name is inspected, so full URLs, mirror paths and bare file names are all
+    /// accepted. the provided string is kept as the resource uri.
+    ///
+    /// # Examples
+    ///
+    /// the 2010 format, where most layers are split into per-county files:
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResource;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let uri = "https://www2.census.gov/geo/tiger/TIGER2010/TABBLOCK/2010/tl_2010_01001_tabblock10.zip";
+    /// let resource = TigerResource::from_uri(uri).unwrap();
+    /// assert_eq!(resource.geoid_type, GeoidType::Block);
+    /// assert_eq!(resource.file_scope, Some(GeoidType::County));
+    /// assert_eq!(resource.year().unwrap(), 2010);
+    /// ```
+    ///
+    /// the 2011-2019 format, with national county files and per-state tract files:
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResource;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let resource = TigerResource::from_uri("/mirror/TIGER2015/COUNTY/tl_2015_us_county.zip").unwrap();
+    /// assert_eq!(resource.geoid_type, GeoidType::County);
+    /// assert_eq!(resource.file_scope, None);
+    /// assert_eq!(resource.year().unwrap(), 2015);
+    ///
+    /// let resource = TigerResource::from_uri("tl_2015_08_tract.zip").unwrap();
+    /// assert_eq!(resource.geoid_type, GeoidType::CensusTract);
+    /// assert_eq!(resource.file_scope, Some(GeoidType::State));
+    /// ```
+    ///
+    /// the 2020+ format. every resource created by a builder parses back to itself:
+    /// ```rust
+    /// use bamcensus_tiger::model::{TigerResource, TigerResourceBuilder};
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let geoids = vec![
+    ///     Geoid::State(fips::State(8)),
+    ///     Geoid::County(fips::State(8), fips::County(59)),
+    ///     Geoid::CountySubdivision(fips::State(8), fips::County(59), fips::CountySubdivision(90760)),
+    ///     Geoid::Place(fips::State(8), fips::Place(20000)),
+    ///     Geoid::CensusTract(fips::State(8), fips::County(59), fips::CensusTract(9838)),
+    ///     Geoid::BlockGroup(fips::State(8), fips::County(59), fips::CensusTract(9838), fips::BlockGroup(1)),
+    ///     Geoid::Block(fips::State(8), fips::County(59), fips::CensusTract(9838), fips::Block(String::from("1000"))),
+    /// ];
+    /// for year in [2010, 2015, 2020, 2022] {
+    ///     let builder = TigerResourceBuilder::new(year).unwrap();
+    ///     for geoid in geoids.iter() {
+    ///         let resource = builder.create_resource(geoid).unwrap();
+    ///         assert_eq!(TigerResource::from_uri(&resource.uri).unwrap(), resource);
+    ///         assert_eq!(resource.year().unwrap(), year);
+    ///     }
+    /// }
+    ///
+    /// assert!(TigerResource::from_uri("tl_2020_us_primaryroads.zip").is_err());
+    /// ```
+    pub fn from_uri(uri: &str) -> Result<TigerResource, String> {
+        let (_, scope, layer) = parse_file_name(uri)?;
+        let file_scope = match scope.len() {
+            _ if scope == "us" => None,
+            2 => Some(GeoidType::State),
+            5 => Some(GeoidType::County),
+            _ => {
+                return Err(format!(
+                    "unrecognized file scope '{scope}' in TIGER uri {uri}"
+                ))
+            }
+        };
+        let layer_name = layer
+            .strip_suffix("10")
+            .or_else(|| layer.strip_suffix("20"))
+            .unwrap_or(layer);
+        let geoid_type = match layer_name {
+            "state" => GeoidType::State,
+            "county" => GeoidType::County,
+            "cousub" => GeoidType::CountySubdivision,
+            "place" => GeoidType::Place,
+            "tract" => GeoidType::CensusTract,
+            "bg" => GeoidType::BlockGroup,
+            "tabblock" => GeoidType::Block,
+            "vtd" => GeoidType::VotingDistrict,
+            _ => {
+                return Err(format!(
+                    "layer '{layer}' in TIGER uri {uri} is not a GEOID-keyed layer"
+                ))
+            }
+        };
+        Ok(TigerResource::new(
+            String::from(uri),
+            geoid_type,
+            file_scope,
+        ))
+    }
+
+    /// the TIGER/Lines vintage of this resource, read from its file name.
+    pub fn year(&self) -> Result<u64, String> {
+        let (year, _, _) = parse_file_name(&self.uri)?;
+        year.parse::<u64>()
+            .map_err(|e| format!("invalid year '{year}' in TIGER uri {}: {e}", self.uri))
+    }
+}
+
+/// splits a TIGER/Lines file name of the form `tl_<year>_<scope>_<layer>.zip` into
+/// its year, scope and layer tokens.
+fn parse_file_name(uri: &str) -> Result<(&str, &str, &str), String> {
+    let file_name = uri.rsplit(['/', '\\']).next().unwrap_or(uri);
+    let stem = file_name
+        .strip_suffix(".zip")
+        .ok_or_else(|| format!("TIGER uri {uri} is not a .zip archive"))?;
+    match stem.split('_').collect::<Vec<_>>()[..] {
+        ["tl", year, scope, layer] => Ok((year, scope, layer)),
+        _ => Err(format!(
+            "TIGER uri {uri} does not match the naming convention tl_<year>_<scope>_<layer>.zip"
+        )),
+    }
 }
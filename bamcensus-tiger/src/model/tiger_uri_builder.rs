@@ -1,5 +1,5 @@
-use super::TigerResource;
-use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString};
+use super::{TigerFeature, TigerResource};
+use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString, StateCode};
 use std::{collections::HashSet, fmt::Display};
 
 /// builds [`super::TigerResource`] instances for valid combinations of TIGER/Lines
@@ -43,6 +43,9 @@ impl Display for TigerResourceBuilder {
 }
 
 impl TigerResourceBuilder {
+    /// the root of the TIGER/Lines directory on census.gov, used unless another root,
+    /// such as a mirror, is given with [`TigerResourceBuilder::create_resources_at`]
+    /// or the `base_url` of [`super::RunOptions`].
     pub const TIGER_BASE_URL: &'static str = "https://www2.census.gov/geo/tiger";
 
     pub fn new(year: u64) -> Result<TigerResourceBuilder, String> {
@@ -57,13 +60,58 @@ impl TigerResourceBuilder {
     /// batch operation that only returns the unique set of TigerUris required to cover
     /// the provided set of Geoids. this is the public API since we should only be
     /// downloading each file once. for details on implementation, see `create_resource`.
+    ///
+    /// resources are returned in the order they are first encountered in the Geoid
+    /// list, so a sorted input produces a stable, reproducible output.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResourceBuilder;
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let builder = TigerResourceBuilder::Tiger2020Format { year: 2020 };
+    /// let co_tract = Geoid::CensusTract(fips::State(8), fips::County(59), fips::CensusTract(9838));
+    /// let ut_tract = Geoid::CensusTract(fips::State(49), fips::County(35), fips::CensusTract(100));
+    /// let co_tract_2 = Geoid::CensusTract(fips::State(8), fips::County(1), fips::CensusTract(100));
+    /// let resources = builder.create_resources(&[&ut_tract, &co_tract, &co_tract_2]).unwrap();
+    /// let uris = resources.iter().map(|r| r.uri.as_str()).collect::<Vec<_>>();
+    /// assert_eq!(uris, vec![
+    ///     "https://www2.census.gov/geo/tiger/TIGER2020/TRACT/tl_2020_49_tract.zip",
+    ///     "https://www2.census.gov/geo/tiger/TIGER2020/TRACT/tl_2020_08_tract.zip",
+    /// ]);
+    /// ```
     pub fn create_resources(&self, geoids: &[&Geoid]) -> Result<Vec<TigerResource>, String> {
-        let mut unique_uris: HashSet<TigerResource> = HashSet::new();
+        self.create_resources_at(TigerResourceBuilder::TIGER_BASE_URL, geoids)
+    }
+
+    /// as [`TigerResourceBuilder::create_resources`], with the URIs under the given
+    /// root of the TIGER/Lines directory instead of [`TigerResourceBuilder::TIGER_BASE_URL`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResourceBuilder;
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// let builder = TigerResourceBuilder::new(2020).unwrap();
+    /// let denver = Geoid::County(fips::State(8), fips::County(31));
+    /// let resources = builder.create_resources_at("http://localhost:8080/", &[&denver]).unwrap();
+    /// assert_eq!(resources[0].uri, "http://localhost:8080/TIGER2020/COUNTY/tl_2020_us_county.zip");
+    /// ```
+    pub fn create_resources_at(
+        &self,
+        root: &str,
+        geoids: &[&Geoid],
+    ) -> Result<Vec<TigerResource>, String> {
+        let mut visited: HashSet<TigerResource> = HashSet::new();
+        let mut uris: Vec<TigerResource> = vec![];
         for geoid in geoids {
-            let uri = self.create_resource(geoid)?;
-            unique_uris.insert(uri);
+            let uri = self.create_resource_at(root, geoid)?;
+            if visited.insert(uri.clone()) {
+                uris.push(uri);
+            }
         }
-        let uris = unique_uris.into_iter().collect::<Vec<_>>();
         Ok(uris)
     }
 
@@ -100,161 +148,299 @@ impl TigerResourceBuilder {
     /// );
     /// assert_eq!(uri, expected);
     /// ```
+    ///
+    /// from 2020, block groups are published in one file per state, keyed by a 12-digit
+    /// GEOID column, so the file scope is the state.
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::{TigerResourceBuilder, TigerResource};
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    ///
+    /// let builder = TigerResourceBuilder::new(2020).unwrap();
+    /// let geoid = Geoid::BlockGroup(
+    ///     fips::State(8),
+    ///     fips::County(31),
+    ///     fips::CensusTract(4102),
+    ///     fips::BlockGroup(1),
+    /// );
+    /// let resource = builder.create_resource(&geoid).unwrap();
+    /// assert_eq!(resource.uri, "https://www2.census.gov/geo/tiger/TIGER2020/BG/tl_2020_08_bg.zip");
+    /// assert_eq!(resource.geoid_type, GeoidType::BlockGroup);
+    /// assert_eq!(resource.file_scope, Some(GeoidType::State));
+    ///
+    /// // the GEOID column value in the file parses to the same Geoid
+    /// let from_file = resource.geoid_type.geoid_from_str("080310041021").unwrap();
+    /// assert_eq!(from_file, geoid);
+    /// ```
+    ///
+    /// places, which include incorporated places and census designated places, are
+    /// published in one file per state in every year. TIGER/Lines has no national
+    /// place file, only the cartographic boundary files do, so a national place query
+    /// downloads one file per state.
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResourceBuilder;
+    /// use bamcensus_core::model::identifier::{fips, Geoid, GeoidType};
+    ///
+    /// // Denver city, Colorado
+    /// let denver = Geoid::Place(fips::State(8), fips::Place(20000));
+    /// for (year, suffix) in [
+    ///     (2010, "TIGER2010/PLACE/2010/tl_2010_08_place10.zip"),
+    ///     (2015, "TIGER2015/PLACE/tl_2015_08_place.zip"),
+    ///     (2019, "TIGER2019/PLACE/tl_2019_08_place.zip"),
+    ///     (2020, "TIGER2020/PLACE/tl_2020_08_place.zip"),
+    ///     (2023, "TIGER2023/PLACE/tl_2023_08_place.zip"),
+    /// ] {
+    ///     let resource = TigerResourceBuilder::new(year).unwrap().create_resource(&denver).unwrap();
+    ///     assert_eq!(resource.uri, format!("{}/{suffix}", TigerResourceBuilder::TIGER_BASE_URL));
+    ///     assert_eq!(resource.geoid_type, GeoidType::Place);
+    ///     assert_eq!(resource.file_scope, Some(GeoidType::State));
+    ///     // the 7-digit GEOID (or GEOID10) column value parses to the same Geoid
+    ///     assert_eq!(resource.geoid_type.geoid_from_str("0820000").unwrap(), denver);
+    /// }
+    /// ```
+    ///
+    /// voting districts are only published with the 2020 redistricting (PL 94-171)
+    /// TIGER/Lines, in one file per state. they join to the decennial PL data, not ACS.
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::{TigerResource, TigerResourceBuilder};
+    /// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+    ///
+    /// let vtd = Geoid::voting_district(8, 31, "000120").unwrap();
+    /// let resource = TigerResourceBuilder::new(2020).unwrap().create_resource(&vtd).unwrap();
+    /// assert_eq!(
+    ///     resource.uri,
+    ///     "https://www2.census.gov/geo/tiger/TIGER2020PL/LAYER/VTD/2020/tl_2020_08_vtd20.zip"
+    /// );
+    /// assert_eq!(resource.geoid_type, GeoidType::VotingDistrict);
+    /// assert_eq!(resource.file_scope, Some(GeoidType::State));
+    /// assert_eq!(TigerResource::from_uri(&resource.uri).unwrap(), resource);
+    /// // the GEOID20 column value parses to the same Geoid
+    /// assert_eq!(resource.geoid_type.geoid_from_str("08031000120").unwrap(), vtd);
+    ///
+    /// assert!(TigerResourceBuilder::new(2022).unwrap().create_resource(&vtd).is_err());
+    /// ```
     pub fn create_resource(&self, geoid: &Geoid) -> Result<TigerResource, String> {
-        let suffix: String = match (self, geoid) {
+        self.create_resource_at(TigerResourceBuilder::TIGER_BASE_URL, geoid)
+    }
+
+    /// as [`TigerResourceBuilder::create_resource`], with the URI under the given root
+    /// of the TIGER/Lines directory, such as a mirror, instead of
+    /// [`TigerResourceBuilder::TIGER_BASE_URL`].
+    pub fn create_resource_at(&self, root: &str, geoid: &Geoid) -> Result<TigerResource, String> {
+        let (state, county) = match geoid {
+            Geoid::Nation | Geoid::Region(_) | Geoid::Division(_) => (String::new(), String::new()),
+            Geoid::State(state) | Geoid::Place(state, _) => (state.geoid_string(), String::new()),
+            Geoid::County(state, county)
+            | Geoid::CountySubdivision(state, county, _)
+            | Geoid::CensusTract(state, county, _)
+            | Geoid::BlockGroup(state, county, _, _)
+            | Geoid::Block(state, county, _, _)
+            | Geoid::VotingDistrict(state, county, _) => {
+                (state.geoid_string(), county.geoid_string())
+            }
+        };
+        self.build_resource(root, geoid.geoid_type(), &state, &county)
+    }
+
+    /// creates the [`TigerResource`] of a geography with `{state}` and `{county}` in
+    /// place of the FIPS codes in the file name, describing every file of that
+    /// geography in this year.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResourceBuilder;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let builder = TigerResourceBuilder::new(2010).unwrap();
+    /// let tracts = builder.create_resource_pattern(GeoidType::CensusTract).unwrap();
+    /// assert_eq!(
+    ///     tracts.uri,
+    ///     "https://www2.census.gov/geo/tiger/TIGER2010/TRACT/2010/tl_2010_{state}{county}_tract10.zip"
+    /// );
+    /// assert_eq!(tracts.file_scope, Some(GeoidType::County));
+    /// ```
+    pub fn create_resource_pattern(&self, geoid_type: GeoidType) -> Result<TigerResource, String> {
+        self.build_resource(
+            TigerResourceBuilder::TIGER_BASE_URL,
+            geoid_type,
+            "{state}",
+            "{county}",
+        )
+    }
+
+    /// builds the resource of a geography under the root of the TIGER/Lines directory
+    /// from the state and county FIPS components of its file name, which are ignored
+    /// by national files.
+    fn build_resource(
+        &self,
+        root: &str,
+        geoid_type: GeoidType,
+        state: &str,
+        county: &str,
+    ) -> Result<TigerResource, String> {
+        use GeoidType as G;
+        use TigerResourceBuilder as B;
+        let suffix: String = match (self, geoid_type) {
             //// ~~~~ 2010 ~~~~ ////
             // 2010 has two versions, one in 2000 format, one in 2010 format
             // so we have to add the "2010" directory to these
-            (TigerResourceBuilder::Tiger2010, Geoid::State(state)) => {
-                format!("STATE/2010/tl_2010_{}_state10.zip", state.geoid_string(),)
+            (B::Tiger2010, G::State) => format!("STATE/2010/tl_2010_{state}_state10.zip"),
+            (B::Tiger2010, G::County) => format!("COUNTY/2010/tl_2010_{state}_county10.zip"),
+            (B::Tiger2010, G::CountySubdivision) => {
+                format!("COUSUB/2010/tl_2010_{state}{county}_cousub10.zip")
             }
-            (TigerResourceBuilder::Tiger2010, Geoid::County(state, _)) => {
-                format!("COUNTY/2010/tl_2010_{}_county10.zip", state.geoid_string(),)
+            (B::Tiger2010, G::Place) => format!("PLACE/2010/tl_2010_{state}_place10.zip"),
+            (B::Tiger2010, G::CensusTract) => {
+                format!("TRACT/2010/tl_2010_{state}{county}_tract10.zip")
             }
-            (TigerResourceBuilder::Tiger2010, Geoid::CountySubdivision(state, county, _)) => {
-                format!(
-                    "COUSUB/2010/tl_2010_{}{}_cousub10.zip",
-                    state.geoid_string(),
-                    county.geoid_string()
-                )
+            (B::Tiger2010, G::BlockGroup) => format!("BG/2010/tl_2010_{state}{county}_bg10.zip"),
+            (B::Tiger2010, G::Block) => {
+                format!("TABBLOCK/2010/tl_2010_{state}{county}_tabblock10.zip")
             }
-            (TigerResourceBuilder::Tiger2010, Geoid::Place(state, _)) => {
-                format!("PLACE/2010/tl_2010_{}_place10.zip", state.geoid_string(),)
+            (B::Tiger2010, G::Region) => String::from("REGION/2010/tl_2010_us_region10.zip"),
+            (B::Tiger2010, G::Division) => String::from("DIVISION/2010/tl_2010_us_division10.zip"),
+            // regions and divisions are only published in the 2010 TIGER/Lines, and
+            // the nation only in the cartographic boundary files
+            (_, G::Nation | G::Region | G::Division) => {
+                return Err(format!(
+                    "{} boundaries are not published in the {} TIGER/Lines",
+                    geoid_type,
+                    self.get_year()
+                ))
             }
-            (TigerResourceBuilder::Tiger2010, Geoid::CensusTract(state, county, _)) => format!(
-                "TRACT/2010/tl_2010_{}{}_tract10.zip",
-                state.geoid_string(),
-                county.geoid_string()
-            ),
-            (TigerResourceBuilder::Tiger2010, Geoid::BlockGroup(state, county, _, _)) => format!(
-                "BG/2010/tl_2010_{}{}_bg10.zip",
-                state.geoid_string(),
-                county.geoid_string()
-            ),
-            (TigerResourceBuilder::Tiger2010, Geoid::Block(state, county, _, _)) => format!(
-                "TABBLOCK/2010/tl_2010_{}{}_tabblock10.zip",
-                state.geoid_string(),
-                county.geoid_string()
-            ),
-            //// ~~~~ 2011-2019 ~~~~ ////
-            (TigerResourceBuilder::Tiger2010Format { year }, Geoid::State(_)) => {
-                format!("STATE/tl_{year}_us_state.zip",)
+            //// ~~~~ 2011-2029 ~~~~ ////
+            (B::Tiger2010Format { year } | B::Tiger2020Format { year }, G::State) => {
+                format!("STATE/tl_{year}_us_state.zip")
             }
-            (TigerResourceBuilder::Tiger2010Format { year }, Geoid::County(_, _)) => {
+            (B::Tiger2010Format { year } | B::Tiger2020Format { year }, G::County) => {
                 format!("COUNTY/tl_{year}_us_county.zip")
             }
-            (
-                TigerResourceBuilder::Tiger2010Format { year },
-                Geoid::CountySubdivision(state, _, _),
-            ) => {
-                format!("COUSUB/tl_{}_{}_cousub.zip", year, state.geoid_string())
+            (B::Tiger2010Format { year } | B::Tiger2020Format { year }, G::CountySubdivision) => {
+                format!("COUSUB/tl_{year}_{state}_cousub.zip")
             }
-            (TigerResourceBuilder::Tiger2010Format { year }, Geoid::Place(state, _)) => {
-                format!("PLACE/tl_{}_{}_place.zip", year, state.geoid_string(),)
+            (B::Tiger2010Format { year } | B::Tiger2020Format { year }, G::Place) => {
+                format!("PLACE/tl_{year}_{state}_place.zip")
             }
-            (TigerResourceBuilder::Tiger2010Format { year }, Geoid::CensusTract(state, _, _)) => {
-                format!("TRACT/tl_{}_{}_tract.zip", year, state.geoid_string())
-            }
-
-            (TigerResourceBuilder::Tiger2010Format { year }, Geoid::BlockGroup(state, _, _, _)) => {
-                format!("BG/tl_{}_{}_bg.zip", year, state.geoid_string())
-            }
-            (TigerResourceBuilder::Tiger2010Format { year }, Geoid::Block(state, _, _, _)) => {
-                format!(
-                    "TABBLOCK/tl_{}_{}_tabblock10.zip",
-                    year,
-                    state.geoid_string()
-                )
-            }
-            //// ~~~~ 2020-2029 ~~~~ ////
-            (TigerResourceBuilder::Tiger2020Format { year }, Geoid::State(_)) => {
-                format!("STATE/tl_{year}_us_state.zip",)
-            }
-            (TigerResourceBuilder::Tiger2020Format { year }, Geoid::County(_, _)) => {
-                format!("COUNTY/tl_{year}_us_county.zip")
+            (B::Tiger2010Format { year } | B::Tiger2020Format { year }, G::CensusTract) => {
+                format!("TRACT/tl_{year}_{state}_tract.zip")
             }
-            (
-                TigerResourceBuilder::Tiger2020Format { year },
-                Geoid::CountySubdivision(state, _, _),
-            ) => {
-                format!("COUSUB/tl_{}_{}_cousub.zip", year, state.geoid_string())
+            (B::Tiger2010Format { year } | B::Tiger2020Format { year }, G::BlockGroup) => {
+                format!("BG/tl_{year}_{state}_bg.zip")
             }
-            (TigerResourceBuilder::Tiger2020Format { year }, Geoid::Place(state, _)) => {
-                format!("PLACE/tl_{}_{}_place.zip", year, state.geoid_string(),)
+            (B::Tiger2010Format { year }, G::Block) => {
+                format!("TABBLOCK/tl_{year}_{state}_tabblock10.zip")
             }
-            (TigerResourceBuilder::Tiger2020Format { year }, Geoid::CensusTract(state, _, _)) => {
-                format!("TRACT/tl_{}_{}_tract.zip", year, state.geoid_string())
+            (B::Tiger2020Format { year }, G::Block) => {
+                format!("TABBLOCK20/tl_{year}_{state}_tabblock20.zip")
             }
-            (TigerResourceBuilder::Tiger2020Format { year }, Geoid::BlockGroup(state, _, _, _)) => {
-                format!("BG/tl_{}_{}_bg.zip", year, state.geoid_string())
+            //// ~~~~ 2020 redistricting ~~~~ ////
+            (B::Tiger2020Format { year: 2020 }, G::VotingDistrict) => {
+                format!("VTD/2020/tl_2020_{state}_vtd20.zip")
             }
-            (TigerResourceBuilder::Tiger2020Format { year }, Geoid::Block(state, _, _, _)) => {
-                format!(
-                    "TABBLOCK20/tl_{}_{}_tabblock20.zip",
-                    year,
-                    state.geoid_string()
-                )
+            (_, G::VotingDistrict) => {
+                return Err(format!(
+                    "voting district boundaries are only published in the 2020 PL 94-171 TIGER/Lines, not {}",
+                    self.get_year()
+                ))
             }
         };
 
-        let file_scope = match (self, geoid) {
-            (TigerResourceBuilder::Tiger2010, Geoid::State(_)) => Some(GeoidType::State),
-            (TigerResourceBuilder::Tiger2010, Geoid::County(_, _)) => Some(GeoidType::State),
-            (TigerResourceBuilder::Tiger2010, Geoid::CountySubdivision(_, _, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2010, Geoid::Place(_, _)) => Some(GeoidType::State),
-            (TigerResourceBuilder::Tiger2010, Geoid::CensusTract(_, _, _)) => {
-                Some(GeoidType::County)
-            }
-            (TigerResourceBuilder::Tiger2010, Geoid::BlockGroup(_, _, _, _)) => {
-                Some(GeoidType::County)
-            }
-            (TigerResourceBuilder::Tiger2010, Geoid::Block(_, _, _, _)) => Some(GeoidType::County),
-            (TigerResourceBuilder::Tiger2010Format { year: _ }, Geoid::State(_)) => None,
-            (TigerResourceBuilder::Tiger2010Format { year: _ }, Geoid::County(_, _)) => None,
-            (
-                TigerResourceBuilder::Tiger2010Format { year: _ },
-                Geoid::CountySubdivision(_, _, _),
-            ) => Some(GeoidType::State),
-            (TigerResourceBuilder::Tiger2010Format { year: _ }, Geoid::Place(_, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2010Format { year: _ }, Geoid::CensusTract(_, _, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2010Format { year: _ }, Geoid::BlockGroup(_, _, _, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2010Format { year: _ }, Geoid::Block(_, _, _, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2020Format { year: _ }, Geoid::State(_)) => None,
-            (TigerResourceBuilder::Tiger2020Format { year: _ }, Geoid::County(_, _)) => None,
-            (
-                TigerResourceBuilder::Tiger2020Format { year: _ },
-                Geoid::CountySubdivision(_, _, _),
-            ) => Some(GeoidType::State),
-            (TigerResourceBuilder::Tiger2020Format { year: _ }, Geoid::Place(_, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2020Format { year: _ }, Geoid::CensusTract(_, _, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2020Format { year: _ }, Geoid::BlockGroup(_, _, _, _)) => {
-                Some(GeoidType::State)
-            }
-            (TigerResourceBuilder::Tiger2020Format { year: _ }, Geoid::Block(_, _, _, _)) => {
-                Some(GeoidType::State)
+        let file_scope = match (self, geoid_type) {
+            (_, G::Nation | G::Region | G::Division) => None,
+            (B::Tiger2010, G::State | G::County | G::Place) => Some(G::State),
+            (B::Tiger2010, G::CountySubdivision | G::CensusTract | G::BlockGroup | G::Block) => {
+                Some(G::County)
             }
+            (_, G::State | G::County) => None,
+            (_, _) => Some(G::State),
         };
 
-        let prefix = self.base_url();
+        // the redistricting layers are published apart from the annual TIGER/Lines
+        let prefix = match geoid_type {
+            G::VotingDistrict => format!("{}PL/LAYER", self.base_url(root)),
+            _ => self.base_url(root),
+        };
         let uri = format!("{prefix}/{suffix}");
-        let geoid_type = geoid.geoid_type();
-        // let geoid_column_name = self.geoid_shapefile_colname(&suffix);
-        let tiger_uri = TigerResource::new(uri, geoid_type, file_scope); //, geoid_column_name);
-        Ok(tiger_uri)
+        Ok(TigerResource::new(uri, geoid_type, file_scope))
+    }
+
+    /// creates the URI to the national file for a [`TigerFeature`]. these files are not
+    /// organized by GEOID, so a single file covers the entire country.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::{TigerFeature, TigerResourceBuilder};
+    ///
+    /// let builder = TigerResourceBuilder::new(2020).unwrap();
+    /// let uri = builder.create_feature_uri(&TigerFeature::PrimaryRoads);
+    /// assert_eq!(uri, "https://www2.census.gov/geo/tiger/TIGER2020/PRIMARYROADS/tl_2020_us_primaryroads.zip");
+    /// let uri = builder.create_feature_uri(&TigerFeature::Rails);
+    /// assert_eq!(uri, "https://www2.census.gov/geo/tiger/TIGER2020/RAILS/tl_2020_us_rails.zip");
+    /// ```
+    pub fn create_feature_uri(&self, feature: &TigerFeature) -> String {
+        self.create_feature_uri_at(TigerResourceBuilder::TIGER_BASE_URL, feature)
+    }
+
+    /// as [`TigerResourceBuilder::create_feature_uri`], with the URI under the given
+    /// root of the TIGER/Lines directory, such as a mirror, instead of
+    /// [`TigerResourceBuilder::TIGER_BASE_URL`].
+    pub fn create_feature_uri_at(&self, root: &str, feature: &TigerFeature) -> String {
+        let prefix = self.base_url(root);
+        let year = self.get_year();
+        let directory = feature.directory_name();
+        format!("{prefix}/{directory}/tl_{year}_us_{feature}.zip")
+    }
+
+    /// best-effort check that a Geoid can be found in this TIGER/Lines vintage, without
+    /// downloading anything. this confirms that:
+    ///   - the state FIPS code is a known state
+    ///   - this builder can create a resource for the Geoid type
+    ///   - the county does not fall on the wrong side of a known county-level change,
+    ///     such as the 2022 replacement of Connecticut counties with planning regions,
+    ///     see [`Geoid::exists_in_vintage`]
+    ///
+    /// passing this check does not guarantee that the Geoid exists in the file. tracts,
+    /// block groups and blocks are redrawn every decennial census, so full existence
+    /// can only be confirmed by downloading the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TigerResourceBuilder;
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    ///
+    /// // Connecticut planning regions replaced counties in 2022
+    /// let capitol_region = Geoid::County(fips::State(9), fips::County(110));
+    /// assert!(TigerResourceBuilder::new(2022).unwrap().validate_geoid(&capitol_region).is_ok());
+    /// assert!(TigerResourceBuilder::new(2015).unwrap().validate_geoid(&capitol_region).is_err());
+    ///
+    /// // 72 is Puerto Rico, which is not supported
+    /// let san_juan = Geoid::County(fips::State(72), fips::County(127));
+    /// assert!(TigerResourceBuilder::new(2015).unwrap().validate_geoid(&san_juan).is_err());
+    ///
+    /// // census regions are only published in the 2010 TIGER/Lines
+    /// let west = Geoid::Region(fips::Region(4));
+    /// assert!(TigerResourceBuilder::new(2010).unwrap().validate_geoid(&west).is_ok());
+    /// assert!(TigerResourceBuilder::new(2020).unwrap().validate_geoid(&west).is_err());
+    /// ```
+    pub fn validate_geoid(&self, geoid: &Geoid) -> Result<(), String> {
+        if matches!(geoid, Geoid::Nation | Geoid::Region(_) | Geoid::Division(_)) {
+            return self.create_resource(geoid).map(|_| ());
+        }
+        let state_code = StateCode::try_from(geoid.clone())?;
+        let _ = self.create_resource(geoid)?;
+        geoid.exists_in_vintage(self.get_year()).map_err(|e| {
+            format!(
+                "{e}, so it is not in the {} TIGER/Lines for {}",
+                self.get_year(),
+                state_code.to_full_name()
+            )
+        })?;
+        Ok(())
     }
 
     // pub fn geoid_shapefile_colname(&self, filename: &str) -> String {
@@ -279,9 +465,9 @@ impl TigerResourceBuilder {
         }
     }
 
-    /// creates a URL to a TIGER file location.
-    fn base_url(&self) -> String {
+    /// creates a URL to a TIGER file location under the root of the TIGER/Lines directory.
+    fn base_url(&self, root: &str) -> String {
         let year = self.get_year();
-        format!("{}/TIGER{}", TigerResourceBuilder::TIGER_BASE_URL, year)
+        format!("{}/TIGER{}", root.trim_end_matches('/'), year)
     }
 }
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// the two census tract vintages related by the census tract relationship files.
+/// tracts are redrawn for each decennial census, and ACS and LODES datasets use the
+/// tracts of the most recent decennial census before their year.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TractVintage {
+    /// tracts of the 2010 decennial census, used by datasets from 2010 through 2019
+    Tract2010,
+    /// tracts of the 2020 decennial census, used by datasets from 2020 onward
+    Tract2020,
+}
+
+impl TractVintage {
+    /// the tract vintage used by a dataset year.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::model::TractVintage;
+    ///
+    /// assert_eq!(TractVintage::for_year(2012).unwrap(), TractVintage::Tract2010);
+    /// assert_eq!(TractVintage::for_year(2020).unwrap(), TractVintage::Tract2020);
+    /// assert!(TractVintage::for_year(2005).is_err());
+    /// ```
+    pub fn for_year(year: u64) -> Result<TractVintage, String> {
+        match year {
+            2010..=2019 => Ok(TractVintage::Tract2010),
+            y if y >= 2020 => Ok(TractVintage::Tract2020),
+            _ => Err(format!(
+                "no tract relationship file covers the tract vintage of year {year}, expected 2010 or later"
+            )),
+        }
+    }
+}
+
+/// a row of a census tract relationship file, describing the part of a 2020 tract
+/// that overlaps a 2010 tract. a tract that was split or merged appears in a row for
+/// each tract of the other vintage that it overlaps. areas are in square meters.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TractRelationship {
+    #[serde(rename = "GEOID_TRACT_20")]
+    pub geoid_tract_20: String,
+    #[serde(rename = "GEOID_TRACT_10")]
+    pub geoid_tract_10: String,
+    /// land area of the overlapping part
+    #[serde(rename = "AREALAND_PART")]
+    pub arealand_part: f64,
+    /// water area of the overlapping part
+    #[serde(rename = "AREAWATER_PART")]
+    pub areawater_part: f64,
+}
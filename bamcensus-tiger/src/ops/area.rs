@@ -0,0 +1,107 @@
+use geo::{Area, GeodesicArea, MapCoords};
+use geo_types::{Coord, Geometry};
+
+/// square meters per square kilometer.
+const SQ_METERS_PER_SQ_KM: f64 = 1_000_000.0;
+/// semi-major axis of the GRS 80 ellipsoid used by NAD83, in meters.
+const GRS80_A: f64 = 6_378_137.0;
+/// flattening of the GRS 80 ellipsoid.
+const GRS80_F: f64 = 1.0 / 298.257_222_101;
+
+/// an Albers equal-area conic projection of the GRS 80 ellipsoid, in meters. areas
+/// measured in an equal-area projection match areas on the ellipsoid, while areas
+/// measured directly in the degrees of TIGER/Lines geometries do not.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AlbersEqualArea {
+    /// first standard parallel, in degrees
+    pub lat_1: f64,
+    /// second standard parallel, in degrees
+    pub lat_2: f64,
+    /// latitude of origin, in degrees
+    pub lat_0: f64,
+    /// central meridian, in degrees
+    pub lon_0: f64,
+}
+
+impl AlbersEqualArea {
+    /// NAD83 / Conus Albers (EPSG:5070), for the contiguous United States.
+    pub const CONUS: AlbersEqualArea = AlbersEqualArea {
+        lat_1: 29.5,
+        lat_2: 45.5,
+        lat_0: 23.0,
+        lon_0: -96.0,
+    };
+    /// NAD83 / Alaska Albers (EPSG:3338).
+    pub const ALASKA: AlbersEqualArea = AlbersEqualArea {
+        lat_1: 55.0,
+        lat_2: 65.0,
+        lat_0: 50.0,
+        lon_0: -154.0,
+    };
+    /// Hawaii Albers Equal Area Conic (ESRI:102007).
+    pub const HAWAII: AlbersEqualArea = AlbersEqualArea {
+        lat_1: 8.0,
+        lat_2: 18.0,
+        lat_0: 3.0,
+        lon_0: -157.0,
+    };
+
+    /// projects a longitude, latitude coordinate in degrees to meters, following
+    /// Snyder, Map Projections: A Working Manual (1987), equations 14-3 to 14-6.
+    pub fn project(&self, coord: Coord) -> Coord {
+        let e2 = GRS80_F * (2.0 - GRS80_F);
+        let e = e2.sqrt();
+        let q = |lat: f64| {
+            let sin = lat.to_radians().sin();
+            (1.0 - e2)
+                * (sin / (1.0 - e2 * sin * sin)
+                    - (1.0 / (2.0 * e)) * ((1.0 - e * sin) / (1.0 + e * sin)).ln())
+        };
+        let m = |lat: f64| {
+            let rad = lat.to_radians();
+            rad.cos() / (1.0 - e2 * rad.sin().powi(2)).sqrt()
+        };
+        let (m1, m2) = (m(self.lat_1), m(self.lat_2));
+        let (q1, q2) = (q(self.lat_1), q(self.lat_2));
+        let n = (m1 * m1 - m2 * m2) / (q2 - q1);
+        let c = m1 * m1 + n * q1;
+        let rho = |lat: f64| GRS80_A * (c - n * q(lat)).sqrt() / n;
+        let rho_0 = rho(self.lat_0);
+        let rho = rho(coord.y);
+        let theta = n * (coord.x - self.lon_0).to_radians();
+        Coord {
+            x: rho * theta.sin(),
+            y: rho_0 - rho * theta.cos(),
+        }
+    }
+
+    /// area of a geometry with coordinates in longitude, latitude degrees, measured
+    /// in this projection, in square kilometers. points and lines have no area.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus_tiger::ops::area::{geodesic_area_sqkm, AlbersEqualArea};
+    /// use geo_types::{polygon, Geometry};
+    ///
+    /// // a tenth of a degree square in Colorado is about 95 square kilometers, while
+    /// // its area in degrees is 0.01
+    /// let square = Geometry::Polygon(polygon![
+    ///     (x: -105.0, y: 39.7), (x: -104.9, y: 39.7), (x: -104.9, y: 39.8),
+    ///     (x: -105.0, y: 39.8), (x: -105.0, y: 39.7),
+    /// ]);
+    /// let albers = AlbersEqualArea::CONUS.area_sqkm(&square);
+    /// let geodesic = geodesic_area_sqkm(&square);
+    /// assert!((albers - 95.2).abs() < 0.5, "found {albers}");
+    /// assert!((albers - geodesic).abs() / geodesic < 0.001);
+    /// ```
+    pub fn area_sqkm(&self, geometry: &Geometry) -> f64 {
+        geometry.map_coords(|c| self.project(c)).unsigned_area() / SQ_METERS_PER_SQ_KM
+    }
+}
+
+/// area of a geometry with coordinates in longitude, latitude degrees, measured on
+/// the WGS 84 ellipsoid, in square kilometers. points and lines have no area.
+pub fn geodesic_area_sqkm(geometry: &Geometry) -> f64 {
+    geometry.geodesic_area_unsigned() / SQ_METERS_PER_SQ_KM
+}
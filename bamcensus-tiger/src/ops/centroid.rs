@@ -0,0 +1,37 @@
+use geo::Centroid;
+use geo_types::{Geometry, Point};
+
+/// replaces a geometry with a point. the `internal_point` is used when provided, as
+/// TIGER/Lines internal points are placed inside the geography, while the centroid of
+/// an irregular shape, such as a crescent or a set of islands, can fall outside of it.
+/// otherwise the geometric centroid is used. empty geometries, which have no centroid,
+/// are returned unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_tiger::ops::centroid::centroid_geometry;
+/// use geo_types::{point, polygon, Geometry};
+///
+/// let square = Geometry::Polygon(polygon![
+///     (x: 0.0, y: 0.0), (x: 10.0, y: 0.0), (x: 10.0, y: 10.0),
+///     (x: 0.0, y: 10.0), (x: 0.0, y: 0.0),
+/// ]);
+/// assert_eq!(
+///     centroid_geometry(square.clone(), None),
+///     Geometry::Point(point!(x: 5.0, y: 5.0))
+/// );
+///
+/// // a published internal point takes precedence
+/// let internal = point!(x: 2.0, y: 3.0);
+/// assert_eq!(
+///     centroid_geometry(square, Some(internal)),
+///     Geometry::Point(internal)
+/// );
+/// ```
+pub fn centroid_geometry(geometry: Geometry, internal_point: Option<Point>) -> Geometry {
+    match internal_point.or_else(|| geometry.centroid()) {
+        Some(point) => Geometry::Point(point),
+        None => geometry,
+    }
+}
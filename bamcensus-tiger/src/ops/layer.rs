@@ -0,0 +1,48 @@
+use bamcensus_core::model::identifier::Geoid;
+use geo_types::Geometry;
+use itertools::Itertools;
+
+/// assembles the per-archive results of [`crate::ops::tiger_api::run`] into one
+/// layer. geographies such as places, county subdivisions and tracts are published
+/// in one file per state, so a region spanning several states is read from several
+/// archives, which complete in any order. the rows of every archive are concatenated
+/// and sorted by geoid, which orders them by state, then county and so on, so that
+/// the same request always produces the same layer. rows sharing a geoid keep the
+/// order of their archives.
+///
+/// archives that failed are not part of the layer, and their errors are returned
+/// alongside it, in the order of the archives.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::{Geoid, HasGeoidString};
+/// use bamcensus_tiger::ops::layer::assemble_layer;
+/// use geo_types::{point, Geometry};
+///
+/// let tract = |geoid: &str| {
+///     let geometry = Geometry::Point(point!(x: 0.0, y: 0.0));
+///     (Geoid::try_from(geoid).unwrap(), geometry)
+/// };
+/// let archives = vec![
+///     Ok(vec![tract("56021000100"), tract("56001962700")]),
+///     Err(String::from("failure downloading tl_2020_31_tract.zip")),
+///     Ok(vec![tract("08069000100"), tract("08031000201")]),
+/// ];
+/// let (layer, errors) = assemble_layer(archives);
+/// let geoids = layer.iter().map(|(g, _)| g.geoid_string()).collect::<Vec<_>>();
+/// assert_eq!(geoids, vec!["08031000201", "08069000100", "56001962700", "56021000100"]);
+/// assert_eq!(errors, vec!["failure downloading tl_2020_31_tract.zip"]);
+/// ```
+pub fn assemble_layer(
+    archives: Vec<Result<Vec<(Geoid, Geometry)>, String>>,
+) -> (Vec<(Geoid, Geometry)>, Vec<String>) {
+    let (rows, errors): (Vec<Vec<(Geoid, Geometry)>>, Vec<String>) =
+        archives.into_iter().partition_result();
+    let layer = rows
+        .into_iter()
+        .flatten()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .collect_vec();
+    (layer, errors)
+}
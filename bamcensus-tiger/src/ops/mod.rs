@@ -1 +1,7 @@
+pub mod area;
+pub mod centroid;
+pub mod layer;
+pub mod simplify;
 pub mod tiger_api;
+pub mod tract_crosswalk;
+pub mod vintage;
@@ -0,0 +1,103 @@
+use geo::{Area, Simplify};
+use geo_types::{Geometry, LineString, MultiLineString, MultiPolygon, Polygon};
+
+/// confirms a simplification tolerance is a finite, positive distance.
+pub fn validate_tolerance(tolerance: f64) -> Result<(), String> {
+    if tolerance.is_finite() && tolerance > 0.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "simplification tolerance must be a positive number, found {tolerance}"
+        ))
+    }
+}
+
+/// simplifies a geometry with the Ramer-Douglas-Peucker algorithm, where the tolerance
+/// is a distance in the native units of the geometry (degrees for TIGER/Lines files).
+///
+/// simplification never produces an empty geometry. polygon parts and holes that
+/// collapse to zero area are dropped, and if every part of a geometry would collapse,
+/// the original geometry is returned unchanged. point geometries are not modified.
+///
+/// # Examples
+///
+/// ```rust
+/// use bamcensus_tiger::ops::simplify::simplify_geometry;
+/// use geo_types::{polygon, Geometry};
+///
+/// // the midpoint of the bottom edge is within the tolerance and is removed
+/// let square = Geometry::Polygon(polygon![
+///     (x: 0.0, y: 0.0), (x: 5.0, y: 0.01), (x: 10.0, y: 0.0),
+///     (x: 10.0, y: 10.0), (x: 0.0, y: 10.0), (x: 0.0, y: 0.0),
+/// ]);
+/// let simplified = simplify_geometry(square, 0.1);
+/// match simplified {
+///     Geometry::Polygon(p) => assert_eq!(p.exterior().0.len(), 5),
+///     _ => panic!("expected a polygon"),
+/// }
+///
+/// // a sliver that would collapse entirely is left as-is
+/// let sliver = Geometry::Polygon(polygon![
+///     (x: 0.0, y: 0.0), (x: 5.0, y: 0.01), (x: 10.0, y: 0.0),
+///     (x: 5.0, y: -0.01), (x: 0.0, y: 0.0),
+/// ]);
+/// assert_eq!(simplify_geometry(sliver.clone(), 1.0), sliver);
+/// ```
+pub fn simplify_geometry(geometry: Geometry, tolerance: f64) -> Geometry {
+    match geometry {
+        Geometry::LineString(l) => Geometry::LineString(simplify_line_string(l, tolerance)),
+        Geometry::MultiLineString(mls) => {
+            let lines = mls
+                .0
+                .into_iter()
+                .map(|l| simplify_line_string(l, tolerance))
+                .collect();
+            Geometry::MultiLineString(MultiLineString::new(lines))
+        }
+        Geometry::Polygon(p) => Geometry::Polygon(simplify_polygon(&p, tolerance).unwrap_or(p)),
+        Geometry::MultiPolygon(mp) => {
+            let parts: Vec<Polygon> = mp
+                .iter()
+                .filter_map(|p| simplify_polygon(p, tolerance))
+                .collect();
+            if parts.is_empty() {
+                Geometry::MultiPolygon(mp)
+            } else {
+                Geometry::MultiPolygon(MultiPolygon::new(parts))
+            }
+        }
+        other => other,
+    }
+}
+
+/// simplifies a line, keeping the original if fewer than two coordinates would remain.
+fn simplify_line_string(line: LineString, tolerance: f64) -> LineString {
+    let simplified = line.simplify(&tolerance);
+    if simplified.0.len() < 2 {
+        line
+    } else {
+        simplified
+    }
+}
+
+/// simplifies a polygon, dropping any holes that collapse. returns None if the
+/// exterior ring collapses to zero area.
+fn simplify_polygon(polygon: &Polygon, tolerance: f64) -> Option<Polygon> {
+    let exterior = Polygon::new(polygon.exterior().simplify(&tolerance), vec![]);
+    if is_collapsed(&exterior) {
+        return None;
+    }
+    let interiors = polygon
+        .interiors()
+        .iter()
+        .map(|ring| Polygon::new(ring.simplify(&tolerance), vec![]))
+        .filter(|ring| !is_collapsed(ring))
+        .map(|ring| ring.exterior().clone())
+        .collect();
+    Some(Polygon::new(exterior.exterior().clone(), interiors))
+}
+
+/// true if a ring no longer encloses any area.
+fn is_collapsed(ring: &Polygon) -> bool {
+    ring.exterior().0.len() < 4 || ring.unsigned_area() == 0.0
+}
@@ -1,19 +1,31 @@
+use crate::model::GeometryTransform;
+use crate::model::PrefetchSummary;
+use crate::model::RunOptions;
+use crate::model::TigerCache;
+use crate::model::TigerFeature;
 use crate::model::TigerResource;
 use crate::model::TigerResourceBuilder;
+use crate::ops::centroid::centroid_geometry;
+use crate::ops::simplify::{simplify_geometry, validate_tolerance};
 use bamcensus_core::model::identifier::Geoid;
 use bamcensus_core::model::identifier::GeoidType;
-use futures::StreamExt;
-use geo_types::Geometry;
+use bamcensus_core::model::{head_content_length, ByteCounter, DownloadEstimate};
+use bamcensus_core::ops::progress::ProgressBar;
+use futures::{Stream, StreamExt};
+use geo_types::{Geometry, Point};
 use itertools::Itertools;
-use kdam::BarExt;
 use log;
+use rayon::prelude::*;
 use reqwest::Client;
 use shapefile::dbase::Record;
 use shapefile::{dbase, Shape, ShapeReader};
 use std::collections::HashSet;
 use std::fs::File;
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::io::AsyncWriteExt;
 use zip::ZipArchive;
 
@@ -24,117 +36,807 @@ use zip::ZipArchive;
 /// site. the archives are Shapefile archives, and there isn't great
 /// tooling to stream these data sources, so here we chose to download
 /// the archives, unpack, and then load from the extracted file paths.
-pub async fn run<'a>(
+///
+/// the run is configured with [`RunOptions`], which are described below.
+///
+/// archives are written to the `scratch_dir` if provided. otherwise, the directory set
+/// by the `BAMCENSUS_SCRATCH_DIR` environment variable is used, falling back to the
+/// system temporary directory (see [`std::env::temp_dir`]). national block files can
+/// be large, so on machines with a small temporary filesystem, point this at a
+/// larger volume.
+///
+/// if a `cache` is provided, archives already in the cache are read from disk and
+/// any downloaded archives are added to it. see [`prefetch`] to fill a cache ahead
+/// of time.
+///
+/// if a `transform` is provided, it is applied to each matching geometry, such as
+/// simplifying it with [`simplify_geometry`] or replacing it with its internal point,
+/// see [`GeometryTransform`].
+///
+/// geoids are read from the first of the "GEOID", "GEOID20" and "GEOID10" columns
+/// present in each file. if a `geoid_column` is provided, only that column is read,
+/// which is useful when a file carries more than one of these columns.
+///
+/// if a `limit` is provided, reading each file stops once that many matching rows
+/// have been found, which is useful for previewing a few geometries of a large file.
+/// archives are still downloaded in full, since the index of a zip archive is at
+/// its end, but records past the limit are not parsed.
+///
+/// if a `keep_files` directory is provided, or the [`KEEP_FILES_ENV_VAR`] environment
+/// variable is set, each archive is copied there along with the .shp and .dbf files
+/// extracted from it, named after the archive, such as `tl_2020_08_tract.dbf`. this
+/// helps diagnose geoids that did not match, by opening the .dbf that was read.
+///
+/// if a `bytes` counter is provided, the size of each archive downloaded is added to
+/// it. archives read from the cache are not counted.
+///
+/// if a `base_url` is provided, archives are downloaded from that root of the
+/// TIGER/Lines directory instead of [`TigerResourceBuilder::TIGER_BASE_URL`], and
+/// archives in the `cache` are stored by their path under that root.
+///
+/// at most `concurrency` archives are downloaded at a time, [`DEFAULT_CONCURRENCY`] if
+/// not provided, and the results are returned in the order of the archives.
+///
+/// if a `cancel` token is provided, cancelling it stops the run: archives that have
+/// not been requested are skipped, downloads in flight are dropped, and the results
+/// of the archives that were already read are returned. each skipped archive is an
+/// error of the form "{uri}: cancelled", so the result still has one entry per
+/// archive. [`run_stream`] is cancelled by dropping the stream.
+pub async fn run(
     client: &Client,
     builder: &TigerResourceBuilder,
     geoids: &[&Geoid],
+    options: &RunOptions,
 ) -> Result<Vec<Result<Vec<(Geoid, Geometry)>, String>>, String> {
-    let uris = builder.create_resources(geoids)?;
-    let lookup = geoids.iter().collect::<HashSet<_>>();
+    if let Some(GeometryTransform::Simplify { tolerance }) = options.transform {
+        validate_tolerance(tolerance)?;
+    }
+    let scratch_dir = resolve_scratch_dir(options.scratch_dir.as_deref());
+    let keep_files = resolve_keep_files(options.keep_files.as_deref());
+    let cancel = options.cancel.as_ref();
+    let concurrency = resolve_concurrency(options.concurrency, DEFAULT_CONCURRENCY)?;
+    let uris = builder.create_resources_at(&options.tiger_base_url(), geoids)?;
+    let n_archives = uris.len();
+    let lookup = geoids.iter().copied().collect::<HashSet<_>>();
+    let n_cancelled = AtomicUsize::new(0);
 
-    let pb_builder = kdam::BarBuilder::default()
-        .total(uris.len())
-        .desc("TIGER/Lines downloads");
-    let pb = Arc::new(Mutex::new(pb_builder.build()?));
+    let pb = Arc::new(Mutex::new(ProgressBar::new(
+        n_archives,
+        "TIGER/Lines downloads",
+    )?));
 
-    let run_results = uris
-        .into_iter()
+    let run_results = futures::stream::iter(uris)
         .map(|tiger| {
             log::debug!("downloading {}", tiger.uri);
             let client = &client;
             let lookup = &lookup;
+            let scratch_dir = &scratch_dir;
+            let keep_files = keep_files.as_deref();
             let pb = pb.clone();
+            let n_cancelled = &n_cancelled;
             async move {
-                // create temporary file for writing .zip download
-                let named_tmp = tempfile::NamedTempFile::new()
-                    .map_err(|e| format!("failure creating temporary zip archive filepath: {e}"))?;
-                let read_path = named_tmp.path().to_path_buf().clone();
-
-                // download archive
-                let write_file = File::create(&read_path)
-                    .map_err(|e| format!("failure creating temporary zip archive file: {e}"))?;
-                download(client, &tiger.uri, write_file).await?;
-
-                // unpack archive
-                let read_file = File::open(&read_path).map_err(|e| {
-                    format!("failure opening temporary zip archive file location: {e}")
-                })?;
-                let mut z = ZipArchive::new(read_file)
-                    .map_err(|e| format!("failure reading temporary zip archive: {e}"))?;
-                let shp_filename = get_zip_filename(&z, ".shp")?;
-                let dbf_filename = get_zip_filename(&z, ".dbf")?;
-                let shp_contents = zip_file_into_string(&mut z, &shp_filename)?;
-                let dbf_contents = zip_file_into_string(&mut z, &dbf_filename)?;
-
-                // read shapes and records
-                let mut reader = create_shapefile_reader(&shp_contents, &dbf_contents)?;
-                let read_result = reader
-                    .iter_shapes_and_records()
-                    .map(|row| {
-                        let (shape, record) = row
-                            .map_err(|e| format!("failure reading shapefile shape/record: {e}"))?;
-                        into_geoid_and_geometry(shape, record, lookup, &tiger)
-                    })
-                    .collect::<Result<Vec<_>, String>>()?;
-                let result = read_result.into_iter().flatten().collect_vec();
-
-                // update progress bar
-                let mut pb_update = pb
-                    .lock()
-                    .map_err(|e| format!("failure aquiring progress bar mutex lock: {e}"))?;
-                pb_update
-                    .update(1)
-                    .map_err(|e| format!("failure on pb update: {e}"))?;
-                pb_update.set_description(tiger.uri.split('/').next_back().unwrap_or_default());
-
-                Ok(result)
+                let read =
+                    read_matching_rows(client, &tiger, lookup, scratch_dir, keep_files, options);
+                let result = match cancel {
+                    // checked first, so that no request is sent once cancelled
+                    Some(token) => tokio::select! {
+                        biased;
+                        _ = token.cancelled() => {
+                            n_cancelled.fetch_add(1, Ordering::Relaxed);
+                            return Err(format!("{}: cancelled", tiger.uri));
+                        }
+                        result = read => result,
+                    },
+                    None => read.await,
+                };
+                result.and_then(|rows| {
+                    // update progress bar
+                    let mut pb_update = pb
+                        .lock()
+                        .map_err(|e| format!("failure aquiring progress bar mutex lock: {e}"))?;
+                    pb_update.update(1)?;
+                    pb_update.set_description(tiger.uri.split('/').next_back().unwrap_or_default());
+                    Ok(rows)
+                })
             }
         })
-        .collect::<Vec<_>>();
-    let result = futures::future::join_all(run_results).await;
-    eprintln!(); // terminate progress bar
+        .buffered(concurrency);
+    let result = run_results.collect::<Vec<_>>().await;
+    if let Ok(pb) = pb.lock() {
+        pb.finish();
+    }
+    let n_cancelled = n_cancelled.into_inner();
+    if n_cancelled > 0 {
+        log::warn!(
+            "TIGER/Lines run cancelled, {} of {} archives were not read",
+            n_cancelled,
+            n_archives
+        );
+    }
     Ok(result)
 }
 
+/// default maximum number of concurrent archive downloads in a [`run`]
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// default maximum number of concurrent archive downloads in a [`run_stream`]
+pub const STREAM_CONCURRENCY: usize = 4;
+
+/// the maximum number of concurrent archive downloads of a run, refusing zero, which
+/// would never start a download.
+fn resolve_concurrency(concurrency: Option<usize>, default: usize) -> Result<usize, String> {
+    match concurrency {
+        Some(0) => Err(String::from("TIGER/Lines concurrency must be at least 1")),
+        Some(n) => Ok(n),
+        None => Ok(default),
+    }
+}
+
+/// a streaming version of [`run`], which yields each matching row as soon as the
+/// archive containing it has been read, rather than collecting every archive first.
+/// rows arrive in the order of the archives, and at most `concurrency` archives,
+/// [`STREAM_CONCURRENCY`] if not provided, are downloaded ahead of the consumer, so a
+/// slow consumer applies backpressure to the downloads. a failure reading an archive is yielded as an
+/// error in place of its rows, and the remaining archives are still read.
+///
+/// options are validated before anything is downloaded, and are used as in [`run`],
+/// except for the cancellation token, since a stream is cancelled by dropping it.
+///
+/// # Example
+///
+/// downloads the 2020 Colorado county file and prints each county as it arrives.
+/// this requires network access, so it is only compiled.
+///
+/// ```no_run
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use bamcensus_core::ops::http;
+/// use bamcensus_tiger::model::{RunOptions, TigerResourceBuilder};
+/// use bamcensus_tiger::ops::tiger_api;
+/// use futures::StreamExt;
+///
+/// let counties = [
+///     Geoid::County(fips::State(8), fips::County(31)),
+///     Geoid::County(fips::State(8), fips::County(59)),
+/// ];
+/// let geoids = counties.iter().collect::<Vec<_>>();
+/// let builder = TigerResourceBuilder::new(2020).unwrap();
+/// let client = http::client_builder().build().unwrap();
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// runtime.block_on(async {
+///     let mut rows =
+///         tiger_api::run_stream(&client, &builder, &geoids, RunOptions::default()).unwrap();
+///     while let Some(row) = rows.next().await {
+///         let (geoid, _geometry) = row.unwrap();
+///         println!("{geoid}");
+///     }
+/// });
+/// ```
+pub fn run_stream<'a>(
+    client: &'a Client,
+    builder: &TigerResourceBuilder,
+    geoids: &'a [&'a Geoid],
+    options: RunOptions,
+) -> Result<impl Stream<Item = Result<(Geoid, Geometry), String>> + 'a, String> {
+    if let Some(GeometryTransform::Simplify { tolerance }) = options.transform {
+        validate_tolerance(tolerance)?;
+    }
+    let scratch_dir = resolve_scratch_dir(options.scratch_dir.as_deref());
+    let keep_files = resolve_keep_files(options.keep_files.as_deref());
+    let concurrency = resolve_concurrency(options.concurrency, STREAM_CONCURRENCY)?;
+    let uris = builder.create_resources_at(&options.tiger_base_url(), geoids)?;
+    let lookup = Arc::new(geoids.iter().copied().collect::<HashSet<_>>());
+    let options = Arc::new(options);
+
+    let rows = futures::stream::iter(uris)
+        .map(move |tiger| {
+            log::debug!("downloading {}", tiger.uri);
+            let lookup = lookup.clone();
+            let scratch_dir = scratch_dir.clone();
+            let keep_files = keep_files.clone();
+            let options = options.clone();
+            async move {
+                read_matching_rows(
+                    client,
+                    &tiger,
+                    &lookup,
+                    &scratch_dir,
+                    keep_files.as_deref(),
+                    &options,
+                )
+                .await
+            }
+        })
+        .buffered(concurrency)
+        .flat_map(|result| {
+            let rows = match result {
+                Ok(rows) => rows.into_iter().map(Ok).collect_vec(),
+                Err(e) => vec![Err(e)],
+            };
+            futures::stream::iter(rows)
+        });
+    Ok(rows)
+}
+
+/// downloads the archive of a [`TigerResource`] and returns the rows matching the
+/// lookup, in file order, stopping after the `limit` of the options if provided. the
+/// scratch and keep directories are resolved by the caller.
+async fn read_matching_rows(
+    client: &Client,
+    tiger: &TigerResource,
+    lookup: &HashSet<&Geoid>,
+    scratch_dir: &Path,
+    keep_files: Option<&Path>,
+    options: &RunOptions,
+) -> Result<Vec<(Geoid, Geometry)>, String> {
+    let start = Instant::now();
+    let cache = options.tiger_cache();
+    let (shp_contents, dbf_contents) = download_shapefile(
+        client,
+        &tiger.uri,
+        scratch_dir,
+        cache.as_ref(),
+        keep_files,
+        options.bytes.as_ref(),
+    )
+    .await?;
+    let geoid_column = options.geoid_column.as_deref();
+    let transform = options.transform;
+    log::info!(
+        "TIGER/Lines {} retrieved {} bytes in {:.2?}",
+        tiger.uri,
+        shp_contents.len() + dbf_contents.len(),
+        start.elapsed()
+    );
+
+    let mut reader = create_shapefile_reader(&shp_contents, &dbf_contents)?;
+    if let Some(limit) = options.limit {
+        // read sequentially so that parsing stops at the limit
+        let mut result = Vec::with_capacity(limit);
+        for row in reader.iter_shapes_and_records() {
+            if result.len() >= limit {
+                break;
+            }
+            let (shape, record) =
+                row.map_err(|e| format!("failure reading shapefile shape/record: {e}"))?;
+            if let Some(matched) =
+                into_geoid_and_geometry(shape, record, lookup, tiger, geoid_column, transform)?
+            {
+                result.push(matched);
+            }
+        }
+        return Ok(result);
+    }
+
+    // read shapes and records sequentially, as the shapefile reader
+    // cannot be shared across threads, and then filter and convert
+    // them in parallel. indexed collection preserves file order.
+    let rows = reader
+        .iter_shapes_and_records()
+        .map(|row| row.map_err(|e| format!("failure reading shapefile shape/record: {e}")))
+        .collect::<Result<Vec<_>, String>>()?;
+    let read_result = rows
+        .into_par_iter()
+        .map(|(shape, record)| {
+            into_geoid_and_geometry(shape, record, lookup, tiger, geoid_column, transform)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(read_result.into_iter().flatten().collect_vec())
+}
+
+/// downloads the national file for a [`TigerFeature`], such as primary roads or rails,
+/// returning each feature's id (from the LINEARID column) alongside its geometry.
+/// these files are not GEOID-keyed, so every row in the file is returned.
+///
+/// the `scratch_dir`, `cache`, `keep_files`, `bytes`, `base_url` and `cancel` options
+/// are used as in [`run`], and a cancelled download is the error "{uri}: cancelled".
+/// the `transform`, `geoid_column` and `limit` options only apply to geoid lookups
+/// and are ignored.
+pub async fn run_feature(
+    client: &Client,
+    builder: &TigerResourceBuilder,
+    feature: &TigerFeature,
+    options: &RunOptions,
+) -> Result<Vec<(String, Geometry)>, String> {
+    let uri = builder.create_feature_uri_at(&options.tiger_base_url(), feature);
+    log::debug!("downloading {uri}");
+    let scratch_dir = resolve_scratch_dir(options.scratch_dir.as_deref());
+    let start = Instant::now();
+    let keep_files = resolve_keep_files(options.keep_files.as_deref());
+    let cache = options.tiger_cache();
+    let download = download_shapefile(
+        client,
+        &uri,
+        &scratch_dir,
+        cache.as_ref(),
+        keep_files.as_deref(),
+        options.bytes.as_ref(),
+    );
+    let (shp_contents, dbf_contents) = match options.cancel.as_ref() {
+        // checked first, so that no request is sent once cancelled
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => return Err(format!("{uri}: cancelled")),
+            result = download => result,
+        },
+        None => download.await,
+    }?;
+    log::info!(
+        "TIGER/Lines {uri} retrieved {} bytes in {:.2?}",
+        shp_contents.len() + dbf_contents.len(),
+        start.elapsed()
+    );
+
+    let mut reader = create_shapefile_reader(&shp_contents, &dbf_contents)?;
+    let rows = reader
+        .iter_shapes_and_records()
+        .map(|row| row.map_err(|e| format!("failure reading shapefile shape/record: {e}")))
+        .collect::<Result<Vec<_>, String>>()?;
+    let id_column = feature.id_column_name();
+    rows.into_par_iter()
+        .map(|(shape, record)| {
+            let id = match record.get(id_column) {
+                Some(dbase::FieldValue::Character(Some(id))) => Ok(id.clone()),
+                Some(other) => Err(format!(
+                    "value at column '{id_column}' is not a valid feature id, found '{other}'"
+                )),
+                None => Err(format!("could not find {id_column} in shapefile")),
+            }?;
+            let geometry: Geometry<f64> = shape
+                .try_into()
+                .map_err(|e| format!("could not convert shape into geometry. {e}"))?;
+            Ok((id, geometry))
+        })
+        .collect::<Result<Vec<_>, String>>()
+}
+
+/// estimates the size of a [`run`] for the geoids without downloading anything, by
+/// requesting only the headers of each archive that covers them.
+pub async fn estimate(
+    client: &Client,
+    builder: &TigerResourceBuilder,
+    geoids: &[&Geoid],
+) -> Result<DownloadEstimate, String> {
+    let uris = builder
+        .create_resources(geoids)?
+        .into_iter()
+        .map(|resource| resource.uri)
+        .collect_vec();
+    Ok(head_content_length(client, &uris, PREFETCH_CONCURRENCY).await)
+}
+
+/// maximum number of concurrent archive downloads during a [`prefetch`]
+pub const PREFETCH_CONCURRENCY: usize = 4;
+
+/// downloads every TIGER/Lines archive needed to cover the geoids in each of the
+/// provided years into the cache, so that later calls to [`run`] with the same
+/// cache are served from disk. archives shared across years are only fetched once,
+/// archives already in the cache are skipped, and at most [`PREFETCH_CONCURRENCY`]
+/// downloads run at a time.
+///
+/// a failure downloading any one archive is reported in the summary, and the
+/// remaining archives are still fetched.
+pub async fn prefetch(
+    client: &Client,
+    builders: &[TigerResourceBuilder],
+    geoids: &[&Geoid],
+    cache: &TigerCache,
+) -> Result<PrefetchSummary, String> {
+    let uris = builders
+        .iter()
+        .map(|builder| builder.create_resources(geoids))
+        .collect::<Result<Vec<_>, String>>()?
+        .into_iter()
+        .flatten()
+        .map(|resource| resource.uri)
+        .unique()
+        .collect_vec();
+    let (cached, missing): (Vec<_>, Vec<_>) = uris.into_iter().partition(|uri| cache.contains(uri));
+
+    let pb = Arc::new(Mutex::new(ProgressBar::new(
+        missing.len(),
+        "TIGER/Lines prefetch",
+    )?));
+    let downloads = futures::stream::iter(missing.into_iter().map(|uri| {
+        let pb = pb.clone();
+        async move {
+            log::debug!("prefetching {uri}");
+            let start = Instant::now();
+            let result = download_to_cache(client, &uri, cache)
+                .await
+                .map_err(|e| format!("{uri}: {e}"));
+            if let Ok(bytes) = &result {
+                log::info!(
+                    "TIGER/Lines {uri} cached {bytes} bytes in {:.2?}",
+                    start.elapsed()
+                );
+            }
+
+            // update progress bar
+            let mut pb_update = pb
+                .lock()
+                .map_err(|e| format!("failure aquiring progress bar mutex lock: {e}"))?;
+            pb_update.update(1)?;
+            pb_update.set_description(uri.split('/').next_back().unwrap_or_default());
+            result
+        }
+    }))
+    .buffer_unordered(PREFETCH_CONCURRENCY)
+    .collect::<Vec<Result<u64, String>>>()
+    .await;
+    if let Ok(pb) = pb.lock() {
+        pb.finish();
+    }
+
+    let (sizes, errors): (Vec<u64>, Vec<String>) = downloads.into_iter().partition_result();
+    Ok(PrefetchSummary {
+        files_fetched: sizes.len(),
+        files_cached: cached.len(),
+        bytes_fetched: sizes.iter().sum(),
+        errors,
+    })
+}
+
+/// downloads an archive into the cache, returning its size in bytes. the archive is
+/// written to a partial file first so that an interrupted download is never mistaken
+/// for a cached archive.
+async fn download_to_cache(client: &Client, uri: &str, cache: &TigerCache) -> Result<u64, String> {
+    let path = cache.path(uri);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failure creating cache directory {}: {e}", parent.display()))?;
+    }
+    let partial_path = path.with_extension("zip.partial");
+    let write_file = File::create(&partial_path)
+        .map_err(|e| format!("failure creating {}: {e}", partial_path.display()))?;
+    download(client, uri, write_file).await?;
+    std::fs::rename(&partial_path, &path).map_err(|e| {
+        format!(
+            "failure moving {} to {}: {e}",
+            partial_path.display(),
+            path.display()
+        )
+    })?;
+    std::fs::metadata(&path)
+        .map(|m| m.len())
+        .map_err(|e| format!("failure reading size of {}: {e}", path.display()))
+}
+
+/// environment variable naming the directory where TIGER/Lines archives are written
+pub const SCRATCH_DIR_ENV_VAR: &str = "BAMCENSUS_SCRATCH_DIR";
+
+/// picks the directory for writing archives: the explicit argument, then the
+/// [`SCRATCH_DIR_ENV_VAR`] environment variable, then the system temporary directory.
+fn resolve_scratch_dir(scratch_dir: Option<&Path>) -> PathBuf {
+    match scratch_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => std::env::var_os(SCRATCH_DIR_ENV_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(std::env::temp_dir),
+    }
+}
+
+/// environment variable naming a directory where downloaded TIGER/Lines archives and
+/// the files extracted from them are kept, see [`run`]
+pub const KEEP_FILES_ENV_VAR: &str = "BAMCENSUS_KEEP_FILES";
+
+/// picks the directory for keeping archives, if any: the explicit argument, then the
+/// [`KEEP_FILES_ENV_VAR`] environment variable when it is set and non-empty.
+fn resolve_keep_files(keep_files: Option<&Path>) -> Option<PathBuf> {
+    match keep_files {
+        Some(dir) => Some(dir.to_path_buf()),
+        None => std::env::var_os(KEEP_FILES_ENV_VAR)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from),
+    }
+}
+
+/// environment variable setting the maximum number of uncompressed bytes read from a
+/// single TIGER/Lines archive, see [`DEFAULT_MAX_EXTRACT_BYTES`]
+pub const MAX_EXTRACT_BYTES_ENV_VAR: &str = "BAMCENSUS_MAX_EXTRACT_BYTES";
+
+/// default maximum number of uncompressed bytes read from a single TIGER/Lines
+/// archive (2 GiB). the largest national files are well below this, so an archive
+/// that expands past it is corrupt, and extraction is aborted before it can
+/// exhaust memory.
+pub const DEFAULT_MAX_EXTRACT_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// the maximum number of uncompressed bytes read from an archive: the
+/// [`MAX_EXTRACT_BYTES_ENV_VAR`] environment variable if set, otherwise
+/// [`DEFAULT_MAX_EXTRACT_BYTES`].
+pub fn max_extract_bytes() -> Result<u64, String> {
+    match std::env::var(MAX_EXTRACT_BYTES_ENV_VAR) {
+        Ok(value) => value.trim().parse::<u64>().map_err(|e| {
+            format!("{MAX_EXTRACT_BYTES_ENV_VAR} must be a number of bytes, found '{value}': {e}")
+        }),
+        Err(_) => Ok(DEFAULT_MAX_EXTRACT_BYTES),
+    }
+}
+
+/// downloads a TIGER/Lines .zip archive to a temporary file in the scratch directory
+/// and returns the contents of the .shp and .dbf files within it. when a cache is
+/// provided, the archive is read from (or downloaded into) the cache instead. when
+/// `keep_files` is provided, the archive and its contents are also copied there.
+async fn download_shapefile(
+    client: &Client,
+    uri: &str,
+    scratch_dir: &Path,
+    cache: Option<&TigerCache>,
+    keep_files: Option<&Path>,
+    bytes: Option<&ByteCounter>,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    if let Some(cache) = cache {
+        if cache.contains(uri) {
+            log::debug!("reading {uri} from cache");
+        } else {
+            let size = download_to_cache(client, uri, cache).await?;
+            if let Some(bytes) = bytes {
+                bytes.add(size);
+            }
+        }
+        let path = cache.path(uri);
+        let contents = read_shapefile_archive(&path, uri)?;
+        if let Some(dir) = keep_files {
+            keep_shapefile(dir, uri, &path, &contents)?;
+        }
+        return Ok(contents);
+    }
+
+    // create temporary file for writing .zip download
+    let named_tmp = tempfile::NamedTempFile::new_in(scratch_dir).map_err(|e| {
+        format!(
+            "failure creating temporary zip archive filepath in {}: {e}",
+            scratch_dir.display()
+        )
+    })?;
+    let read_path = named_tmp.path().to_path_buf().clone();
+
+    // download archive
+    let write_file = File::create(&read_path)
+        .map_err(|e| format!("failure creating temporary zip archive file: {e}"))?;
+    let size = download(client, uri, write_file).await?;
+    if let Some(bytes) = bytes {
+        bytes.add(size);
+    }
+    let contents = read_shapefile_archive(&read_path, uri)?;
+    if let Some(dir) = keep_files {
+        keep_shapefile(dir, uri, &read_path, &contents)?;
+    }
+    Ok(contents)
+}
+
+/// copies an archive into the `dir` directory, named after the file in its URI, and
+/// writes the .shp and .dbf contents extracted from it alongside.
+fn keep_shapefile(
+    dir: &Path,
+    uri: &str,
+    archive: &Path,
+    (shp_contents, dbf_contents): &(Vec<u8>, Vec<u8>),
+) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("failure creating directory {}: {e}", dir.display()))?;
+    let filename = uri.rsplit('/').next().unwrap_or(uri);
+    let zip_path = dir.join(filename);
+    std::fs::copy(archive, &zip_path)
+        .map_err(|e| format!("failure copying {uri} to {}: {e}", zip_path.display()))?;
+    for (extension, contents) in [("shp", shp_contents), ("dbf", dbf_contents)] {
+        let path = zip_path.with_extension(extension);
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("failure writing {}: {e}", path.display()))?;
+    }
+    log::info!(
+        "kept {uri} and its .shp and .dbf files in {}",
+        dir.display()
+    );
+    Ok(())
+}
+
+/// returns the contents of the .shp and .dbf files within a .zip archive on disk,
+/// downloaded from `uri`. see [`select_shapefile_members`].
+fn read_shapefile_archive(read_path: &Path, uri: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let read_file = File::open(read_path)
+        .map_err(|e| format!("failure opening zip archive {}: {e}", read_path.display()))?;
+    let mut z = ZipArchive::new(read_file)
+        .map_err(|e| format!("failure reading temporary zip archive: {e}"))?;
+    let archive_name = uri.rsplit('/').next().unwrap_or(uri);
+    let preferred = archive_name.strip_suffix(".zip").unwrap_or(archive_name);
+    let members = select_shapefile_members(z.file_names(), Some(preferred))
+        .map_err(|e| format!("{uri}: {e}"))?;
+    // the limit covers the archive as a whole, so it is shared by both files
+    let max_bytes = max_extract_bytes()?;
+    let shp_contents = extract_zip_file(&mut z, &members.shp, max_bytes)
+        .map_err(|e| format!("{}: {e}", read_path.display()))?;
+    let remaining = max_bytes.saturating_sub(shp_contents.len() as u64);
+    let dbf_contents = extract_zip_file(&mut z, &members.dbf, remaining)
+        .map_err(|e| format!("{}: {e}", read_path.display()))?;
+    Ok((shp_contents, dbf_contents))
+}
+
+/// the names of the .shp and .dbf members of one shapefile within an archive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShapefileMembers {
+    pub shp: String,
+    pub dbf: String,
+}
+
+/// selects the shapefile to read from the member names of an archive. the .dbf is
+/// the one sharing the base name of the .shp, such as `tl_2020_08_tract.dbf` for
+/// `tl_2020_08_tract.shp`, so that the geometries are never paired with the records
+/// of another shapefile. extensions are matched case-insensitively.
+///
+/// TIGER/Lines archives hold a single shapefile. if an archive holds more than one,
+/// the one whose base name is `preferred`, such as the name of the archive, is
+/// selected, and otherwise selection fails with the list of shapefiles found.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_tiger::ops::tiger_api::select_shapefile_members;
+///
+/// let names = ["tl_2020_08_tract.shp.xml", "tl_2020_08_tract.dbf", "tl_2020_08_tract.prj", "tl_2020_08_tract.shp"];
+/// let members = select_shapefile_members(names, None).unwrap();
+/// assert_eq!(members.shp, "tl_2020_08_tract.shp");
+/// assert_eq!(members.dbf, "tl_2020_08_tract.dbf");
+///
+/// // the .dbf is paired by base name, not taken from the first .dbf in the archive
+/// let names = ["extra/notes.dbf", "tl_2020_08_tract.SHP", "tl_2020_08_tract.DBF"];
+/// let members = select_shapefile_members(names, None).unwrap();
+/// assert_eq!(members.dbf, "tl_2020_08_tract.DBF");
+///
+/// // an archive with two shapefiles needs a preferred one
+/// let names = ["a.shp", "a.dbf", "b.shp", "b.dbf"];
+/// let error = select_shapefile_members(names, None).unwrap_err();
+/// assert!(error.contains("a.shp, b.shp"));
+/// assert_eq!(select_shapefile_members(names, Some("b")).unwrap().dbf, "b.dbf");
+///
+/// assert!(select_shapefile_members(["a.shp", "b.dbf"], None).is_err());
+/// ```
+pub fn select_shapefile_members<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    preferred: Option<&str>,
+) -> Result<ShapefileMembers, String> {
+    let names = names.into_iter().sorted().collect_vec();
+    let with_extension = |extension: &str| {
+        names
+            .iter()
+            .filter_map(|name| {
+                let (base, ext) = name.rsplit_once('.')?;
+                ext.eq_ignore_ascii_case(extension).then_some((base, *name))
+            })
+            .collect_vec()
+    };
+    let shapefiles = with_extension("shp");
+    let (base, shp) = match &shapefiles[..] {
+        [] => return Err(String::from("no files in archive have '.shp' suffix")),
+        [single] => *single,
+        multiple => {
+            let file_name = |base: &str| base.rsplit('/').next().unwrap_or(base).to_string();
+            let chosen = preferred.and_then(|p| multiple.iter().find(|(b, _)| file_name(b) == p));
+            match chosen {
+                Some(chosen) => *chosen,
+                None => {
+                    return Err(format!(
+                        "archive holds {} shapefiles, {}, and none is named {}",
+                        multiple.len(),
+                        multiple.iter().map(|(_, name)| name).join(", "),
+                        preferred
+                            .map(|p| format!("{p}.shp"))
+                            .unwrap_or_else(|| String::from("after the archive"))
+                    ))
+                }
+            }
+        }
+    };
+    let dbf = with_extension("dbf")
+        .into_iter()
+        .find(|(b, _)| *b == base)
+        .map(|(_, name)| name)
+        .ok_or_else(|| format!("archive has {shp} but no matching {base}.dbf"))?;
+    Ok(ShapefileMembers {
+        shp: String::from(shp),
+        dbf: String::from(dbf),
+    })
+}
+
 fn into_geoid_and_geometry(
     shape: Shape,
     record: Record,
-    lookup: &HashSet<&&Geoid>,
+    lookup: &HashSet<&Geoid>,
     tiger_uri: &TigerResource,
+    geoid_column: Option<&str>,
+    transform: Option<GeometryTransform>,
 ) -> Result<Option<(Geoid, Geometry)>, String> {
-    let geoid = get_geoid_from_record(&record, &tiger_uri.geoid_type)?;
-    if lookup.contains(&&geoid) {
+    let geoid = get_geoid_from_record(&record, &tiger_uri.geoid_type, geoid_column)
+        .map_err(|e| format!("{}: {e}", tiger_uri.uri))?;
+    if lookup.contains(&geoid) {
         let geometry: Geometry<f64> = shape
             .try_into()
             .map_err(|e| format!("could not convert shape into geometry. {e}"))?;
+        let geometry = match transform {
+            None => geometry,
+            Some(GeometryTransform::Simplify { tolerance }) => {
+                simplify_geometry(geometry, tolerance)
+            }
+            Some(GeometryTransform::Centroid) => {
+                let internal_point = get_internal_point_from_record(&record)
+                    .map_err(|e| format!("{}: {e}", tiger_uri.uri))?;
+                centroid_geometry(geometry, internal_point)
+            }
+        };
         Ok(Some((geoid, geometry)))
     } else {
         Ok(None)
     }
 }
 
+/// suffixes of the internal point columns across TIGER/Lines vintages, such as
+/// INTPTLAT and INTPTLAT20
+const INTERNAL_POINT_SUFFIXES: [&str; 3] = ["", "20", "10"];
+
+/// reads the internal point of a record from the first pair of INTPTLON and INTPTLAT
+/// columns present, or None if the file does not publish internal points.
+fn get_internal_point_from_record(record: &Record) -> Result<Option<Point>, String> {
+    for suffix in INTERNAL_POINT_SUFFIXES {
+        let lon_column = format!("INTPTLON{suffix}");
+        let lat_column = format!("INTPTLAT{suffix}");
+        if let (Some(lon), Some(lat)) = (record.get(&lon_column), record.get(&lat_column)) {
+            let lon = parse_coordinate(&lon_column, lon)?;
+            let lat = parse_coordinate(&lat_column, lat)?;
+            return Ok(Some(Point::new(lon, lat)));
+        }
+    }
+    Ok(None)
+}
+
+/// parses an internal point coordinate, which is stored as signed text such as "+39.7".
+fn parse_coordinate(column: &str, value: &dbase::FieldValue) -> Result<f64, String> {
+    match value {
+        dbase::FieldValue::Character(Some(s)) => s.trim().parse::<f64>().map_err(|e| {
+            format!("value at column '{column}' is not a valid coordinate, found '{s}': {e}")
+        }),
+        _ => Err(format!(
+            "value at column '{column}' is not a valid coordinate, found '{value}'"
+        )),
+    }
+}
+
 const GEOID_COLUMN_NAMES: [&str; 3] = ["GEOID", "GEOID20", "GEOID10"];
 
+/// reads the GEOID of a record from the `geoid_column`, if provided. otherwise,
 /// attempts all three conventions for GEOID column names. order is:
 /// 1. "GEOID"   - most general
 /// 2. "GEOID20" - latest
 /// 3. "GEOID10" - when general or latest is not present
-fn get_geoid_from_record(record: &Record, geoid_type: &GeoidType) -> Result<Geoid, String> {
-    let field_name = GEOID_COLUMN_NAMES
-        .iter()
-        .find(|col| record.get(col).is_some())
-        .ok_or_else(|| {
-            format!(
-                "could not find any of {} in shapefile",
-                GEOID_COLUMN_NAMES.iter().join(","),
-            )
-        })?;
-    let field_value = record.get(field_name).ok_or_else(|| {
-        format!(
-            "could not find any of {} in shapefile",
-            GEOID_COLUMN_NAMES.iter().join(","),
-        )
-    })?;
+fn get_geoid_from_record(
+    record: &Record,
+    geoid_type: &GeoidType,
+    geoid_column: Option<&str>,
+) -> Result<Geoid, String> {
+    let (field_name, field_value) = match geoid_column {
+        Some(column) => {
+            let value = record
+                .get(column)
+                .ok_or_else(|| format!("could not find GEOID column {column} in shapefile"))?;
+            (column, value)
+        }
+        None => {
+            let field_name = GEOID_COLUMN_NAMES
+                .iter()
+                .find(|col| record.get(col).is_some())
+                .ok_or_else(|| {
+                    format!(
+                        "could not find any of {} in shapefile",
+                        GEOID_COLUMN_NAMES.iter().join(","),
+                    )
+                })?;
+            let value = record.get(field_name).ok_or_else(|| {
+                format!(
+                    "could not find any of {} in shapefile",
+                    GEOID_COLUMN_NAMES.iter().join(","),
+                )
+            })?;
+            (*field_name, value)
+        }
+    };
     let geoid = match field_value {
         dbase::FieldValue::Character(s) => match s {
             Some(geoid_string) => geoid_type.geoid_from_str(geoid_string),
@@ -149,19 +851,27 @@ fn get_geoid_from_record(record: &Record, geoid_type: &GeoidType) -> Result<Geoi
     Ok(geoid)
 }
 
-async fn download(client: &Client, uri: &str, write_file: File) -> Result<(), String> {
+/// downloads the body of a response into a file, returning its size in bytes.
+async fn download(client: &Client, uri: &str, write_file: File) -> Result<u64, String> {
     let mut async_file = tokio::fs::File::from(write_file);
 
-    let mut response = client
+    let response = client
         .get(uri)
         .send()
         .await
         .map_err(|e| format!("failure retrieving TIGER zip archive: {e}"))?
-        .bytes_stream();
+        .error_for_status()
+        .map_err(|e| format!("TIGER zip archive request failed: {e}"))?;
+    check_not_redirected(uri, &response)?;
+    if response.url().as_str() != uri {
+        log::debug!("TIGER {uri} redirected to {}", response.url());
+    }
+    let mut response = response.bytes_stream();
 
+    let mut size = 0;
     while let Some(buf) = response.next().await {
         let item = buf.map_err(|e| format!("failed to buffer response: {e}"))?;
-        tokio::io::copy(&mut item.as_ref(), &mut async_file)
+        size += tokio::io::copy(&mut item.as_ref(), &mut async_file)
             .await
             .map_err(|e| format!("failed to write response buffer: {e}"))?;
     }
@@ -170,27 +880,75 @@ async fn download(client: &Client, uri: &str, write_file: File) -> Result<(), St
         .flush()
         .await
         .map_err(|e| format!("error closing async write connection to temp zip file: {e}"))?;
-    Ok(())
+    Ok(size)
 }
 
-fn get_zip_filename(archive: &ZipArchive<File>, suffix: &str) -> Result<String, String> {
-    let shp_filename = archive
-        .file_names()
-        .find(|s| s.ends_with(suffix))
-        .ok_or_else(|| format!("no files in archive have '{suffix}' suffix"))?;
-    Ok(String::from(shp_filename))
+/// fails if the response is a redirect, which the client was not configured to
+/// follow. see [`bamcensus_core::ops::http::client_builder`].
+fn check_not_redirected(uri: &str, response: &reqwest::Response) -> Result<(), String> {
+    if response.status().is_redirection() {
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("an unknown location");
+        Err(format!(
+            "TIGER request for {uri} was redirected to {location} ({}), but the HTTP client does not follow redirects. build the client with bamcensus_core::ops::http::client_builder",
+            response.status()
+        ))
+    } else {
+        Ok(())
+    }
 }
 
-fn zip_file_into_string(archive: &mut ZipArchive<File>, filename: &str) -> Result<Vec<u8>, String> {
-    let mut contents = Vec::new();
-    let mut zipfile = archive.by_name(filename).map_err(|e| {
+/// reads a file from a zip archive into memory, failing once more than `max_bytes`
+/// have been read. the size declared in the archive is checked first, and the bytes
+/// actually read are also counted, so that an archive with a corrupt or misleading
+/// size header cannot exceed the limit either.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_tiger::ops::tiger_api::extract_zip_file;
+/// use std::io::{Cursor, Write};
+/// use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+///
+/// let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+/// writer.start_file("tl_2020_08_tract.shp", SimpleFileOptions::default()).unwrap();
+/// writer.write_all(&[0u8; 1000]).unwrap();
+/// let mut archive = ZipArchive::new(writer.finish().unwrap()).unwrap();
+///
+/// let contents = extract_zip_file(&mut archive, "tl_2020_08_tract.shp", 1000).unwrap();
+/// assert_eq!(contents.len(), 1000);
+///
+/// let error = extract_zip_file(&mut archive, "tl_2020_08_tract.shp", 999).unwrap_err();
+/// assert!(error.contains("exceeds the extraction limit of 999 bytes"));
+/// ```
+pub fn extract_zip_file<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    filename: &str,
+    max_bytes: u64,
+) -> Result<Vec<u8>, String> {
+    let zipfile = archive.by_name(filename).map_err(|e| {
         format!("expected file {filename} cannot be retrieved by name from zip archive: {e}")
     })?;
+    let exceeded = || {
+        format!(
+            "{filename} exceeds the extraction limit of {max_bytes} bytes, the archive may be corrupt. the limit can be raised with {MAX_EXTRACT_BYTES_ENV_VAR}"
+        )
+    };
+    if zipfile.size() > max_bytes {
+        return Err(exceeded());
+    }
+    let mut contents = Vec::with_capacity(zipfile.size() as usize);
+    // read one byte past the limit to detect archives that expand beyond their header
     zipfile
+        .take(max_bytes.saturating_add(1))
         .read_to_end(&mut contents)
         .map_err(|e| format!("failure reading {filename} from zip archive: {e}"))?;
-    // let string =
-    //     String::from_utf8(contents).map_err(|e| format!("failure parsing zip as utf-8: {}", e))?;
+    if contents.len() as u64 > max_bytes {
+        return Err(exceeded());
+    }
     Ok(contents)
 }
 
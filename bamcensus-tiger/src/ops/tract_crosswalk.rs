@@ -0,0 +1,142 @@
+//! translates census tracts between the 2010 and 2020 vintages using the census
+//! tract relationship files, see
+//! <https://www.census.gov/geographies/reference-files/time-series/geo/relationship-files.html>.
+use crate::model::{TractRelationship, TractVintage};
+use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+use bamcensus_core::ops::agg::Crosswalk;
+use reqwest::Client;
+use std::io::Read;
+use std::time::Instant;
+
+/// location of the 2020 to 2010 census tract relationship files
+pub const RELATIONSHIP_BASE_URL: &str =
+    "https://www2.census.gov/geo/docs/maps-data/data/rel2020/tract";
+
+/// the URI of the tract relationship file for a state, or the national file if no
+/// state is provided.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::fips;
+/// use bamcensus_tiger::ops::tract_crosswalk;
+///
+/// assert_eq!(
+///     tract_crosswalk::relationship_file_uri(Some(fips::State(8))),
+///     "https://www2.census.gov/geo/docs/maps-data/data/rel2020/tract/tab20_tract20_tract10_st08.txt"
+/// );
+/// assert_eq!(
+///     tract_crosswalk::relationship_file_uri(None),
+///     "https://www2.census.gov/geo/docs/maps-data/data/rel2020/tract/tab20_tract20_tract10_natl.txt"
+/// );
+/// ```
+pub fn relationship_file_uri(state: Option<fips::State>) -> String {
+    let scope = match state {
+        Some(s) => format!("st{}", s.geoid_string()),
+        None => String::from("natl"),
+    };
+    format!("{RELATIONSHIP_BASE_URL}/tab20_tract20_tract10_{scope}.txt")
+}
+
+/// reads the rows of a pipe-delimited tract relationship file.
+pub fn read_relationships<R: Read>(source: R) -> Result<Vec<TractRelationship>, String> {
+    csv::ReaderBuilder::new()
+        .delimiter(b'|')
+        .from_reader(source)
+        .deserialize()
+        .map(|r| r.map_err(|e| format!("failure reading tract relationship row: {e}")))
+        .collect()
+}
+
+/// builds a crosswalk from tracts of one vintage to tracts of the other, weighted by
+/// the land area of each overlapping part, so that counts are apportioned where people
+/// can live. a tract with no land area is split evenly across the tracts it overlaps.
+///
+/// # Example
+///
+/// a 2010 tract that was split in two for 2020, with three quarters of its land area
+/// in the first 2020 tract:
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::Geoid;
+/// use bamcensus_core::ops::agg::NumericAggregation;
+/// use bamcensus_tiger::model::TractVintage;
+/// use bamcensus_tiger::ops::tract_crosswalk;
+///
+/// let file = "\
+/// OID_TRACT_20|GEOID_TRACT_20|AREALAND_TRACT_20|OID_TRACT_10|GEOID_TRACT_10|AREALAND_PART|AREAWATER_PART
+/// 1|08031004103|300|9|08031004102|300|0
+/// 2|08031004104|100|9|08031004102|100|50
+/// ";
+/// let relationships = tract_crosswalk::read_relationships(file.as_bytes()).unwrap();
+/// let crosswalk = tract_crosswalk::crosswalk(
+///     &relationships,
+///     TractVintage::Tract2010,
+///     TractVintage::Tract2020,
+/// )
+/// .unwrap();
+///
+/// let tract_2010 = Geoid::census_tract(8, 31, 4102).unwrap();
+/// let (translated, errors) = crosswalk.translate(&[(tract_2010, 1000.0)], &NumericAggregation::Sum);
+/// assert!(errors.is_empty());
+/// assert_eq!(translated, vec![
+///     (Geoid::census_tract(8, 31, 4103).unwrap(), 750.0),
+///     (Geoid::census_tract(8, 31, 4104).unwrap(), 250.0),
+/// ]);
+/// ```
+pub fn crosswalk(
+    relationships: &[TractRelationship],
+    from: TractVintage,
+    to: TractVintage,
+) -> Result<Crosswalk, String> {
+    if from == to {
+        return Err(format!(
+            "tract crosswalk requires two different vintages, found {from:?} for both"
+        ));
+    }
+    let entries = relationships
+        .iter()
+        // parts of tracts that do not exist in one of the vintages have an empty geoid
+        .filter(|r| !r.geoid_tract_10.is_empty() && !r.geoid_tract_20.is_empty())
+        .map(|r| {
+            let tract_10 = GeoidType::CensusTract.geoid_from_str(&r.geoid_tract_10)?;
+            let tract_20 = GeoidType::CensusTract.geoid_from_str(&r.geoid_tract_20)?;
+            let (source, target) = match from {
+                TractVintage::Tract2010 => (tract_10, tract_20),
+                TractVintage::Tract2020 => (tract_20, tract_10),
+            };
+            Ok((source, target, r.arealand_part))
+        })
+        .collect::<Result<Vec<(Geoid, Geoid, f64)>, String>>()?;
+    Crosswalk::new(entries)
+}
+
+/// downloads the tract relationship file for a state, or the national file if no
+/// state is provided, and builds the crosswalk between the two tract vintages.
+/// see [`crosswalk`].
+pub async fn download_crosswalk(
+    client: &Client,
+    state: Option<fips::State>,
+    from: TractVintage,
+    to: TractVintage,
+) -> Result<Crosswalk, String> {
+    let uri = relationship_file_uri(state);
+    let start = Instant::now();
+    let bytes = client
+        .get(&uri)
+        .send()
+        .await
+        .map_err(|e| format!("failure retrieving tract relationship file {uri}: {e}"))?
+        .error_for_status()
+        .map_err(|e| format!("tract relationship file request for {uri} failed: {e}"))?
+        .bytes()
+        .await
+        .map_err(|e| format!("failure reading tract relationship file {uri}: {e}"))?;
+    let relationships = read_relationships(bytes.as_ref())?;
+    log::info!(
+        "tract relationships {uri} read {} rows in {:.2?}",
+        relationships.len(),
+        start.elapsed()
+    );
+    crosswalk(&relationships, from, to)
+}
@@ -0,0 +1,67 @@
+use bamcensus_core::model::identifier::GeoidType;
+use itertools::Itertools;
+
+/// the decennial census whose blocks, block groups and census tracts are used by
+/// data from a year. these geographies are redrawn once a decade, so 2010 through
+/// 2019 data uses 2010 census tracts, and 2020 onward uses 2020 census tracts.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_tiger::ops::vintage;
+///
+/// assert_eq!(vintage::decennial_year(2019), 2010);
+/// assert_eq!(vintage::decennial_year(2020), 2020);
+/// assert_eq!(vintage::decennial_year(2022), 2020);
+/// ```
+pub fn decennial_year(year: u64) -> u64 {
+    year - year % 10
+}
+
+/// compares the geography vintage a dataset was tabulated on, `data_year`, with the
+/// TIGER/Lines vintage chosen for its geometries, returning a warning describing the
+/// mismatch, or None if they align.
+///
+/// blocks, block groups and census tracts are redrawn for each decennial census, so
+/// joining them across a decennial census leaves most geoids without a geometry or
+/// with the wrong one. the boundaries of other geographies, such as counties and
+/// places, are updated every year, so any difference in years may leave some
+/// geoids without a matching geometry.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus_core::model::identifier::GeoidType;
+/// use bamcensus_tiger::ops::vintage;
+///
+/// assert_eq!(vintage::check_vintage(2022, 2022, &[GeoidType::CensusTract]), None);
+///
+/// let warning = vintage::check_vintage(2022, 2018, &[GeoidType::CensusTract]).unwrap();
+/// assert!(warning.starts_with("2022 data uses 2020 census blocks"));
+/// assert!(warning.contains("2018 TIGER/Lines geometries use 2010 census ones"));
+///
+/// let warning = vintage::check_vintage(2022, 2020, &[GeoidType::County]).unwrap();
+/// assert!(warning.contains("county geoids that changed"));
+/// ```
+pub fn check_vintage(data_year: u64, tiger_year: u64, geoid_types: &[GeoidType]) -> Option<String> {
+    if data_year == tiger_year {
+        return None;
+    }
+    let (data_decennial, tiger_decennial) = (decennial_year(data_year), decennial_year(tiger_year));
+    let types = geoid_types.iter().unique().join(", ");
+    let redrawn = geoid_types.iter().any(|t| {
+        matches!(
+            t,
+            GeoidType::CensusTract | GeoidType::BlockGroup | GeoidType::Block
+        )
+    });
+    if redrawn && data_decennial != tiger_decennial {
+        Some(format!(
+            "{data_year} data uses {data_decennial} census blocks, block groups and tracts, but {tiger_year} TIGER/Lines geometries use {tiger_decennial} census ones, so most {types} geoids will be missing geometries or joined to the wrong boundaries"
+        ))
+    } else {
+        Some(format!(
+            "{data_year} data uses {data_year} geography boundaries but {tiger_year} TIGER/Lines geometries were requested, so {types} geoids that changed in between will be missing geometries or have different boundaries"
+        ))
+    }
+}
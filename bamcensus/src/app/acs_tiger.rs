@@ -1,17 +1,38 @@
 use crate::model::acs_tiger_row::AcsTigerRow;
+use crate::model::area_of_interest::AreaOfInterest;
+use crate::model::join_options::JoinOptions;
+use crate::model::join_summary::JoinSummary;
+use crate::model::run_summary::{RequestCounts, RunSummary};
+use crate::ops::join::{self, MissingGeometry};
 use bamcensus_acs::api::acs_api;
-use bamcensus_acs::model::AcsApiQueryParams;
-use bamcensus_core::model::identifier::Geoid;
-use bamcensus_tiger::model::TigerResourceBuilder;
-use bamcensus_tiger::ops::tiger_api;
+use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType, AcsValue};
+use bamcensus_core::model::identifier::{Geoid, GeoidType, StateSelection};
+use bamcensus_core::model::ByteCounter;
+use bamcensus_core::ops::http;
+use bamcensus_tiger::model::{RunOptions, TigerResourceBuilder};
+use bamcensus_tiger::ops::{tiger_api, vintage};
 use geo::Geometry;
 use itertools::Itertools;
 use reqwest::Client;
+use std::collections::HashSet;
+use std::time::Instant;
 
 pub struct AcsTigerResponse {
     pub join_dataset: Vec<AcsTigerRow>,
     pub tiger_errors: Vec<String>,
     pub join_errors: Vec<String>,
+    /// counts of the geoids that did and did not match a TIGER/Lines geometry
+    pub join_summary: JoinSummary,
+    /// counts of the requests made and rows produced, and the duration of the run
+    pub summary: RunSummary,
+}
+
+/// ACS values without geometries, see [`run_without_geometry`].
+pub struct AcsResponse {
+    pub dataset: Vec<(Geoid, Vec<AcsValue>)>,
+    /// counts of the requests made and rows produced, and the duration of the run.
+    /// no TIGER/Lines files are requested.
+    pub summary: RunSummary,
 }
 
 /// runs a query to ACS. the result will include a list of GEOIDs alongside
@@ -44,7 +65,7 @@ pub struct AcsTigerResponse {
 ///     None,
 /// );
 /// # tokio_test::block_on(async {
-///     let res = acs_tiger::run(&query_params).await.unwrap();
+///     let res = acs_tiger::run(&query_params, &JoinOptions::default()).await.unwrap();
 ///     println!(
 ///         "found {} responses, {}/{} errors",
 ///         res.join_dataset.len(),
@@ -57,12 +78,447 @@ pub struct AcsTigerResponse {
 /// # })
 ///
 /// ```
-pub async fn run(query: &AcsApiQueryParams) -> Result<AcsTigerResponse, String> {
-    run_batch(&vec![query.clone()]).await
+pub async fn run(
+    query: &AcsApiQueryParams,
+    options: &JoinOptions,
+) -> Result<AcsTigerResponse, String> {
+    run_batch(std::slice::from_ref(query), options).await
+}
+
+/// runs a batch of ACS queries sharing one dataset year, see [`run`]. if a
+/// `geoid_column` option is provided, geoids are read only from that column of the
+/// TIGER/Lines files, see [`tiger_api::run`]. if a `transform` is provided, it is
+/// applied to each geometry, such as replacing it with its internal point.
+///
+/// geometries come from the TIGER/Lines vintage matching the ACS year, unless a
+/// `tiger_year` is provided. an override keeps boundaries consistent across a panel
+/// of ACS years, such as joining 2018 through 2022 data to 2020 geometries. geoids
+/// that do not exist in the chosen vintage, such as counties created or renamed in
+/// between, are reported as missing geometries.
+///
+/// geoids without a geometry are reported as join errors and dropped, unless
+/// `keep_unmatched` is true, in which case their rows are kept with no geometry,
+/// see [`join::dataset_with_optional_geometries`].
+///
+/// if `functioning_mcds_only` is true, county subdivision queries in states without
+/// functioning minor civil divisions are skipped, see [`functioning_mcd_queries`].
+///
+/// if a `cancel` token is provided, cancelling it stops the ACS queries and the
+/// TIGER/Lines downloads that have not completed. the rows of the completed queries
+/// are still joined, and each archive that was not read is a TIGER/Lines error, so
+/// the geoids it would have matched are join errors.
+pub async fn run_batch(
+    queries: &[AcsApiQueryParams],
+    options: &JoinOptions,
+) -> Result<AcsTigerResponse, String> {
+    run_queries(queries, None, options).await
+}
+
+/// runs a batch of ACS queries without downloading TIGER/Lines geometries, for
+/// tabular-only output. the queries may have different years, since no geometry
+/// vintage needs to be chosen.
+pub async fn run_without_geometry(queries: &[AcsApiQueryParams]) -> Result<AcsResponse, String> {
+    let start = Instant::now();
+    let client: Client = http::client_builder()
+        .build()
+        .map_err(|e| format!("failure building HTTP client: {e}"))?;
+    let bytes = ByteCounter::default();
+    let dataset = acs_api::batch_run(&client, queries, None, Some(&bytes)).await?;
+    let rows = dataset.iter().map(|(_, values)| values.len()).sum();
+    let summary = RunSummary::new(
+        RequestCounts::new(queries.len(), 0),
+        RequestCounts::default(),
+        rows,
+        bytes.total(),
+        start.elapsed(),
+    );
+    Ok(AcsResponse { dataset, summary })
 }
 
-pub async fn run_batch(queries: &Vec<AcsApiQueryParams>) -> Result<AcsTigerResponse, String> {
-    let client: Client = Client::new();
+/// runs ACS + TIGER/Lines for an arbitrary list of geoids, which may be of mixed
+/// types, such as a few counties alongside a few tracts. the geoids are grouped by
+/// type and parent geography. a lone geoid is queried exactly, while several geoids
+/// sharing a parent are fetched with a single wildcard query within that parent and
+/// filtered back down to the requested geoids. all queries share one dataset year,
+/// so the geometries come from a single TIGER/Lines vintage, and the options are
+/// used as in [`run_batch`]. geoids whose county does not exist in the geographies
+/// of `year` are logged, see [`Geoid::exists_in_vintage`].
+pub async fn run_geoids(
+    geoids: &[Geoid],
+    year: u64,
+    acs_type: AcsType,
+    get_query: Vec<String>,
+    api_token: Option<String>,
+    options: &JoinOptions,
+) -> Result<AcsTigerResponse, String> {
+    if geoids.is_empty() {
+        return Err(String::from("acs.run_geoids requires at least one geoid"));
+    }
+    for geoid in geoids {
+        if let Err(e) = geoid.exists_in_vintage(year) {
+            log::warn!("{e}");
+        }
+    }
+    let requested = geoids.iter().cloned().collect::<HashSet<_>>();
+    let queries = group_geoid_queries(geoids)?
+        .into_iter()
+        .map(|for_query| {
+            AcsApiQueryParams::new(
+                options.acs_base_url.clone(),
+                year,
+                acs_type,
+                get_query.clone(),
+                for_query,
+                api_token.clone(),
+            )
+        })
+        .collect_vec();
+    run_queries(&queries, Some(&requested), options).await
+}
+
+/// runs ACS + TIGER/Lines for every geography of `geoid_type` that intersects an
+/// area of interest, such as all block groups touching a study area polygon.
+///
+/// the covering geographies are found from coarse to fine. the national state file
+/// is read to find the states that intersect the area. the counties of those states
+/// in the TIGER/Lines vintage come from the embedded county reference, see
+/// [`Geoid::children`], and the county file is read to find the ones that intersect
+/// it. when the TIGER/Lines vintage differs from `year`, the intersecting counties
+/// are carried back to the ACS vintage, see [`Geoid::counties_in_vintage`]. ACS is queried for every
+/// geography of `geoid_type` within those counties, or within the states for
+/// places, and the joined rows are filtered to the geometries that intersect the
+/// area. every TIGER/Lines file comes from the vintage of `year`, unless a
+/// `tiger_year` option is provided as in [`run_batch`]. candidates are tested
+/// against their full boundaries, so the `transform` and `keep_unmatched` options
+/// are not used.
+///
+/// states, counties, county subdivisions, places, census tracts and block groups
+/// are supported. candidates without a geometry cannot be tested against the area,
+/// so they are reported in the join errors.
+///
+/// # Example
+///
+/// the 2020 population of the block groups intersecting a GeoJSON study area. this
+/// requires network access, so it is only compiled.
+///
+/// ```no_run
+/// use bamcensus::app::acs_tiger;
+/// use bamcensus::model::area_of_interest::AreaOfInterest;
+/// use bamcensus::model::join_options::JoinOptions;
+/// use bamcensus_acs::model::AcsType;
+/// use bamcensus_core::model::identifier::GeoidType;
+///
+/// let geojson = std::fs::read_to_string("study_area.geojson").unwrap();
+/// let aoi = AreaOfInterest::from_geojson(&geojson).unwrap();
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// let response = runtime
+///     .block_on(acs_tiger::run_aoi(
+///         &aoi,
+///         GeoidType::BlockGroup,
+///         2020,
+///         AcsType::FiveYear,
+///         vec![String::from("B01001_001E")],
+///         None,
+///         &JoinOptions::default(),
+///     ))
+///     .unwrap();
+/// for row in response.join_dataset.iter() {
+///     println!("{row}");
+/// }
+/// ```
+pub async fn run_aoi(
+    aoi: &AreaOfInterest,
+    geoid_type: GeoidType,
+    year: u64,
+    acs_type: AcsType,
+    get_query: Vec<String>,
+    api_token: Option<String>,
+    options: &JoinOptions,
+) -> Result<AcsTigerResponse, String> {
+    let start = Instant::now();
+    let client: Client = http::client_builder()
+        .build()
+        .map_err(|e| format!("failure building HTTP client: {e}"))?;
+    let tiger_year = options.tiger_year.unwrap_or(year);
+    let tiger_uri_builder = TigerResourceBuilder::new(tiger_year)?;
+    let options = JoinOptions {
+        transform: None,
+        keep_unmatched: false,
+        ..options.clone()
+    };
+    // the bytes of the state and county archives read to narrow down the queries
+    let coarse_bytes = ByteCounter::default();
+    let tiger_options = options.tiger_options().with_bytes(coarse_bytes.clone());
+
+    let selection = StateSelection {
+        include_puerto_rico: true,
+        ..Default::default()
+    };
+    let (states, mut coarse_files) = intersecting_geoids(
+        &client,
+        &tiger_uri_builder,
+        aoi,
+        &Geoid::states(&selection),
+        &tiger_options,
+    )
+    .await?;
+    if states.is_empty() {
+        return Err(String::from(
+            "area of interest does not intersect any state",
+        ));
+    }
+    let for_queries = match geoid_type {
+        GeoidType::State => group_geoid_queries(&states)?,
+        GeoidType::Place => states
+            .iter()
+            .map(|state| AcsGeoidQuery::within(state.clone(), GeoidType::Place))
+            .collect::<Result<Vec<_>, String>>()?,
+        GeoidType::County
+        | GeoidType::CountySubdivision
+        | GeoidType::CensusTract
+        | GeoidType::BlockGroup => {
+            // the counties of the intersecting states in the TIGER/Lines vintage come
+            // from the embedded county reference, and are looked up in the county file
+            let candidates = states
+                .iter()
+                .map(|state| state.children(GeoidType::County, tiger_year))
+                .collect::<Result<Vec<_>, String>>()?
+                .into_iter()
+                .flatten()
+                .collect_vec();
+            let (counties, county_files) = intersecting_geoids(
+                &client,
+                &tiger_uri_builder,
+                aoi,
+                &candidates,
+                &tiger_options,
+            )
+            .await?;
+            coarse_files += county_files;
+            // the counties are queried in the ACS vintage
+            let counties = Geoid::counties_in_vintage(&counties, tiger_year, year);
+            if geoid_type == GeoidType::County {
+                group_geoid_queries(&counties)?
+            } else {
+                counties
+                    .into_iter()
+                    .map(|county| AcsGeoidQuery::within(county, geoid_type))
+                    .collect::<Result<Vec<_>, String>>()?
+            }
+        }
+        other => return Err(format!("acs.run_aoi does not support {other} geographies")),
+    };
+    if for_queries.is_empty() {
+        return Err(String::from(
+            "area of interest does not intersect any county",
+        ));
+    }
+    let queries = for_queries
+        .into_iter()
+        .map(|for_query| {
+            AcsApiQueryParams::new(
+                options.acs_base_url.clone(),
+                year,
+                acs_type,
+                get_query.clone(),
+                for_query,
+                api_token.clone(),
+            )
+        })
+        .collect_vec();
+    let mut response = run_queries(&queries, None, &options).await?;
+
+    // each geoid has one row per ACS value, so each geometry is tested once
+    let inside = response
+        .join_dataset
+        .iter()
+        .unique_by(|row| &row.geoid)
+        .filter(|row| row.geometry.as_ref().is_some_and(|g| aoi.intersects(g)))
+        .map(|row| row.geoid.clone())
+        .collect::<HashSet<_>>();
+    response
+        .join_dataset
+        .retain(|row| inside.contains(&row.geoid));
+
+    let tiger_files = &mut response.summary.tiger_files;
+    *tiger_files = RequestCounts::new(tiger_files.attempted + coarse_files, tiger_files.failed);
+    response.summary.rows = response.join_dataset.len();
+    response.summary.bytes += coarse_bytes.total();
+    response.summary.elapsed_secs = start.elapsed().as_secs_f64();
+    Ok(response)
+}
+
+/// the candidate geoids whose TIGER/Lines geometries intersect the area of
+/// interest, in order, along with the number of files read. a failed download is
+/// an error, since the geographies in the file could not be ruled out.
+async fn intersecting_geoids(
+    client: &Client,
+    builder: &TigerResourceBuilder,
+    aoi: &AreaOfInterest,
+    candidates: &[Geoid],
+    options: &RunOptions,
+) -> Result<(Vec<Geoid>, usize), String> {
+    let candidates = candidates.iter().collect_vec();
+    let results = tiger_api::run(client, builder, &candidates, options).await?;
+    let files = results.len();
+    let rows = results.into_iter().collect::<Result<Vec<_>, String>>()?;
+    let geoids = rows
+        .into_iter()
+        .flatten()
+        .filter(|(_, geometry)| aoi.intersects(geometry))
+        .map(|(geoid, _)| geoid)
+        .sorted()
+        .collect_vec();
+    Ok((geoids, files))
+}
+
+/// the county subdivision queries within states that have functioning minor civil
+/// divisions, logging the ones that are skipped, see
+/// [`Geoid::filter_functioning_mcd_states`]. fails if a query is not for county
+/// subdivisions, or if every query is skipped.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::app::acs_tiger::functioning_mcd_queries;
+/// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+/// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+///
+/// let query = |state: u64| {
+///     let for_query =
+///         AcsGeoidQuery::new(Some(Geoid::state(state).unwrap()), Some(GeoidType::CountySubdivision))
+///             .unwrap();
+///     AcsApiQueryParams::new(None, 2020, AcsType::FiveYear, vec![], for_query, None)
+/// };
+/// // Massachusetts towns are governments, Colorado county subdivisions are statistical
+/// let kept = functioning_mcd_queries(&[query(25), query(8)]).unwrap();
+/// assert_eq!(kept.len(), 1);
+/// assert_eq!(kept[0].for_query.to_query_key(), "&for=county%20subdivision:*&in=state:25&in=county:*");
+/// assert!(functioning_mcd_queries(&[query(8)]).is_err());
+/// ```
+pub fn functioning_mcd_queries(
+    queries: &[AcsApiQueryParams],
+) -> Result<Vec<AcsApiQueryParams>, String> {
+    let states = queries
+        .iter()
+        .map(|query| match &query.for_query {
+            AcsGeoidQuery::CountySubdivision(state, _, _) => Ok(Geoid::State(*state)),
+            other => Err(format!(
+                "functioning minor civil divisions only apply to county subdivision queries, found '{}'",
+                other.to_query_key()
+            )),
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let states = states.into_iter().unique().collect_vec();
+    let (keep, skip) =
+        Geoid::filter_functioning_mcd_states(states, Some(GeoidType::CountySubdivision))?;
+    if !skip.is_empty() {
+        log::warn!(
+            "skipping county subdivisions in states without functioning minor civil divisions: {}",
+            skip.iter().join(",")
+        );
+    }
+    let kept = queries
+        .iter()
+        .filter(|query| match &query.for_query {
+            AcsGeoidQuery::CountySubdivision(state, _, _) => keep.contains(&Geoid::State(*state)),
+            _ => false,
+        })
+        .cloned()
+        .collect_vec();
+    Ok(kept)
+}
+
+/// groups geoids by type and parent geography into the fewest ACS queries that
+/// cover them. see [`run_geoids`].
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::app::acs_tiger::group_geoid_queries;
+/// use bamcensus_core::model::identifier::Geoid;
+///
+/// let geoids = vec![
+///     Geoid::county(8, 59).unwrap(),
+///     Geoid::county(8, 31).unwrap(),
+///     Geoid::county(49, 35).unwrap(),
+///     Geoid::census_tract(8, 59, 9838).unwrap(),
+/// ];
+/// let keys = group_geoid_queries(&geoids)
+///     .unwrap()
+///     .iter()
+///     .map(|q| q.to_query_key())
+///     .collect::<Vec<_>>();
+/// assert_eq!(keys, vec![
+///     "&for=county:*&in=state:08",
+///     "&for=county:035&in=state:49",
+///     "&for=tract:009838&in=state:08&in=county:059",
+/// ]);
+/// ```
+pub fn group_geoid_queries(geoids: &[Geoid]) -> Result<Vec<AcsGeoidQuery>, String> {
+    let groups = geoids
+        .iter()
+        .unique()
+        .map(|geoid| Ok((query_parent(geoid)?, geoid.clone())))
+        .collect::<Result<Vec<_>, String>>()?
+        .into_iter()
+        .sorted()
+        .chunk_by(|(parent, geoid)| (parent.clone(), geoid.geoid_type()))
+        .into_iter()
+        .map(|(key, group)| (key, group.map(|(_, geoid)| geoid).collect_vec()))
+        .collect_vec();
+    groups
+        .into_iter()
+        .map(|((parent, geoid_type), group)| match (&group[..], parent) {
+            ([geoid], _) => AcsGeoidQuery::new(Some(geoid.clone()), None),
+            (_, None) => AcsGeoidQuery::new(None, Some(geoid_type)),
+            (_, Some(parent)) => AcsGeoidQuery::within(parent, geoid_type),
+        })
+        .collect()
+}
+
+/// the parent geography that ACS queries for this geoid's type are scoped within,
+/// or None for states and the geographies above them, which are queried nationally.
+fn query_parent(geoid: &Geoid) -> Result<Option<Geoid>, String> {
+    let parent_type = match geoid.geoid_type() {
+        GeoidType::Nation | GeoidType::Region | GeoidType::Division | GeoidType::State => {
+            return Ok(None)
+        }
+        GeoidType::County | GeoidType::Place => GeoidType::State,
+        GeoidType::CountySubdivision | GeoidType::CensusTract => GeoidType::County,
+        GeoidType::BlockGroup => GeoidType::CensusTract,
+        GeoidType::Block => {
+            return Err(format!(
+                "acs does not support block-level queries, found {geoid}"
+            ))
+        }
+        GeoidType::VotingDistrict => {
+            return Err(format!(
+                "acs does not publish voting districts, which join to the decennial PL 94-171 data, found {geoid}"
+            ))
+        }
+    };
+    geoid.truncate_geoid_to_type(&parent_type).map(Some)
+}
+
+/// runs the ACS queries and joins the result with TIGER/Lines geometries. if
+/// `keep` is provided, ACS rows for any other geoids are dropped before the
+/// geometries are downloaded.
+async fn run_queries(
+    queries: &[AcsApiQueryParams],
+    keep: Option<&HashSet<Geoid>>,
+    options: &JoinOptions,
+) -> Result<AcsTigerResponse, String> {
+    let start = Instant::now();
+    let client: Client = http::client_builder()
+        .build()
+        .map_err(|e| format!("failure building HTTP client: {e}"))?;
+    let queries = if options.functioning_mcds_only {
+        functioning_mcd_queries(queries)?
+    } else {
+        queries.to_vec()
+    };
+    let queries = &queries[..];
 
     // todo: run tiger downloads for all requested years
     let year = match &queries.iter().map(|q| q.year).unique().collect_vec()[..] {
@@ -73,19 +529,73 @@ pub async fn run_batch(queries: &Vec<AcsApiQueryParams>) -> Result<AcsTigerRespo
         )),
     }?;
 
-    let acs_rows = acs_api::batch_run(&client, queries).await?;
+    let bytes = ByteCounter::default();
+    let acs_rows =
+        acs_api::batch_run(&client, queries, options.cancel.as_ref(), Some(&bytes)).await?;
+    let acs_rows = match keep {
+        Some(keep) => acs_rows
+            .into_iter()
+            .filter(|(geoid, _)| keep.contains(geoid))
+            .collect_vec(),
+        None => acs_rows,
+    };
 
     // execute TIGER/Lines downloads
-    let tiger_uri_builder = TigerResourceBuilder::new(year)?;
+    let tiger_year = match options.tiger_year {
+        Some(tiger_year) => {
+            let census_year = queries[0].acs_type.geography_year(year);
+            log::info!(
+                "joining {year} ACS data, tabulated on {census_year} census tracts, to {tiger_year} TIGER/Lines geometries"
+            );
+            let geoid_types = acs_rows
+                .iter()
+                .map(|(geoid, _)| geoid.geoid_type())
+                .unique()
+                .collect_vec();
+            if let Some(warning) = vintage::check_vintage(year, tiger_year, &geoid_types) {
+                log::warn!("{warning}");
+            }
+            tiger_year
+        }
+        None => year,
+    };
+    let tiger_uri_builder = TigerResourceBuilder::new(tiger_year)?;
     let geoids = &acs_rows.iter().map(|(geoid, _)| geoid).collect_vec();
-    let tiger_response = tiger_api::run(&client, &tiger_uri_builder, geoids).await?;
+    let tiger_options = options.tiger_options().with_bytes(bytes.clone());
+    let tiger_response =
+        tiger_api::run(&client, &tiger_uri_builder, geoids, &tiger_options).await?;
+
+    // tiger_api::run returns one result per resource, in the order they are created,
+    // so failed downloads can be traced back to their archive
+    let tiger_base_url = tiger_options.tiger_base_url();
+    let resources = tiger_uri_builder.create_resources_at(&tiger_base_url, geoids)?;
+    let failed_uris = resources
+        .iter()
+        .zip(tiger_response.iter())
+        .filter(|(_, result)| result.is_err())
+        .map(|(resource, _)| resource.uri.clone())
+        .collect::<HashSet<_>>();
+
+    let tiger_files = RequestCounts::new(tiger_response.len(), failed_uris.len());
 
     type NestedResult = (Vec<Vec<(Geoid, Geometry<f64>)>>, Vec<String>);
     let (tiger_rows_nested, tiger_errors): NestedResult =
         tiger_response.into_iter().partition_result();
 
-    let (join_dataset, join_errors) =
-        crate::ops::join::dataset_with_geometries(acs_rows, tiger_rows_nested)?;
+    let (join_dataset, join_errors, join_summary) = join::dataset_with_optional_geometries(
+        acs_rows,
+        tiger_rows_nested,
+        |geoid| {
+            let expected_uri = tiger_uri_builder
+                .create_resource_at(&tiger_base_url, geoid)
+                .map(|resource| resource.uri);
+            Some(MissingGeometry::new(expected_uri, &failed_uris))
+        },
+        options.keep_unmatched,
+    )?;
+    if join_summary.missing() > 0 {
+        log::warn!("{join_summary}");
+    }
     let output_dataset = join_dataset
         .into_iter()
         .flat_map(|(geoid, geometry, acs_values)| {
@@ -95,10 +605,20 @@ pub async fn run_batch(queries: &Vec<AcsApiQueryParams>) -> Result<AcsTigerRespo
         })
         .collect_vec();
 
+    // acs_api::batch_run fails as a whole, so every ACS query that reaches here succeeded
+    let summary = RunSummary::new(
+        RequestCounts::new(queries.len(), 0),
+        tiger_files,
+        output_dataset.len(),
+        bytes.total(),
+        start.elapsed(),
+    );
     let result = AcsTigerResponse {
         join_dataset: output_dataset,
         tiger_errors,
         join_errors,
+        join_summary,
+        summary,
     };
     Ok(result)
 }
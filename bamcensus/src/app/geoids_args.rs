@@ -0,0 +1,10 @@
+use bamcensus_core::model::identifier::Geoid;
+use bamcensus_core::ops::geoids_arg::try_parse_geoids_arg;
+use clap::error::ErrorKind;
+
+/// parses the geoids of a command line flag with [`try_parse_geoids_arg`], exiting
+/// with a usage error before anything is downloaded if they are invalid.
+pub fn parse_geoids_arg(flag: &str, value: &str) -> Vec<Geoid> {
+    try_parse_geoids_arg(flag, value)
+        .unwrap_or_else(|e| clap::Error::raw(ErrorKind::ValueValidation, format!("{e}\n")).exit())
+}
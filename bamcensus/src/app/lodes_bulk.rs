@@ -0,0 +1,148 @@
+use super::lodes_tiger::{self, LodesTigerResponse};
+use crate::model::bulk_manifest::{BulkManifest, BulkSettings};
+use crate::model::join_options::JoinOptions;
+use bamcensus_core::model::identifier::Geoid;
+use bamcensus_lehd::model::RetryPolicy;
+use futures::StreamExt;
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
+
+/// default number of states downloaded at a time by [`run`]. block geometries of a
+/// large state take several GB of memory, so this is kept low.
+pub const DEFAULT_STATE_CONCURRENCY: usize = 2;
+
+/// runs the LODES + TIGER/Lines workflow for each state as a resumable bulk download,
+/// such as every block in the country, with progress recorded in the
+/// [`BulkManifest`] at `manifest_path`.
+///
+/// states already complete in the manifest are skipped, so running again after an
+/// interruption resumes the download. up to `options.concurrency` states are run at a
+/// time, see [`DEFAULT_STATE_CONCURRENCY`], and each state downloads one file at a
+/// time. a state whose LODES or TIGER/Lines downloads fail is retried as a whole,
+/// following the `retry` policy of the options or the [`RetryPolicy`] read from the
+/// environment, and is marked failed in the manifest once its retries run out so that
+/// the other states can finish. a manifest written with other `settings` or another
+/// `tiger_year` option is not resumed, see [`BulkManifest::load_or_new`].
+/// TIGER/Lines archives are kept in the `cache` of the options, if provided, so that retries and
+/// resumed runs do not download them again. states without LODES data for the
+/// dataset are left out, see
+/// [`bamcensus_lehd::model::LodesDataset::check_coverage`].
+///
+/// each complete state's response is handed to `write_state`, which writes it and
+/// returns the path of the output file. the manifest is saved after each state, and
+/// returned once every state has been attempted.
+pub async fn run<F>(
+    manifest_path: &Path,
+    states: &[Geoid],
+    settings: &BulkSettings,
+    options: &JoinOptions,
+    mut write_state: F,
+) -> Result<BulkManifest, String>
+where
+    F: FnMut(&Geoid, LodesTigerResponse) -> Result<PathBuf, String>,
+{
+    let BulkSettings {
+        dataset,
+        output_resolution: agg_geoid_type,
+        wac_segments,
+        ..
+    } = settings;
+    let states = match states.len() {
+        0 => Geoid::all_states(),
+        _ => states
+            .iter()
+            .map(|g| g.to_state())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unique()
+            .collect_vec(),
+    };
+    let (states, _) = lodes_tiger::covered_states(states, dataset);
+    let mut manifest =
+        BulkManifest::load_or_new(manifest_path, settings, options.tiger_year, &states)?;
+    manifest.save(manifest_path)?;
+    let remaining = manifest
+        .remaining()
+        .into_iter()
+        .filter(|state| states.contains(state))
+        .collect_vec();
+    let total = remaining.len();
+    log::info!(
+        "{} of {} states already complete, {total} to download",
+        states.len() - total,
+        states.len()
+    );
+
+    let retry = match options.retry {
+        Some(retry) => retry,
+        None => RetryPolicy::from_env()?,
+    };
+    let state_options = &JoinOptions {
+        concurrency: 1,
+        ..options.clone()
+    };
+    let run_state = |state: Geoid| async move {
+        let mut attempt = 0;
+        loop {
+            let result = lodes_tiger::run(
+                std::slice::from_ref(&state),
+                agg_geoid_type,
+                wac_segments,
+                dataset,
+                state_options,
+            )
+            .await
+            .and_then(check_response);
+            match result {
+                Err(e) if attempt < retry.max_retries => {
+                    attempt += 1;
+                    let wait = retry.backoff(attempt);
+                    log::warn!(
+                        "state {state} failed: {e}, retrying in {wait:.2?} ({attempt}/{})",
+                        retry.max_retries
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+                result => return (state, attempt + 1, result),
+            }
+        }
+    };
+
+    let mut runs = futures::stream::iter(remaining)
+        .map(run_state)
+        .buffer_unordered(options.concurrency.max(1));
+    let mut finished = 0;
+    while let Some((state, attempts, result)) = runs.next().await {
+        finished += 1;
+        let written = result.and_then(|response| {
+            let rows = response.join_dataset.len();
+            write_state(&state, response).map(|output| (output, rows))
+        });
+        match written {
+            Ok((output, rows)) => {
+                log::info!("state {state} complete ({finished}/{total})");
+                manifest.mark_complete(&state, attempts, &output, rows);
+            }
+            Err(e) => {
+                log::error!("state {state} failed after {attempts} attempt(s): {e}");
+                manifest.mark_failed(&state, attempts, e);
+            }
+        }
+        manifest.save(manifest_path)?;
+    }
+    Ok(manifest)
+}
+
+/// fails a state's response if any of its LODES or TIGER/Lines downloads failed,
+/// since its output would be missing rows.
+fn check_response(response: LodesTigerResponse) -> Result<LodesTigerResponse, String> {
+    let errors = response
+        .lodes_errors
+        .iter()
+        .chain(response.tiger_errors.iter())
+        .collect_vec();
+    match errors.as_slice() {
+        [] => Ok(response),
+        _ => Err(errors.iter().join("; ")),
+    }
+}
@@ -0,0 +1,184 @@
+use super::geoids_args::parse_geoids_arg;
+use super::lodes_bulk;
+use super::lodes_tiger_args::write_response;
+use crate::model::bulk_manifest::{BulkManifest, BulkSettings, StateStatus};
+use crate::model::geometry_encoding::GeometryEncoding;
+use crate::model::geometry_output::GeometryOutput;
+use crate::model::join_options::JoinOptions;
+use crate::model::output_format::OutputFormat;
+use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString, StateSelection};
+use bamcensus_lehd::model::{
+    LodesDataset, LodesEdition, LodesJobType, WacSegment, WorkplaceSegment,
+};
+use bamcensus_tiger::model::{tiger_base_url_from_env, TigerCache};
+use clap::Parser;
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
+
+/// resumable download of LODES WAC values with TIGER/Lines geometries, writing one
+/// file per state into an output directory alongside a manifest of the states
+/// completed. running the same command again resumes an interrupted download.
+#[derive(Parser)]
+pub struct LodesBulkCli {
+    /// directory for the output files and the manifest
+    #[arg(long)]
+    pub output_dir: PathBuf,
+    /// comma-delimited list of state geoids to download, instead of every state
+    #[arg(long)]
+    pub states: Option<String>,
+    /// without --states, download only the 48 contiguous states and DC, leaving out
+    /// Alaska and Hawaii
+    #[arg(long, default_value_t = false, conflicts_with = "states")]
+    contiguous: bool,
+    /// without --states, leave out the District of Columbia
+    #[arg(long, default_value_t = false, conflicts_with = "states")]
+    exclude_dc: bool,
+    /// produce output rows at the given geospatial resolution. blocks if not specified.
+    #[arg(short, long)]
+    pub output_resolution: Option<GeoidType>,
+    /// dataset year
+    #[arg(long)]
+    pub year: u64,
+    /// workplace area characteristic segments, see LODES documentation
+    #[arg(long, default_value_t = String::from("C000"))]
+    wac_segments: String,
+    /// LODES definition, see LODES documentation, default latest
+    #[arg(long, default_value = "lodes8")]
+    edition: LodesEdition,
+    /// LODES workforce segment defined in LODES schema documentation
+    #[arg(long, default_value = "s000")]
+    segment: WorkplaceSegment,
+    /// WAC job type defined in LODES schema documentation
+    #[arg(long, default_value = "jt00")]
+    jobtype: LodesJobType,
+    /// file format of the output files
+    #[arg(long, default_value = "csv")]
+    format: OutputFormat,
+    /// encoding of the geometry column in CSV output
+    #[arg(long, default_value = "wkt")]
+    geometry_encoding: GeometryEncoding,
+    /// write one row per geoid with a column per variable instead of one row per value
+    #[arg(long, default_value_t = false)]
+    wide: bool,
+    /// read geoids from this column of the TIGER/Lines files, such as GEOID20, instead of
+    /// the first of GEOID, GEOID20 and GEOID10 found
+    #[arg(long)]
+    geoid_column: Option<String>,
+    /// geometry written for each geography: the full boundary, or a single point inside it
+    #[arg(long, default_value = "polygon")]
+    geometry: GeometryOutput,
+    /// TIGER/Lines vintage of the geometries, instead of the vintage of the LODES edition
+    #[arg(long)]
+    tiger_year: Option<u64>,
+    /// maximum number of states to download at a time
+    #[arg(long, default_value_t = lodes_bulk::DEFAULT_STATE_CONCURRENCY)]
+    state_concurrency: usize,
+    /// directory to keep downloaded TIGER/Lines archives in, so that retried and
+    /// resumed states do not download them again
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+}
+
+impl LodesBulkCli {
+    pub async fn run(&self) {
+        let states = match &self.states {
+            Some(s) => parse_geoids_arg("--states", s),
+            None => Geoid::states(&StateSelection {
+                exclude_alaska_hawaii: self.contiguous,
+                exclude_dc: self.exclude_dc,
+                ..Default::default()
+            }),
+        };
+        let dataset = LodesDataset::WAC {
+            edition: self.edition,
+            job_type: self.jobtype,
+            segment: self.segment,
+            year: self.year,
+        };
+        let wac_segments = self
+            .wac_segments
+            .split(',')
+            .map(WacSegment::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        std::fs::create_dir_all(&self.output_dir).unwrap();
+        let options = JoinOptions {
+            geoid_column: self.geoid_column.clone(),
+            transform: self.geometry.transform(),
+            tiger_year: self.tiger_year,
+            concurrency: self.state_concurrency,
+            cache: self.cache_dir.as_deref().map(TigerCache::new),
+            tiger_base_url: tiger_base_url_from_env(),
+            ..Default::default()
+        };
+        let stem = Path::new(&dataset.output_filename(&self.output_resolution))
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let manifest_path = self.output_dir.join(BulkManifest::FILENAME);
+
+        let settings = BulkSettings {
+            dataset,
+            output_resolution: self.output_resolution,
+            wac_segments,
+            format: self.format,
+            wide: self.wide,
+        };
+
+        let manifest = lodes_bulk::run(
+            &manifest_path,
+            &states,
+            &settings,
+            &options,
+            |state, res| {
+                // write to a partial file first so that a crash mid-write does not
+                // leave a truncated file behind a complete manifest entry
+                let filename = self.output_dir.join(format!(
+                    "{stem}_{}.{}",
+                    state.geoid_string(),
+                    self.format.extension()
+                ));
+                let partial_filename = filename.with_extension("partial");
+                write_response(
+                    res,
+                    &partial_filename,
+                    self.format,
+                    self.geometry_encoding,
+                    self.wide,
+                    false,
+                    None,
+                )?;
+                std::fs::rename(&partial_filename, &filename).map_err(|e| {
+                    format!(
+                        "failure moving {} to {}: {e}",
+                        partial_filename.display(),
+                        filename.display()
+                    )
+                })?;
+                println!("wrote {}", filename.display());
+                Ok(filename)
+            },
+        )
+        .await
+        .unwrap();
+
+        let failed = manifest
+            .states
+            .iter()
+            .filter(|(_, entry)| entry.status == StateStatus::Failed)
+            .collect_vec();
+        if failed.is_empty() {
+            println!("all states complete, see {}", manifest_path.display());
+        } else {
+            println!("FAILED STATES");
+            for (state, entry) in failed {
+                println!("{state}: {}", entry.error.as_deref().unwrap_or_default());
+            }
+            println!(
+                "run the same command again to retry the failed states, see {}",
+                manifest_path.display()
+            );
+        }
+    }
+}
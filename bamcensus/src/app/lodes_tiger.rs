@@ -1,20 +1,56 @@
+use crate::model::join_options::JoinOptions;
 use crate::model::lodes_wac_tiger_row::LodesWacTigerRow;
+use crate::model::run_summary::{RequestCounts, RunSummary};
 use bamcensus_core::model::identifier::Geoid;
 use bamcensus_core::model::identifier::GeoidType;
-use bamcensus_lehd::api::lodes_api;
-use bamcensus_lehd::model::{LodesDataset, WacSegment};
+use bamcensus_core::model::identifier::HasGeoidString;
+use bamcensus_core::model::{head_content_length, ByteCounter, DownloadEstimate};
+use bamcensus_core::ops::batch::group_geoids_by_state;
+use bamcensus_core::ops::http;
+use bamcensus_lehd::api::lodes_api::{self, LodesResult};
+use bamcensus_lehd::model::{DownloadOptions, LodesDataset, WacSegment, WacValue, BASE_URL};
 use bamcensus_tiger::model::TigerResourceBuilder;
-use bamcensus_tiger::ops::tiger_api;
+use bamcensus_tiger::ops::{tiger_api, vintage};
 use geo::Geometry;
 use itertools::Itertools;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// seconds to wait when establishing a connection to a LODES or TIGER/Lines server
+const CONNECT_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Serialize, Deserialize)]
 pub struct LodesTigerResponse {
     pub join_dataset: Vec<LodesWacTigerRow>,
+    /// states skipped because LODES does not publish the dataset for them
+    pub skipped_states: Vec<String>,
+    pub lodes_errors: Vec<String>,
     pub tiger_errors: Vec<String>,
     pub join_errors: Vec<String>,
+    /// counts of the requests made and rows produced, and the duration of the run
+    pub summary: RunSummary,
+}
+
+/// the response of [`run_without_geometry`], which holds the LODES values of each
+/// geoid without downloading TIGER/Lines geometries.
+#[derive(Serialize, Deserialize)]
+pub struct LodesResponse {
+    pub dataset: Vec<(Geoid, Vec<WacValue>)>,
+    /// states skipped because LODES does not publish the dataset for them
+    pub skipped_states: Vec<String>,
+    pub lodes_errors: Vec<String>,
+    /// counts of the requests made and rows produced, and the duration of the run
+    pub summary: RunSummary,
+}
+
+/// the LODES values of a run before they are written, with the skipped states and
+/// failed requests of the LODES downloads.
+struct LodesRows {
+    dataset: Vec<(Geoid, Vec<WacValue>)>,
+    skipped_states: Vec<String>,
+    lodes_errors: Vec<String>,
+    requests: RequestCounts,
 }
 
 /// runs a query to ACS. the result will include a list of GEOIDs alongside
@@ -22,6 +58,31 @@ pub struct LodesTigerResponse {
 /// datasets. the geometries from TIGER are combined with the ACS data producing
 /// AcsTigerRows.
 ///
+/// at most `options.concurrency` LODES files, and as many TIGER/Lines archives, are
+/// downloaded at a time, and failed downloads are retried following the `retry`
+/// policy of the options. if the options provide a `geoid_column`, geoids are read
+/// only from that column of the TIGER/Lines files, see [`tiger_api::run`]. if they provide a `transform`, it is applied to each
+/// geometry, such as replacing it with its internal point. if they provide a `cache`,
+/// TIGER/Lines archives are read from it when present and downloads are added to it,
+/// so that a retried run does not download them again. if they provide a `cancel`
+/// token, cancelling it stops the LODES and TIGER/Lines downloads, and each file that
+/// was not read is an error of the response, see [`lodes_api::run_wac`] and
+/// [`tiger_api::run`].
+///
+/// geometries come from the TIGER/Lines vintage of the LODES edition, see
+/// [`LodesDataset::tiger_year`], unless the options provide a `tiger_year`. LODES
+/// blocks are coded to the edition's vintage, so an override that crosses a decennial
+/// census leaves most blocks (and often tracts and block groups) without a geometry.
+///
+/// states without LODES data for the dataset year and edition, such as Puerto Rico or
+/// states missing from early years, are skipped with a warning and listed in the
+/// skipped states of the response, see [`LodesDataset::check_coverage`].
+///
+/// failures for individual LODES or TIGER/Lines files are collected into the
+/// error vectors of the response so that the remaining data is still returned.
+/// the outer `Result` only fails when the run cannot be set up, which includes
+/// options with `keep_unmatched`: LODES rows are only kept with a geometry.
+///
 /// # Example
 ///
 /// ```ignore
@@ -52,48 +113,54 @@ pub struct LodesTigerResponse {
 ///
 /// ```
 pub async fn run(
-    geoids: &Vec<Geoid>,
+    geoids: &[Geoid],
     agg_geoid_type: &Option<GeoidType>,
     wac_segments: &[WacSegment],
     dataset: &LodesDataset,
+    options: &JoinOptions,
 ) -> Result<LodesTigerResponse, String> {
-    // input: i have a set of geoids that describe a region. i want to download
-    // lodes data and aggregate it to some GeoidType.
-    // use the LODES dataset argument to build URIs for all LODES downloads
-    // if the user did not provide geoids, use all states
-    let geoids = match geoids.len() {
-        0 => Geoid::all_states(),
-        _ => geoids.iter().map(|g| g.to_state()).unique().collect_vec(),
-    };
-    let lodes_queries = geoids
-        .iter()
-        .map(|geoid| dataset.create_uri(geoid))
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let agg_fn = bamcensus_core::ops::agg::NumericAggregation::Sum;
-    let agg = agg_geoid_type.map(|g| (g, agg_fn));
-
-    // execute LODES downloads
-
-    let client: Client = Client::new();
-    let lodes_rows = lodes_api::run_wac(&client, &lodes_queries, wac_segments, agg).await?;
-
-    // filter result. LODES collects by State. here we only accept rows where the
-    // input geoids are the (FIPS hierarchical) parent.
-    let lodes_filtered = lodes_rows
-        .into_iter()
-        .filter(|(c, _)| geoids.iter().any(|p| p.is_parent_of(c)))
-        .collect_vec();
+    if options.keep_unmatched {
+        return Err(String::from(
+            "keep_unmatched is only supported when joining ACS data, LODES rows without a geometry are join errors",
+        ));
+    }
+    let start = Instant::now();
+    let client = build_client()?;
+    let bytes = ByteCounter::default();
+    let LodesRows {
+        dataset: lodes_filtered,
+        skipped_states,
+        lodes_errors,
+        requests: lodes_requests,
+    } = run_lodes(
+        &client,
+        geoids,
+        agg_geoid_type,
+        wac_segments,
+        dataset,
+        &options.lodes_options()?.with_bytes(bytes.clone()),
+    )
+    .await?;
 
     // execute TIGER/Lines downloads selecting a data vintage based on the LODES edition chosen
-    let tiger_year = dataset.tiger_year();
+    let tiger_year = tiger_year(dataset, agg_geoid_type, options.tiger_year);
     let tiger_uri_builder = TigerResourceBuilder::new(tiger_year)?;
     let lodes_geoids = &lodes_filtered.iter().map(|(geoid, _)| geoid).collect_vec();
-    let tiger_response = tiger_api::run(&client, &tiger_uri_builder, lodes_geoids).await?;
+    let tiger_response = tiger_api::run(
+        &client,
+        &tiger_uri_builder,
+        lodes_geoids,
+        &options.tiger_options().with_bytes(bytes.clone()),
+    )
+    .await?;
 
+    let tiger_attempted = tiger_response.len();
     type NestedResult = (Vec<Vec<(Geoid, Geometry<f64>)>>, Vec<String>);
     let (tiger_rows_nested, tiger_errors): NestedResult =
         tiger_response.into_iter().partition_result();
+    for error in tiger_errors.iter() {
+        log::warn!("TIGER/Lines failure: {error}");
+    }
 
     let (join_dataset, join_errors) =
         crate::ops::join::dataset_with_geometries(lodes_filtered, tiger_rows_nested)?;
@@ -106,10 +173,289 @@ pub async fn run(
         })
         .collect_vec();
 
+    let summary = RunSummary::new(
+        lodes_requests,
+        RequestCounts::new(tiger_attempted, tiger_errors.len()),
+        output_dataset.len(),
+        bytes.total(),
+        start.elapsed(),
+    );
     let result = LodesTigerResponse {
         join_dataset: output_dataset,
+        skipped_states,
+        lodes_errors,
         tiger_errors,
         join_errors,
+        summary,
     };
     Ok(result)
 }
+
+/// runs the LODES half of [`run`] without downloading any TIGER/Lines files, returning
+/// the LODES values of each geoid at the output resolution. this is much faster when
+/// only the tabular values are needed. only the `concurrency`, `retry` and
+/// `lodes_base_url` of the options are used.
+pub async fn run_without_geometry(
+    geoids: &[Geoid],
+    agg_geoid_type: &Option<GeoidType>,
+    wac_segments: &[WacSegment],
+    dataset: &LodesDataset,
+    options: &JoinOptions,
+) -> Result<LodesResponse, String> {
+    let start = Instant::now();
+    let client = build_client()?;
+    let bytes = ByteCounter::default();
+    let lodes = run_lodes(
+        &client,
+        geoids,
+        agg_geoid_type,
+        wac_segments,
+        dataset,
+        &options.lodes_options()?.with_bytes(bytes.clone()),
+    )
+    .await?;
+    // one row is written for each value of each geoid
+    let rows = lodes.dataset.iter().map(|(_, values)| values.len()).sum();
+    let summary = RunSummary::new(
+        lodes.requests,
+        RequestCounts::default(),
+        rows,
+        bytes.total(),
+        start.elapsed(),
+    );
+    Ok(LodesResponse {
+        dataset: lodes.dataset,
+        skipped_states: lodes.skipped_states,
+        lodes_errors: lodes.lodes_errors,
+        summary,
+    })
+}
+
+/// downloads, aggregates and filters the LODES data of a run.
+async fn run_lodes(
+    client: &Client,
+    geoids: &[Geoid],
+    agg_geoid_type: &Option<GeoidType>,
+    wac_segments: &[WacSegment],
+    dataset: &LodesDataset,
+    download_options: &DownloadOptions,
+) -> Result<LodesRows, String> {
+    // input: i have a set of geoids that describe a region. i want to download
+    // lodes data and aggregate it to some GeoidType.
+    // use the LODES dataset argument to build URIs for all LODES downloads
+    // if the user did not provide geoids, use all states
+    let geoids = match geoids.len() {
+        0 => Geoid::all_states(),
+        _ => geoids
+            .iter()
+            .map(|g| g.to_state())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unique()
+            .collect_vec(),
+    };
+    let (geoids, skipped_states) = covered_states(geoids, dataset);
+    let lodes_attempted = geoids.len();
+
+    let agg_fn = bamcensus_core::ops::agg::NumericAggregation::Sum;
+    let agg = agg_geoid_type.map(|g| (g, agg_fn));
+
+    // execute LODES downloads
+    let lodes_result = lodes_api::run(
+        client,
+        dataset,
+        &geoids,
+        wac_segments,
+        agg,
+        download_options,
+    )
+    .await?;
+    let LodesResult::Wac((lodes_rows, lodes_errors)) = lodes_result else {
+        return Err(String::from(
+            "LODES runs only support WAC datasets, not RAC or OD",
+        ));
+    };
+    for error in lodes_errors.iter() {
+        log::warn!("LODES failure: {error}");
+    }
+
+    // filter result. LODES collects by State. here we only accept rows where the
+    // input geoids are the (FIPS hierarchical) parent.
+    let lodes_filtered = lodes_rows
+        .into_iter()
+        .filter(|(c, _)| geoids.iter().any(|p| p.is_parent_of(c)))
+        .collect_vec();
+
+    let requests = RequestCounts::new(lodes_attempted, lodes_errors.len());
+    Ok(LodesRows {
+        dataset: lodes_filtered,
+        skipped_states,
+        lodes_errors,
+        requests,
+    })
+}
+
+fn build_client() -> Result<Client, String> {
+    http::client_builder()
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("failure building HTTP client: {e}"))
+}
+
+/// upfront estimate of the LODES and TIGER/Lines downloads for a [`run`].
+pub struct LodesTigerEstimate {
+    pub lodes: DownloadEstimate,
+    pub tiger: DownloadEstimate,
+}
+
+/// estimates the files and bytes that [`run`] would download for the same arguments,
+/// using HEAD requests instead of downloading. LODES files are organized by state,
+/// so they are known exactly. TIGER/Lines files are estimated for each state at the
+/// output resolution (blocks if not aggregated). vintages that publish per-county
+/// files, such as 2010 tracts, cannot be resolved until the LODES data is read and
+/// are reported as errors in the TIGER/Lines estimate. at most `options.concurrency`
+/// HEAD requests are sent at a time, as with the downloads of [`run`].
+pub async fn estimate(
+    geoids: &[Geoid],
+    agg_geoid_type: &Option<GeoidType>,
+    dataset: &LodesDataset,
+    options: &JoinOptions,
+) -> Result<LodesTigerEstimate, String> {
+    let states = match geoids.len() {
+        0 => Geoid::all_states(),
+        _ => geoids
+            .iter()
+            .map(|g| g.to_state())
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unique()
+            .collect_vec(),
+    };
+    let (states, _) = covered_states(states, dataset);
+    let lodes_base_url = options.lodes_base_url.as_deref().unwrap_or(BASE_URL);
+    let (lodes_queries, lodes_errors): (Vec<String>, Vec<String>) = states
+        .iter()
+        .map(|geoid| dataset.create_uri_at(lodes_base_url, geoid))
+        .partition_result();
+
+    let client = build_client()?;
+    let mut lodes = lodes_api::estimate(&client, &lodes_queries, options.concurrency).await;
+    lodes.errors.extend(lodes_errors);
+
+    // the TIGER/Lines file names only depend on the state, so a placeholder geoid
+    // at the output resolution within each state resolves the file for that state
+    let tiger_year = tiger_year(dataset, agg_geoid_type, options.tiger_year);
+    let builder = TigerResourceBuilder::new(tiger_year)?;
+    let output_type = agg_geoid_type.unwrap_or(GeoidType::Block);
+    let placeholders = states
+        .iter()
+        .map(|state| placeholder_geoid(state, output_type))
+        .collect::<Result<Vec<_>, String>>()?;
+    let (state_resources, county_resources): (Vec<_>, Vec<_>) = builder
+        .create_resources_at(
+            &options.tiger_options().tiger_base_url(),
+            &placeholders.iter().collect_vec(),
+        )?
+        .into_iter()
+        .partition(|resource| resource.file_scope != Some(GeoidType::County));
+    let tiger_uris = state_resources.into_iter().map(|r| r.uri).collect_vec();
+    let mut tiger = head_content_length(&client, &tiger_uris, options.concurrency).await;
+    if !county_resources.is_empty() {
+        tiger.errors.push(format!(
+            "TIGER/Lines {tiger_year} {output_type} files are published per county and cannot be estimated before the LODES data is read"
+        ));
+    }
+    Ok(LodesTigerEstimate { lodes, tiger })
+}
+
+/// splits states into those with LODES data for the dataset and messages for those
+/// without, logging a warning for each skipped state.
+pub(crate) fn covered_states(
+    states: Vec<Geoid>,
+    dataset: &LodesDataset,
+) -> (Vec<Geoid>, Vec<String>) {
+    let (covered, skipped): (Vec<Geoid>, Vec<String>) = states
+        .into_iter()
+        .map(|state| match dataset.check_coverage(&state) {
+            Ok(()) => Ok(state),
+            Err(e) => Err(format!("skipping state {}: {e}", state.geoid_string())),
+        })
+        .partition_result();
+    for message in skipped.iter() {
+        log::warn!("{message}");
+    }
+    (covered, skipped)
+}
+
+/// the TIGER/Lines vintage for a run, warning when an override differs from the
+/// vintage the LODES edition is coded to, see [`vintage::check_vintage`].
+fn tiger_year(
+    dataset: &LodesDataset,
+    agg_geoid_type: &Option<GeoidType>,
+    tiger_year_override: Option<u64>,
+) -> u64 {
+    let edition_year = dataset.tiger_year();
+    match tiger_year_override {
+        Some(year) => {
+            let output_type = agg_geoid_type.unwrap_or(GeoidType::Block);
+            if let Some(warning) = vintage::check_vintage(edition_year, year, &[output_type]) {
+                log::warn!("LODES {warning}");
+            }
+            year
+        }
+        None => edition_year,
+    }
+}
+
+/// a geoid of the given type within a state, with all other components set to zero.
+fn placeholder_geoid(state: &Geoid, geoid_type: GeoidType) -> Result<Geoid, String> {
+    let Geoid::State(s) = state.to_state()? else {
+        return Err(format!("expected a state geoid, found {state}"));
+    };
+    let s = s.0;
+    match geoid_type {
+        GeoidType::Nation | GeoidType::Region | GeoidType::Division => Err(format!(
+            "{geoid_type} geographies span multiple states and cannot be placed within state {s}"
+        )),
+        GeoidType::State => Geoid::state(s),
+        GeoidType::County => Geoid::county(s, 0),
+        GeoidType::CountySubdivision => Geoid::county_subdivision(s, 0, 0),
+        GeoidType::Place => Geoid::place(s, 0),
+        GeoidType::CensusTract => Geoid::census_tract(s, 0, 0),
+        GeoidType::BlockGroup => Geoid::block_group(s, 0, 0, 0),
+        GeoidType::Block => Geoid::block(s, 0, 0, "0000"),
+        GeoidType::VotingDistrict => Geoid::voting_district(s, 0, "000000"),
+    }
+}
+
+/// runs the LODES + TIGER/Lines workflow one state at a time, handing each state's
+/// response to `on_state` as soon as it completes so that the caller can persist
+/// results incrementally. states are processed in ascending GEOID order. if no
+/// geoids are provided, all states are run. each state is run with the options, see
+/// [`run`].
+pub async fn run_by_state<F>(
+    geoids: &[Geoid],
+    agg_geoid_type: &Option<GeoidType>,
+    wac_segments: &[WacSegment],
+    dataset: &LodesDataset,
+    options: &JoinOptions,
+    mut on_state: F,
+) -> Result<(), String>
+where
+    F: FnMut(&Geoid, LodesTigerResponse) -> Result<(), String>,
+{
+    let geoids = match geoids.len() {
+        0 => Geoid::all_states(),
+        _ => geoids.to_vec(),
+    };
+    let batches = group_geoids_by_state(&geoids)?
+        .into_iter()
+        .sorted_by_key(|(state, _)| state.geoid_string())
+        .collect_vec();
+    for (state, state_geoids) in batches.iter() {
+        log::info!("running LODES + TIGER/Lines for state {state}");
+        let response = run(state_geoids, agg_geoid_type, wac_segments, dataset, options).await?;
+        on_state(state, response)?;
+    }
+    Ok(())
+}
@@ -1,12 +1,26 @@
+use super::geoids_args::parse_geoids_arg;
 use super::lodes_tiger;
-use crate::model::lodes_tiger_output_row::LodesTigerOutputRow;
+use super::lodes_tiger::LodesTigerResponse;
+use crate::model::area_projection::AreaProjection;
+use crate::model::geometry_encoding::GeometryEncoding;
+use crate::model::geometry_output::GeometryOutput;
+use crate::model::join_options::JoinOptions;
+use crate::model::output_format::OutputFormat;
+use crate::model::run_metadata::RunMetadata;
+use crate::model::run_summary::RunSummary;
+use crate::model::wide_table::WideTable;
+use crate::ops::sink;
 use bamcensus_core::model::identifier::GeoidType;
-use bamcensus_core::model::identifier::{Geoid, StateCode};
+use bamcensus_core::model::identifier::{Geoid, HasGeoidString, StateSelection};
+use bamcensus_core::ops::batch::group_geoids_by_state;
+use bamcensus_lehd::api::lodes_api;
 use bamcensus_lehd::model::{
     LodesDataset, LodesEdition, LodesJobType, WacSegment, WorkplaceSegment,
 };
+use bamcensus_tiger::model::tiger_base_url_from_env;
 use clap::{Args, Parser, Subcommand};
 use itertools::Itertools;
+use std::path::Path;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -21,8 +35,6 @@ pub struct LodesTigerCli {
 pub enum LodesTigerDatasetCli {
     /// Workplace-Area characteristics (WAC) LODES data downloader
     Wac(LodesTigerWacApi),
-    Od,
-    Rac,
 }
 
 #[derive(Args)]
@@ -30,6 +42,13 @@ pub struct LodesTigerWacApi {
     /// comma-delimited list of geoids representing the geographic area for download
     #[arg(short, long)]
     pub geoids: Option<String>,
+    /// without --geoids, download only the 48 contiguous states and DC, leaving out
+    /// Alaska and Hawaii
+    #[arg(long, default_value_t = false, conflicts_with = "geoids")]
+    contiguous: bool,
+    /// without --geoids, leave out the District of Columbia
+    #[arg(long, default_value_t = false, conflicts_with = "geoids")]
+    exclude_dc: bool,
     /// produce output rows at the given geospatial resolution. original resolution if not specified.
     #[arg(short, long)]
     pub output_resolution: Option<GeoidType>,
@@ -48,31 +67,76 @@ pub struct LodesTigerWacApi {
     /// WAC job type defined in LODES schema documentation
     #[arg(long, default_value = "jt00")]
     jobtype: LodesJobType,
+    /// file format of the output dataset
+    #[arg(long, default_value = "csv")]
+    format: OutputFormat,
+    /// encoding of the geometry column in CSV output
+    #[arg(long, default_value = "wkt")]
+    geometry_encoding: GeometryEncoding,
+    /// write one row per geoid with a column per variable instead of one row per value
+    #[arg(long, default_value_t = false)]
+    wide: bool,
+    /// add segment_description and segment_naics columns describing each WAC segment.
+    /// not supported with --wide.
+    #[arg(long, default_value_t = false, conflicts_with = "wide")]
+    with_descriptions: bool,
+    /// read geoids from this column of the TIGER/Lines files, such as GEOID20, instead of
+    /// the first of GEOID, GEOID20 and GEOID10 found
+    #[arg(long)]
+    geoid_column: Option<String>,
+    /// geometry written for each geography: the full boundary, or a single point inside it
+    #[arg(long, default_value = "polygon")]
+    geometry: GeometryOutput,
+    /// TIGER/Lines vintage of the geometries, instead of the vintage of the LODES edition
+    #[arg(long)]
+    tiger_year: Option<u64>,
+    /// also write a <output>.meta.json file describing the dataset and run that produced
+    /// each output file
+    #[arg(long, default_value_t = false)]
+    metadata: bool,
+    /// maximum number of LODES files, and of TIGER/Lines archives, to download at a time
+    #[arg(long, default_value_t = lodes_api::DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+    /// print an estimate of the files and bytes to download, then exit without downloading
+    #[arg(long, default_value_t = false)]
+    estimate: bool,
+    /// run one state at a time, writing a separate output file per state.
+    #[arg(long, default_value_t = false)]
+    by_state: bool,
+    /// with --by-state, skip states whose output file already exists and is non-empty,
+    /// so that an interrupted run can be resumed.
+    #[arg(long, default_value_t = false, requires = "by_state")]
+    skip_existing: bool,
+    /// write the values only, without downloading TIGER/Lines geometries or writing a
+    /// geometry column. not supported with --by-state.
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["by_state", "geoid_column", "tiger_year"]
+    )]
+    no_geometry: bool,
+    /// with --wide, add an area_sqkm column with the area of each geography, measured on
+    /// the ellipsoid or in an equal-area projection, for computing densities
+    #[arg(long, requires = "wide", conflicts_with = "no_geometry")]
+    area: Option<AreaProjection>,
 }
 
 impl LodesTigerCli {
     pub async fn run(&self) {
         match &self.dataset {
             LodesTigerDatasetCli::Wac(wac) => run_wac(wac).await,
-            LodesTigerDatasetCli::Od => todo!(),
-            LodesTigerDatasetCli::Rac => todo!(),
         }
     }
 }
 
 async fn run_wac(args: &LodesTigerWacApi) {
     let geoids = match &args.geoids {
-        Some(s) => s
-            .split(',')
-            .map(|g| Geoid::try_from(g).unwrap())
-            .collect_vec(),
-        None => StateCode::ALL
-            .iter()
-            .map(|sc| {
-                let fips = sc.to_fips_string();
-                Geoid::try_from(fips.as_str()).unwrap()
-            })
-            .collect_vec(),
+        Some(s) => parse_geoids_arg("--geoids", s),
+        None => Geoid::states(&StateSelection {
+            exclude_alaska_hawaii: args.contiguous,
+            exclude_dc: args.exclude_dc,
+            ..Default::default()
+        }),
     };
     let dataset = LodesDataset::WAC {
         edition: args.edition,
@@ -87,33 +151,198 @@ async fn run_wac(args: &LodesTigerWacApi) {
         .map(WacSegment::try_from)
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
+    let options = JoinOptions {
+        geoid_column: args.geoid_column.clone(),
+        transform: args.geometry.transform(),
+        tiger_year: args.tiger_year,
+        concurrency: args.concurrency,
+        tiger_base_url: tiger_base_url_from_env(),
+        ..Default::default()
+    };
+
+    if args.estimate {
+        let estimate = lodes_tiger::estimate(&geoids, &wildcard, &dataset, &options)
+            .await
+            .unwrap();
+        println!("LODES: {}", estimate.lodes);
+        println!("TIGER/Lines: {}", estimate.tiger);
+        for error in estimate
+            .lodes
+            .errors
+            .iter()
+            .chain(estimate.tiger.errors.iter())
+        {
+            println!("{error}");
+        }
+        println!("total: {}", estimate.lodes.merge(estimate.tiger));
+        return;
+    }
 
-    let res = lodes_tiger::run(&geoids, &wildcard, &wac_segments, &dataset)
+    let filename =
+        Path::new(&dataset.output_filename(&wildcard)).with_extension(args.format.extension());
+    let write_metadata = |path: &Path, geoids: &[Geoid], summary: RunSummary| {
+        if !args.metadata {
+            return Ok(());
+        }
+        let sidecar = RunMetadata::lodes(
+            &dataset,
+            &wac_segments,
+            geoids,
+            wildcard,
+            (!args.no_geometry).then_some(args.geometry),
+            &options,
+            summary,
+        )
+        .write_sidecar(path)?;
+        println!("wrote {}", sidecar.display());
+        Ok::<(), String>(())
+    };
+
+    if args.by_state {
+        // write one file per state, optionally skipping states written by a previous run
+        let state_filename = |state: &Geoid| {
+            let stem = filename.file_stem().unwrap_or_default().to_string_lossy();
+            filename.with_file_name(format!(
+                "{stem}_{}.{}",
+                state.geoid_string(),
+                args.format.extension()
+            ))
+        };
+        let remaining = group_geoids_by_state(&geoids)
+            .unwrap()
+            .into_iter()
+            .filter(|(state, _)| {
+                let skip = args.skip_existing && is_non_empty_file(&state_filename(state));
+                if skip {
+                    println!("skipping state {state}, output already exists");
+                }
+                !skip
+            })
+            .flat_map(|(_, state_geoids)| state_geoids)
+            .collect_vec();
+        if remaining.is_empty() {
+            println!("all states already written");
+            return;
+        }
+        lodes_tiger::run_by_state(
+            &remaining,
+            &wildcard,
+            &wac_segments,
+            &dataset,
+            &options,
+            |state, res| {
+                // write to a partial file first so that a crash mid-write does not
+                // leave a truncated file that --skip-existing would accept
+                let final_filename = state_filename(state);
+                let partial_filename = final_filename.with_extension("partial");
+                let summary = res.summary.clone();
+                write_response(
+                    res,
+                    &partial_filename,
+                    args.format,
+                    args.geometry_encoding,
+                    args.wide,
+                    args.with_descriptions,
+                    args.area,
+                )?;
+                std::fs::rename(&partial_filename, &final_filename).map_err(|e| {
+                    format!(
+                        "failure moving {} to {}: {e}",
+                        partial_filename.display(),
+                        final_filename.display()
+                    )
+                })?;
+                let state_geoids = remaining
+                    .iter()
+                    .filter(|g| *g == state || state.is_parent_of(g))
+                    .cloned()
+                    .collect_vec();
+                write_metadata(&final_filename, &state_geoids, summary)
+            },
+        )
         .await
         .unwrap();
-    println!(
-        "found {} responses, {} errors",
-        res.join_dataset.len(),
-        res.tiger_errors.len() + res.join_errors.len(),
-    );
+    } else if args.no_geometry {
+        let res = lodes_tiger::run_without_geometry(
+            &geoids,
+            &wildcard,
+            &wac_segments,
+            &dataset,
+            &options,
+        )
+        .await
+        .unwrap();
+        println!("{}", res.summary);
+        report_errors("SKIPPED STATES", res.skipped_states);
+        report_errors("LODES ERRORS", res.lodes_errors);
+        let output_sink = sink::create(args.format, &filename, args.geometry_encoding).unwrap();
+        if args.wide {
+            let table = WideTable::from_lodes_values(res.dataset);
+            sink::write_wide_table(output_sink, &table, false)
+        } else {
+            sink::write_lodes_values(output_sink, res.dataset, args.with_descriptions)
+        }
+        .unwrap();
+        write_metadata(&filename, &geoids, res.summary).unwrap();
+    } else {
+        let res = lodes_tiger::run(&geoids, &wildcard, &wac_segments, &dataset, &options)
+            .await
+            .unwrap();
+        let summary = res.summary.clone();
+        write_response(
+            res,
+            &filename,
+            args.format,
+            args.geometry_encoding,
+            args.wide,
+            args.with_descriptions,
+            args.area,
+        )
+        .unwrap();
+        write_metadata(&filename, &geoids, summary).unwrap();
+    }
+}
 
-    if !res.tiger_errors.is_empty() {
-        println!("TIGER ERRORS");
-        for row in res.tiger_errors.into_iter() {
-            println!("{row}")
+/// reports the errors of a response and writes its rows to the output file.
+pub(crate) fn write_response(
+    res: LodesTigerResponse,
+    filename: &Path,
+    format: OutputFormat,
+    geometry_encoding: GeometryEncoding,
+    wide: bool,
+    with_descriptions: bool,
+    area: Option<AreaProjection>,
+) -> Result<(), String> {
+    println!("{}", res.summary);
+    report_errors("SKIPPED STATES", res.skipped_states);
+    report_errors("LODES ERRORS", res.lodes_errors);
+    report_errors("TIGER ERRORS", res.tiger_errors);
+    report_errors("DATASET JOIN ERRORS", res.join_errors);
+    let output_sink = sink::create(format, filename, geometry_encoding)?;
+    if wide {
+        let mut table = res.join_dataset.into_iter().collect::<WideTable>();
+        if let Some(projection) = area {
+            table.append_area(projection)?;
         }
+        sink::write_wide_table(output_sink, &table, false)
+    } else {
+        sink::write_lodes_rows(output_sink, res.join_dataset, with_descriptions)
     }
-    if !res.join_errors.is_empty() {
-        println!("DATASET JOIN ERRORS");
-        for row in res.join_errors.into_iter() {
+}
+
+/// prints a heading followed by each error, if there are any.
+pub(crate) fn report_errors(heading: &str, errors: Vec<String>) {
+    if !errors.is_empty() {
+        println!("{heading}");
+        for row in errors.into_iter() {
             println!("{row}")
         }
     }
-    let mut writer = csv::WriterBuilder::new()
-        .from_path(dataset.output_filename(&wildcard))
-        .unwrap();
-    for row in res.join_dataset {
-        let out_row = LodesTigerOutputRow::from(row);
-        writer.serialize(out_row).unwrap();
-    }
+}
+
+/// true if the path is a file with at least one byte of content.
+fn is_non_empty_file(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .map(|m| m.is_file() && m.len() > 0)
+        .unwrap_or(false)
 }
@@ -0,0 +1,52 @@
+use clap::{ArgAction, Args};
+use log::LevelFilter;
+
+/// command line flags controlling log output. an explicit `--log-level` takes
+/// precedence over `-v`, which takes precedence over the `RUST_LOG` environment
+/// variable. without any of these, warnings and errors are logged.
+#[derive(Args, Debug, Clone)]
+pub struct LoggingArgs {
+    /// log level, one of off, error, warn, info, debug or trace
+    #[arg(long, global = true)]
+    pub log_level: Option<LevelFilter>,
+    /// increase log verbosity. -v logs each download with its timing, -vv adds debug detail
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+}
+
+impl LoggingArgs {
+    /// the log level requested by these flags, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use bamcensus::app::logging_args::LoggingArgs;
+    /// use log::LevelFilter;
+    ///
+    /// let args = LoggingArgs { log_level: None, verbose: 2 };
+    /// assert_eq!(args.level(), Some(LevelFilter::Debug));
+    /// let args = LoggingArgs { log_level: Some(LevelFilter::Error), verbose: 2 };
+    /// assert_eq!(args.level(), Some(LevelFilter::Error));
+    /// let args = LoggingArgs { log_level: None, verbose: 0 };
+    /// assert_eq!(args.level(), None);
+    /// ```
+    pub fn level(&self) -> Option<LevelFilter> {
+        match (self.log_level, self.verbose) {
+            (Some(level), _) => Some(level),
+            (None, 0) => None,
+            (None, 1) => Some(LevelFilter::Info),
+            (None, 2) => Some(LevelFilter::Debug),
+            (None, _) => Some(LevelFilter::Trace),
+        }
+    }
+
+    /// initializes the global logger. should be called once at program start.
+    pub fn init(&self) {
+        let env = env_logger::Env::default().default_filter_or("warn");
+        let mut builder = env_logger::Builder::from_env(env);
+        if let Some(level) = self.level() {
+            builder.filter_level(level);
+        }
+        builder.init();
+    }
+}
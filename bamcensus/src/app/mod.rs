@@ -3,9 +3,15 @@
 //! An API for programmatically executing bamcensus from Rust code. see
 //! the following functions for an entry point:
 //!   - American Community Survey (ACS) with Tiger/LINES geometries: [`crate::app::acs_tiger::run`]
+//!     - for the geographies intersecting a study area polygon: [`crate::app::acs_tiger::run_aoi`]
 //!   - Longitudinal Employer-Household Dynamics (LEHD):
 //!     - Origin-Destination Employment Statistics (LODES) [`crate::app::lodes_tiger::run`]
+//!     - resumable bulk download with one file per state: [`crate::app::lodes_bulk::run`]
 
 pub mod acs_tiger;
+pub mod geoids_args;
+pub mod lodes_bulk;
+pub mod lodes_bulk_args;
 pub mod lodes_tiger;
 pub mod lodes_tiger_args;
+pub mod logging_args;
@@ -1,10 +1,25 @@
 use bamcensus::app::acs_tiger;
-use bamcensus::model::acs_tiger_output_row::AcsTigerOutputRow;
-use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+use bamcensus::app::geoids_args::parse_geoids_arg;
+use bamcensus::app::logging_args::LoggingArgs;
+use bamcensus::model::area_projection::AreaProjection;
+use bamcensus::model::geometry_encoding::GeometryEncoding;
+use bamcensus::model::geometry_output::GeometryOutput;
+use bamcensus::model::join_options::JoinOptions;
+use bamcensus::model::output_format::OutputFormat;
+use bamcensus::model::regions::{RegionMethod, Regions};
+use bamcensus::model::run_metadata::RunMetadata;
+use bamcensus::model::run_summary::RunSummary;
+use bamcensus::model::wide_table::WideTable;
+use bamcensus::ops::{regions, sink};
+use bamcensus_acs::model::{
+    api_token_from_env, parse_extra_param, AcsApiQueryParams, AcsGeoidQuery, AcsType,
+};
 use bamcensus_core::model::identifier::Geoid;
 use bamcensus_core::model::identifier::GeoidType;
+use bamcensus_tiger::model::tiger_base_url_from_env;
 use clap::Parser;
 use itertools::Itertools;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -24,25 +39,106 @@ pub struct AcsTigerAppCli {
     /// one or five year estimates, see ACS documentation for more information
     #[arg(short, long)]
     pub acs_type: AcsType,
-    /// if provided, token for ACS API (to avoid public rate limits)
+    /// also retrieve the NAME label of each geography, if not already in --acs-query
+    #[arg(long, default_value_t = false)]
+    pub include_name: bool,
+    /// additional ACS query parameter as NAME=VALUE, such as a value range predicate
+    /// B01001_001E=1000: that keeps geographies with a population of at least 1000.
+    /// may be repeated.
+    #[arg(long = "acs-param", value_parser = parse_extra_param)]
+    pub acs_params: Vec<(String, String)>,
+    /// if provided, token for ACS API (to avoid public rate limits). if not provided,
+    /// the CENSUS_API_KEY environment variable is used.
     #[arg(short, long)]
     pub acs_token: Option<String>,
-    /// path and file to write result. if not provided, will use a concatenation of the CLI arguments
+    /// path and file to write result, or "-" to write to stdout. if not provided, will use a
+    /// concatenation of the CLI arguments
     #[arg(short, long)]
     pub output_file: Option<String>,
+    /// file format of the output dataset
+    #[arg(long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// encoding of the geometry column in CSV output
+    #[arg(long, default_value = "wkt")]
+    pub geometry_encoding: GeometryEncoding,
+    /// write one row per geoid with a column per variable instead of one row per value
+    #[arg(long, default_value_t = false)]
+    pub wide: bool,
+    /// read geoids from this column of the TIGER/Lines files, such as GEOID20, instead of
+    /// the first of GEOID, GEOID20 and GEOID10 found
+    #[arg(long)]
+    pub geoid_column: Option<String>,
+    /// geometry written for each geography: the full boundary, or a single point inside it
+    #[arg(long, default_value = "polygon")]
+    pub geometry: GeometryOutput,
+    /// TIGER/Lines vintage of the geometries, instead of the ACS year, such as 2020 to
+    /// join several ACS years to the same boundaries
+    #[arg(long)]
+    pub tiger_year: Option<u64>,
+    /// keep the rows of geoids that no TIGER/Lines geometry matched, with an empty
+    /// geometry, instead of reporting them as join errors and dropping them
+    #[arg(long, default_value_t = false, conflicts_with = "no_geometry")]
+    pub keep_unmatched: bool,
+    /// also write a <output>.meta.json file describing the query and run that produced
+    /// the output
+    #[arg(long, default_value_t = false)]
+    pub metadata: bool,
+    /// with a county-subdivision output resolution, skip geoids in states where county
+    /// subdivisions are statistical only, keeping the 20 states whose minor civil
+    /// divisions are functioning governments
+    #[arg(long, default_value_t = false)]
+    pub functioning_mcds_only: bool,
+    /// write the values only, without downloading TIGER/Lines geometries or writing a
+    /// geometry column
+    #[arg(long, default_value_t = false, conflicts_with_all = ["geoid_column", "tiger_year"])]
+    pub no_geometry: bool,
+    /// with --wide, add an area_sqkm column with the area of each geography, measured on
+    /// the ellipsoid or in an equal-area projection, for computing densities
+    #[arg(long, requires = "wide", conflicts_with = "no_geometry")]
+    pub area: Option<AreaProjection>,
+    /// with --wide, add region and region_split columns tagging each geography with
+    /// the region it falls in, from a GeoJSON FeatureCollection of labeled polygons
+    #[arg(long, requires = "wide", conflicts_with = "no_geometry")]
+    pub regions: Option<PathBuf>,
+    /// property of the --regions features holding their labels
+    #[arg(long, default_value = "name", requires = "regions")]
+    pub region_property: String,
+    /// how geographies are assigned to --regions: by the region containing a point
+    /// inside them, or by the region covering the largest part of them
+    #[arg(long, default_value = "centroid", requires = "regions")]
+    pub region_method: RegionMethod,
+    #[command(flatten)]
+    pub logging: LoggingArgs,
 }
 
 #[tokio::main]
 async fn main() {
     let args = AcsTigerAppCli::parse();
+    args.logging.init();
     let acs_get_query = args.acs_query.split(',').map(String::from).collect_vec();
-    let geoids = args
-        .geoids
-        .split(",")
-        .map(Geoid::try_from)
-        .collect::<Result<Vec<Geoid>, _>>()
-        .unwrap();
+    let geoids = parse_geoids_arg("--geoids", &args.geoids);
+    let geoids = if args.functioning_mcds_only {
+        let (keep, skip) = Geoid::filter_functioning_mcd_states(geoids, args.output_resolution)
+            .map_err(|e| format!("--functioning-mcds-only: {e}"))
+            .unwrap();
+        if !skip.is_empty() {
+            eprintln!(
+                "skipping {} geoid(s) in states without functioning minor civil divisions: {}",
+                skip.len(),
+                skip.iter().join(",")
+            );
+        }
+        keep
+    } else {
+        geoids
+    };
+    for geoid in geoids.iter() {
+        if let Err(e) = geoid.exists_in_vintage(args.year) {
+            log::warn!("{e}");
+        }
+    }
     // let geoid = Geoid::try_from(args.geoid.as_str()).unwrap();
+    let acs_token = args.acs_token.clone().or_else(api_token_from_env);
     let queries = geoids
         .into_iter()
         .map(|geoid| {
@@ -53,8 +149,10 @@ async fn main() {
                 args.acs_type,
                 acs_get_query.clone(),
                 query,
-                args.acs_token.clone(),
-            );
+                acs_token.clone(),
+            )
+            .with_include_name(args.include_name)
+            .with_extra_params(args.acs_params.clone());
             Ok(query_params)
         })
         .collect::<Result<Vec<_>, String>>()
@@ -64,34 +162,102 @@ async fn main() {
         Some(res) => res.to_string(),
         None => String::new(),
     };
-    let filename = match args.output_file {
-        None => format!("{}-{}-{}.csv", args.year, args.acs_type, res_msg),
+    let filename = match &args.output_file {
+        None => format!(
+            "{}-{}-{}.{}",
+            args.year,
+            args.acs_type,
+            res_msg,
+            args.format.extension()
+        ),
         Some(f) => f.clone(),
     };
-    let res = acs_tiger::run_batch(&queries).await.unwrap();
-    let total_errors = res.tiger_errors.len() + res.join_errors.len();
-    println!(
-        "found {} responses, {} errors",
-        res.join_dataset.len(),
-        total_errors
-    );
+    let path = Path::new(&filename);
+    let options = JoinOptions {
+        geoid_column: args.geoid_column.clone(),
+        transform: args.geometry.transform(),
+        tiger_year: args.tiger_year,
+        keep_unmatched: args.keep_unmatched,
+        tiger_base_url: tiger_base_url_from_env(),
+        ..Default::default()
+    };
+    let summary = if args.no_geometry {
+        let res = acs_tiger::run_without_geometry(&queries).await.unwrap();
+        eprintln!("{}", res.summary);
+        let output_sink = sink::create(args.format, path, args.geometry_encoding).unwrap();
+        if args.wide {
+            let table = WideTable::from_acs_values(res.dataset);
+            sink::write_wide_table(output_sink, &table, false)
+        } else {
+            sink::write_acs_values(output_sink, res.dataset)
+        }
+        .unwrap();
+        res.summary
+    } else {
+        run_with_geometry(&args, &queries, &options, path).await
+    };
+
+    if args.metadata {
+        if filename == "-" {
+            eprintln!("warning: --metadata is ignored when writing to stdout");
+        } else {
+            let metadata = RunMetadata::acs(
+                &queries,
+                (!args.no_geometry).then_some(args.geometry),
+                &options,
+                summary,
+            )
+            .unwrap();
+            let sidecar = metadata.write_sidecar(path).unwrap();
+            eprintln!("wrote {}", sidecar.display());
+        }
+    }
+}
+
+/// runs the queries joined to TIGER/Lines geometries, reporting errors and writing
+/// the rows to the output file.
+async fn run_with_geometry(
+    args: &AcsTigerAppCli,
+    queries: &[AcsApiQueryParams],
+    options: &JoinOptions,
+    path: &Path,
+) -> RunSummary {
+    // regions are read before downloading so that a bad file fails fast
+    let regions = args.regions.as_ref().map(|regions_path| {
+        let text = std::fs::read_to_string(regions_path).unwrap();
+        Regions::from_geojson(&text, &args.region_property).unwrap()
+    });
+    let res = acs_tiger::run_batch(queries, options).await.unwrap();
+    // status is reported on stderr so that stdout can carry the output rows
+    eprintln!("{}", res.summary);
     if !res.tiger_errors.is_empty() {
-        println!("TIGER ERRORS");
+        eprintln!("TIGER ERRORS");
         for row in res.tiger_errors.into_iter() {
-            println!("{row}")
+            eprintln!("{row}")
         }
     }
 
     if !res.join_errors.is_empty() {
-        println!("JOIN ERRORS");
+        eprintln!("JOIN ERRORS");
         for row in res.join_errors.into_iter() {
-            println!("{row}")
+            eprintln!("{row}")
         }
     }
+    eprintln!("{}", res.join_summary);
 
-    let mut writer = csv::WriterBuilder::new().from_path(filename).unwrap();
-    for row in res.join_dataset {
-        let out_row = AcsTigerOutputRow::from(row);
-        writer.serialize(out_row).unwrap();
+    let output_sink = sink::create(args.format, path, args.geometry_encoding).unwrap();
+    if args.wide {
+        let mut table = res.join_dataset.into_iter().collect::<WideTable>();
+        if let Some(projection) = args.area {
+            table.append_area(projection).unwrap();
+        }
+        if let Some(regions) = &regions {
+            regions::append_regions(&mut table, regions, args.region_method).unwrap();
+        }
+        sink::write_wide_table(output_sink, &table, args.keep_unmatched)
+    } else {
+        sink::write_acs_rows(output_sink, res.join_dataset, args.keep_unmatched)
     }
+    .unwrap();
+    res.summary
 }
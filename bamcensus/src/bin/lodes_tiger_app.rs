@@ -1,9 +1,19 @@
 use bamcensus::app::lodes_tiger_args::LodesTigerCli;
+use bamcensus::app::logging_args::LoggingArgs;
 use clap::Parser;
 
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+struct LodesTigerAppCli {
+    #[command(flatten)]
+    lodes: LodesTigerCli,
+    #[command(flatten)]
+    logging: LoggingArgs,
+}
+
 #[tokio::main]
 async fn main() {
-    env_logger::init();
-    let cli = LodesTigerCli::parse();
-    cli.run().await
+    let cli = LodesTigerAppCli::parse();
+    cli.logging.init();
+    cli.lodes.run().await
 }
@@ -1,14 +1,31 @@
 //! Command line tool for running BAMCENSUS Apps.
 use bamcensus::app::acs_tiger;
+use bamcensus::app::lodes_bulk_args::LodesBulkCli;
 use bamcensus::app::lodes_tiger_args::LodesTigerCli;
-use bamcensus::model::acs_tiger_output_row::AcsTigerOutputRow;
-use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+use bamcensus::app::logging_args::LoggingArgs;
+use bamcensus::model::area_projection::AreaProjection;
+use bamcensus::model::geometry_encoding::GeometryEncoding;
+use bamcensus::model::geometry_output::GeometryOutput;
+use bamcensus::model::join_options::JoinOptions;
+use bamcensus::model::output_format::OutputFormat;
+use bamcensus::model::reference_table::{self, ReferenceRow, StateReference, TigerReference};
+use bamcensus::model::run_metadata::RunMetadata;
+use bamcensus::model::run_summary::RunSummary;
+use bamcensus::model::wide_table::WideTable;
+use bamcensus::ops::sink;
+use bamcensus_acs::api::acs_variables_api;
+use bamcensus_acs::model::{
+    api_token_from_env, parse_extra_param, AcsApiQueryParams, AcsGeoidQuery, AcsType,
+};
 use bamcensus_core::model::identifier::Geoid;
 use bamcensus_core::model::identifier::GeoidType;
-use clap::command;
+use bamcensus_core::ops::http;
+use bamcensus_tiger::model::tiger_base_url_from_env;
+use clap::error::ErrorKind;
 use clap::Parser;
 use clap::Subcommand;
 use itertools::Itertools;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -16,15 +33,22 @@ use itertools::Itertools;
 pub struct BamCensusCli {
     #[command(subcommand)]
     pub command: BamCensusApp,
+    #[command(flatten)]
+    pub logging: LoggingArgs,
 }
 
 #[derive(Subcommand)]
 pub enum BamCensusApp {
     /// American Community Survey (ACS) Download Tools
     AcsApp(AcsAppCli),
+    /// Report which ACS dataset years contain a variable
+    AcsVariables(AcsVariablesCli),
     /// Longitudinal Employer-Household Dynamics (LEHD) Download Tools
     #[command(subcommand)]
     LehdApp(LehdAppCli),
+    /// Export the reference tables built into bamcensus
+    #[command(subcommand)]
+    Reference(ReferenceCli),
 }
 
 #[derive(Parser, Debug)]
@@ -45,28 +69,149 @@ pub struct AcsAppCli {
     /// one or five-year ACS dataset
     #[arg(short, long)]
     pub acs_type: AcsType,
-    /// optional API token, may be required depending on server limits.
+    /// also retrieve the NAME label of each geography, if not already in --acs-query
+    #[arg(long, default_value_t = false)]
+    pub include_name: bool,
+    /// additional ACS query parameter as NAME=VALUE, such as a value range predicate
+    /// B01001_001E=1000: that keeps geographies with a population of at least 1000.
+    /// may be repeated.
+    #[arg(long = "acs-param", value_parser = parse_extra_param)]
+    pub acs_params: Vec<(String, String)>,
+    /// optional API token, may be required depending on server limits. if not provided,
+    /// the CENSUS_API_KEY environment variable is used.
     #[arg(short, long)]
     pub acs_token: Option<String>,
+    /// file format of the output dataset
+    #[arg(long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// encoding of the geometry column in CSV output
+    #[arg(long, default_value = "wkt")]
+    pub geometry_encoding: GeometryEncoding,
+    /// write one row per geoid with a column per variable instead of one row per value
+    #[arg(long, default_value_t = false)]
+    pub wide: bool,
+    /// read geoids from this column of the TIGER/Lines files, such as GEOID20, instead of
+    /// the first of GEOID, GEOID20 and GEOID10 found
+    #[arg(long)]
+    pub geoid_column: Option<String>,
+    /// geometry written for each geography: the full boundary, or a single point inside it
+    #[arg(long, default_value = "polygon")]
+    pub geometry: GeometryOutput,
+    /// TIGER/Lines vintage of the geometries, instead of the ACS year, such as 2020 to
+    /// join several ACS years to the same boundaries
+    #[arg(long)]
+    pub tiger_year: Option<u64>,
+    /// keep the rows of geoids that no TIGER/Lines geometry matched, with an empty
+    /// geometry, instead of reporting them as join errors and dropping them
+    #[arg(long, default_value_t = false, conflicts_with = "no_geometry")]
+    pub keep_unmatched: bool,
+    /// also write a <output>.meta.json file describing the query and run that produced
+    /// the output
+    #[arg(long, default_value_t = false)]
+    pub metadata: bool,
+    /// write the values only, without downloading TIGER/Lines geometries or writing a
+    /// geometry column
+    #[arg(long, default_value_t = false, conflicts_with_all = ["geoid_column", "tiger_year"])]
+    pub no_geometry: bool,
+    /// with --wide, add an area_sqkm column with the area of each geography, measured on
+    /// the ellipsoid or in an equal-area projection, for computing densities
+    #[arg(long, requires = "wide", conflicts_with = "no_geometry")]
+    pub area: Option<AreaProjection>,
+}
+
+#[derive(Parser, Debug)]
+pub struct AcsVariablesCli {
+    /// ACS variable code, such as B19013_001E
+    #[arg(long)]
+    pub variable: String,
+    /// first dataset year to check
+    #[arg(long)]
+    pub start_year: u64,
+    /// last dataset year to check, inclusive
+    #[arg(long)]
+    pub end_year: u64,
+    /// one or five-year ACS dataset
+    #[arg(short, long)]
+    pub acs_type: AcsType,
 }
 
 #[derive(Subcommand)]
 pub enum LehdAppCli {
     Lodes(LodesTigerCli),
+    /// Resumable download of LODES WAC + TIGER/Lines data with one file per state
+    LodesBulk(LodesBulkCli),
+}
+
+#[derive(Subcommand)]
+pub enum ReferenceCli {
+    /// FIPS code, abbreviation and name of each state and the District of Columbia
+    States(ReferenceArgs),
+    /// TIGER/Lines file of each geography in each supported year
+    Tiger(ReferenceArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ReferenceArgs {
+    /// file format of the table
+    #[arg(long, default_value = "csv")]
+    pub format: OutputFormat,
+    /// file to write the table to, instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() {
     let args = BamCensusCli::parse();
+    args.logging.init();
     match args.command {
         BamCensusApp::AcsApp(acs_args) => acs(&acs_args).await,
+        BamCensusApp::AcsVariables(variables_args) => acs_variables(&variables_args).await,
         BamCensusApp::LehdApp(LehdAppCli::Lodes(lodes_args)) => lodes_args.run().await,
+        BamCensusApp::LehdApp(LehdAppCli::LodesBulk(bulk_args)) => bulk_args.run().await,
+        BamCensusApp::Reference(ReferenceCli::States(reference_args)) => {
+            reference(&StateReference::all(), &reference_args)
+        }
+        BamCensusApp::Reference(ReferenceCli::Tiger(reference_args)) => {
+            reference(&TigerReference::all(), &reference_args)
+        }
+    }
+}
+
+fn reference<T: ReferenceRow>(rows: &[T], args: &ReferenceArgs) {
+    let path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(sink::STDOUT_PATH));
+    let sink = sink::create(args.format, &path, GeometryEncoding::default()).unwrap();
+    reference_table::write_table(rows, sink).unwrap();
+}
+
+async fn acs_variables(args: &AcsVariablesCli) {
+    let client = http::client_builder().build().unwrap();
+    let years = (args.start_year..=args.end_year).collect_vec();
+    let report =
+        acs_variables_api::variable_report(&client, None, args.acs_type, &args.variable, &years)
+            .await
+            .unwrap();
+    print!("{report}");
+    if !report.is_consistent() {
+        eprintln!(
+            "warning: {} is missing or relabeled in some years, check the report above",
+            args.variable
+        );
     }
 }
 
 async fn acs(args: &AcsAppCli) {
     let acs_get_query = args.acs_query.split(',').map(String::from).collect_vec();
-    let geoid = Geoid::try_from(args.geoid.as_str()).unwrap();
+    let geoid = Geoid::try_from(args.geoid.trim()).unwrap_or_else(|e| {
+        clap::Error::raw(
+            ErrorKind::ValueValidation,
+            format!("invalid --geoid: {e}\n"),
+        )
+        .exit()
+    });
     let query: AcsGeoidQuery = AcsGeoidQuery::new(Some(geoid), args.aggregation).unwrap();
     let query_params = AcsApiQueryParams::new(
         None,
@@ -74,17 +219,62 @@ async fn acs(args: &AcsAppCli) {
         args.acs_type,
         acs_get_query,
         query,
-        args.acs_token.clone(),
-    );
+        args.acs_token.clone().or_else(api_token_from_env),
+    )
+    .with_include_name(args.include_name)
+    .with_extra_params(args.acs_params.clone());
+
+    let filename =
+        Path::new(&query_params.output_filename()).with_extension(args.format.extension());
+    let options = JoinOptions {
+        geoid_column: args.geoid_column.clone(),
+        transform: args.geometry.transform(),
+        tiger_year: args.tiger_year,
+        keep_unmatched: args.keep_unmatched,
+        tiger_base_url: tiger_base_url_from_env(),
+        ..Default::default()
+    };
+    let summary = if args.no_geometry {
+        let res = acs_tiger::run_without_geometry(std::slice::from_ref(&query_params))
+            .await
+            .unwrap();
+        println!("{}", res.summary);
+        let output_sink = sink::create(args.format, &filename, args.geometry_encoding).unwrap();
+        if args.wide {
+            let table = WideTable::from_acs_values(res.dataset);
+            sink::write_wide_table(output_sink, &table, false)
+        } else {
+            sink::write_acs_values(output_sink, res.dataset)
+        }
+        .unwrap();
+        res.summary
+    } else {
+        acs_with_geometry(args, &query_params, &options, &filename).await
+    };
+
+    if args.metadata {
+        let metadata = RunMetadata::acs(
+            std::slice::from_ref(&query_params),
+            (!args.no_geometry).then_some(args.geometry),
+            &options,
+            summary,
+        )
+        .unwrap();
+        let sidecar = metadata.write_sidecar(&filename).unwrap();
+        println!("wrote {}", sidecar.display());
+    }
+}
 
-    let filename = &query_params.output_filename();
-    let res = acs_tiger::run(&query_params).await.unwrap();
-    println!(
-        "found {} responses, {}/{} errors",
-        res.join_dataset.len(),
-        res.tiger_errors.len(),
-        res.join_errors.len(),
-    );
+/// runs the query joined to TIGER/Lines geometries, reporting errors and writing the
+/// rows to the output file.
+async fn acs_with_geometry(
+    args: &AcsAppCli,
+    query_params: &AcsApiQueryParams,
+    options: &JoinOptions,
+    filename: &Path,
+) -> RunSummary {
+    let res = acs_tiger::run(query_params, options).await.unwrap();
+    println!("{}", res.summary);
     println!("TIGER ERRORS");
     for row in res.tiger_errors.into_iter() {
         println!("{row}")
@@ -93,10 +283,18 @@ async fn acs(args: &AcsAppCli) {
     for row in res.join_errors.into_iter() {
         println!("{row}")
     }
+    println!("{}", res.join_summary);
 
-    let mut writer = csv::WriterBuilder::new().from_path(filename).unwrap();
-    for row in res.join_dataset {
-        let out_row = AcsTigerOutputRow::from(row);
-        writer.serialize(out_row).unwrap();
+    let output_sink = sink::create(args.format, filename, args.geometry_encoding).unwrap();
+    if args.wide {
+        let mut table = res.join_dataset.into_iter().collect::<WideTable>();
+        if let Some(projection) = args.area {
+            table.append_area(projection).unwrap();
+        }
+        sink::write_wide_table(output_sink, &table, args.keep_unmatched)
+    } else {
+        sink::write_acs_rows(output_sink, res.join_dataset, args.keep_unmatched)
     }
+    .unwrap();
+    res.summary
 }
@@ -9,11 +9,13 @@ use wkt::ToWkt;
 pub struct AcsTigerRow {
     pub geoid: Geoid,
     pub acs_value: AcsValue,
-    pub geometry: Geometry,
+    /// the TIGER/Lines geometry of the geoid, or None if no geometry matched it and
+    /// unmatched rows were kept, see [`crate::app::acs_tiger::run`].
+    pub geometry: Option<Geometry>,
 }
 
 impl AcsTigerRow {
-    pub fn new(geoid: Geoid, acs_value: AcsValue, geometry: Geometry) -> AcsTigerRow {
+    pub fn new(geoid: Geoid, acs_value: AcsValue, geometry: Option<Geometry>) -> AcsTigerRow {
         AcsTigerRow {
             geoid,
             acs_value,
@@ -29,7 +31,10 @@ impl Display for AcsTigerRow {
             "{} - {} - {}",
             self.geoid.geoid_string(),
             self.acs_value,
-            self.geometry.to_wkt()
+            self.geometry
+                .as_ref()
+                .map(|g| g.to_wkt().to_string())
+                .unwrap_or_else(|| String::from("EMPTY"))
         )
     }
 }
@@ -0,0 +1,96 @@
+use geo::{BoundingRect, Geometry, Intersects, MultiPolygon, Rect};
+
+/// a study area polygon, in NAD83 degrees as are TIGER/Lines geometries, used to
+/// select the geographies that intersect it. see [`crate::app::acs_tiger::run_aoi`].
+#[derive(Clone, Debug)]
+pub struct AreaOfInterest {
+    geometry: Geometry,
+    bounds: Rect,
+}
+
+impl AreaOfInterest {
+    /// creates an area of interest from a polygon or multipolygon.
+    pub fn new(geometry: Geometry) -> Result<AreaOfInterest, String> {
+        match geometry {
+            Geometry::Polygon(_) | Geometry::MultiPolygon(_) => Ok(()),
+            _ => Err(String::from(
+                "area of interest must be a Polygon or MultiPolygon",
+            )),
+        }?;
+        let bounds = geometry
+            .bounding_rect()
+            .ok_or_else(|| String::from("area of interest is empty"))?;
+        Ok(AreaOfInterest { geometry, bounds })
+    }
+
+    /// reads an area of interest from GeoJSON text: a Polygon or MultiPolygon
+    /// geometry, a Feature with one, or a FeatureCollection, whose polygons are
+    /// combined.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::area_of_interest::AreaOfInterest;
+    /// use geo::{point, Geometry};
+    ///
+    /// let geojson = r#"{
+    ///     "type": "Feature",
+    ///     "properties": {"name": "study area"},
+    ///     "geometry": {
+    ///         "type": "Polygon",
+    ///         "coordinates": [[[-105.0, 39.7], [-104.9, 39.7], [-104.9, 39.8], [-105.0, 39.8], [-105.0, 39.7]]]
+    ///     }
+    /// }"#;
+    /// let aoi = AreaOfInterest::from_geojson(geojson).unwrap();
+    /// assert!(aoi.intersects(&Geometry::Point(point!(x: -104.95, y: 39.75))));
+    /// assert!(!aoi.intersects(&Geometry::Point(point!(x: -104.5, y: 39.75))));
+    ///
+    /// let line = r#"{"type": "LineString", "coordinates": [[-105.0, 39.7], [-104.9, 39.8]]}"#;
+    /// assert!(AreaOfInterest::from_geojson(line).is_err());
+    /// ```
+    pub fn from_geojson(text: &str) -> Result<AreaOfInterest, String> {
+        let geojson = text
+            .parse::<geojson::GeoJson>()
+            .map_err(|e| format!("failure parsing area of interest GeoJSON: {e}"))?;
+        let geometries = match geojson {
+            geojson::GeoJson::Geometry(g) => vec![Some(g)],
+            geojson::GeoJson::Feature(f) => vec![f.geometry],
+            geojson::GeoJson::FeatureCollection(fc) => {
+                fc.features.into_iter().map(|f| f.geometry).collect()
+            }
+        };
+        let mut polygons = vec![];
+        for geometry in geometries.into_iter().flatten() {
+            let geometry = Geometry::try_from(geometry)
+                .map_err(|e| format!("failure reading area of interest geometry: {e}"))?;
+            match geometry {
+                Geometry::Polygon(p) => polygons.push(p),
+                Geometry::MultiPolygon(mp) => polygons.extend(mp),
+                other => {
+                    return Err(format!(
+                        "area of interest must contain only polygons, found {other:?}"
+                    ))
+                }
+            }
+        }
+        match polygons.len() {
+            0 => Err(String::from("area of interest GeoJSON has no polygons")),
+            1 => AreaOfInterest::new(Geometry::Polygon(polygons.remove(0))),
+            _ => AreaOfInterest::new(Geometry::MultiPolygon(MultiPolygon(polygons))),
+        }
+    }
+
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// whether a geometry intersects the area of interest, including touching its
+    /// boundary. geometries outside of its bounding box are rejected without
+    /// comparing them to the polygon.
+    pub fn intersects(&self, geometry: &Geometry) -> bool {
+        match geometry.bounding_rect() {
+            Some(rect) if rect.intersects(&self.bounds) => geometry.intersects(&self.geometry),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use bamcensus_tiger::ops::area::{self, AlbersEqualArea};
+use clap::ValueEnum;
+use geo::Geometry;
+use serde::{Deserialize, Serialize};
+
+/// how the area of each geography is measured for the area_sqkm column. TIGER/Lines
+/// geometries are in NAD83 degrees, so areas are measured on the ellipsoid or in an
+/// equal-area projection rather than in degrees.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AreaProjection {
+    /// on the ellipsoid, without projecting
+    #[default]
+    Geodesic,
+    /// NAD83 / Conus Albers (EPSG:5070), for the contiguous United States
+    ConusAlbers,
+    /// NAD83 / Alaska Albers (EPSG:3338)
+    AlaskaAlbers,
+    /// Hawaii Albers Equal Area Conic (ESRI:102007)
+    HawaiiAlbers,
+}
+
+impl AreaProjection {
+    /// the area of a geometry in square kilometers.
+    pub fn area_sqkm(&self, geometry: &Geometry) -> f64 {
+        match self {
+            AreaProjection::Geodesic => area::geodesic_area_sqkm(geometry),
+            AreaProjection::ConusAlbers => AlbersEqualArea::CONUS.area_sqkm(geometry),
+            AreaProjection::AlaskaAlbers => AlbersEqualArea::ALASKA.area_sqkm(geometry),
+            AreaProjection::HawaiiAlbers => AlbersEqualArea::HAWAII.area_sqkm(geometry),
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use super::output_format::OutputFormat;
+use bamcensus_core::model::identifier::{Geoid, GeoidType, HasGeoidString};
+use bamcensus_lehd::model::{LodesDataset, WacSegment};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// progress of one state in a bulk download.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StateStatus {
+    #[default]
+    Pending,
+    Complete,
+    Failed,
+}
+
+/// the manifest entry of one state in a bulk download.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateEntry {
+    pub status: StateStatus,
+    /// number of attempts made across all runs
+    pub attempts: u32,
+    /// output file written for the state, once complete
+    pub output: Option<PathBuf>,
+    /// number of rows written to the output file
+    pub rows: usize,
+    /// error of the last failed attempt
+    pub error: Option<String>,
+}
+
+/// the settings of a bulk download that determine the contents of its output files,
+/// see [`crate::app::lodes_bulk::run`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BulkSettings {
+    pub dataset: LodesDataset,
+    pub output_resolution: Option<GeoidType>,
+    pub wac_segments: Vec<WacSegment>,
+    /// file format of the output files
+    pub format: OutputFormat,
+    /// true if the output files have one row per geoid with a column per variable
+    pub wide: bool,
+}
+
+/// the on-disk record of a bulk download of one file per state, see
+/// [`crate::app::lodes_bulk::run`]. the manifest is saved after each state finishes,
+/// so that an interrupted download can be resumed by running it again, which skips
+/// the states already completed. a download is only resumed with the settings and
+/// TIGER/Lines vintage it was started with, so that every file of the download
+/// holds the same columns.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::bulk_manifest::{BulkManifest, BulkSettings, StateStatus};
+/// use bamcensus::model::output_format::OutputFormat;
+/// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+/// use bamcensus_lehd::model::{
+///     LodesDataset, LodesEdition, LodesJobType, WacSegment, WorkplaceSegment,
+/// };
+/// use std::path::Path;
+///
+/// let settings = BulkSettings {
+///     dataset: LodesDataset::WAC {
+///         edition: LodesEdition::Lodes8,
+///         job_type: LodesJobType::JT00,
+///         segment: WorkplaceSegment::S000,
+///         year: 2020,
+///     },
+///     output_resolution: None,
+///     wac_segments: vec![WacSegment::C000],
+///     format: OutputFormat::Csv,
+///     wide: false,
+/// };
+/// let states = [Geoid::state(8).unwrap(), Geoid::state(56).unwrap()];
+/// let mut manifest = BulkManifest::new(&settings, None, &states);
+/// assert_eq!(manifest.remaining(), states.to_vec());
+///
+/// manifest.mark_complete(&states[0], 1, Path::new("Cargo.toml"), 10);
+/// manifest.mark_failed(&states[1], 4, String::from("timed out"));
+/// assert_eq!(manifest.remaining(), vec![states[1].clone()]);
+/// assert_eq!(manifest.states["56"].status, StateStatus::Failed);
+/// assert_eq!(manifest.states["56"].attempts, 4);
+/// assert!(!manifest.is_complete());
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BulkManifest {
+    /// output file name of the dataset, which identifies the download
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: BulkSettings,
+    /// TIGER/Lines vintage of the geometries, when it is not the vintage of the dataset
+    pub tiger_year: Option<u64>,
+    /// the entry of each state, keyed by state FIPS code
+    pub states: BTreeMap<String, StateEntry>,
+}
+
+impl BulkManifest {
+    /// the manifest file name within the output directory of a bulk download.
+    pub const FILENAME: &'static str = "bamcensus_manifest.json";
+
+    /// a manifest with every state pending.
+    pub fn new(settings: &BulkSettings, tiger_year: Option<u64>, states: &[Geoid]) -> BulkManifest {
+        let states = states
+            .iter()
+            .map(|state| (state.geoid_string(), StateEntry::default()))
+            .collect();
+        BulkManifest {
+            name: settings
+                .dataset
+                .output_filename(&settings.output_resolution),
+            settings: settings.clone(),
+            tiger_year,
+            states,
+        }
+    }
+
+    /// reads the manifest at `path` to resume a download, or creates a new one if
+    /// there is no file. states missing from an existing manifest are added as
+    /// pending. fails if the existing manifest was written with other settings or
+    /// another TIGER/Lines vintage, naming each one that differs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::bulk_manifest::{BulkManifest, BulkSettings};
+    /// use bamcensus::model::output_format::OutputFormat;
+    /// use bamcensus_core::model::identifier::Geoid;
+    /// use bamcensus_lehd::model::{
+    ///     LodesDataset, LodesEdition, LodesJobType, WacSegment, WorkplaceSegment,
+    /// };
+    ///
+    /// let settings = BulkSettings {
+    ///     dataset: LodesDataset::WAC {
+    ///         edition: LodesEdition::Lodes8,
+    ///         job_type: LodesJobType::JT00,
+    ///         segment: WorkplaceSegment::S000,
+    ///         year: 2020,
+    ///     },
+    ///     output_resolution: None,
+    ///     wac_segments: vec![WacSegment::C000],
+    ///     format: OutputFormat::Csv,
+    ///     wide: false,
+    /// };
+    /// let states = [Geoid::state(8).unwrap()];
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join(BulkManifest::FILENAME);
+    /// BulkManifest::new(&settings, None, &states).save(&path).unwrap();
+    ///
+    /// assert!(BulkManifest::load_or_new(&path, &settings, None, &states).is_ok());
+    /// let wide = BulkSettings { wide: true, ..settings.clone() };
+    /// let error = BulkManifest::load_or_new(&path, &wide, Some(2010), &states).unwrap_err();
+    /// assert!(error.contains("wide false instead of true"), "{error}");
+    /// assert!(error.contains("tiger_year null instead of 2010"), "{error}");
+    /// ```
+    pub fn load_or_new(
+        path: &Path,
+        settings: &BulkSettings,
+        tiger_year: Option<u64>,
+        states: &[Geoid],
+    ) -> Result<BulkManifest, String> {
+        let expected = BulkManifest::new(settings, tiger_year, states);
+        if !path.exists() {
+            return Ok(expected);
+        }
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("failure opening manifest {}: {e}", path.display()))?;
+        let mut manifest: BulkManifest = serde_json::from_reader(std::io::BufReader::new(file))
+            .map_err(|e| format!("failure reading manifest {}: {e}", path.display()))?;
+        let differences = manifest.differences(&expected)?;
+        if !differences.is_empty() {
+            return Err(format!(
+                "manifest {} was written with {}. use the same settings to resume, or another output directory",
+                path.display(),
+                differences.join(", ")
+            ));
+        }
+        for (state, entry) in expected.states {
+            manifest.states.entry(state).or_insert(entry);
+        }
+        Ok(manifest)
+    }
+
+    /// the states still to download, in FIPS order: those pending or failed, and
+    /// those complete whose output file has since been removed.
+    pub fn remaining(&self) -> Vec<Geoid> {
+        self.states
+            .iter()
+            .filter(|(_, entry)| match (&entry.status, &entry.output) {
+                (StateStatus::Complete, Some(output)) => !output.is_file(),
+                _ => true,
+            })
+            .filter_map(|(state, _)| Geoid::try_from(state.as_str()).ok())
+            .collect()
+    }
+
+    /// true when every state is complete.
+    pub fn is_complete(&self) -> bool {
+        self.states
+            .values()
+            .all(|entry| entry.status == StateStatus::Complete)
+    }
+
+    /// records `attempts` more attempts at a state, the last of which wrote `rows`
+    /// rows to `output`.
+    pub fn mark_complete(&mut self, state: &Geoid, attempts: u32, output: &Path, rows: usize) {
+        let entry = self.entry(state);
+        entry.status = StateStatus::Complete;
+        entry.attempts += attempts;
+        entry.output = Some(output.to_path_buf());
+        entry.rows = rows;
+        entry.error = None;
+    }
+
+    /// records `attempts` more attempts at a state, all of which failed.
+    pub fn mark_failed(&mut self, state: &Geoid, attempts: u32, error: String) {
+        let entry = self.entry(state);
+        entry.status = StateStatus::Failed;
+        entry.attempts += attempts;
+        entry.error = Some(error);
+    }
+
+    /// writes the manifest as pretty-printed JSON. the manifest is written to a
+    /// temporary file first and then moved into place, so an interruption never
+    /// leaves a truncated manifest.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let partial = path.with_extension("json.partial");
+        let file = std::fs::File::create(&partial)
+            .map_err(|e| format!("failure creating {}: {e}", partial.display()))?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|e| format!("failure writing {}: {e}", partial.display()))?;
+        std::fs::rename(&partial, path).map_err(|e| {
+            format!(
+                "failure moving {} to {}: {e}",
+                partial.display(),
+                path.display()
+            )
+        })
+    }
+
+    /// each field other than the states whose value differs from the one in `other`,
+    /// described as this value "instead of" the other.
+    fn differences(&self, other: &BulkManifest) -> Result<Vec<String>, String> {
+        let fields = |manifest: &BulkManifest| match serde_json::to_value(manifest) {
+            Ok(serde_json::Value::Object(mut fields)) => {
+                fields.remove("states");
+                Ok(fields)
+            }
+            Ok(other) => Err(format!("manifest serialized to {other}, not an object")),
+            Err(e) => Err(format!("failure serializing manifest: {e}")),
+        };
+        let (written, expected) = (fields(self)?, fields(other)?);
+        let differences = expected
+            .iter()
+            .filter_map(|(field, value)| match written.get(field) {
+                Some(found) if found == value => None,
+                found => Some(format!(
+                    "{field} {} instead of {value}",
+                    found.unwrap_or(&serde_json::Value::Null)
+                )),
+            })
+            .collect();
+        Ok(differences)
+    }
+
+    fn entry(&mut self, state: &Geoid) -> &mut StateEntry {
+        self.states.entry(state.geoid_string()).or_default()
+    }
+}
@@ -0,0 +1,47 @@
+use clap::ValueEnum;
+use geo::Geometry;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use wkt::ToWkt;
+
+/// text encodings for geometry columns in CSV output.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeometryEncoding {
+    /// well-known text, human-readable
+    #[default]
+    Wkt,
+    /// well-known binary written as a hex string, compact and fast to bulk-load
+    /// (for example, via a Postgres `COPY`)
+    WkbHex,
+}
+
+impl GeometryEncoding {
+    /// encodes a geometry as a string using this encoding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::geometry_encoding::GeometryEncoding;
+    /// use geo::{point, Geometry};
+    ///
+    /// let geometry = Geometry::Point(point!(x: 1.0, y: 2.0));
+    /// let wkt = GeometryEncoding::Wkt.encode(&geometry).unwrap();
+    /// assert_eq!(wkt, "POINT(1 2)");
+    /// ```
+    pub fn encode(&self, geometry: &Geometry<f64>) -> Result<String, String> {
+        match self {
+            GeometryEncoding::Wkt => Ok(geometry.to_wkt().to_string()),
+            GeometryEncoding::WkbHex => {
+                let bytes = wkb::geom_to_wkb(geometry)
+                    .map_err(|e| format!("failure encoding geometry as WKB: {e:?}"))?;
+                let mut hex = String::with_capacity(bytes.len() * 2);
+                for byte in bytes.iter() {
+                    write!(hex, "{byte:02X}")
+                        .map_err(|e| format!("failure writing WKB as hex: {e}"))?;
+                }
+                Ok(hex)
+            }
+        }
+    }
+}
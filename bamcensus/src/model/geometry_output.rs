@@ -0,0 +1,35 @@
+use bamcensus_tiger::model::GeometryTransform;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// the geometry written for each geography.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GeometryOutput {
+    /// the full TIGER/Lines boundary
+    #[default]
+    Polygon,
+    /// a single point per geography: the TIGER/Lines internal point, which falls
+    /// inside the geography, or the geometric centroid if the file has none
+    Centroid,
+}
+
+impl GeometryOutput {
+    /// the transform applied to TIGER/Lines geometries to produce this output, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::geometry_output::GeometryOutput;
+    /// use bamcensus_tiger::model::GeometryTransform;
+    ///
+    /// assert_eq!(GeometryOutput::Polygon.transform(), None);
+    /// assert_eq!(GeometryOutput::Centroid.transform(), Some(GeometryTransform::Centroid));
+    /// ```
+    pub fn transform(&self) -> Option<GeometryTransform> {
+        match self {
+            GeometryOutput::Polygon => None,
+            GeometryOutput::Centroid => Some(GeometryTransform::Centroid),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use bamcensus_lehd::api::lodes_api;
+use bamcensus_lehd::model::{DownloadOptions, RetryPolicy};
+use bamcensus_tiger::model::{GeometryTransform, RunOptions, TigerCache};
+use tokio_util::sync::CancellationToken;
+
+/// optional settings of a run that joins ACS or LODES data to TIGER/Lines geometries,
+/// such as [`crate::app::acs_tiger::run`] or [`crate::app::lodes_tiger::run`]. the
+/// defaults read full boundaries from the vintage matching the data, with
+/// [`lodes_api::DEFAULT_CONCURRENCY`] downloads at a time.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::join_options::JoinOptions;
+/// use bamcensus_tiger::model::GeometryTransform;
+///
+/// let options = JoinOptions::default()
+///     .with_tiger_year(2020)
+///     .with_transform(GeometryTransform::Centroid)
+///     .with_keep_unmatched(true);
+/// assert_eq!(options.tiger_year, Some(2020));
+/// assert_eq!(options.tiger_options().transform, Some(GeometryTransform::Centroid));
+/// ```
+#[derive(Debug, Clone)]
+pub struct JoinOptions {
+    /// the only TIGER/Lines column geoids are read from, see [`RunOptions::geoid_column`]
+    pub geoid_column: Option<String>,
+    /// transform applied to each geometry, such as replacing it with its internal point
+    pub transform: Option<GeometryTransform>,
+    /// TIGER/Lines vintage used in place of the one matching the data
+    pub tiger_year: Option<u64>,
+    /// keep the rows of geoids without a geometry instead of reporting join errors.
+    /// ACS runs only, LODES runs refuse it
+    pub keep_unmatched: bool,
+    /// maximum number of LODES files, and of TIGER/Lines archives, downloaded at a time
+    pub concurrency: usize,
+    /// cache of TIGER/Lines archives, read before downloading and filled with downloads
+    pub cache: Option<TigerCache>,
+    /// root of the TIGER/Lines directory, see [`RunOptions::base_url`]
+    pub tiger_base_url: Option<String>,
+    /// root of the LODES directory, see [`DownloadOptions::base_url`]
+    pub lodes_base_url: Option<String>,
+    /// how failed LODES downloads are retried, read with [`RetryPolicy::from_env`]
+    /// when not provided
+    pub retry: Option<RetryPolicy>,
+    /// root of the ACS API for the queries a run builds itself, such as in
+    /// [`crate::app::acs_tiger::run_aoi`], see [`bamcensus_acs::model::AcsApiQueryParams::base_url`]
+    pub acs_base_url: Option<String>,
+    /// skip county subdivision queries in states whose minor civil divisions are not
+    /// functioning governments, see [`crate::app::acs_tiger::functioning_mcd_queries`]
+    pub functioning_mcds_only: bool,
+    /// token that stops the ACS queries and the LODES and TIGER/Lines downloads of the
+    /// run once cancelled, see [`RunOptions::cancel`] and [`DownloadOptions::cancel`]
+    pub cancel: Option<CancellationToken>,
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        JoinOptions {
+            geoid_column: None,
+            transform: None,
+            tiger_year: None,
+            keep_unmatched: false,
+            concurrency: lodes_api::DEFAULT_CONCURRENCY,
+            cache: None,
+            tiger_base_url: None,
+            lodes_base_url: None,
+            retry: None,
+            acs_base_url: None,
+            functioning_mcds_only: false,
+            cancel: None,
+        }
+    }
+}
+
+impl JoinOptions {
+    pub fn with_geoid_column(mut self, geoid_column: &str) -> JoinOptions {
+        self.geoid_column = Some(String::from(geoid_column));
+        self
+    }
+
+    pub fn with_transform(mut self, transform: GeometryTransform) -> JoinOptions {
+        self.transform = Some(transform);
+        self
+    }
+
+    pub fn with_tiger_year(mut self, tiger_year: u64) -> JoinOptions {
+        self.tiger_year = Some(tiger_year);
+        self
+    }
+
+    pub fn with_keep_unmatched(mut self, keep_unmatched: bool) -> JoinOptions {
+        self.keep_unmatched = keep_unmatched;
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: usize) -> JoinOptions {
+        self.concurrency = concurrency;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: TigerCache) -> JoinOptions {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn with_tiger_base_url(mut self, tiger_base_url: &str) -> JoinOptions {
+        self.tiger_base_url = Some(String::from(tiger_base_url));
+        self
+    }
+
+    pub fn with_lodes_base_url(mut self, lodes_base_url: &str) -> JoinOptions {
+        self.lodes_base_url = Some(String::from(lodes_base_url));
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> JoinOptions {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn with_acs_base_url(mut self, acs_base_url: &str) -> JoinOptions {
+        self.acs_base_url = Some(String::from(acs_base_url));
+        self
+    }
+
+    pub fn with_functioning_mcds_only(mut self, functioning_mcds_only: bool) -> JoinOptions {
+        self.functioning_mcds_only = functioning_mcds_only;
+        self
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> JoinOptions {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// the options of the TIGER/Lines downloads of the run.
+    pub fn tiger_options(&self) -> RunOptions {
+        RunOptions {
+            cache: self.cache.clone(),
+            transform: self.transform,
+            geoid_column: self.geoid_column.clone(),
+            base_url: self.tiger_base_url.clone(),
+            cancel: self.cancel.clone(),
+            concurrency: Some(self.concurrency),
+            ..Default::default()
+        }
+    }
+
+    /// the options of the LODES downloads of the run. fails if no `retry` policy is
+    /// provided and the one in the environment is invalid.
+    pub fn lodes_options(&self) -> Result<DownloadOptions, String> {
+        let retry = match self.retry {
+            Some(retry) => retry,
+            None => RetryPolicy::from_env()?,
+        };
+        Ok(DownloadOptions {
+            concurrency: self.concurrency,
+            retry,
+            base_url: self.lodes_base_url.clone(),
+            cancel: self.cancel.clone(),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// counts of the data rows of a dataset join that did and did not find a geometry.
+/// the rows that are missing a geometry are further counted by the reason the
+/// geometry was not found, when it is known.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct JoinSummary {
+    /// geoids with a matching geometry
+    pub matched: usize,
+    /// geoids whose TIGER/Lines archive failed to download
+    pub missing_not_downloaded: usize,
+    /// geoids whose TIGER/Lines archive was read but had no row with the geoid
+    pub missing_not_in_file: usize,
+    /// geoids missing a geometry for any other reason
+    pub missing_other: usize,
+}
+
+impl JoinSummary {
+    /// the total number of geoids missing a geometry.
+    pub fn missing(&self) -> usize {
+        self.missing_not_downloaded + self.missing_not_in_file + self.missing_other
+    }
+}
+
+impl Display for JoinSummary {
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::join_summary::JoinSummary;
+    ///
+    /// let summary = JoinSummary {
+    ///     matched: 10,
+    ///     missing_not_downloaded: 2,
+    ///     missing_not_in_file: 1,
+    ///     missing_other: 0,
+    /// };
+    /// assert_eq!(
+    ///     summary.to_string(),
+    ///     "10 of 13 geoids matched a geometry. missing: 2 in archives that failed to download, 1 not found in downloaded archives, 0 other"
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of {} geoids matched a geometry. missing: {} in archives that failed to download, {} not found in downloaded archives, {} other",
+            self.matched,
+            self.matched + self.missing(),
+            self.missing_not_downloaded,
+            self.missing_not_in_file,
+            self.missing_other
+        )
+    }
+}
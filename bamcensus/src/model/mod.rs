@@ -1,5 +1,16 @@
 //! input and output types for working with [`crate::app`] functions.
-pub mod acs_tiger_output_row;
 pub mod acs_tiger_row;
-pub mod lodes_tiger_output_row;
+pub mod area_of_interest;
+pub mod area_projection;
+pub mod bulk_manifest;
+pub mod geometry_encoding;
+pub mod geometry_output;
+pub mod join_options;
+pub mod join_summary;
 pub mod lodes_wac_tiger_row;
+pub mod output_format;
+pub mod reference_table;
+pub mod regions;
+pub mod run_metadata;
+pub mod run_summary;
+pub mod wide_table;
@@ -0,0 +1,48 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// file formats supported when writing joined dataset rows.
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// comma-delimited rows with geometries encoded as WKT or hex WKB
+    #[default]
+    Csv,
+    /// newline-delimited JSON objects with geometries encoded as GeoJSON
+    Ndjson,
+    /// a GeoJSON FeatureCollection with the row values as feature properties
+    #[value(name = "geojson")]
+    #[serde(rename = "geojson")]
+    GeoJson,
+    /// Apache Parquet with every column written as text. requires the `parquet` feature.
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// a GeoPackage database with one table of the rows, which GIS tools such as QGIS
+    /// open directly. requires the `gpkg` feature.
+    #[cfg(feature = "gpkg")]
+    #[value(name = "gpkg")]
+    #[serde(rename = "gpkg")]
+    GeoPackage,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl OutputFormat {
+    /// file extension for this format, without the leading dot
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::GeoJson => "geojson",
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => "parquet",
+            #[cfg(feature = "gpkg")]
+            OutputFormat::GeoPackage => "gpkg",
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::ops::sink::{GeometryColumn, OutputSink};
+use bamcensus_core::model::identifier::{GeoidType, StateCode};
+use bamcensus_tiger::model::constants::LATEST_YEAR;
+use bamcensus_tiger::model::TigerResourceBuilder;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// a row of the state reference table: the FIPS code, USPS abbreviation and name of
+/// each of the 50 states and the District of Columbia.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateReference {
+    pub fips: String,
+    pub abbreviation: String,
+    pub name: String,
+}
+
+impl StateReference {
+    /// every state and the District of Columbia, ordered by FIPS code.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::reference_table::StateReference;
+    ///
+    /// let states = StateReference::all();
+    /// assert_eq!(states.len(), 51);
+    /// let colorado = states.iter().find(|s| s.abbreviation == "CO").unwrap();
+    /// assert_eq!(colorado.fips, "08");
+    /// assert_eq!(colorado.name, "Colorado");
+    /// ```
+    pub fn all() -> Vec<StateReference> {
+        StateCode::ALL
+            .iter()
+            .map(|state| StateReference {
+                fips: state.to_fips_string(),
+                abbreviation: state.to_state_abbreviation(),
+                name: state.to_full_name(),
+            })
+            .collect()
+    }
+}
+
+/// a row of the TIGER/Lines reference table: the file that holds the geometries of
+/// a geography in a TIGER/Lines year, as downloaded by
+/// [`bamcensus_tiger::ops::tiger_api::run`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TigerReference {
+    pub year: u64,
+    pub geography: GeoidType,
+    /// the geography each file covers, such as one file per state, or "national"
+    pub file_scope: String,
+    /// the file URI, with `{state}` and `{county}` in place of the FIPS codes of
+    /// files that cover one state or county
+    pub uri_pattern: String,
+}
+
+impl TigerReference {
+    /// the files of each geography published in each supported TIGER/Lines year,
+    /// from 2010 through [`LATEST_YEAR`]. geographies that are not published in a
+    /// year, such as regions after 2010, are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::reference_table::TigerReference;
+    /// use bamcensus_core::model::identifier::GeoidType;
+    ///
+    /// let files = TigerReference::all();
+    /// let tracts = files
+    ///     .iter()
+    ///     .find(|f| f.year == 2020 && f.geography == GeoidType::CensusTract)
+    ///     .unwrap();
+    /// assert_eq!(tracts.file_scope, "state");
+    /// assert_eq!(
+    ///     tracts.uri_pattern,
+    ///     "https://www2.census.gov/geo/tiger/TIGER2020/TRACT/tl_2020_{state}_tract.zip"
+    /// );
+    /// assert!(files.iter().any(|f| f.year == 2010 && f.geography == GeoidType::Region));
+    /// assert!(!files.iter().any(|f| f.year == 2020 && f.geography == GeoidType::Region));
+    ///
+    /// // voting districts are only published with the 2020 redistricting data
+    /// let vtds = files.iter().filter(|f| f.geography == GeoidType::VotingDistrict).collect::<Vec<_>>();
+    /// assert_eq!(vtds.len(), 1);
+    /// assert_eq!(
+    ///     vtds[0].uri_pattern,
+    ///     "https://www2.census.gov/geo/tiger/TIGER2020PL/LAYER/VTD/2020/tl_2020_{state}_vtd20.zip"
+    /// );
+    /// ```
+    pub fn all() -> Vec<TigerReference> {
+        let mut rows = vec![];
+        for year in 2010..=LATEST_YEAR {
+            let Ok(builder) = TigerResourceBuilder::new(year) else {
+                continue;
+            };
+            for geography in GeoidType::value_variants() {
+                let Ok(resource) = builder.create_resource_pattern(*geography) else {
+                    continue;
+                };
+                let file_scope = resource
+                    .file_scope
+                    .map(|scope| scope.to_string())
+                    .unwrap_or_else(|| String::from("national"));
+                rows.push(TigerReference {
+                    year,
+                    geography: *geography,
+                    file_scope,
+                    uri_pattern: resource.uri,
+                });
+            }
+        }
+        rows
+    }
+}
+
+/// a row of a reference table, written through an [`OutputSink`] with the columns
+/// in the order of its fields.
+pub trait ReferenceRow {
+    /// the column names of the table.
+    fn columns() -> Vec<String>;
+    /// the values of this row, aligned with [`ReferenceRow::columns`].
+    fn values(&self) -> Vec<Value>;
+}
+
+impl ReferenceRow for StateReference {
+    fn columns() -> Vec<String> {
+        ["fips", "abbreviation", "name"].map(String::from).to_vec()
+    }
+
+    fn values(&self) -> Vec<Value> {
+        vec![json![self.fips], json![self.abbreviation], json![self.name]]
+    }
+}
+
+impl ReferenceRow for TigerReference {
+    fn columns() -> Vec<String> {
+        ["year", "geography", "file_scope", "uri_pattern"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            json![self.year],
+            json![self.geography],
+            json![self.file_scope],
+            json![self.uri_pattern],
+        ]
+    }
+}
+
+/// writes the rows of a reference table to a sink without geometries, then finishes
+/// the sink.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::geometry_encoding::GeometryEncoding;
+/// use bamcensus::model::output_format::OutputFormat;
+/// use bamcensus::model::reference_table::{write_table, StateReference};
+/// use bamcensus::ops::sink;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let path = dir.path().join("states.csv");
+/// let sink = sink::create(OutputFormat::Csv, &path, GeometryEncoding::default()).unwrap();
+/// write_table(&StateReference::all(), sink).unwrap();
+/// let csv = std::fs::read_to_string(&path).unwrap();
+/// let mut lines = csv.lines();
+/// assert_eq!(lines.next(), Some("fips,abbreviation,name"));
+/// assert_eq!(lines.next(), Some("01,AL,Alabama"));
+/// ```
+pub fn write_table<T: ReferenceRow>(
+    rows: &[T],
+    mut sink: Box<dyn OutputSink>,
+) -> Result<(), String> {
+    sink.write_header(&T::columns(), GeometryColumn::Absent)?;
+    for row in rows {
+        sink.write_row(&row.values(), None)?;
+    }
+    sink.finish()
+}
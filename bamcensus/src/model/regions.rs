@@ -0,0 +1,233 @@
+use clap::ValueEnum;
+use geo::{
+    Area, BooleanOps, BoundingRect, Geometry, InteriorPoint, Intersects, MultiPolygon, Point, Rect,
+};
+use serde::{Deserialize, Serialize};
+
+/// how a geography is assigned to one of a set of [`Regions`].
+#[derive(Serialize, Deserialize, ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionMethod {
+    /// the region containing a point inside the geography, see [`geo::InteriorPoint`].
+    /// unlike the centroid, this point never falls outside a concave geography or
+    /// one with holes
+    #[default]
+    Centroid,
+    /// the region covering the largest part of the geography
+    LargestOverlap,
+}
+
+/// a labeled polygon, such as a sales territory, in NAD83 degrees as are
+/// TIGER/Lines geometries.
+#[derive(Clone, Debug)]
+pub struct Region {
+    pub label: String,
+    geometry: MultiPolygon,
+    bounds: Rect,
+}
+
+/// a set of labeled polygons that geographies are tagged with, see
+/// [`crate::ops::regions::append_regions`].
+#[derive(Clone, Debug)]
+pub struct Regions {
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// creates a set of regions from labels and polygon or multipolygon geometries.
+    pub fn new(regions: Vec<(String, Geometry)>) -> Result<Regions, String> {
+        let regions = regions
+            .into_iter()
+            .map(|(label, geometry)| {
+                let geometry = match geometry {
+                    Geometry::Polygon(p) => Ok(MultiPolygon(vec![p])),
+                    Geometry::MultiPolygon(mp) => Ok(mp),
+                    other => Err(format!(
+                        "region {label} must be a Polygon or MultiPolygon, found {other:?}"
+                    )),
+                }?;
+                let bounds = geometry
+                    .bounding_rect()
+                    .ok_or_else(|| format!("region {label} is empty"))?;
+                Ok(Region {
+                    label,
+                    geometry,
+                    bounds,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Regions { regions })
+    }
+
+    /// reads regions from a GeoJSON FeatureCollection, with one region per polygon
+    /// feature labeled by its `label_property`. string labels are used as-is and
+    /// other values are written as JSON.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::regions::Regions;
+    ///
+    /// let geojson = r#"{
+    ///     "type": "FeatureCollection",
+    ///     "features": [
+    ///         {
+    ///             "type": "Feature",
+    ///             "properties": {"territory": "west"},
+    ///             "geometry": {
+    ///                 "type": "Polygon",
+    ///                 "coordinates": [[[-106.0, 39.0], [-105.0, 39.0], [-105.0, 40.0], [-106.0, 40.0], [-106.0, 39.0]]]
+    ///             }
+    ///         },
+    ///         {
+    ///             "type": "Feature",
+    ///             "properties": {"territory": "east"},
+    ///             "geometry": {
+    ///                 "type": "Polygon",
+    ///                 "coordinates": [[[-105.0, 39.0], [-104.0, 39.0], [-104.0, 40.0], [-105.0, 40.0], [-105.0, 39.0]]]
+    ///             }
+    ///         }
+    ///     ]
+    /// }"#;
+    /// let regions = Regions::from_geojson(geojson, "territory").unwrap();
+    /// assert_eq!(regions.regions().iter().map(|r| r.label.as_str()).collect::<Vec<_>>(), vec!["west", "east"]);
+    /// assert!(Regions::from_geojson(geojson, "name").is_err());
+    /// ```
+    pub fn from_geojson(text: &str, label_property: &str) -> Result<Regions, String> {
+        let geojson = text
+            .parse::<geojson::GeoJson>()
+            .map_err(|e| format!("failure parsing regions GeoJSON: {e}"))?;
+        let features = match geojson {
+            geojson::GeoJson::FeatureCollection(fc) => Ok(fc.features),
+            geojson::GeoJson::Feature(f) => Ok(vec![f]),
+            geojson::GeoJson::Geometry(_) => Err(String::from(
+                "regions GeoJSON must contain features with labels, found a bare geometry",
+            )),
+        }?;
+        let regions = features
+            .into_iter()
+            .enumerate()
+            .map(|(idx, feature)| {
+                let label = match feature.property(label_property) {
+                    Some(serde_json::Value::String(s)) => Ok(s.clone()),
+                    Some(serde_json::Value::Null) | None => Err(format!(
+                        "region feature {idx} has no {label_property} property"
+                    )),
+                    Some(other) => Ok(other.to_string()),
+                }?;
+                let geometry = feature
+                    .geometry
+                    .ok_or_else(|| format!("region {label} has no geometry"))?;
+                let geometry = Geometry::try_from(geometry)
+                    .map_err(|e| format!("failure reading region {label} geometry: {e}"))?;
+                Ok((label, geometry))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if regions.is_empty() {
+            return Err(String::from("regions GeoJSON has no features"));
+        }
+        Regions::new(regions)
+    }
+
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// the region a geography is assigned to with a method, if any, and the number of
+    /// regions whose interiors overlap the geography. a polygon crossing a region
+    /// boundary overlaps more than one region, while one that only touches a
+    /// neighboring region does not. points overlap the region containing them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::regions::{RegionMethod, Regions};
+    /// use geo::{point, polygon, Geometry, Polygon};
+    ///
+    /// let square = |x0: f64, x1: f64| {
+    ///     Geometry::Polygon(polygon![(x: x0, y: 0.0), (x: x1, y: 0.0), (x: x1, y: 1.0), (x: x0, y: 1.0), (x: x0, y: 0.0)])
+    /// };
+    /// let regions = Regions::new(vec![
+    ///     (String::from("west"), square(0.0, 1.0)),
+    ///     (String::from("east"), square(1.0, 2.0)),
+    /// ])
+    /// .unwrap();
+    ///
+    /// // a geography crossing into the east region overlaps both, and most of it is east
+    /// let straddling = Geometry::Polygon(polygon![
+    ///     (x: 0.0, y: 0.0), (x: 1.2, y: 0.0), (x: 1.2, y: 1.0), (x: 0.9, y: 1.0), (x: 0.9, y: 0.1), (x: 0.0, y: 0.1), (x: 0.0, y: 0.0),
+    /// ]);
+    /// let (region, count) = regions.assign(&straddling, RegionMethod::LargestOverlap);
+    /// assert_eq!((region.map(|r| r.label.as_str()), count), (Some("east"), 2));
+    ///
+    /// // a geography wrapped around a lake has its centroid in the lake, but is
+    /// // assigned by a point inside it, on the shore
+    /// let rect = |x0: f64, x1: f64| {
+    ///     polygon![(x: x0, y: x0), (x: x1, y: x0), (x: x1, y: x1), (x: x0, y: x1), (x: x0, y: x0)]
+    /// };
+    /// let with_hole = |outer: Polygon, hole: Polygon| {
+    ///     Geometry::Polygon(Polygon::new(outer.exterior().clone(), vec![hole.exterior().clone()]))
+    /// };
+    /// let lakes = Regions::new(vec![
+    ///     (String::from("lake"), Geometry::Polygon(rect(1.0, 2.0))),
+    ///     (String::from("shore"), with_hole(rect(0.0, 3.0), rect(1.0, 2.0))),
+    /// ])
+    /// .unwrap();
+    /// let around = with_hole(rect(0.5, 2.5), rect(0.9, 2.1));
+    /// let (region, count) = lakes.assign(&around, RegionMethod::Centroid);
+    /// assert_eq!((region.map(|r| r.label.as_str()), count), (Some("shore"), 1));
+    ///
+    /// // touching a neighbor is not overlapping it
+    /// assert_eq!(regions.assign(&square(0.5, 1.0), RegionMethod::LargestOverlap).1, 1);
+    /// let (region, count) = regions.assign(&Geometry::Point(point!(x: 5.0, y: 0.5)), RegionMethod::Centroid);
+    /// assert!(region.is_none());
+    /// assert_eq!(count, 0);
+    /// ```
+    pub fn assign(&self, geometry: &Geometry, method: RegionMethod) -> (Option<&Region>, usize) {
+        let overlaps = self.overlaps(geometry);
+        let region = match method {
+            RegionMethod::Centroid => geometry.interior_point().and_then(|p| self.containing(&p)),
+            // ties go to the first region, as with containment
+            RegionMethod::LargestOverlap => overlaps
+                .iter()
+                .rev()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(idx, _)| &self.regions[*idx]),
+        };
+        (region, overlaps.len())
+    }
+
+    /// the first region containing a point.
+    fn containing(&self, point: &Point) -> Option<&Region> {
+        self.regions
+            .iter()
+            .find(|r| r.bounds.intersects(point) && r.geometry.intersects(point))
+    }
+
+    /// the index of each region whose interior overlaps a geography, with the area of
+    /// the overlap in square degrees, which is only used to compare regions. points
+    /// and lines overlap the regions they intersect with an area of 0.
+    fn overlaps(&self, geometry: &Geometry) -> Vec<(usize, f64)> {
+        let bounds = match geometry.bounding_rect() {
+            Some(b) => b,
+            None => return vec![],
+        };
+        let polygons = match geometry {
+            Geometry::Polygon(p) => Some(MultiPolygon(vec![p.clone()])),
+            Geometry::MultiPolygon(mp) => Some(mp.clone()),
+            _ => None,
+        };
+        self.regions
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.bounds.intersects(&bounds) && r.geometry.intersects(geometry))
+            .filter_map(|(idx, r)| match &polygons {
+                Some(p) => {
+                    let area = r.geometry.intersection(p).unsigned_area();
+                    (area > 0.0).then_some((idx, area))
+                }
+                None => Some((idx, 0.0)),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,203 @@
+use super::geometry_output::GeometryOutput;
+use super::join_options::JoinOptions;
+use super::run_summary::RunSummary;
+use bamcensus_acs::api::acs_api;
+use bamcensus_acs::model::{AcsApiQueryParams, AcsType};
+use bamcensus_core::model::identifier::{Geoid, GeoidType};
+use bamcensus_lehd::model::{LodesDataset, WacSegment};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// a description of how a data file was produced, written next to it as a
+/// `<output>.meta.json` sidecar file. see [`RunMetadata::write_sidecar`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunMetadata {
+    /// version of bamcensus that produced the file
+    pub tool_version: String,
+    /// when the metadata was created, as an RFC 3339 UTC timestamp
+    pub created_at: String,
+    /// the source dataset and its query parameters
+    pub dataset: DatasetMetadata,
+    /// TIGER/Lines vintage of the geometries, or None if no geometries were written
+    pub tiger_year: Option<u64>,
+    /// whether full boundaries or internal points were written, or None if the output
+    /// is tabular only
+    pub geometry: Option<GeometryOutput>,
+    /// TIGER/Lines column that geoids were read from, if one was chosen
+    pub geoid_column: Option<String>,
+    /// request counts, rows and duration of the run
+    pub summary: RunSummary,
+}
+
+/// query parameters of the source dataset of a run.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum DatasetMetadata {
+    Acs {
+        year: u64,
+        acs_type: AcsType,
+        variables: Vec<String>,
+        /// ACS API URLs requested, with any API key redacted
+        queries: Vec<String>,
+    },
+    Lodes {
+        dataset: LodesDataset,
+        wac_segments: Vec<WacSegment>,
+        /// requested geoids, which are every state when none were provided
+        geoids: Vec<String>,
+        output_resolution: Option<GeoidType>,
+    },
+}
+
+impl RunMetadata {
+    /// metadata for an ACS + TIGER/Lines run of the queries, which share one year.
+    /// geometries come from the vintage of the ACS year unless the options provide
+    /// a `tiger_year`. `geometry` is None for a tabular-only run, which has no TIGER/Lines
+    /// vintage.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::geometry_output::GeometryOutput;
+    /// use bamcensus::model::join_options::JoinOptions;
+    /// use bamcensus::model::run_metadata::RunMetadata;
+    /// use bamcensus::model::run_summary::RunSummary;
+    /// use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+    /// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+    ///
+    /// let for_query = AcsGeoidQuery::new(Some(Geoid::state(8).unwrap()), Some(GeoidType::County)).unwrap();
+    /// let query = AcsApiQueryParams::new(
+    ///     None,
+    ///     2022,
+    ///     AcsType::FiveYear,
+    ///     vec![String::from("B01001_001E")],
+    ///     for_query,
+    ///     Some(String::from("secret")),
+    /// );
+    /// let metadata = RunMetadata::acs(
+    ///     &[query],
+    ///     Some(GeometryOutput::Polygon),
+    ///     &JoinOptions::default().with_tiger_year(2020),
+    ///     RunSummary::default(),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(metadata.tiger_year, Some(2020));
+    ///
+    /// let json = serde_json::to_value(&metadata).unwrap();
+    /// assert_eq!(json["dataset"]["source"], "acs");
+    /// assert_eq!(json["dataset"]["year"], 2022);
+    /// assert_eq!(
+    ///     json["dataset"]["queries"][0],
+    ///     "https://api.census.gov/data/2022/acs/acs5?get=B01001_001E&for=county:*&in=state:08&key=<redacted>"
+    /// );
+    /// ```
+    pub fn acs(
+        queries: &[AcsApiQueryParams],
+        geometry: Option<GeometryOutput>,
+        options: &JoinOptions,
+        summary: RunSummary,
+    ) -> Result<RunMetadata, String> {
+        let first = queries
+            .first()
+            .ok_or_else(|| String::from("cannot describe a run without ACS queries"))?;
+        let urls = queries
+            .iter()
+            .map(|q| q.build_url().map(|url| acs_api::redact_api_key(&url)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let variables = queries
+            .iter()
+            .flat_map(|q| q.get_columns())
+            .unique()
+            .collect_vec();
+        let dataset = DatasetMetadata::Acs {
+            year: first.year,
+            acs_type: first.acs_type,
+            variables,
+            queries: urls,
+        };
+        let tiger_year = geometry.map(|_| options.tiger_year.unwrap_or(first.year));
+        Ok(RunMetadata::new(
+            dataset,
+            tiger_year,
+            geometry,
+            options.geoid_column.as_deref(),
+            summary,
+        ))
+    }
+
+    /// metadata for a LODES + TIGER/Lines run. geometries come from the vintage of
+    /// the LODES edition unless the options provide a `tiger_year`. `geometry` is None
+    /// for a tabular-only run.
+    pub fn lodes(
+        dataset: &LodesDataset,
+        wac_segments: &[WacSegment],
+        geoids: &[Geoid],
+        output_resolution: Option<GeoidType>,
+        geometry: Option<GeometryOutput>,
+        options: &JoinOptions,
+        summary: RunSummary,
+    ) -> RunMetadata {
+        let metadata = DatasetMetadata::Lodes {
+            dataset: *dataset,
+            wac_segments: wac_segments.to_vec(),
+            geoids: geoids.iter().map(|g| g.to_string()).collect_vec(),
+            output_resolution,
+        };
+        let tiger_year = geometry.map(|_| options.tiger_year.unwrap_or(dataset.tiger_year()));
+        RunMetadata::new(
+            metadata,
+            tiger_year,
+            geometry,
+            options.geoid_column.as_deref(),
+            summary,
+        )
+    }
+
+    fn new(
+        dataset: DatasetMetadata,
+        tiger_year: Option<u64>,
+        geometry: Option<GeometryOutput>,
+        geoid_column: Option<&str>,
+        summary: RunSummary,
+    ) -> RunMetadata {
+        RunMetadata {
+            tool_version: String::from(env!("CARGO_PKG_VERSION")),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            dataset,
+            tiger_year,
+            geometry,
+            geoid_column: geoid_column.map(String::from),
+            summary,
+        }
+    }
+
+    /// the sidecar path for a data file, which appends `.meta.json` to the full
+    /// file name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::run_metadata::RunMetadata;
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// let path = RunMetadata::sidecar_path(Path::new("out/2020-five-year-county.csv"));
+    /// assert_eq!(path, PathBuf::from("out/2020-five-year-county.csv.meta.json"));
+    /// ```
+    pub fn sidecar_path(output: &Path) -> PathBuf {
+        let mut name = output.as_os_str().to_owned();
+        name.push(".meta.json");
+        PathBuf::from(name)
+    }
+
+    /// writes this metadata as pretty-printed JSON to the sidecar path of the data
+    /// file at `output`, returning the sidecar path.
+    pub fn write_sidecar(&self, output: &Path) -> Result<PathBuf, String> {
+        let path = RunMetadata::sidecar_path(output);
+        let file = std::fs::File::create(&path)
+            .map_err(|e| format!("failure creating {}: {e}", path.display()))?;
+        serde_json::to_writer_pretty(file, self)
+            .map_err(|e| format!("failure writing {}: {e}", path.display()))?;
+        Ok(path)
+    }
+}
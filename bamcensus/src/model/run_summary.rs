@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::time::Duration;
+
+/// machine-readable statistics of an app run, such as [`crate::app::acs_tiger::run`],
+/// which serializes to JSON for run metadata.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RunSummary {
+    /// requests to the data source: ACS API queries, or LODES files
+    pub data_requests: RequestCounts,
+    /// TIGER/Lines archives downloaded for the geometries
+    pub tiger_files: RequestCounts,
+    /// rows in the joined dataset
+    pub rows: usize,
+    /// bytes of the response bodies downloaded: ACS responses, LODES files and
+    /// TIGER/Lines archives. archives read from a cache are not counted
+    pub bytes: u64,
+    /// wall-clock duration of the run, in seconds
+    pub elapsed_secs: f64,
+}
+
+/// the number of requests made, and how many of them succeeded and failed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestCounts {
+    pub attempted: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl RequestCounts {
+    /// counts for `attempted` requests, `failed` of which failed.
+    pub fn new(attempted: usize, failed: usize) -> RequestCounts {
+        RequestCounts {
+            attempted,
+            succeeded: attempted.saturating_sub(failed),
+            failed,
+        }
+    }
+}
+
+impl RunSummary {
+    pub fn new(
+        data_requests: RequestCounts,
+        tiger_files: RequestCounts,
+        rows: usize,
+        bytes: u64,
+        elapsed: Duration,
+    ) -> RunSummary {
+        RunSummary {
+            data_requests,
+            tiger_files,
+            rows,
+            bytes,
+            elapsed_secs: elapsed.as_secs_f64(),
+        }
+    }
+}
+
+impl Display for RunSummary {
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::run_summary::{RequestCounts, RunSummary};
+    /// use std::time::Duration;
+    ///
+    /// let summary = RunSummary::new(
+    ///     RequestCounts::new(2, 0),
+    ///     RequestCounts::new(3, 1),
+    ///     640,
+    ///     2_500_000,
+    ///     Duration::from_millis(12500),
+    /// );
+    /// assert_eq!(
+    ///     summary.to_string(),
+    ///     "640 rows in 12.50s, 2500000 bytes downloaded. data requests: 2 of 2 succeeded. TIGER/Lines files: 2 of 3 succeeded"
+    /// );
+    ///
+    /// let json = serde_json::to_value(&summary).unwrap();
+    /// assert_eq!(json["tiger_files"]["failed"], 1);
+    /// assert_eq!(json["bytes"], 2_500_000);
+    /// assert_eq!(json["elapsed_secs"], 12.5);
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} rows in {:.2}s, {} bytes downloaded. data requests: {} of {} succeeded. TIGER/Lines files: {} of {} succeeded",
+            self.rows,
+            self.elapsed_secs,
+            self.bytes,
+            self.data_requests.succeeded,
+            self.data_requests.attempted,
+            self.tiger_files.succeeded,
+            self.tiger_files.attempted
+        )
+    }
+}
@@ -0,0 +1,258 @@
+use super::acs_tiger_row::AcsTigerRow;
+use super::area_projection::AreaProjection;
+use super::lodes_wac_tiger_row::LodesWacTigerRow;
+use bamcensus_acs::model::AcsValue;
+use bamcensus_core::model::identifier::Geoid;
+use bamcensus_lehd::model::WacValue;
+use geo::Geometry;
+use std::collections::HashMap;
+
+/// name of the column added by [`WideTable::append_area`].
+pub const AREA_COLUMN: &str = "area_sqkm";
+
+/// joined dataset rows pivoted to wide format, with one row per geoid and one
+/// column per variable. geoids and columns keep the order they first appear in.
+pub struct WideTable {
+    /// names of the value columns
+    pub columns: Vec<String>,
+    pub rows: Vec<WideRow>,
+    /// whether the rows have geometries, which is false for tabular-only output
+    pub with_geometry: bool,
+}
+
+/// a single geoid in a [`WideTable`]. `values` aligns with the table columns,
+/// with `None` where the geoid has no value for that column.
+pub struct WideRow {
+    pub geoid: Geoid,
+    pub geometry: Option<Geometry>,
+    pub values: Vec<Option<serde_json::Value>>,
+}
+
+impl WideTable {
+    /// pivots long-format (geoid, column, value, geometry) rows into a wide table.
+    /// the geometry of the first row seen for each geoid is kept. the table has
+    /// geometries unless its first row has none.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::wide_table::WideTable;
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    /// use geo::{point, Geometry};
+    /// use serde_json::json;
+    ///
+    /// let a = Geoid::County(fips::State(8), fips::County(1));
+    /// let b = Geoid::County(fips::State(8), fips::County(59));
+    /// let geometry = Geometry::Point(point!(x: 0.0, y: 0.0));
+    /// let rows = vec![
+    ///     (a.clone(), String::from("B01001_001E"), json!["10"], Some(geometry.clone())),
+    ///     (b.clone(), String::from("B01001_001E"), json!["20"], Some(geometry.clone())),
+    ///     (a.clone(), String::from("B19013_001E"), json!["50000"], Some(geometry.clone())),
+    /// ];
+    /// let table = WideTable::pivot(rows);
+    /// assert!(table.with_geometry);
+    /// assert_eq!(table.columns, vec!["B01001_001E", "B19013_001E"]);
+    /// assert_eq!(table.rows.len(), 2);
+    /// assert_eq!(table.rows[0].values, vec![Some(json!["10"]), Some(json!["50000"])]);
+    /// assert_eq!(table.rows[1].values, vec![Some(json!["20"]), None]);
+    /// ```
+    pub fn pivot(
+        rows: impl IntoIterator<Item = (Geoid, String, serde_json::Value, Option<Geometry>)>,
+    ) -> WideTable {
+        let mut columns: Vec<String> = vec![];
+        let mut column_index: HashMap<String, usize> = HashMap::new();
+        let mut row_index: HashMap<Geoid, usize> = HashMap::new();
+        let mut wide_rows: Vec<(Geoid, Option<Geometry>, HashMap<usize, serde_json::Value>)> =
+            vec![];
+
+        for (geoid, column, value, geometry) in rows {
+            let col = match column_index.get(&column) {
+                Some(idx) => *idx,
+                None => {
+                    column_index.insert(column.clone(), columns.len());
+                    columns.push(column);
+                    columns.len() - 1
+                }
+            };
+            let row = match row_index.get(&geoid) {
+                Some(idx) => *idx,
+                None => {
+                    row_index.insert(geoid.clone(), wide_rows.len());
+                    wide_rows.push((geoid, geometry, HashMap::new()));
+                    wide_rows.len() - 1
+                }
+            };
+            wide_rows[row].2.insert(col, value);
+        }
+
+        let with_geometry = wide_rows.first().is_none_or(|(_, g, _)| g.is_some());
+        let n_columns = columns.len();
+        let rows = wide_rows
+            .into_iter()
+            .map(|(geoid, geometry, mut values)| WideRow {
+                geoid,
+                geometry,
+                values: (0..n_columns).map(|col| values.remove(&col)).collect(),
+            })
+            .collect();
+        WideTable {
+            columns,
+            rows,
+            with_geometry,
+        }
+    }
+
+    /// appends an area_sqkm column with the area of each row geometry, measured with
+    /// the given projection, so that values can be divided into densities. the
+    /// geometries must be polygons, since points have no area. rows without a
+    /// geometry have a null area.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::area_projection::AreaProjection;
+    /// use bamcensus::model::wide_table::WideTable;
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    /// use geo::{point, polygon, Geometry};
+    /// use serde_json::json;
+    ///
+    /// let county = Geoid::County(fips::State(8), fips::County(31));
+    /// let square = Geometry::Polygon(polygon![
+    ///     (x: -105.0, y: 39.7), (x: -104.9, y: 39.7), (x: -104.9, y: 39.8),
+    ///     (x: -105.0, y: 39.8), (x: -105.0, y: 39.7),
+    /// ]);
+    /// let rows = vec![(county.clone(), String::from("C000"), json![950.0], Some(square))];
+    /// let mut table = WideTable::pivot(rows);
+    /// table.append_area(AreaProjection::ConusAlbers).unwrap();
+    /// assert_eq!(table.columns, vec!["C000", "area_sqkm"]);
+    /// let area = table.rows[0].values[1].as_ref().and_then(|v| v.as_f64()).unwrap();
+    /// assert!((area - 95.2).abs() < 0.5);
+    ///
+    /// // centroids have no area
+    /// let centroid = Geometry::Point(point!(x: -105.0, y: 39.7));
+    /// let rows = vec![(county, String::from("C000"), json![950.0], Some(centroid))];
+    /// assert!(WideTable::pivot(rows).append_area(AreaProjection::Geodesic).is_err());
+    /// ```
+    pub fn append_area(&mut self, projection: AreaProjection) -> Result<(), String> {
+        if !self.with_geometry {
+            return Err(String::from(
+                "cannot compute area_sqkm for a table without geometries",
+            ));
+        }
+        let areas = self
+            .rows
+            .iter()
+            .map(|row| match &row.geometry {
+                Some(g @ (Geometry::Polygon(_) | Geometry::MultiPolygon(_))) => {
+                    Ok(Some(projection.area_sqkm(g)))
+                }
+                None => Ok(None),
+                Some(_) => Err(format!(
+                    "cannot compute area_sqkm for {}, which has no polygon geometry",
+                    row.geoid
+                )),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        self.columns.push(String::from(AREA_COLUMN));
+        for (row, area) in self.rows.iter_mut().zip(areas) {
+            row.values.push(area.map(|a| serde_json::json![a]));
+        }
+        Ok(())
+    }
+
+    /// pivots ACS values without geometries into a wide table, for tabular-only output.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::wide_table::WideTable;
+    /// use bamcensus_acs::model::AcsValue;
+    /// use bamcensus_core::model::identifier::{fips, Geoid};
+    /// use serde_json::json;
+    ///
+    /// let rows = vec![(
+    ///     Geoid::County(fips::State(8), fips::County(59)),
+    ///     vec![AcsValue::new(String::from("B01001_001E"), json!["579581"])],
+    /// )];
+    /// let table = WideTable::from_acs_values(rows);
+    /// assert!(!table.with_geometry);
+    /// assert_eq!(table.rows[0].geometry, None);
+    /// assert_eq!(table.rows[0].values, vec![Some(json!["579581"])]);
+    /// ```
+    pub fn from_acs_values(rows: impl IntoIterator<Item = (Geoid, Vec<AcsValue>)>) -> WideTable {
+        WideTable::pivot(rows.into_iter().flat_map(|(geoid, values)| {
+            values
+                .into_iter()
+                .map(move |v| (geoid.clone(), v.name, v.value, None))
+        }))
+    }
+
+    /// pivots LODES WAC values without geometries into a wide table, for tabular-only
+    /// output, with a column per WAC segment.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use bamcensus::model::wide_table::WideTable;
+    /// use bamcensus_core::model::identifier::Geoid;
+    /// use bamcensus_lehd::model::{WacSegment, WacValue};
+    /// use serde_json::json;
+    ///
+    /// let values = |jobs: [f64; 3]| {
+    ///     [WacSegment::C000, WacSegment::CA01, WacSegment::CNS01]
+    ///         .into_iter()
+    ///         .zip(jobs)
+    ///         .map(|(segment, value)| WacValue::new(segment, value))
+    ///         .collect::<Vec<_>>()
+    /// };
+    /// let rows = vec![
+    ///     (Geoid::county(8, 31).unwrap(), values([500.0, 100.0, 2.0])),
+    ///     (Geoid::county(8, 59).unwrap(), values([300.0, 60.0, 5.0])),
+    /// ];
+    /// let table = WideTable::from_lodes_values(rows);
+    /// assert_eq!(table.columns, vec!["C000", "CA01", "CNS01"]);
+    /// assert_eq!(table.rows[1].values, vec![Some(json![300.0]), Some(json![60.0]), Some(json![5.0])]);
+    /// ```
+    pub fn from_lodes_values(rows: impl IntoIterator<Item = (Geoid, Vec<WacValue>)>) -> WideTable {
+        WideTable::pivot(rows.into_iter().flat_map(|(geoid, values)| {
+            values.into_iter().map(move |v| {
+                (
+                    geoid.clone(),
+                    v.segment.to_string(),
+                    serde_json::json![v.value],
+                    None,
+                )
+            })
+        }))
+    }
+}
+
+impl FromIterator<AcsTigerRow> for WideTable {
+    /// pivots joined ACS rows, which always have a geometry column, even when the
+    /// first geoid kept no geometry because none matched it.
+    fn from_iter<T: IntoIterator<Item = AcsTigerRow>>(iter: T) -> Self {
+        let mut table = WideTable::pivot(iter.into_iter().map(|row| {
+            (
+                row.geoid,
+                row.acs_value.name,
+                row.acs_value.value,
+                row.geometry,
+            )
+        }));
+        table.with_geometry = true;
+        table
+    }
+}
+
+impl FromIterator<LodesWacTigerRow> for WideTable {
+    fn from_iter<T: IntoIterator<Item = LodesWacTigerRow>>(iter: T) -> Self {
+        WideTable::pivot(iter.into_iter().map(|row| {
+            (
+                row.geoid,
+                row.value.segment.to_string(),
+                serde_json::json![row.value.value],
+                Some(row.geometry),
+            )
+        }))
+    }
+}
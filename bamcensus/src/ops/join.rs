@@ -1,45 +1,209 @@
-use bamcensus_core::model::identifier::Geoid;
+use crate::model::join_summary::JoinSummary;
+use bamcensus_core::model::identifier::{Geoid, HasGeoidString};
+use bamcensus_core::ops::progress::ProgressBar;
 use geo::Geometry;
 use itertools::Itertools;
-use kdam::BarExt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 type PartitionedJoinResult<T> = (Vec<(Geoid, Geometry, Vec<T>)>, Vec<String>);
+type DiagnosedJoinResult<T> = (Vec<(Geoid, Geometry, Vec<T>)>, Vec<String>, JoinSummary);
+type OptionalJoinResult<T> = (
+    Vec<(Geoid, Option<Geometry>, Vec<T>)>,
+    Vec<String>,
+    JoinSummary,
+);
+
+/// the reason a geoid has no geometry in a dataset join, see [`MissingGeometry::new`].
+pub enum MissingGeometry {
+    /// the TIGER/Lines archive expected to hold the geometry failed to download
+    NotDownloaded(String),
+    /// the TIGER/Lines archive was read, but no row in it matched the geoid
+    NotInFile(String),
+    /// no archive is known for the geoid
+    Unknown(String),
+}
+
+impl MissingGeometry {
+    /// diagnoses a missing geometry from the URI of the TIGER/Lines archive that
+    /// should have contained it and the set of archive URIs that failed to download.
+    pub fn new(expected_uri: Result<String, String>, failed_uris: &HashSet<String>) -> Self {
+        match expected_uri {
+            Ok(uri) if failed_uris.contains(&uri) => MissingGeometry::NotDownloaded(uri),
+            Ok(uri) => MissingGeometry::NotInFile(uri),
+            Err(e) => MissingGeometry::Unknown(e),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            MissingGeometry::NotDownloaded(uri) => {
+                format!("expected in {uri}, which failed to download")
+            }
+            MissingGeometry::NotInFile(uri) => {
+                format!("expected in {uri}, which was read but has no row with this GEOID")
+            }
+            MissingGeometry::Unknown(e) => format!("no TIGER/Lines file is known: {e}"),
+        }
+    }
+}
 
 /// joins a dataset with a geometry dataset. it is assumed that all Geoids in the data rows
 /// are present in the tiger rows. this join builds an index over the geometries, steps through
 /// the data row iterator, and looks up the geometry in the index. the geometry value is cloned
 /// and added to a tuple with the original data.
+///
+/// geoids on both sides are compared by their [`Geoid::normalize`] form, which is the
+/// geoid of each joined row, so that formatting differences do not lose rows.
 pub fn dataset_with_geometries<T>(
     data_rows: Vec<(Geoid, Vec<T>)>,
     tiger_rows: Vec<Vec<(Geoid, Geometry<f64>)>>,
 ) -> Result<PartitionedJoinResult<T>, String> {
-    let mut pb = kdam::Bar::builder()
-        .total(data_rows.len())
-        .desc("dataset join")
-        .build()?;
+    let (join_dataset, join_errors, _) =
+        dataset_with_diagnosed_geometries(data_rows, tiger_rows, |_| None)?;
+    Ok((join_dataset, join_errors))
+}
+
+/// joins a dataset with a geometry dataset as in [`dataset_with_geometries`]. for each
+/// geoid without a geometry, `diagnose` may report why the geometry is missing, such
+/// as which TIGER/Lines archive should have contained it, which is added to the join
+/// error. the matched and missing geoids are counted in the returned [`JoinSummary`].
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::join::{dataset_with_diagnosed_geometries, MissingGeometry};
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use geo::{point, Geometry};
+/// use std::collections::HashSet;
+///
+/// let found = Geoid::County(fips::State(8), fips::County(1));
+/// let missing = Geoid::County(fips::State(8), fips::County(59));
+/// let data_rows = vec![(found.clone(), vec![1]), (missing.clone(), vec![2])];
+/// let tiger_rows = vec![vec![(found.clone(), Geometry::Point(point!(x: 0.0, y: 0.0)))]];
+/// let failed = HashSet::from([String::from("tl_2020_us_county.zip")]);
+///
+/// let (joined, errors, summary) = dataset_with_diagnosed_geometries(
+///     data_rows,
+///     tiger_rows,
+///     |_| Some(MissingGeometry::new(Ok(String::from("tl_2020_us_county.zip")), &failed)),
+/// )
+/// .unwrap();
+/// assert_eq!(joined.len(), 1);
+/// assert_eq!(
+///     errors,
+///     vec!["geometry not found for geoid 08059, has 1 values from API response, expected in tl_2020_us_county.zip, which failed to download"]
+/// );
+/// assert_eq!(summary.matched, 1);
+/// assert_eq!(summary.missing_not_downloaded, 1);
+///
+/// // block codes match regardless of the case of their suffix letter
+/// let block = Geoid::try_from("080590098380105b").unwrap();
+/// let tiger_block = Geoid::try_from("080590098380105B").unwrap();
+/// let (joined, _, _) = dataset_with_diagnosed_geometries(
+///     vec![(block, vec![1])],
+///     vec![vec![(tiger_block.clone(), Geometry::Point(point!(x: 0.0, y: 0.0)))]],
+///     |_| None,
+/// )
+/// .unwrap();
+/// assert_eq!(joined[0].0, tiger_block);
+/// ```
+pub fn dataset_with_diagnosed_geometries<T>(
+    data_rows: Vec<(Geoid, Vec<T>)>,
+    tiger_rows: Vec<Vec<(Geoid, Geometry<f64>)>>,
+    diagnose: impl Fn(&Geoid) -> Option<MissingGeometry>,
+) -> Result<DiagnosedJoinResult<T>, String> {
+    let (join_dataset, join_errors, summary) =
+        dataset_with_optional_geometries(data_rows, tiger_rows, diagnose, false)?;
+    let join_dataset = join_dataset
+        .into_iter()
+        .filter_map(|(geoid, geometry, values)| geometry.map(|g| (geoid, g, values)))
+        .collect_vec();
+    Ok((join_dataset, join_errors, summary))
+}
+
+/// joins a dataset with a geometry dataset as in [`dataset_with_diagnosed_geometries`].
+/// if `keep_unmatched` is true, each geoid without a geometry is kept as a row with
+/// no geometry instead of a join error, so that no data is lost when a few
+/// geometries are missing. kept geoids are still counted as missing in the
+/// [`JoinSummary`].
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::join::dataset_with_optional_geometries;
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use geo::{point, Geometry};
+///
+/// let found = Geoid::County(fips::State(8), fips::County(1));
+/// let missing = Geoid::County(fips::State(8), fips::County(59));
+/// let point = Geometry::Point(point!(x: 0.0, y: 0.0));
+/// let data_rows = || vec![(found.clone(), vec![1]), (missing.clone(), vec![2])];
+/// let tiger_rows = || vec![vec![(found.clone(), point.clone())]];
+///
+/// let (joined, errors, summary) =
+///     dataset_with_optional_geometries(data_rows(), tiger_rows(), |_| None, true).unwrap();
+/// assert_eq!(joined, vec![(found.clone(), Some(point.clone()), vec![1]), (missing.clone(), None, vec![2])]);
+/// assert!(errors.is_empty());
+/// assert_eq!((summary.matched, summary.missing()), (1, 1));
+///
+/// // otherwise the unmatched geoid is a join error
+/// let (joined, errors, _) =
+///     dataset_with_optional_geometries(data_rows(), tiger_rows(), |_| None, false).unwrap();
+/// assert_eq!(joined.len(), 1);
+/// assert_eq!(errors, vec!["geometry not found for geoid 08059, has 1 values from API response"]);
+/// ```
+pub fn dataset_with_optional_geometries<T>(
+    data_rows: Vec<(Geoid, Vec<T>)>,
+    tiger_rows: Vec<Vec<(Geoid, Geometry<f64>)>>,
+    diagnose: impl Fn(&Geoid) -> Option<MissingGeometry>,
+    keep_unmatched: bool,
+) -> Result<OptionalJoinResult<T>, String> {
+    let mut pb = ProgressBar::new(data_rows.len(), "dataset join")?;
+    let mut summary = JoinSummary::default();
 
     let tiger_lookup = tiger_rows
         .into_iter()
         .flatten()
+        .map(|(geoid, geometry)| (geoid.normalize(), geometry))
         .collect::<HashMap<Geoid, Geometry>>();
 
     let (join_dataset, join_errors) = data_rows
         .into_iter()
-        .map(|(geoid, lodes_values)| {
+        .map(|(geoid, values)| {
+            let geoid = geoid.normalize();
             let row = match tiger_lookup.get(&geoid) {
-                Some(geometry) => Ok((geoid, geometry.clone(), lodes_values)),
-                None => Err(format!(
-                    "geometry not found for geoid {}, has {} LODES values from API response",
-                    geoid,
-                    lodes_values.len()
-                )),
+                Some(geometry) => {
+                    summary.matched += 1;
+                    Ok((geoid, Some(geometry.clone()), values))
+                }
+                None => {
+                    let missing = diagnose(&geoid);
+                    match missing {
+                        Some(MissingGeometry::NotDownloaded(_)) => {
+                            summary.missing_not_downloaded += 1
+                        }
+                        Some(MissingGeometry::NotInFile(_)) => summary.missing_not_in_file += 1,
+                        Some(MissingGeometry::Unknown(_)) | None => summary.missing_other += 1,
+                    }
+                    if keep_unmatched {
+                        Ok((geoid, None, values))
+                    } else {
+                        let reason = missing
+                            .map(|m| format!(", {}", m.describe()))
+                            .unwrap_or_default();
+                        Err(format!(
+                            "geometry not found for geoid {}, has {} values from API response{reason}",
+                            geoid.geoid_string(),
+                            values.len()
+                        ))
+                    }
+                }
             };
             let _ = pb.update(1); // ignore progress failures
             row
         })
         .partition_result();
 
-    eprintln!(); // finish progress bar
-    Ok((join_dataset, join_errors))
+    pb.finish();
+    Ok((join_dataset, join_errors, summary))
 }
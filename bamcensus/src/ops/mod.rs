@@ -1,2 +1,5 @@
 //! utilities for integrating various Census datasets
 pub mod join;
+pub mod regions;
+pub mod sink;
+pub mod weighted_centroid;
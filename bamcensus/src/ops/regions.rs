@@ -0,0 +1,78 @@
+use crate::model::regions::{RegionMethod, Regions};
+use crate::model::wide_table::WideTable;
+use serde_json::json;
+
+/// name of the region label column added by [`append_regions`].
+pub const REGION_COLUMN: &str = "region";
+/// name of the column added by [`append_regions`] flagging geographies that span
+/// more than one region.
+pub const REGION_SPLIT_COLUMN: &str = "region_split";
+
+/// tags each row of a wide table with the region it falls in, such as a sales
+/// territory, appending a region column with the region label and a region_split
+/// column that is true when the geography overlaps more than one region. rows are
+/// assigned by a point inside their geometry or by the region covering most of it,
+/// see [`RegionMethod`], and rows outside every region have a null label. rows
+/// without a geometry have null values in both columns.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::regions::{RegionMethod, Regions};
+/// use bamcensus::model::wide_table::WideTable;
+/// use bamcensus::ops::regions::append_regions;
+/// use bamcensus_core::model::identifier::{fips, Geoid};
+/// use geo::{polygon, Geometry};
+/// use serde_json::json;
+///
+/// let square = |x0: f64, x1: f64| {
+///     Geometry::Polygon(polygon![(x: x0, y: 0.0), (x: x1, y: 0.0), (x: x1, y: 1.0), (x: x0, y: 1.0), (x: x0, y: 0.0)])
+/// };
+/// let regions = Regions::new(vec![
+///     (String::from("west"), square(0.0, 1.0)),
+///     (String::from("east"), square(1.0, 2.0)),
+/// ])
+/// .unwrap();
+/// let tract = |tract: u64| Geoid::CensusTract(fips::State(8), fips::County(31), fips::CensusTract(tract));
+/// let rows = vec![
+///     (tract(100), String::from("B01001_001E"), json!["1200"], Some(square(0.2, 0.4))),
+///     (tract(200), String::from("B01001_001E"), json!["800"], Some(square(0.6, 1.6))),
+///     (tract(300), String::from("B01001_001E"), json!["500"], Some(square(3.0, 4.0))),
+/// ];
+/// let mut table = WideTable::pivot(rows);
+/// append_regions(&mut table, &regions, RegionMethod::LargestOverlap).unwrap();
+///
+/// assert_eq!(table.columns, vec!["B01001_001E", "region", "region_split"]);
+/// assert_eq!(table.rows[0].values[1..], [Some(json!["west"]), Some(json![false])]);
+/// assert_eq!(table.rows[1].values[1..], [Some(json!["east"]), Some(json![true])]);
+/// assert_eq!(table.rows[2].values[1..], [None, Some(json![false])]);
+/// ```
+pub fn append_regions(
+    table: &mut WideTable,
+    regions: &Regions,
+    method: RegionMethod,
+) -> Result<(), String> {
+    if !table.with_geometry {
+        return Err(String::from(
+            "cannot assign regions to a table without geometries",
+        ));
+    }
+    let assigned = table
+        .rows
+        .iter()
+        .map(|row| match &row.geometry {
+            Some(g) => {
+                let (region, count) = regions.assign(g, method);
+                (region.map(|r| json![r.label]), Some(json![count > 1]))
+            }
+            None => (None, None),
+        })
+        .collect::<Vec<_>>();
+    table.columns.push(String::from(REGION_COLUMN));
+    table.columns.push(String::from(REGION_SPLIT_COLUMN));
+    for (row, (label, split)) in table.rows.iter_mut().zip(assigned) {
+        row.values.push(label);
+        row.values.push(split);
+    }
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use super::{check_geometry, check_row_length, value_as_text, GeometryColumn, OutputSink};
+use crate::model::geometry_encoding::GeometryEncoding;
+use geo::Geometry;
+use serde_json::Value;
+use std::io::Write;
+
+/// writes rows as comma-delimited text, with the geometry column, if any, written in
+/// the configured [`GeometryEncoding`]. string values are written without quotes and
+/// null values, including the null geometries of a nullable geometry column, as empty
+/// cells.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::geometry_encoding::GeometryEncoding;
+/// use bamcensus::ops::sink::{CsvSink, GeometryColumn, OutputSink};
+/// use geo::{point, Geometry};
+/// use serde_json::json;
+///
+/// let mut buf: Vec<u8> = vec![];
+/// let mut sink = Box::new(CsvSink::new(&mut buf, GeometryEncoding::Wkt));
+/// let geometry = Geometry::Point(point!(x: 1.0, y: 2.0));
+/// let header = [String::from("geoid"), String::from("value")];
+/// sink.write_header(&header, GeometryColumn::Nullable).unwrap();
+/// sink.write_row(&[json!("08059"), json!(1.5)], Some(&geometry)).unwrap();
+/// sink.write_row(&[json!("08001"), json!(null)], Some(&geometry)).unwrap();
+/// // a row without a geometry has an empty geometry cell in a nullable column
+/// sink.write_row(&[json!("08031"), json!(2.5)], None).unwrap();
+/// sink.finish().unwrap();
+/// let out = String::from_utf8(buf).unwrap();
+/// assert_eq!(out, "geoid,value,geometry\n08059,1.5,POINT(1 2)\n08001,,POINT(1 2)\n08031,2.5,\n");
+///
+/// // and is rejected when the column is required
+/// let mut buf: Vec<u8> = vec![];
+/// let mut sink = Box::new(CsvSink::new(&mut buf, GeometryEncoding::Wkt));
+/// sink.write_header(&header, GeometryColumn::Required).unwrap();
+/// assert!(sink.write_row(&[json!("08031"), json!(2.5)], None).is_err());
+///
+/// // tabular output has no geometry column
+/// let mut buf: Vec<u8> = vec![];
+/// let mut sink = Box::new(CsvSink::new(&mut buf, GeometryEncoding::Wkt));
+/// sink.write_header(&[String::from("geoid")], GeometryColumn::Absent).unwrap();
+/// sink.write_row(&[json!("08059")], None).unwrap();
+/// assert!(sink.write_row(&[json!("08001")], Some(&geometry)).is_err());
+/// sink.finish().unwrap();
+/// assert_eq!(String::from_utf8(buf).unwrap(), "geoid\n08059\n");
+/// ```
+pub struct CsvSink<W: Write> {
+    writer: csv::Writer<W>,
+    geometry_encoding: GeometryEncoding,
+    columns: Vec<String>,
+    geometry_column: GeometryColumn,
+}
+
+impl<W: Write> CsvSink<W> {
+    pub fn new(writer: W, geometry_encoding: GeometryEncoding) -> CsvSink<W> {
+        CsvSink {
+            writer: csv::WriterBuilder::new().from_writer(writer),
+            geometry_encoding,
+            columns: vec![],
+            geometry_column: GeometryColumn::Required,
+        }
+    }
+}
+
+impl<W: Write> OutputSink for CsvSink<W> {
+    fn write_header(&mut self, columns: &[String], geometry: GeometryColumn) -> Result<(), String> {
+        self.columns = columns.to_vec();
+        self.geometry_column = geometry;
+        let header = columns
+            .iter()
+            .map(String::as_str)
+            .chain(geometry.is_present().then_some("geometry"));
+        self.writer
+            .write_record(header)
+            .map_err(|e| format!("failure writing CSV header: {e}"))
+    }
+
+    fn write_row(&mut self, values: &[Value], geometry: Option<&Geometry>) -> Result<(), String> {
+        check_row_length(&self.columns, values)?;
+        let geometry = check_geometry(self.geometry_column, geometry)?
+            .map(|g| self.geometry_encoding.encode(g))
+            .transpose()?;
+        let record = values
+            .iter()
+            .map(|v| value_as_text(v).unwrap_or_default())
+            .chain(
+                self.geometry_column
+                    .is_present()
+                    .then(|| geometry.unwrap_or_default()),
+            );
+        self.writer
+            .write_record(record)
+            .map_err(|e| format!("failure writing CSV row: {e}"))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("failure flushing CSV output: {e}"))
+    }
+}
@@ -0,0 +1,103 @@
+use super::{check_geometry, check_row_length, GeometryColumn, JsonObject, OutputSink};
+use geo::Geometry;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{BufWriter, Write};
+
+/// writes rows as a GeoJSON FeatureCollection, with each row a Feature whose
+/// properties are the row values in header order. features are streamed as they
+/// are written, and the collection is closed by [`OutputSink::finish`]. rows written
+/// without geometries, under a nullable or absent geometry column, are features with
+/// a null geometry.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::sink::{GeoJsonSink, GeometryColumn, OutputSink};
+/// use geo::{point, Geometry};
+/// use serde_json::json;
+///
+/// let mut buf: Vec<u8> = vec![];
+/// let mut sink = Box::new(GeoJsonSink::new(&mut buf));
+/// let geometry = Geometry::Point(point!(x: 1.0, y: 2.0));
+/// sink.write_header(&[String::from("geoid")], GeometryColumn::Required).unwrap();
+/// sink.write_row(&[json!("08059")], Some(&geometry)).unwrap();
+/// sink.write_row(&[json!("08001")], Some(&geometry)).unwrap();
+/// sink.finish().unwrap();
+///
+/// let collection: geojson::FeatureCollection = String::from_utf8(buf).unwrap().parse::<geojson::GeoJson>()
+///     .unwrap()
+///     .try_into()
+///     .unwrap();
+/// assert_eq!(collection.features.len(), 2);
+/// assert_eq!(collection.features[1].property("geoid"), Some(&json!("08001")));
+/// ```
+pub struct GeoJsonSink<W: Write> {
+    writer: BufWriter<W>,
+    columns: Vec<String>,
+    geometry_column: GeometryColumn,
+    rows_written: usize,
+}
+
+#[derive(Serialize)]
+struct Feature<'a> {
+    #[serde(rename = "type")]
+    feature_type: &'static str,
+    geometry: Option<geojson::Geometry>,
+    properties: JsonObject<'a>,
+}
+
+impl<W: Write> GeoJsonSink<W> {
+    pub fn new(writer: W) -> GeoJsonSink<W> {
+        GeoJsonSink {
+            writer: BufWriter::new(writer),
+            columns: vec![],
+            geometry_column: GeometryColumn::Required,
+            rows_written: 0,
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.writer
+            .write_all(bytes)
+            .map_err(|e| format!("failure writing GeoJSON output: {e}"))
+    }
+}
+
+impl<W: Write> OutputSink for GeoJsonSink<W> {
+    fn write_header(&mut self, columns: &[String], geometry: GeometryColumn) -> Result<(), String> {
+        self.columns = columns.to_vec();
+        self.geometry_column = geometry;
+        self.write_bytes(b"{\"type\":\"FeatureCollection\",\"features\":[\n")
+    }
+
+    fn write_row(&mut self, values: &[Value], geometry: Option<&Geometry>) -> Result<(), String> {
+        check_row_length(&self.columns, values)?;
+        let geometry = check_geometry(self.geometry_column, geometry)?
+            .map(|g| geojson::Geometry::new(geojson::Value::from(g)));
+        if self.rows_written > 0 {
+            self.write_bytes(b",\n")?;
+        }
+        let feature = Feature {
+            feature_type: "Feature",
+            geometry,
+            properties: JsonObject {
+                columns: &self.columns,
+                values,
+                with_geometry: false,
+                geometry: None,
+            },
+        };
+        serde_json::to_writer(&mut self.writer, &feature)
+            .map_err(|e| format!("failure serializing row as GeoJSON: {e}"))?;
+        self.rows_written += 1;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.write_bytes(b"\n]}\n")?;
+        self.writer
+            .flush()
+            .map_err(|e| format!("failure flushing GeoJSON output: {e}"))
+    }
+}
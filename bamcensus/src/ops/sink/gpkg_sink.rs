@@ -0,0 +1,424 @@
+use super::{check_geometry, check_row_length, GeometryColumn, OutputSink, STDOUT_PATH};
+use geo::{BoundingRect, Geometry, Rect};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection};
+use serde_json::Value;
+use std::path::Path;
+
+/// spatial reference system of TIGER/Lines geometries, NAD83 in degrees.
+const NAD83_SRS_ID: i32 = 4269;
+const NAD83_DEFINITION: &str = r#"GEOGCS["NAD83",DATUM["North_American_Datum_1983",SPHEROID["GRS 1980",6378137,298.257222101,AUTHORITY["EPSG","7019"]],TOWGS84[0,0,0,0,0,0,0],AUTHORITY["EPSG","6269"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4269"]]"#;
+const WGS84_DEFINITION: &str = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]"#;
+
+/// "GPKG" as the SQLite application id, and version 1.4 of the GeoPackage spec.
+const APPLICATION_ID: i32 = 0x4750_4B47;
+const USER_VERSION: i32 = 10400;
+
+/// the GeoPackage tables describing spatial reference systems, contents and
+/// geometry columns, with the spatial reference systems required by the spec and
+/// the NAD83 system of TIGER/Lines geometries.
+const METADATA_SQL: &str = r#"
+CREATE TABLE gpkg_spatial_ref_sys (
+    srs_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL PRIMARY KEY,
+    organization TEXT NOT NULL,
+    organization_coordsys_id INTEGER NOT NULL,
+    definition TEXT NOT NULL,
+    description TEXT
+);
+CREATE TABLE gpkg_contents (
+    table_name TEXT NOT NULL PRIMARY KEY,
+    data_type TEXT NOT NULL,
+    identifier TEXT UNIQUE,
+    description TEXT DEFAULT '',
+    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+    min_x DOUBLE,
+    min_y DOUBLE,
+    max_x DOUBLE,
+    max_y DOUBLE,
+    srs_id INTEGER,
+    CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+);
+CREATE TABLE gpkg_geometry_columns (
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL,
+    geometry_type_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL,
+    z TINYINT NOT NULL,
+    m TINYINT NOT NULL,
+    CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+    CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+    CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id)
+);
+INSERT INTO gpkg_spatial_ref_sys VALUES
+    ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', 'undefined cartesian coordinate reference system'),
+    ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', 'undefined geographic coordinate reference system');
+"#;
+
+/// writes rows to a GeoPackage, a SQLite database that GIS tools such as QGIS open
+/// directly, as a single table named after the file. rows with geometries are
+/// written as a feature table with a `geom` column in NAD83 (EPSG:4269), the
+/// coordinate reference system of TIGER/Lines, and tabular-only rows as an
+/// attributes table. an existing file at the path is replaced.
+///
+/// as rows are streamed, column types are not known ahead of time, so rows are
+/// staged in a temporary table and copied into the output table by
+/// [`OutputSink::finish`], with each column declared INTEGER, REAL or TEXT by the
+/// values it holds. the layer extent and geometry type are also recorded then.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::sink::{GeoPackageSink, GeometryColumn, OutputSink};
+/// use geo::{point, Geometry};
+/// use serde_json::json;
+///
+/// let path = std::env::temp_dir().join("bamcensus_gpkg_sink_example.gpkg");
+/// let mut sink = Box::new(GeoPackageSink::create(&path).unwrap());
+/// let header = [String::from("geoid"), String::from("value")];
+/// sink.write_header(&header, GeometryColumn::Required).unwrap();
+/// sink.write_row(&[json!("08059"), json!(1.5)], Some(&Geometry::Point(point!(x: -105.0, y: 39.5)))).unwrap();
+/// sink.write_row(&[json!("08001"), json!(null)], Some(&Geometry::Point(point!(x: -104.5, y: 39.9)))).unwrap();
+/// sink.finish().unwrap();
+///
+/// let conn = rusqlite::Connection::open(&path).unwrap();
+/// let (table, data_type, srs_id, min_x): (String, String, i64, f64) = conn
+///     .query_row("SELECT table_name, data_type, srs_id, min_x FROM gpkg_contents", [], |r| {
+///         Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))
+///     })
+///     .unwrap();
+/// assert_eq!((table.as_str(), data_type.as_str(), srs_id, min_x), ("bamcensus_gpkg_sink_example", "features", 4269, -105.0));
+/// let geometry_type: String = conn
+///     .query_row("SELECT geometry_type_name FROM gpkg_geometry_columns", [], |r| r.get(0))
+///     .unwrap();
+/// assert_eq!(geometry_type, "POINT");
+/// let (value, blob): (Option<f64>, Vec<u8>) = conn
+///     .query_row("SELECT value, geom FROM bamcensus_gpkg_sink_example WHERE geoid = '08059'", [], |r| {
+///         Ok((r.get(0)?, r.get(1)?))
+///     })
+///     .unwrap();
+/// assert_eq!(value, Some(1.5));
+/// assert_eq!(&blob[..2], b"GP");
+///
+/// // tabular output is an attributes table, replacing the file
+/// let mut sink = Box::new(GeoPackageSink::create(&path).unwrap());
+/// let header = [String::from("geoid"), String::from("jobs")];
+/// sink.write_header(&header, GeometryColumn::Absent).unwrap();
+/// sink.write_row(&[json!("08059"), json!(12)], None).unwrap();
+/// sink.finish().unwrap();
+/// let conn = rusqlite::Connection::open(&path).unwrap();
+/// let data_type: String = conn
+///     .query_row("SELECT data_type FROM gpkg_contents", [], |r| r.get(0))
+///     .unwrap();
+/// assert_eq!(data_type, "attributes");
+/// let jobs: i64 = conn
+///     .query_row("SELECT jobs FROM bamcensus_gpkg_sink_example", [], |r| r.get(0))
+///     .unwrap();
+/// assert_eq!(jobs, 12);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct GeoPackageSink {
+    conn: Connection,
+    table_name: String,
+    columns: Vec<String>,
+    column_types: Vec<ColumnType>,
+    geometry_column: GeometryColumn,
+    geometry_type: Option<&'static str>,
+    extent: Option<Rect>,
+}
+
+impl GeoPackageSink {
+    /// creates a GeoPackage at `path`, replacing any existing file. the table is
+    /// named after the file stem.
+    pub fn create(path: &Path) -> Result<GeoPackageSink, String> {
+        if path == Path::new(STDOUT_PATH) {
+            return Err(String::from(
+                "GeoPackage output must be written to a file, not stdout",
+            ));
+        }
+        if path.exists() {
+            std::fs::remove_file(path)
+                .map_err(|e| format!("failure replacing {}: {e}", path.display()))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("failure creating GeoPackage {}: {e}", path.display()))?;
+        let table_name = table_name(path);
+        Ok(GeoPackageSink {
+            conn,
+            table_name,
+            columns: vec![],
+            column_types: vec![],
+            geometry_column: GeometryColumn::Required,
+            geometry_type: None,
+            extent: None,
+        })
+    }
+
+    /// columns of the staging table, c0, c1, ..., followed by geom.
+    fn staging_columns(&self) -> Vec<String> {
+        (0..self.columns.len())
+            .map(|i| format!("c{i}"))
+            .chain(
+                self.geometry_column
+                    .is_present()
+                    .then(|| String::from("geom")),
+            )
+            .collect()
+    }
+
+    /// copies the staged rows into the output table and records it in the
+    /// GeoPackage contents.
+    fn write_table(&self) -> rusqlite::Result<()> {
+        let table = quote(&self.table_name);
+        let definitions = self
+            .columns
+            .iter()
+            .zip(self.column_types.iter())
+            .map(|(c, t)| format!("{} {}", quote(c), t.declared_type()))
+            .chain(
+                self.geometry_column
+                    .is_present()
+                    .then(|| format!("geom {}", self.geometry_type.unwrap_or("GEOMETRY"))),
+            )
+            .collect::<Vec<_>>();
+        self.conn.execute_batch(&format!(
+            "CREATE TABLE {table} (fid INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, {});",
+            definitions.join(", ")
+        ))?;
+        let output_columns = self
+            .columns
+            .iter()
+            .map(|c| quote(c))
+            .chain(
+                self.geometry_column
+                    .is_present()
+                    .then(|| String::from("geom")),
+            )
+            .collect::<Vec<_>>();
+        self.conn.execute_batch(&format!(
+            "INSERT INTO {table} ({}) SELECT {} FROM temp.staging ORDER BY rowid;",
+            output_columns.join(", "),
+            self.staging_columns().join(", ")
+        ))?;
+
+        let (data_type, srs_id) = if self.geometry_column.is_present() {
+            ("features", Some(NAD83_SRS_ID))
+        } else {
+            ("attributes", None)
+        };
+        let extent = self
+            .extent
+            .map(|r| (r.min().x, r.min().y, r.max().x, r.max().y));
+        self.conn.execute(
+            "INSERT INTO gpkg_contents (table_name, data_type, identifier, min_x, min_y, max_x, max_y, srs_id)
+             VALUES (?1, ?2, ?1, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                self.table_name,
+                data_type,
+                extent.map(|e| e.0),
+                extent.map(|e| e.1),
+                extent.map(|e| e.2),
+                extent.map(|e| e.3),
+                srs_id
+            ],
+        )?;
+        if self.geometry_column.is_present() {
+            self.conn.execute(
+                "INSERT INTO gpkg_geometry_columns VALUES (?1, 'geom', ?2, ?3, 0, 0)",
+                rusqlite::params![
+                    self.table_name,
+                    self.geometry_type.unwrap_or("GEOMETRY"),
+                    NAD83_SRS_ID
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputSink for GeoPackageSink {
+    fn write_header(&mut self, columns: &[String], geometry: GeometryColumn) -> Result<(), String> {
+        self.columns = columns.to_vec();
+        self.column_types = vec![ColumnType::Unknown; columns.len()];
+        self.geometry_column = geometry;
+        let staging = self.staging_columns().join(", ");
+        self.conn
+            .execute_batch(&format!(
+                "PRAGMA application_id = {APPLICATION_ID};
+                 PRAGMA user_version = {USER_VERSION};
+                 BEGIN;
+                 {METADATA_SQL}
+                 CREATE TEMP TABLE staging ({staging});"
+            ))
+            .map_err(|e| format!("failure creating GeoPackage tables: {e}"))?;
+        self.conn
+            .execute(
+                "INSERT INTO gpkg_spatial_ref_sys VALUES (?1, ?2, 'EPSG', ?2, ?3, ?4), (?5, ?6, 'EPSG', ?6, ?7, ?8)",
+                rusqlite::params![
+                    "WGS 84 geodetic",
+                    4326,
+                    WGS84_DEFINITION,
+                    "longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid",
+                    "NAD83",
+                    NAD83_SRS_ID,
+                    NAD83_DEFINITION,
+                    "longitude/latitude coordinates in decimal degrees on the NAD83 datum, as used by TIGER/Lines"
+                ],
+            )
+            .map_err(|e| format!("failure writing GeoPackage spatial reference systems: {e}"))?;
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value], geometry: Option<&Geometry>) -> Result<(), String> {
+        check_row_length(&self.columns, values)?;
+        let geometry = check_geometry(self.geometry_column, geometry)?;
+        for (column_type, value) in self.column_types.iter_mut().zip(values.iter()) {
+            *column_type = column_type.widen(value);
+        }
+        let blob = match geometry {
+            Some(g) => {
+                self.geometry_type = Some(match self.geometry_type {
+                    None => geometry_type_name(g),
+                    Some(t) if t == geometry_type_name(g) => t,
+                    Some(_) => "GEOMETRY",
+                });
+                if let Some(rect) = g.bounding_rect() {
+                    self.extent = Some(match self.extent {
+                        None => rect,
+                        Some(e) => Rect::new(
+                            (e.min().x.min(rect.min().x), e.min().y.min(rect.min().y)),
+                            (e.max().x.max(rect.max().x), e.max().y.max(rect.max().y)),
+                        ),
+                    });
+                }
+                Some(SqlValue::Blob(geometry_blob(g)?))
+            }
+            None => self.geometry_column.is_present().then_some(SqlValue::Null),
+        };
+        let params = values.iter().map(sql_value).chain(blob);
+        let placeholders = vec!["?"; self.staging_columns().len()].join(", ");
+        let mut statement = self
+            .conn
+            .prepare_cached(&format!("INSERT INTO temp.staging VALUES ({placeholders})"))
+            .map_err(|e| format!("failure preparing GeoPackage insert: {e}"))?;
+        statement
+            .execute(params_from_iter(params))
+            .map_err(|e| format!("failure writing GeoPackage row: {e}"))?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), String> {
+        self.write_table()
+            .and_then(|_| self.conn.execute_batch("COMMIT;"))
+            .map_err(|e| format!("failure writing GeoPackage table {}: {e}", self.table_name))
+    }
+}
+
+/// the narrowest SQLite type holding every non-null value seen in a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ColumnType {
+    Unknown,
+    Integer,
+    Real,
+    Text,
+}
+
+impl ColumnType {
+    fn widen(self, value: &Value) -> ColumnType {
+        let seen = match value {
+            Value::Null => return self,
+            Value::Bool(_) => ColumnType::Integer,
+            Value::Number(n) if n.is_i64() => ColumnType::Integer,
+            Value::Number(_) => ColumnType::Real,
+            _ => ColumnType::Text,
+        };
+        match (self, seen) {
+            (ColumnType::Unknown, seen) => seen,
+            (current, seen) if current == seen => current,
+            (ColumnType::Integer, ColumnType::Real) | (ColumnType::Real, ColumnType::Integer) => {
+                ColumnType::Real
+            }
+            _ => ColumnType::Text,
+        }
+    }
+
+    /// columns with only null values are declared TEXT.
+    fn declared_type(&self) -> &'static str {
+        match self {
+            ColumnType::Integer => "INTEGER",
+            ColumnType::Real => "REAL",
+            ColumnType::Unknown | ColumnType::Text => "TEXT",
+        }
+    }
+}
+
+fn sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => SqlValue::Integer(i),
+            (None, Some(f)) => SqlValue::Real(f),
+            (None, None) => SqlValue::Text(n.to_string()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+/// encodes a geometry as a GeoPackage geometry blob: a little-endian header with
+/// the spatial reference system and XY envelope, followed by the geometry as WKB.
+fn geometry_blob(geometry: &Geometry) -> Result<Vec<u8>, String> {
+    let wkb = wkb::geom_to_wkb(geometry)
+        .map_err(|e| format!("failure encoding geometry as WKB: {e:?}"))?;
+    let envelope = geometry.bounding_rect();
+    // flags: little-endian header, with an XY envelope or marked empty
+    let flags: u8 = match envelope {
+        Some(_) => 0b0000_0011,
+        None => 0b0001_0001,
+    };
+    let mut blob = Vec::with_capacity(40 + wkb.len());
+    blob.extend_from_slice(b"GP");
+    blob.push(0);
+    blob.push(flags);
+    blob.extend_from_slice(&NAD83_SRS_ID.to_le_bytes());
+    if let Some(rect) = envelope {
+        for coordinate in [rect.min().x, rect.max().x, rect.min().y, rect.max().y] {
+            blob.extend_from_slice(&coordinate.to_le_bytes());
+        }
+    }
+    blob.extend_from_slice(&wkb);
+    Ok(blob)
+}
+
+fn geometry_type_name(geometry: &Geometry) -> &'static str {
+    match geometry {
+        Geometry::Point(_) => "POINT",
+        Geometry::LineString(_) | Geometry::Line(_) => "LINESTRING",
+        Geometry::Polygon(_) | Geometry::Rect(_) | Geometry::Triangle(_) => "POLYGON",
+        Geometry::MultiPoint(_) => "MULTIPOINT",
+        Geometry::MultiLineString(_) => "MULTILINESTRING",
+        Geometry::MultiPolygon(_) => "MULTIPOLYGON",
+        Geometry::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+    }
+}
+
+/// the file stem with characters other than letters, digits and underscores
+/// replaced, so that the table name needs no quoting in GIS tools.
+fn table_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match stem.as_str() {
+        "" => String::from("bamcensus"),
+        _ => stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect(),
+    }
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
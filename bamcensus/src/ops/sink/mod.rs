@@ -0,0 +1,347 @@
+//! pluggable destinations for joined dataset rows.
+//!
+//! every [`OutputFormat`] is written by an [`OutputSink`], which receives a header
+//! of value column names followed by rows of values aligned with the header and,
+//! unless the output is tabular only, a geometry. sinks add the geometry column
+//! themselves, encoding it as appropriate for the format. to support a new format,
+//! implement [`OutputSink`] and add a case to [`create`].
+mod csv_sink;
+mod geojson_sink;
+#[cfg(feature = "gpkg")]
+mod gpkg_sink;
+mod ndjson_sink;
+#[cfg(feature = "parquet")]
+mod parquet_sink;
+
+pub use csv_sink::CsvSink;
+pub use geojson_sink::GeoJsonSink;
+#[cfg(feature = "gpkg")]
+pub use gpkg_sink::GeoPackageSink;
+pub use ndjson_sink::NdjsonSink;
+#[cfg(feature = "parquet")]
+pub use parquet_sink::ParquetSink;
+
+use crate::model::acs_tiger_row::AcsTigerRow;
+use crate::model::geometry_encoding::GeometryEncoding;
+use crate::model::lodes_wac_tiger_row::LodesWacTigerRow;
+use crate::model::output_format::OutputFormat;
+use crate::model::wide_table::WideTable;
+use bamcensus_acs::model::AcsValue;
+use bamcensus_core::model::identifier::{Geoid, HasGeoidString};
+use bamcensus_lehd::model::{WacSegment, WacValue};
+use geo::Geometry;
+use itertools::Itertools;
+use serde::ser::SerializeMap;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// path that, when used as an output file, writes to stdout instead.
+pub const STDOUT_PATH: &str = "-";
+
+/// whether the output of a sink has a geometry column, and whether rows may leave it
+/// null.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::sink::GeometryColumn;
+///
+/// assert_eq!(GeometryColumn::new(true, false), GeometryColumn::Required);
+/// assert_eq!(GeometryColumn::new(true, true), GeometryColumn::Nullable);
+/// assert_eq!(GeometryColumn::new(false, true), GeometryColumn::Absent);
+/// assert!(!GeometryColumn::Absent.is_present());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeometryColumn {
+    /// tabular output, rows have no geometry
+    Absent,
+    /// every row has a geometry
+    Required,
+    /// rows without a geometry, such as geoids kept by a join with `keep_unmatched`,
+    /// have a null geometry
+    Nullable,
+}
+
+impl GeometryColumn {
+    /// the geometry column of an output, which is nullable only when rows without a
+    /// geometry were kept on purpose.
+    pub fn new(with_geometry: bool, keep_unmatched: bool) -> GeometryColumn {
+        match (with_geometry, keep_unmatched) {
+            (false, _) => GeometryColumn::Absent,
+            (true, false) => GeometryColumn::Required,
+            (true, true) => GeometryColumn::Nullable,
+        }
+    }
+
+    /// true if the output has a geometry column.
+    pub fn is_present(&self) -> bool {
+        *self != GeometryColumn::Absent
+    }
+}
+
+/// a destination for rows of a joined dataset.
+pub trait OutputSink {
+    /// begins the output with the names of the value columns. must be called once,
+    /// before any rows are written. unless the `geometry` column is absent, it is
+    /// added by the sink.
+    fn write_header(&mut self, columns: &[String], geometry: GeometryColumn) -> Result<(), String>;
+
+    /// writes a row. the values align with the columns passed to
+    /// [`OutputSink::write_header`], and a geometry may only be provided when the
+    /// header was written with one. rows without a geometry are only accepted under a
+    /// nullable geometry column, where they are written with a null geometry.
+    fn write_row(&mut self, values: &[Value], geometry: Option<&Geometry>) -> Result<(), String>;
+
+    /// writes any buffered rows and completes the output.
+    fn finish(self: Box<Self>) -> Result<(), String>;
+}
+
+/// creates the sink for an output format, writing to the file at `path`, or to
+/// stdout if the path is [`STDOUT_PATH`]. the geometry encoding applies to
+/// formats that write geometries as text. GeoPackages are databases rather than
+/// streams, so they can only be written to a file.
+pub fn create(
+    format: OutputFormat,
+    path: &Path,
+    geometry_encoding: GeometryEncoding,
+) -> Result<Box<dyn OutputSink>, String> {
+    let sink: Box<dyn OutputSink> = match format {
+        OutputFormat::Csv => Box::new(CsvSink::new(open_writer(path)?, geometry_encoding)),
+        OutputFormat::Ndjson => Box::new(NdjsonSink::new(open_writer(path)?)),
+        OutputFormat::GeoJson => Box::new(GeoJsonSink::new(open_writer(path)?)),
+        #[cfg(feature = "parquet")]
+        OutputFormat::Parquet => Box::new(ParquetSink::new(open_writer(path)?, geometry_encoding)),
+        #[cfg(feature = "gpkg")]
+        OutputFormat::GeoPackage => Box::new(GeoPackageSink::create(path)?),
+    };
+    Ok(sink)
+}
+
+/// opens the file at `path` for writing, or stdout if the path is [`STDOUT_PATH`].
+fn open_writer(path: &Path) -> Result<Box<dyn Write + Send>, String> {
+    if path == Path::new(STDOUT_PATH) {
+        Ok(Box::new(std::io::stdout()))
+    } else {
+        let file = File::create(path)
+            .map_err(|e| format!("failure opening {} for writing: {e}", path.display()))?;
+        Ok(Box::new(file))
+    }
+}
+
+/// writes ACS rows to a sink with columns geoid, acs_field, acs_value and geometry,
+/// then finishes the sink. rows without a geometry are written with a null geometry
+/// if `keep_unmatched` is true, and fail the output otherwise.
+pub fn write_acs_rows(
+    mut sink: Box<dyn OutputSink>,
+    rows: impl IntoIterator<Item = AcsTigerRow>,
+    keep_unmatched: bool,
+) -> Result<(), String> {
+    let geometry = GeometryColumn::new(true, keep_unmatched);
+    sink.write_header(&columns(&["geoid", "acs_field", "acs_value"]), geometry)?;
+    for row in rows {
+        let values = acs_values(&row.geoid, row.acs_value);
+        sink.write_row(&values, row.geometry.as_ref())?;
+    }
+    sink.finish()
+}
+
+/// writes ACS values to a sink with columns geoid, acs_field and acs_value, without
+/// a geometry column, then finishes the sink.
+pub fn write_acs_values(
+    mut sink: Box<dyn OutputSink>,
+    rows: impl IntoIterator<Item = (Geoid, Vec<AcsValue>)>,
+) -> Result<(), String> {
+    let header = columns(&["geoid", "acs_field", "acs_value"]);
+    sink.write_header(&header, GeometryColumn::Absent)?;
+    for (geoid, acs_values_row) in rows {
+        for acs_value in acs_values_row {
+            sink.write_row(&acs_values(&geoid, acs_value), None)?;
+        }
+    }
+    sink.finish()
+}
+
+fn acs_values(geoid: &Geoid, acs_value: AcsValue) -> [Value; 3] {
+    [
+        Value::String(geoid.geoid_string()),
+        Value::String(acs_value.name),
+        acs_value.value,
+    ]
+}
+
+/// writes LODES WAC rows to a sink with columns geoid, lodes_field, lodes_value and
+/// geometry, then finishes the sink. if `with_descriptions` is true, the
+/// segment_description and segment_naics columns follow lodes_field, see
+/// [`segment_description_columns`].
+pub fn write_lodes_rows(
+    mut sink: Box<dyn OutputSink>,
+    rows: impl IntoIterator<Item = LodesWacTigerRow>,
+    with_descriptions: bool,
+) -> Result<(), String> {
+    sink.write_header(&lodes_columns(with_descriptions), GeometryColumn::Required)?;
+    for row in rows {
+        let values = lodes_values(&row.geoid, &row.value, with_descriptions);
+        sink.write_row(&values, Some(&row.geometry))?;
+    }
+    sink.finish()
+}
+
+/// writes LODES WAC values to a sink as in [`write_lodes_rows`], without a geometry
+/// column, then finishes the sink.
+pub fn write_lodes_values(
+    mut sink: Box<dyn OutputSink>,
+    rows: impl IntoIterator<Item = (Geoid, Vec<WacValue>)>,
+    with_descriptions: bool,
+) -> Result<(), String> {
+    sink.write_header(&lodes_columns(with_descriptions), GeometryColumn::Absent)?;
+    for (geoid, wac_values) in rows {
+        for value in wac_values.iter() {
+            sink.write_row(&lodes_values(&geoid, value, with_descriptions), None)?;
+        }
+    }
+    sink.finish()
+}
+
+fn lodes_columns(with_descriptions: bool) -> Vec<String> {
+    if with_descriptions {
+        columns(&[
+            "geoid",
+            "lodes_field",
+            "segment_description",
+            "segment_naics",
+            "lodes_value",
+        ])
+    } else {
+        columns(&["geoid", "lodes_field", "lodes_value"])
+    }
+}
+
+fn lodes_values(geoid: &Geoid, value: &WacValue, with_descriptions: bool) -> Vec<Value> {
+    let mut values = vec![
+        Value::String(geoid.geoid_string()),
+        Value::String(value.segment.to_string()),
+    ];
+    if with_descriptions {
+        let (description, naics) = segment_description_columns(&value.segment);
+        values.push(Value::String(description));
+        values.push(Value::String(naics));
+    }
+    values.push(serde_json::json![value.value]);
+    values
+}
+
+/// the human-readable description of a WAC segment and its semicolon-delimited
+/// NAICS sector codes. segments that are not industry sectors have no NAICS codes,
+/// which is written as an empty string so that every row has the same columns.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::sink::segment_description_columns;
+/// use bamcensus_lehd::model::WacSegment;
+///
+/// let (description, naics) = segment_description_columns(&WacSegment::CNS05);
+/// assert_eq!(description, "Number of jobs in NAICS sector 31-33 (Manufacturing)");
+/// assert_eq!(naics, "31;32;33");
+///
+/// let (_, naics) = segment_description_columns(&WacSegment::C000);
+/// assert_eq!(naics, "");
+/// ```
+pub fn segment_description_columns(segment: &WacSegment) -> (String, String) {
+    let naics = segment
+        .naics()
+        .map(|codes| codes.iter().join(";"))
+        .unwrap_or_default();
+    (segment.description(), naics)
+}
+
+/// writes a wide table to a sink with columns geoid, each value column, and
+/// geometry if the table has geometries, then finishes the sink. missing values are
+/// written as null, and so are missing geometries if `keep_unmatched` is true.
+pub fn write_wide_table(
+    mut sink: Box<dyn OutputSink>,
+    table: &WideTable,
+    keep_unmatched: bool,
+) -> Result<(), String> {
+    let header = std::iter::once(String::from("geoid"))
+        .chain(table.columns.iter().cloned())
+        .collect::<Vec<_>>();
+    let geometry = GeometryColumn::new(table.with_geometry, keep_unmatched);
+    sink.write_header(&header, geometry)?;
+    for row in table.rows.iter() {
+        let values = std::iter::once(Value::String(row.geoid.geoid_string()))
+            .chain(row.values.iter().map(|v| v.clone().unwrap_or(Value::Null)))
+            .collect::<Vec<_>>();
+        sink.write_row(&values, row.geometry.as_ref())?;
+    }
+    sink.finish()
+}
+
+fn columns(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| String::from(*n)).collect()
+}
+
+/// writes a value as a text cell. strings are written without quotes and nulls
+/// as empty cells.
+fn value_as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// a JSON object of columns and values that keeps the column order of the header,
+/// followed by a GeoJSON geometry, or null, if `with_geometry` is true.
+struct JsonObject<'a> {
+    columns: &'a [String],
+    values: &'a [Value],
+    with_geometry: bool,
+    geometry: Option<&'a geojson::Geometry>,
+}
+
+impl Serialize for JsonObject<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (column, value) in self.columns.iter().zip(self.values.iter()) {
+            map.serialize_entry(column, value)?;
+        }
+        if self.with_geometry {
+            map.serialize_entry("geometry", &self.geometry)?;
+        }
+        map.end()
+    }
+}
+
+/// checks that a row only has a geometry when the header has a geometry column, and
+/// only lacks one when that column is nullable.
+fn check_geometry(
+    column: GeometryColumn,
+    geometry: Option<&Geometry>,
+) -> Result<Option<&Geometry>, String> {
+    match (column, geometry) {
+        (GeometryColumn::Absent, Some(_)) => Err(String::from(
+            "row has a geometry but the header has no geometry column",
+        )),
+        (GeometryColumn::Required, None) => Err(String::from(
+            "row has no geometry but the header geometry column is not nullable",
+        )),
+        (_, geometry) => Ok(geometry),
+    }
+}
+
+/// checks that a row has one value per header column.
+fn check_row_length(columns: &[String], values: &[Value]) -> Result<(), String> {
+    if columns.len() != values.len() {
+        Err(format!(
+            "row has {} values but the header has {} columns",
+            values.len(),
+            columns.len()
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use super::{check_geometry, check_row_length, GeometryColumn, JsonObject, OutputSink};
+use geo::Geometry;
+use serde_json::Value;
+use std::io::{BufWriter, Write};
+
+/// writes rows as newline-delimited JSON, one object per row with a key per column
+/// in header order, followed by the geometry, if the header has one, as a GeoJSON
+/// geometry object, or null for rows without a geometry under a nullable geometry
+/// column.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::sink::{GeometryColumn, NdjsonSink, OutputSink};
+/// use geo::{point, Geometry};
+/// use serde_json::json;
+///
+/// let mut buf: Vec<u8> = vec![];
+/// let mut sink = Box::new(NdjsonSink::new(&mut buf));
+/// let geometry = Geometry::Point(point!(x: 1.0, y: 2.0));
+/// let header = [String::from("value"), String::from("geoid")];
+/// sink.write_header(&header, GeometryColumn::Nullable).unwrap();
+/// sink.write_row(&[json!(1.5), json!("08059")], Some(&geometry)).unwrap();
+/// sink.write_row(&[json!(2.5), json!("08031")], None).unwrap();
+/// sink.finish().unwrap();
+/// let out = String::from_utf8(buf).unwrap();
+/// assert_eq!(
+///     out,
+///     "{\"value\":1.5,\"geoid\":\"08059\",\"geometry\":{\"type\":\"Point\",\"coordinates\":[1.0,2.0]}}\n\
+///      {\"value\":2.5,\"geoid\":\"08031\",\"geometry\":null}\n"
+/// );
+/// ```
+pub struct NdjsonSink<W: Write> {
+    writer: BufWriter<W>,
+    columns: Vec<String>,
+    geometry_column: GeometryColumn,
+}
+
+impl<W: Write> NdjsonSink<W> {
+    pub fn new(writer: W) -> NdjsonSink<W> {
+        NdjsonSink {
+            writer: BufWriter::new(writer),
+            columns: vec![],
+            geometry_column: GeometryColumn::Required,
+        }
+    }
+}
+
+impl<W: Write> OutputSink for NdjsonSink<W> {
+    fn write_header(&mut self, columns: &[String], geometry: GeometryColumn) -> Result<(), String> {
+        self.columns = columns.to_vec();
+        self.geometry_column = geometry;
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value], geometry: Option<&Geometry>) -> Result<(), String> {
+        check_row_length(&self.columns, values)?;
+        let geometry = check_geometry(self.geometry_column, geometry)?
+            .map(|g| geojson::Geometry::new(geojson::Value::from(g)));
+        let object = JsonObject {
+            columns: &self.columns,
+            values,
+            with_geometry: self.geometry_column.is_present(),
+            geometry: geometry.as_ref(),
+        };
+        serde_json::to_writer(&mut self.writer, &object)
+            .map_err(|e| format!("failure serializing row as JSON: {e}"))?;
+        self.writer
+            .write_all(b"\n")
+            .map_err(|e| format!("failure writing JSON row: {e}"))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.writer
+            .flush()
+            .map_err(|e| format!("failure flushing JSON output: {e}"))
+    }
+}
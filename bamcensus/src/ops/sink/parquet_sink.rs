@@ -0,0 +1,151 @@
+use super::{check_geometry, check_row_length, value_as_text, GeometryColumn, OutputSink};
+use crate::model::geometry_encoding::GeometryEncoding;
+use arrow_array::{builder::StringBuilder, ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+use geo::Geometry;
+use parquet::arrow::ArrowWriter;
+use serde_json::Value;
+use std::io::Write;
+use std::sync::Arc;
+
+/// number of rows buffered before they are written as a parquet row group.
+const BATCH_SIZE: usize = 8192;
+
+/// writes rows to an Apache Parquet file. as rows are streamed, column types are not
+/// known ahead of time, so every column is written as nullable text, with values
+/// formatted as in CSV output and the geometry, if any, in the configured
+/// [`GeometryEncoding`].
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::geometry_encoding::GeometryEncoding;
+/// use bamcensus::ops::sink::{GeometryColumn, OutputSink, ParquetSink};
+/// use geo::{point, Geometry};
+/// use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+/// use serde_json::json;
+///
+/// let path = std::env::temp_dir().join("bamcensus_parquet_sink_example.parquet");
+/// let file = std::fs::File::create(&path).unwrap();
+/// let mut sink = Box::new(ParquetSink::new(file, GeometryEncoding::Wkt));
+/// let geometry = Geometry::Point(point!(x: 1.0, y: 2.0));
+/// let header = [String::from("geoid"), String::from("value")];
+/// sink.write_header(&header, GeometryColumn::Required).unwrap();
+/// sink.write_row(&[json!("08059"), json!(1.5)], Some(&geometry)).unwrap();
+/// sink.write_row(&[json!("08001"), json!(null)], Some(&geometry)).unwrap();
+/// sink.finish().unwrap();
+///
+/// let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+///     .unwrap()
+///     .build()
+///     .unwrap();
+/// let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(batches[0].num_rows(), 2);
+/// assert_eq!(batches[0].schema().field(2).name(), "geometry");
+/// assert_eq!(batches[0].column(1).null_count(), 1);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct ParquetSink<W: Write + Send> {
+    writer: Option<W>,
+    arrow_writer: Option<ArrowWriter<W>>,
+    schema: Arc<Schema>,
+    geometry_encoding: GeometryEncoding,
+    columns: Vec<String>,
+    geometry_column: GeometryColumn,
+    builders: Vec<StringBuilder>,
+    buffered_rows: usize,
+}
+
+impl<W: Write + Send> ParquetSink<W> {
+    pub fn new(writer: W, geometry_encoding: GeometryEncoding) -> ParquetSink<W> {
+        ParquetSink {
+            writer: Some(writer),
+            arrow_writer: None,
+            schema: Arc::new(Schema::empty()),
+            geometry_encoding,
+            columns: vec![],
+            geometry_column: GeometryColumn::Required,
+            builders: vec![],
+            buffered_rows: 0,
+        }
+    }
+
+    /// writes the buffered rows as a record batch.
+    fn flush_batch(&mut self) -> Result<(), String> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let arrow_writer = self
+            .arrow_writer
+            .as_mut()
+            .ok_or_else(|| String::from("parquet header must be written before rows"))?;
+        let arrays = self
+            .builders
+            .iter_mut()
+            .map(|b| Arc::new(b.finish()) as ArrayRef)
+            .collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(self.schema.clone(), arrays)
+            .map_err(|e| format!("failure building parquet record batch: {e}"))?;
+        arrow_writer
+            .write(&batch)
+            .map_err(|e| format!("failure writing parquet record batch: {e}"))?;
+        self.buffered_rows = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> OutputSink for ParquetSink<W> {
+    fn write_header(&mut self, columns: &[String], geometry: GeometryColumn) -> Result<(), String> {
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| String::from("parquet header has already been written"))?;
+        let fields = columns
+            .iter()
+            .map(String::as_str)
+            .chain(geometry.is_present().then_some("geometry"))
+            .map(|name| Field::new(name, DataType::Utf8, true))
+            .collect::<Vec<_>>();
+        self.schema = Arc::new(Schema::new(fields));
+        let arrow_writer = ArrowWriter::try_new(writer, self.schema.clone(), None)
+            .map_err(|e| format!("failure creating parquet writer: {e}"))?;
+        self.arrow_writer = Some(arrow_writer);
+        self.columns = columns.to_vec();
+        self.geometry_column = geometry;
+        self.builders = (0..self.schema.fields().len())
+            .map(|_| StringBuilder::new())
+            .collect();
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[Value], geometry: Option<&Geometry>) -> Result<(), String> {
+        check_row_length(&self.columns, values)?;
+        let geometry = check_geometry(self.geometry_column, geometry)?
+            .map(|g| self.geometry_encoding.encode(g))
+            .transpose()?;
+        let cells = values
+            .iter()
+            .map(value_as_text)
+            .chain(self.geometry_column.is_present().then_some(geometry));
+        for (builder, cell) in self.builders.iter_mut().zip(cells) {
+            builder.append_option(cell);
+        }
+        self.buffered_rows += 1;
+        if self.buffered_rows >= BATCH_SIZE {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), String> {
+        self.flush_batch()?;
+        let arrow_writer = self
+            .arrow_writer
+            .take()
+            .ok_or_else(|| String::from("parquet header must be written before finishing"))?;
+        arrow_writer
+            .close()
+            .map_err(|e| format!("failure closing parquet writer: {e}"))?;
+        Ok(())
+    }
+}
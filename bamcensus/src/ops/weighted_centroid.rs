@@ -0,0 +1,129 @@
+use crate::model::wide_table::WideTable;
+use bamcensus_core::model::identifier::{Geoid, GeoidType};
+use geo::Point;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// name of the longitude column added by [`append_weighted_centroids`].
+pub const WEIGHTED_CENTROID_LON_COLUMN: &str = "weighted_centroid_lon";
+/// name of the latitude column added by [`append_weighted_centroids`].
+pub const WEIGHTED_CENTROID_LAT_COLUMN: &str = "weighted_centroid_lat";
+
+/// computes the weighted centroid of each parent geography of `parent_type` from the
+/// centroids and weights of its children, typically blocks and their decennial
+/// populations, giving population-weighted centroids. children are grouped by
+/// truncating their geoid, so any child type below the parent works, such as tracts
+/// within counties. child centroids can be read with the
+/// [`bamcensus_tiger::model::GeometryTransform::Centroid`] transform.
+///
+/// the centroid is computed as the Census Bureau computes centers of population: the
+/// latitude is the weighted mean latitude, and the longitude is the weighted mean
+/// longitude with each weight scaled by the cosine of the latitude, which corrects
+/// for meridians converging towards the poles. parents whose children all have a
+/// weight of zero have no weighted centroid and map to None, in which case the
+/// geometric centroid of the parent is the usual fallback. weights must be
+/// non-negative numbers, so ACS sentinel values must be removed first.
+///
+/// see <https://www.census.gov/geographies/reference-files/time-series/geo/centers-population.html>
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::ops::weighted_centroid::weighted_centroids;
+/// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+/// use geo::point;
+///
+/// let block = |county: u64, code: &str| Geoid::block(8, county, 100, code).unwrap();
+/// let blocks = vec![
+///     (block(31, "1000"), point!(x: -105.0, y: 39.7), 300.0),
+///     (block(31, "1001"), point!(x: -104.9, y: 39.7), 100.0),
+///     (block(31, "1002"), point!(x: -104.8, y: 39.8), 0.0),
+///     (block(59, "1000"), point!(x: -105.2, y: 39.6), 0.0),
+/// ];
+/// let centroids = weighted_centroids(&blocks, GeoidType::County).unwrap();
+///
+/// let denver = centroids[&Geoid::county(8, 31).unwrap()].unwrap();
+/// assert!((denver.y() - 39.7).abs() < 1e-9);
+/// assert!((denver.x() - -104.975).abs() < 1e-9);
+///
+/// // a county without any population has no weighted centroid
+/// assert_eq!(centroids[&Geoid::county(8, 59).unwrap()], None);
+///
+/// let negative = vec![(block(31, "1000"), point!(x: -105.0, y: 39.7), -666666666.0)];
+/// assert!(weighted_centroids(&negative, GeoidType::County).is_err());
+/// ```
+pub fn weighted_centroids(
+    children: &[(Geoid, Point, f64)],
+    parent_type: GeoidType,
+) -> Result<BTreeMap<Geoid, Option<Point>>, String> {
+    // per parent: sum of weights, of weighted latitudes, and of cosine-scaled weights
+    // and cosine-scaled weighted longitudes
+    let mut sums: BTreeMap<Geoid, [f64; 4]> = BTreeMap::new();
+    for (geoid, point, weight) in children.iter() {
+        if !weight.is_finite() || *weight < 0.0 {
+            return Err(format!(
+                "weight of {geoid} must be a non-negative number, found {weight}"
+            ));
+        }
+        let parent = geoid.truncate_geoid_to_type(&parent_type)?;
+        let cos_lat = point.y().to_radians().cos();
+        let sum = sums.entry(parent).or_insert([0.0; 4]);
+        sum[0] += weight;
+        sum[1] += weight * point.y();
+        sum[2] += weight * cos_lat;
+        sum[3] += weight * cos_lat * point.x();
+    }
+    let centroids = sums
+        .into_iter()
+        .map(|(parent, [weight, lat, cos_weight, lon])| {
+            let centroid = (weight > 0.0 && cos_weight > 0.0)
+                .then(|| Point::new(lon / cos_weight, lat / weight));
+            (parent, centroid)
+        })
+        .collect();
+    Ok(centroids)
+}
+
+/// appends the weighted centroid of each row of a wide table, as computed by
+/// [`weighted_centroids`], in a longitude and a latitude column. rows without a
+/// weighted centroid, either because they are missing from `centroids` or because
+/// their weight is zero, have null values in both columns.
+///
+/// # Example
+///
+/// ```rust
+/// use bamcensus::model::wide_table::WideTable;
+/// use bamcensus::ops::weighted_centroid::{append_weighted_centroids, weighted_centroids};
+/// use bamcensus_core::model::identifier::{Geoid, GeoidType};
+/// use geo::point;
+/// use serde_json::json;
+///
+/// let blocks = vec![(Geoid::block(8, 31, 100, "1000").unwrap(), point!(x: -105.0, y: 39.7), 300.0)];
+/// let centroids = weighted_centroids(&blocks, GeoidType::County).unwrap();
+///
+/// let rows = vec![
+///     (Geoid::county(8, 31).unwrap(), String::from("B01001_001E"), json!["715522"], None),
+///     (Geoid::county(8, 59).unwrap(), String::from("B01001_001E"), json!["582910"], None),
+/// ];
+/// let mut table = WideTable::pivot(rows);
+/// append_weighted_centroids(&mut table, &centroids);
+/// assert_eq!(table.columns, vec!["B01001_001E", "weighted_centroid_lon", "weighted_centroid_lat"]);
+/// assert_eq!(table.rows[0].values[1..], [Some(json![-105.0]), Some(json![39.7])]);
+/// assert_eq!(table.rows[1].values[1..], [None, None]);
+/// ```
+pub fn append_weighted_centroids(
+    table: &mut WideTable,
+    centroids: &BTreeMap<Geoid, Option<Point>>,
+) {
+    table
+        .columns
+        .push(String::from(WEIGHTED_CENTROID_LON_COLUMN));
+    table
+        .columns
+        .push(String::from(WEIGHTED_CENTROID_LAT_COLUMN));
+    for row in table.rows.iter_mut() {
+        let centroid = centroids.get(&row.geoid).copied().flatten();
+        row.values.push(centroid.map(|p| json![p.x()]));
+        row.values.push(centroid.map(|p| json![p.y()]));
+    }
+}
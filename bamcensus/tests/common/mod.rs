@@ -0,0 +1,176 @@
+//! helpers for exercising the API request paths offline against a local [`MockServer`],
+//! serving canned responses in place of the census ACS API, TIGER/Lines directory and
+//! LODES directory.
+use std::io::{Cursor, Write};
+use std::time::Duration;
+use wiremock::matchers::path;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// registers the response of a server for a request path, such as
+/// `/data/2020/acs/acs5`, whatever the method and query string. requests for paths
+/// without a response are answered with 404.
+pub async fn route(server: &MockServer, request_path: &str, status: u16, body: impl Into<Vec<u8>>) {
+    Mock::given(path(request_path))
+        .respond_with(ResponseTemplate::new(status).set_body_bytes(body))
+        .mount(server)
+        .await;
+}
+
+/// registers the response of a server for a request path as in [`route`], sent only
+/// after `delay`, such as to keep a download in flight.
+pub async fn route_delayed(
+    server: &MockServer,
+    request_path: &str,
+    delay: Duration,
+    body: impl Into<Vec<u8>>,
+) {
+    Mock::given(path(request_path))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_bytes(body)
+                .set_delay(delay),
+        )
+        .mount(server)
+        .await;
+}
+
+/// the path and query of each request a server received for a path, in order.
+pub async fn requests_for(server: &MockServer, request_path: &str) -> Vec<String> {
+    server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|request| request.url.path() == request_path)
+        .map(|request| match request.url.query() {
+            Some(query) => format!("{request_path}?{query}"),
+            None => String::from(request_path),
+        })
+        .collect()
+}
+
+/// registers the responses of a server for a request path that fail with `status`
+/// for the first `failures` requests, and succeed with `body` afterwards.
+pub async fn route_failing(
+    server: &MockServer,
+    request_path: &str,
+    failures: u64,
+    status: u16,
+    body: impl Into<Vec<u8>>,
+) {
+    Mock::given(path(request_path))
+        .respond_with(ResponseTemplate::new(status))
+        .up_to_n_times(failures)
+        .mount(server)
+        .await;
+    route(server, request_path, 200, body).await;
+}
+
+/// a gzipped LODES WAC file with a C000 job count for each block, and every other
+/// segment zero, in the layout of a LODES download.
+pub fn wac_gz(rows: &[(&str, u64)]) -> Vec<u8> {
+    const SEGMENTS: [&str; 41] = [
+        "C000", "CA01", "CA02", "CA03", "CE01", "CE02", "CE03", "CNS01", "CNS02", "CNS03", "CNS04",
+        "CNS05", "CNS06", "CNS07", "CNS08", "CNS09", "CNS10", "CNS11", "CNS12", "CNS13", "CNS14",
+        "CNS15", "CNS16", "CNS17", "CNS18", "CNS19", "CNS20", "CR01", "CR02", "CR03", "CR04",
+        "CR05", "CR07", "CT01", "CT02", "CD01", "CD02", "CD03", "CD04", "CS01", "CS02",
+    ];
+    let mut csv = format!("w_geocode,{},createdate\n", SEGMENTS.join(","));
+    for (block, jobs) in rows {
+        let zeros = vec!["0"; SEGMENTS.len() - 1].join(",");
+        csv.push_str(&format!("{block},{jobs},{zeros},20230321\n"));
+    }
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(csv.as_bytes()).expect("write gzip");
+    encoder.finish().expect("finish gzip")
+}
+
+/// a zip archive holding a polygon shapefile with one rectangular feature per row,
+/// keyed by a GEOID column, in the layout of a TIGER/Lines download. each rectangle
+/// is (min x, min y, max x, max y) in degrees.
+pub fn shapefile_zip(rows: &[(&str, [f64; 4])]) -> Vec<u8> {
+    zip_shapefile("GEOID", rows)
+}
+
+/// a zip archive as in [`shapefile_zip`], keyed by the LINEARID column of the
+/// national feature files, such as primary roads.
+pub fn feature_zip(rows: &[(&str, [f64; 4])]) -> Vec<u8> {
+    zip_shapefile("LINEARID", rows)
+}
+
+fn zip_shapefile(column: &str, rows: &[(&str, [f64; 4])]) -> Vec<u8> {
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    let files = [
+        ("mock.shp", shp_bytes(rows)),
+        ("mock.dbf", dbf_bytes(column, rows)),
+    ];
+    for (name, contents) in files {
+        archive.start_file(name, options).expect("start zip entry");
+        archive.write_all(&contents).expect("write zip entry");
+    }
+    archive.finish().expect("finish zip").into_inner()
+}
+
+/// a .shp file of rectangles, following the ESRI shapefile technical description.
+/// outer rings are clockwise.
+fn shp_bytes(rows: &[(&str, [f64; 4])]) -> Vec<u8> {
+    const POLYGON: i32 = 5;
+    let mut records = Vec::new();
+    for (idx, (_, [x0, y0, x1, y1])) in rows.iter().enumerate() {
+        let ring = [(*x0, *y0), (*x0, *y1), (*x1, *y1), (*x1, *y0), (*x0, *y0)];
+        let mut content = Vec::new();
+        content.extend(POLYGON.to_le_bytes());
+        for v in [x0, y0, x1, y1] {
+            content.extend(v.to_le_bytes());
+        }
+        content.extend(1i32.to_le_bytes()); // parts
+        content.extend((ring.len() as i32).to_le_bytes()); // points
+        content.extend(0i32.to_le_bytes()); // first part starts at point 0
+        for (x, y) in ring {
+            content.extend(x.to_le_bytes());
+            content.extend(y.to_le_bytes());
+        }
+        records.extend((idx as i32 + 1).to_be_bytes());
+        records.extend((content.len() as i32 / 2).to_be_bytes());
+        records.extend(content);
+    }
+    let bbox = rows.iter().fold(
+        [f64::MAX, f64::MAX, f64::MIN, f64::MIN],
+        |[a, b, c, d], (_, [x0, y0, x1, y1])| [a.min(*x0), b.min(*y0), c.max(*x1), d.max(*y1)],
+    );
+    let mut header = Vec::new();
+    header.extend(9994i32.to_be_bytes());
+    header.extend([0u8; 20]);
+    header.extend(((100 + records.len()) as i32 / 2).to_be_bytes());
+    header.extend(1000i32.to_le_bytes());
+    header.extend(POLYGON.to_le_bytes());
+    for v in bbox {
+        header.extend(v.to_le_bytes());
+    }
+    header.extend([0u8; 32]); // z and m ranges
+    header.extend(records);
+    header
+}
+
+/// a dBase III .dbf file with a single character column, of at most 10 characters.
+fn dbf_bytes(column: &str, rows: &[(&str, [f64; 4])]) -> Vec<u8> {
+    let width = rows.iter().map(|(g, _)| g.len()).max().unwrap_or(1);
+    let mut bytes = vec![0x03, 124, 1, 1];
+    bytes.extend((rows.len() as u32).to_le_bytes());
+    bytes.extend(((32 + 32 + 1) as u16).to_le_bytes()); // header length
+    bytes.extend(((1 + width) as u16).to_le_bytes()); // record length
+    bytes.extend([0u8; 20]);
+    let mut field = [0u8; 32];
+    field[..column.len()].copy_from_slice(column.as_bytes());
+    field[11] = b'C';
+    field[16] = width as u8;
+    bytes.extend(field);
+    bytes.push(0x0D);
+    for (geoid, _) in rows {
+        bytes.push(b' '); // not deleted
+        bytes.extend(format!("{geoid:<width$}").as_bytes());
+    }
+    bytes.push(0x1A);
+    bytes
+}
@@ -0,0 +1,844 @@
+//! runs the ACS, LODES and TIGER/Lines request paths against a local mock server, so
+//! that the request and response plumbing is tested without reaching census.gov.
+mod common;
+
+use bamcensus::app::{acs_tiger, lodes_bulk, lodes_tiger};
+use bamcensus::model::area_of_interest::AreaOfInterest;
+use bamcensus::model::bulk_manifest::{BulkManifest, BulkSettings, StateStatus};
+use bamcensus::model::geometry_encoding::GeometryEncoding;
+use bamcensus::model::join_options::JoinOptions;
+use bamcensus::model::output_format::OutputFormat;
+use bamcensus::ops::sink::{self, CsvSink};
+use bamcensus_acs::api::{acs_api, acs_variables_api};
+use bamcensus_acs::model::{AcsApiQueryParams, AcsGeoidQuery, AcsType};
+use bamcensus_acs::ops::acs_table;
+use bamcensus_core::model::identifier::{fips, Geoid, GeoidType, HasGeoidString};
+use bamcensus_core::model::ByteCounter;
+use bamcensus_core::ops::http;
+use bamcensus_lehd::api::lodes_api::{self, LodesResult};
+use bamcensus_lehd::model::{
+    DownloadOptions, LodesDataset, LodesEdition, LodesJobType, RetryPolicy, WacSegment,
+    WorkplaceSegment,
+};
+use bamcensus_tiger::model::{RunOptions, TigerFeature, TigerResourceBuilder};
+use bamcensus_tiger::ops::tiger_api;
+use common::{requests_for, route, route_failing};
+use geo::{BoundingRect, Geometry, Rect};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use wiremock::MockServer;
+
+const ADAMS: (u64, u64) = (8, 1);
+const DENVER: (u64, u64) = (8, 31);
+const JEFFERSON: (u64, u64) = (8, 59);
+const DENVER_CITY: (u64, u64) = (8, 20000);
+const CHEYENNE: (u64, u64) = (56, 13900);
+
+/// starts a server for one test, which ACS queries and TIGER/Lines downloads are
+/// directed to with their base URLs. it serves:
+///   - 2020 ACS county populations in Colorado
+///   - a 2019 ACS error envelope and a 2018 ACS server error
+///   - the 2020 TIGER/Lines county archive, holding Denver and Jefferson counties
+///   - 2021 ACS place populations nationally, for one place in each of Colorado and
+///     Wyoming, and the 2021 TIGER/Lines place archives of both states
+///   - 2022 ACS county populations in Colorado, for Adams and Denver counties, and a
+///     2022 TIGER/Lines county archive holding only Denver county
+///   - the 2022 ACS metadata of table B19001, abridged to its first two estimates
+///   - the 2020 TIGER/Lines block group archive of Colorado, holding two block groups
+///     of a Denver tract and one of Jefferson county
+async fn start() -> MockServer {
+    let server = MockServer::start().await;
+
+    let counties = serde_json::json!([
+        ["NAME", "B01001_001E", "state", "county"],
+        ["Denver County, Colorado", "715522", "08", "031"],
+        ["Jefferson County, Colorado", "582910", "08", "059"]
+    ]);
+    route(&server, "/data/2020/acs/acs5", 200, counties.to_string()).await;
+    let error = serde_json::json!({"error": "error: unknown variable 'B01001_999E'"});
+    route(&server, "/data/2019/acs/acs5", 200, error.to_string()).await;
+    route(&server, "/data/2018/acs/acs5", 500, "unavailable").await;
+
+    let archive = common::shapefile_zip(&[
+        ("08031", [-105.11, 39.61, -104.60, 39.91]),
+        ("08059", [-105.40, 39.13, -105.05, 39.93]),
+    ]);
+    route(&server, &archive_path(2020, &county(DENVER)), 200, archive).await;
+
+    let places = serde_json::json!([
+        ["NAME", "B01001_001E", "state", "place"],
+        ["Denver city, Colorado", "706799", "08", "20000"],
+        ["Cheyenne city, Wyoming", "65010", "56", "13900"]
+    ]);
+    route(&server, "/data/2021/acs/acs5", 200, places.to_string()).await;
+    // each state archive also holds a place that was not queried
+    let colorado = common::shapefile_zip(&[
+        ("0804000", [-104.89, 39.61, -104.60, 39.77]),
+        ("0820000", [-105.11, 39.61, -104.60, 39.91]),
+    ]);
+    route(
+        &server,
+        &archive_path(2021, &place(DENVER_CITY)),
+        200,
+        colorado,
+    )
+    .await;
+    let wyoming = common::shapefile_zip(&[
+        ("5613900", [-104.87, 41.08, -104.65, 41.20]),
+        ("5613150", [-106.37, 42.79, -106.20, 42.90]),
+    ]);
+    route(&server, &archive_path(2021, &place(CHEYENNE)), 200, wyoming).await;
+
+    let counties = serde_json::json!([
+        ["NAME", "B01001_001E", "state", "county"],
+        ["Adams County, Colorado", "519572", "08", "001"],
+        ["Denver County, Colorado", "713252", "08", "031"]
+    ]);
+    route(&server, "/data/2022/acs/acs5", 200, counties.to_string()).await;
+    let archive = common::shapefile_zip(&[("08031", [-105.11, 39.61, -104.60, 39.91])]);
+    route(&server, &archive_path(2022, &county(DENVER)), 200, archive).await;
+
+    let variable = |label: &str| serde_json::json!({"label": label, "group": "B19001"});
+    let group = serde_json::json!({"variables": {
+        "B19001_002E": variable("Estimate!!Total:!!Less than $10,000"),
+        "B19001_001EA": variable("Annotation of Estimate!!Total:"),
+        "B19001_001M": variable("Margin of Error!!Total:"),
+        "B19001_001E": variable("Estimate!!Total:"),
+        "NAME": {"label": "Geographic Area Name"},
+    }});
+    let group_path = "/data/2022/acs/acs5/groups/B19001.json";
+    route(&server, group_path, 200, group.to_string()).await;
+
+    let block_groups = common::shapefile_zip(&[
+        ("080310041021", [-104.99, 39.70, -104.98, 39.71]),
+        ("080310041022", [-104.98, 39.70, -104.97, 39.71]),
+        ("080590001001", [-105.08, 39.74, -105.07, 39.75]),
+    ]);
+    let block_group_path = archive_path(2020, &denver_block_group());
+    route(&server, &block_group_path, 200, block_groups).await;
+    server
+}
+
+/// the path of the 2020 county archive on the mock server.
+fn county_archive_path() -> String {
+    archive_path(2020, &county(DENVER))
+}
+
+/// the path on the mock server of the TIGER/Lines archive holding a geoid in a year.
+fn archive_path(year: u64, geoid: &Geoid) -> String {
+    TigerResourceBuilder::new(year)
+        .unwrap()
+        .create_resource_at("", geoid)
+        .unwrap()
+        .uri
+}
+
+/// the options of TIGER/Lines runs that download from the mock server.
+fn run_options(server: &MockServer) -> RunOptions {
+    RunOptions::default().with_base_url(&server.uri())
+}
+
+/// the options of joins that download TIGER/Lines archives from the mock server.
+fn join_options(server: &MockServer) -> JoinOptions {
+    JoinOptions::default().with_tiger_base_url(&server.uri())
+}
+
+fn county((state, county): (u64, u64)) -> Geoid {
+    Geoid::County(fips::State(state), fips::County(county))
+}
+
+fn place((state, place): (u64, u64)) -> Geoid {
+    Geoid::Place(fips::State(state), fips::Place(place))
+}
+
+/// a block group in tract 41.02 of Denver county.
+fn denver_block_group() -> Geoid {
+    Geoid::BlockGroup(
+        fips::State(8),
+        fips::County(31),
+        fips::CensusTract(4102),
+        fips::BlockGroup(1),
+    )
+}
+
+fn colorado_counties(server: &MockServer, year: u64) -> AcsApiQueryParams {
+    let for_query =
+        AcsGeoidQuery::new(Some(Geoid::State(fips::State(8))), Some(GeoidType::County)).unwrap();
+    AcsApiQueryParams::new(
+        Some(format!("{}/data", server.uri())),
+        year,
+        AcsType::FiveYear,
+        vec![String::from("NAME"), String::from("B01001_001E")],
+        for_query,
+        Some(String::from("test-key")),
+    )
+}
+
+#[tokio::test]
+async fn acs_run_reads_rows_from_the_api() {
+    let server = start().await;
+    let client = reqwest::Client::new();
+    let rows = acs_api::run(&client, &colorado_counties(&server, 2020), None)
+        .await
+        .unwrap();
+
+    let geoids = rows.iter().map(|(g, _)| g.clone()).collect::<Vec<_>>();
+    assert_eq!(geoids, vec![county(DENVER), county(JEFFERSON)]);
+    assert_eq!(rows[0].1[1].name, "B01001_001E");
+    assert_eq!(rows[0].1[1].as_f64_safe().unwrap(), 715522.0);
+
+    let requests = requests_for(&server, "/data/2020/acs/acs5").await;
+    assert!(requests
+        .iter()
+        .any(|r| r
+            == "/data/2020/acs/acs5?get=NAME,B01001_001E&for=county:*&in=state:08&key=test-key"));
+}
+
+#[tokio::test]
+async fn acs_run_reports_api_errors() {
+    let server = start().await;
+    let client = reqwest::Client::new();
+    let error = acs_api::run(&client, &colorado_counties(&server, 2019), None)
+        .await
+        .unwrap_err();
+    assert!(error.contains("unknown variable 'B01001_999E'"), "{error}");
+
+    let error = acs_api::run(&client, &colorado_counties(&server, 2018), None)
+        .await
+        .unwrap_err();
+    assert!(error.contains("500"), "{error}");
+    assert!(
+        !error.contains("test-key"),
+        "API key should be redacted: {error}"
+    );
+}
+
+#[tokio::test]
+async fn tiger_run_reads_geometries_from_an_archive() {
+    let server = start().await;
+    let client = http::client_builder().build().unwrap();
+    let builder = TigerResourceBuilder::new(2020).unwrap();
+    let scratch_dir = tempfile::tempdir().unwrap();
+    let keep_dir = tempfile::tempdir().unwrap();
+    let denver = county(DENVER);
+
+    let result = tiger_api::run(
+        &client,
+        &builder,
+        &[&denver],
+        &run_options(&server)
+            .with_scratch_dir(scratch_dir.path())
+            .with_keep_files(keep_dir.path()),
+    )
+    .await
+    .unwrap();
+
+    // one archive covers the county, and only the requested county is returned
+    assert_eq!(result.len(), 1);
+    let rows = result.into_iter().next().unwrap().unwrap();
+    assert_eq!(rows.len(), 1);
+    let (geoid, geometry) = &rows[0];
+    assert_eq!(geoid, &denver);
+    assert!(matches!(
+        geometry,
+        Geometry::Polygon(_) | Geometry::MultiPolygon(_)
+    ));
+    let bounds = geometry.bounding_rect().unwrap();
+    assert_eq!((bounds.min().x, bounds.max().y), (-105.11, 39.91));
+    assert!(!requests_for(&server, &county_archive_path())
+        .await
+        .is_empty());
+
+    // the archive and the files extracted from it are kept for inspection
+    for kept in [
+        "tl_2020_us_county.zip",
+        "tl_2020_us_county.shp",
+        "tl_2020_us_county.dbf",
+    ] {
+        assert!(keep_dir.path().join(kept).is_file(), "{kept} was not kept");
+    }
+}
+
+#[tokio::test]
+async fn tiger_run_matches_block_groups_in_a_state_archive() {
+    let server = start().await;
+    let client = http::client_builder().build().unwrap();
+    let builder = TigerResourceBuilder::new(2020).unwrap();
+    let block_group = denver_block_group();
+
+    // from 2020, block groups are published in one archive per state
+    let resource = builder.create_resource(&block_group).unwrap();
+    assert_eq!(resource.file_scope, Some(GeoidType::State));
+    assert!(resource.uri.ends_with("/BG/tl_2020_08_bg.zip"));
+
+    let result = tiger_api::run(&client, &builder, &[&block_group], &run_options(&server))
+        .await
+        .unwrap();
+
+    assert_eq!(result.len(), 1);
+    let rows = result.into_iter().next().unwrap().unwrap();
+    // only the requested block group matches, not its neighbour in the same tract
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].0, block_group);
+    let bounds = rows[0].1.bounding_rect().unwrap();
+    assert_eq!((bounds.min().x, bounds.min().y), (-104.99, 39.70));
+    assert!(!requests_for(&server, &archive_path(2020, &block_group))
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn tiger_run_cancelled_before_start_requests_nothing() {
+    let server = start().await;
+    let client = http::client_builder().build().unwrap();
+    let builder = TigerResourceBuilder::new(2020).unwrap();
+    let denver = county(DENVER);
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+
+    let result = tiger_api::run(
+        &client,
+        &builder,
+        &[&denver],
+        &run_options(&server).with_cancel(cancel.clone()),
+    )
+    .await
+    .unwrap();
+
+    // the archive is reported as cancelled rather than left out of the result
+    assert_eq!(result.len(), 1);
+    let error = result[0].as_ref().unwrap_err();
+    assert!(error.ends_with(": cancelled"), "{error}");
+    assert!(requests_for(&server, &county_archive_path())
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn tiger_run_feature_reads_a_national_file_with_the_run_options() {
+    let server = MockServer::start().await;
+    let client = http::client_builder().build().unwrap();
+    let builder = TigerResourceBuilder::new(2020).unwrap();
+    let roads_path = "/TIGER2020/PRIMARYROADS/tl_2020_us_primaryroads.zip";
+    let roads = common::feature_zip(&[
+        ("1104486629826", [-105.0, 39.7, -104.9, 39.8]),
+        ("1104486630033", [-105.2, 39.9, -105.1, 40.0]),
+    ]);
+    route(&server, roads_path, 200, roads).await;
+    let bytes = ByteCounter::default();
+    let options = RunOptions::default()
+        .with_base_url(&server.uri())
+        .with_bytes(bytes.clone());
+
+    let rows = tiger_api::run_feature(&client, &builder, &TigerFeature::PrimaryRoads, &options)
+        .await
+        .unwrap();
+
+    let ids = rows.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["1104486629826", "1104486630033"]);
+    assert_eq!(requests_for(&server, roads_path).await.len(), 1);
+    assert!(bytes.total() > 0);
+
+    // a cancelled run requests nothing
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let options = options.with_cancel(cancel);
+    let result =
+        tiger_api::run_feature(&client, &builder, &TigerFeature::PrimaryRoads, &options).await;
+    let error = result.unwrap_err();
+    assert!(
+        error.ends_with(&format!("{roads_path}: cancelled")),
+        "{error}"
+    );
+    assert_eq!(requests_for(&server, roads_path).await.len(), 1);
+}
+
+#[tokio::test]
+async fn acs_tiger_run_cancelled_mid_run_keeps_the_archives_already_read() {
+    let server = MockServer::start().await;
+    let places = serde_json::json!([
+        ["B01001_001E", "state", "place"],
+        ["706799", "08", "20000"],
+        ["65010", "56", "13900"]
+    ]);
+    route(&server, "/data/2021/acs/acs5", 200, places.to_string()).await;
+    let colorado = common::shapefile_zip(&[("0820000", [-105.11, 39.61, -104.60, 39.91])]);
+    let colorado_path = archive_path(2021, &place(DENVER_CITY));
+    route(&server, &colorado_path, 200, colorado).await;
+    // the Wyoming archive is still downloading when the run is cancelled
+    let wyoming = common::shapefile_zip(&[("5613900", [-104.87, 41.08, -104.65, 41.20])]);
+    let wyoming_path = archive_path(2021, &place(CHEYENNE));
+    common::route_delayed(&server, &wyoming_path, Duration::from_secs(60), wyoming).await;
+
+    let cancel = CancellationToken::new();
+    let canceller = {
+        let (server, cancel, wyoming_path) = (&server, cancel.clone(), wyoming_path.clone());
+        async move {
+            while requests_for(server, &wyoming_path).await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            // leaves time for the Colorado archive to be read
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel.cancel();
+        }
+    };
+    let query = AcsApiQueryParams::new(
+        Some(format!("{}/data", server.uri())),
+        2021,
+        AcsType::FiveYear,
+        vec![String::from("B01001_001E")],
+        AcsGeoidQuery::new(None, Some(GeoidType::Place)).unwrap(),
+        None,
+    );
+    let options = join_options(&server).with_cancel(cancel.clone());
+    let start = std::time::Instant::now();
+    let (response, _) = tokio::join!(acs_tiger::run(&query, &options), canceller);
+    let response = response.unwrap();
+
+    // the delayed download is dropped rather than awaited
+    assert!(start.elapsed() < Duration::from_secs(30));
+    assert!(cancel.is_cancelled());
+    assert_eq!(response.join_dataset.len(), 1);
+    assert_eq!(response.join_dataset[0].geoid, place(DENVER_CITY));
+    assert_eq!(response.tiger_errors.len(), 1);
+    let error = &response.tiger_errors[0];
+    assert!(error.contains(&wyoming_path), "{error}");
+    assert!(error.ends_with(": cancelled"), "{error}");
+    assert_eq!(response.join_errors.len(), 1, "{:?}", response.join_errors);
+    assert!(response.join_errors[0].contains("5613900"));
+    assert_eq!(response.summary.tiger_files.failed, 1);
+}
+
+#[tokio::test]
+async fn acs_tiger_run_joins_rows_to_geometries() {
+    let server = start().await;
+    let response = acs_tiger::run(&colorado_counties(&server, 2020), &join_options(&server))
+        .await
+        .unwrap();
+
+    assert!(
+        response.tiger_errors.is_empty(),
+        "{:?}",
+        response.tiger_errors
+    );
+    assert!(
+        response.join_errors.is_empty(),
+        "{:?}",
+        response.join_errors
+    );
+    assert_eq!(response.join_summary.missing(), 0);
+    // two counties with two values each
+    assert_eq!(response.join_dataset.len(), 4);
+    assert_eq!(response.summary.data_requests.succeeded, 1);
+    assert_eq!(response.summary.tiger_files.succeeded, 1);
+    // the ACS response and the county archive are both counted
+    let tabular = acs_tiger::run_without_geometry(&[colorado_counties(&server, 2020)])
+        .await
+        .unwrap();
+    assert!(tabular.summary.bytes > 0);
+    assert!(response.summary.bytes > tabular.summary.bytes);
+    let jefferson = response
+        .join_dataset
+        .iter()
+        .find(|row| row.geoid == county(JEFFERSON))
+        .unwrap();
+    let bounds = jefferson
+        .geometry
+        .as_ref()
+        .unwrap()
+        .bounding_rect()
+        .unwrap();
+    assert_eq!(bounds.min().y, 39.13);
+}
+
+#[tokio::test]
+async fn acs_tiger_run_joins_places_from_each_state_archive() {
+    let server = start().await;
+    let for_query = AcsGeoidQuery::new(None, Some(GeoidType::Place)).unwrap();
+    let query = AcsApiQueryParams::new(
+        Some(format!("{}/data", server.uri())),
+        2021,
+        AcsType::FiveYear,
+        vec![String::from("NAME"), String::from("B01001_001E")],
+        for_query,
+        Some(String::from("test-key")),
+    );
+    let response = acs_tiger::run(&query, &join_options(&server))
+        .await
+        .unwrap();
+
+    assert!(
+        response.tiger_errors.is_empty(),
+        "{:?}",
+        response.tiger_errors
+    );
+    assert!(
+        response.join_errors.is_empty(),
+        "{:?}",
+        response.join_errors
+    );
+    assert_eq!(response.join_summary.missing(), 0);
+    // places are published per state, so one archive is read for each state
+    assert_eq!(response.summary.tiger_files.succeeded, 2);
+    for geoid in [place(DENVER_CITY), place(CHEYENNE)] {
+        let path = archive_path(2021, &geoid);
+        assert!(
+            !requests_for(&server, &path).await.is_empty(),
+            "{path} not requested"
+        );
+    }
+    // two places with two values each, and no rows for the places not queried
+    assert_eq!(response.join_dataset.len(), 4);
+    let cheyenne = response
+        .join_dataset
+        .iter()
+        .find(|row| row.geoid == place(CHEYENNE))
+        .unwrap();
+    let bounds = cheyenne.geometry.as_ref().unwrap().bounding_rect().unwrap();
+    assert_eq!(bounds.min().y, 41.08);
+}
+
+#[tokio::test]
+async fn acs_tiger_run_keeps_unmatched_rows_when_asked() {
+    let server = start().await;
+    let response = acs_tiger::run(&colorado_counties(&server, 2022), &join_options(&server))
+        .await
+        .unwrap();
+    assert_eq!(response.join_errors.len(), 1, "{:?}", response.join_errors);
+    assert!(response.join_errors[0].contains("08001"));
+    assert!(response
+        .join_dataset
+        .iter()
+        .all(|row| row.geoid == county(DENVER)));
+
+    let response = acs_tiger::run(
+        &colorado_counties(&server, 2022),
+        &join_options(&server).with_keep_unmatched(true),
+    )
+    .await
+    .unwrap();
+    assert!(
+        response.join_errors.is_empty(),
+        "{:?}",
+        response.join_errors
+    );
+    // the unmatched county is still counted as missing
+    assert_eq!(response.join_summary.missing(), 1);
+    // two counties with two values each, the values of Adams county kept without a geometry
+    assert_eq!(response.join_dataset.len(), 4);
+    for row in response.join_dataset.iter() {
+        assert_eq!(row.geometry.is_some(), row.geoid == county(DENVER));
+    }
+    assert!(response
+        .join_dataset
+        .iter()
+        .any(|row| row.geoid == county(ADAMS) && row.acs_value.name == "B01001_001E"));
+
+    // the rows without a geometry are only written under a nullable geometry column
+    let csv = || Box::new(CsvSink::new(vec![], GeometryEncoding::Wkt));
+    let (matched, unmatched): (Vec<_>, Vec<_>) = response
+        .join_dataset
+        .into_iter()
+        .partition(|row| row.geometry.is_some());
+    sink::write_acs_rows(csv(), matched, false).unwrap();
+    let error = sink::write_acs_rows(csv(), unmatched, false).unwrap_err();
+    assert!(error.contains("not nullable"), "{error}");
+}
+
+#[tokio::test]
+async fn lodes_tiger_run_refuses_keep_unmatched() {
+    let server = MockServer::start().await;
+    let dataset = LodesDataset::WAC {
+        edition: LodesEdition::Lodes8,
+        job_type: LodesJobType::JT00,
+        segment: WorkplaceSegment::S000,
+        year: 2020,
+    };
+    let options = join_options(&server)
+        .with_lodes_base_url(&server.uri())
+        .with_keep_unmatched(true);
+    let error = lodes_tiger::run(
+        &[Geoid::State(fips::State(8))],
+        &Some(GeoidType::County),
+        &[WacSegment::C000],
+        &dataset,
+        &options,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert!(error.contains("keep_unmatched"), "{error}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn acs_tiger_run_aoi_finds_counties_from_the_embedded_reference() {
+    let server = start().await;
+    let colorado = Geoid::State(fips::State(8));
+    let states = common::shapefile_zip(&[("08", [-109.06, 36.99, -102.04, 41.00])]);
+    route(&server, &archive_path(2020, &colorado), 200, states).await;
+    let aoi = AreaOfInterest::new(Geometry::Polygon(
+        Rect::new((-104.95, 39.70), (-104.90, 39.75)).to_polygon(),
+    ))
+    .unwrap();
+    let options = join_options(&server).with_acs_base_url(&format!("{}/data", server.uri()));
+    let response = acs_tiger::run_aoi(
+        &aoi,
+        GeoidType::County,
+        2020,
+        AcsType::FiveYear,
+        vec![String::from("NAME"), String::from("B01001_001E")],
+        None,
+        &options,
+    )
+    .await
+    .unwrap();
+
+    // the counties of Colorado come from the embedded reference, so the only ACS
+    // query is for the data of the intersecting county
+    let requests = requests_for(&server, "/data/2020/acs/acs5").await;
+    assert_eq!(requests.len(), 1, "{requests:?}");
+    assert!(!requests[0].contains("county:*"), "{requests:?}");
+    assert!(response
+        .join_dataset
+        .iter()
+        .all(|row| row.geoid == county(DENVER)));
+    assert_eq!(response.join_dataset.len(), 2);
+    assert_eq!(response.summary.data_requests.attempted, 1);
+    assert_eq!(response.summary.tiger_files.attempted, 3);
+}
+
+#[tokio::test]
+async fn acs_tiger_run_skips_states_without_functioning_mcds() {
+    let server = start().await;
+    let for_query = AcsGeoidQuery::new(
+        Some(Geoid::State(fips::State(8))),
+        Some(GeoidType::CountySubdivision),
+    )
+    .unwrap();
+    let query = AcsApiQueryParams {
+        for_query,
+        ..colorado_counties(&server, 2020)
+    };
+    let options = join_options(&server).with_functioning_mcds_only(true);
+    // Colorado county subdivisions are statistical, so nothing is left to request
+    let error = acs_tiger::run(&query, &options).await.err().unwrap();
+    assert!(error.contains("no geoids remain"), "{error}");
+    assert!(requests_for(&server, "/data/2020/acs/acs5")
+        .await
+        .is_empty());
+
+    let error = acs_tiger::run(&colorado_counties(&server, 2020), &options)
+        .await
+        .err()
+        .unwrap();
+    assert!(error.contains("county subdivision"), "{error}");
+}
+
+#[tokio::test]
+async fn acs_table_expands_into_estimates_from_group_metadata() {
+    let server = start().await;
+    let client = reqwest::Client::new();
+    let base_url = format!("{}/data", server.uri());
+    let metadata =
+        acs_variables_api::get_group(&client, Some(&base_url), 2022, AcsType::FiveYear, "B19001")
+            .await
+            .unwrap();
+    assert_eq!(metadata.len(), 5);
+    assert_eq!(metadata[0].name, "B19001_001E");
+    assert_eq!(metadata[0].label, "Estimate!!Total:");
+
+    let estimates = acs_table::expand_table("B19001", &metadata);
+    assert_eq!(estimates, vec!["B19001_001E", "B19001_002E"]);
+}
+
+#[tokio::test]
+async fn lodes_run_cancelled_mid_run_keeps_the_files_already_read() {
+    let server = MockServer::start().await;
+    let dataset = LodesDataset::WAC {
+        edition: LodesEdition::Lodes8,
+        job_type: LodesJobType::JT00,
+        segment: WorkplaceSegment::S000,
+        year: 2020,
+    };
+    let wac_path = |state: u64| {
+        dataset
+            .create_uri_at("", &Geoid::State(fips::State(state)))
+            .unwrap()
+    };
+    let colorado = common::wac_gz(&[("080310001001000", 10)]);
+    route(&server, &wac_path(8), 200, colorado).await;
+    // the Wyoming file is still downloading when the run is cancelled
+    let wyoming = common::wac_gz(&[("560210001001000", 7)]);
+    common::route_delayed(&server, &wac_path(56), Duration::from_secs(60), wyoming).await;
+
+    let cancel = CancellationToken::new();
+    let canceller = {
+        let (server, cancel, wyoming_path) = (&server, cancel.clone(), wac_path(56));
+        async move {
+            while requests_for(server, &wyoming_path).await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            // leaves time for the Colorado file to be read
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            cancel.cancel();
+        }
+    };
+    let client = http::client_builder().build().unwrap();
+    let states = [Geoid::State(fips::State(8)), Geoid::State(fips::State(56))];
+    let options = DownloadOptions::default()
+        .with_base_url(&server.uri())
+        .with_cancel(cancel.clone());
+    let run = lodes_api::run(
+        &client,
+        &dataset,
+        &states,
+        &[WacSegment::C000],
+        None,
+        &options,
+    );
+    let start = std::time::Instant::now();
+    let (result, _) = tokio::join!(run, canceller);
+
+    assert!(start.elapsed() < Duration::from_secs(30));
+    let Ok(LodesResult::Wac((rows, errors))) = result else {
+        panic!("expected WAC rows");
+    };
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].0.geoid_string(), "080310001001000");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains(&wac_path(56)), "{}", errors[0]);
+    assert!(errors[0].ends_with(": cancelled"), "{}", errors[0]);
+}
+
+#[tokio::test]
+async fn lodes_run_wac_refuses_segments_not_published_in_the_file_year() {
+    let server = MockServer::start().await;
+    let dataset = LodesDataset::WAC {
+        edition: LodesEdition::Lodes8,
+        job_type: LodesJobType::JT00,
+        segment: WorkplaceSegment::S000,
+        year: 2005,
+    };
+    let path = dataset
+        .create_uri_at("", &Geoid::State(fips::State(8)))
+        .unwrap();
+    route(
+        &server,
+        &path,
+        200,
+        common::wac_gz(&[("080310001001000", 10)]),
+    )
+    .await;
+
+    let client = http::client_builder().build().unwrap();
+    let queries = vec![format!("{}{path}", server.uri())];
+    let options = DownloadOptions::default();
+    let segments = [WacSegment::C000, WacSegment::CD01];
+    let result = lodes_api::run_wac(&client, &queries, &segments, None, &options).await;
+
+    assert_eq!(
+        result.err().unwrap(),
+        "WAC segment CD01 is not available before 2009, found year 2005"
+    );
+    assert!(requests_for(&server, &path).await.is_empty());
+
+    // the segments published that year are read
+    let result = lodes_api::run_wac(&client, &queries, &[WacSegment::C000], None, &options).await;
+    let (rows, errors) = result.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(errors.is_empty(), "{errors:?}");
+}
+
+#[tokio::test]
+async fn lodes_bulk_retries_failed_states_and_resumes() {
+    let server = MockServer::start().await;
+    let dataset = LodesDataset::WAC {
+        edition: LodesEdition::Lodes8,
+        job_type: LodesJobType::JT00,
+        segment: WorkplaceSegment::S000,
+        year: 2020,
+    };
+    let wac_path = |state: u64| {
+        dataset
+            .create_uri_at("", &Geoid::State(fips::State(state)))
+            .unwrap()
+    };
+    let colorado = common::wac_gz(&[("080310001001000", 10), ("080590001001000", 5)]);
+    route(&server, &wac_path(8), 200, colorado).await;
+    // every attempt of the first run fails: two downloads in each of two state attempts
+    let wyoming = common::wac_gz(&[("560210001001000", 7)]);
+    route_failing(&server, &wac_path(56), 4, 503, wyoming).await;
+    let archive = common::shapefile_zip(&[
+        ("08031", [-105.11, 39.61, -104.60, 39.91]),
+        ("08059", [-105.40, 39.13, -105.05, 39.93]),
+        ("56021", [-105.28, 40.99, -104.05, 41.66]),
+    ]);
+    let county_path = archive_path(dataset.tiger_year(), &county(DENVER));
+    route(&server, &county_path, 200, archive).await;
+
+    let settings = BulkSettings {
+        dataset,
+        output_resolution: Some(GeoidType::County),
+        wac_segments: vec![WacSegment::C000],
+        format: OutputFormat::Csv,
+        wide: false,
+    };
+    let retry = RetryPolicy {
+        max_retries: 1,
+        initial_backoff: Duration::from_millis(1),
+        timeout: Duration::from_secs(10),
+    };
+    let options = join_options(&server)
+        .with_lodes_base_url(&server.uri())
+        .with_retry(retry);
+    let dir = tempfile::tempdir().unwrap();
+    let manifest_path = dir.path().join(BulkManifest::FILENAME);
+    let states = [Geoid::State(fips::State(8)), Geoid::State(fips::State(56))];
+    let write_state = |state: &Geoid, _| {
+        let output = dir.path().join(format!("{}.csv", state.geoid_string()));
+        std::fs::write(&output, "").map_err(|e| e.to_string())?;
+        Ok(output)
+    };
+
+    let manifest = lodes_bulk::run(&manifest_path, &states, &settings, &options, write_state)
+        .await
+        .unwrap();
+    assert_eq!(manifest.states["08"].status, StateStatus::Complete);
+    assert_eq!(manifest.states["08"].rows, 2);
+    let wyoming = &manifest.states["56"];
+    assert_eq!(wyoming.status, StateStatus::Failed);
+    assert_eq!(wyoming.attempts, 2);
+    assert!(wyoming.error.as_deref().unwrap_or_default().contains("503"));
+
+    // the second run only downloads the failed state
+    let manifest = lodes_bulk::run(&manifest_path, &states, &settings, &options, write_state)
+        .await
+        .unwrap();
+    assert!(manifest.is_complete());
+    assert_eq!(manifest.states["56"].attempts, 3);
+    assert_eq!(manifest.states["56"].rows, 1);
+    assert_eq!(requests_for(&server, &wac_path(8)).await.len(), 1);
+    assert_eq!(requests_for(&server, &wac_path(56)).await.len(), 5);
+
+    // a run with other settings does not resume the download
+    let wide = BulkSettings {
+        wide: true,
+        ..settings.clone()
+    };
+    let error = lodes_bulk::run(&manifest_path, &states, &wide, &options, write_state)
+        .await
+        .unwrap_err();
+    assert!(error.contains("wide false instead of true"), "{error}");
+    let other_vintage = options.clone().with_tiger_year(2010);
+    let error = lodes_bulk::run(
+        &manifest_path,
+        &states,
+        &settings,
+        &other_vintage,
+        write_state,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.contains("tiger_year null instead of 2010"), "{error}");
+}